@@ -1,3 +1,4 @@
+use csml_interpreter::data::csml_bot::Module;
 use serde::{Deserialize, Serialize};
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::prelude::*;
@@ -127,6 +128,9 @@ pub struct Manifest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<String>,
     pub commands: Vec<Vec<String>>,
+    // vendored in modules/ by `csml vendor`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modules: Option<Vec<Module>>,
 }
 
 impl Manifest {
@@ -141,6 +145,7 @@ impl Manifest {
             repository: None,
             license: None,
             commands: vec![],
+            modules: None,
         }
     }
 }
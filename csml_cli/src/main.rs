@@ -4,8 +4,16 @@ mod run;
 
 use clap::Parser;
 use clap_derive::{Parser, Subcommand};
-use csml_engine::data::models::BotOpt;
+use csml_engine::data::{
+    models::{BotOpt, MigrationCheckpoint},
+    EngineError,
+};
+use csml_interpreter::data::Client;
+use csml_interpreter::module_registry::{ModuleRegistry, ModuleVersion};
+use std::fs;
+use std::path::{Path, PathBuf};
 
+use init_package::Manifest;
 use interface::{chat_menu::format_initial_payload, StartUI};
 use run::load_info;
 
@@ -31,6 +39,204 @@ pub enum Commands {
     },
     #[command(about = "Create a new CSML Bot in the selected directory")]
     Init,
+    #[command(about = "Run the scheduled flow triggers whose date is passed")]
+    Schedules {
+        #[arg(
+            short,
+            long,
+            default_value_t = 100,
+            help = "Maximum number of schedules to run"
+        )]
+        limit: u32,
+    },
+    #[command(about = "Export the data of a client, or of every client of a bot")]
+    Export {
+        #[arg(short, long, help = "Bot id")]
+        bot_id: String,
+        #[arg(short, long, requires = "user_id", help = "Channel id of the client")]
+        channel_id: Option<String>,
+        #[arg(short, long, requires = "channel_id", help = "User id of the client")]
+        user_id: Option<String>,
+        #[arg(
+            short,
+            long,
+            help = "Write the document to this file instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+    #[command(about = "Import a document made by export, replacing the data of its clients")]
+    Import {
+        #[arg(help = "Path of the document")]
+        path: PathBuf,
+    },
+    #[command(about = "Copy all the data of a database into another one")]
+    Migrate {
+        #[arg(
+            long,
+            help = "Url of the source database: sqlite://<path>, postgresql://..., mongodb://.../<database>, dynamodb:// or memory://"
+        )]
+        from: String,
+        #[arg(long, help = "Url of the target database")]
+        to: String,
+        #[arg(
+            short,
+            long,
+            default_value_t = 100,
+            help = "Number of records copied at once"
+        )]
+        batch_size: u32,
+        #[arg(
+            short,
+            long,
+            help = "File keeping the progress, to resume an interrupted migration"
+        )]
+        checkpoint: Option<PathBuf>,
+    },
+    #[command(about = "Encrypt the stored data again with the current key of ENCRYPTION_SECRETS")]
+    RotateKey {
+        #[arg(
+            short,
+            long,
+            default_value_t = 100,
+            help = "Number of records encrypted at once"
+        )]
+        batch_size: u32,
+        #[arg(
+            short,
+            long,
+            help = "File keeping the progress, to resume an interrupted rotation"
+        )]
+        checkpoint: Option<PathBuf>,
+    },
+    #[command(about = "Download the modules of the manifest in modules/ to run the bot offline")]
+    Vendor,
+}
+
+fn export(
+    bot_id: String,
+    client: Option<(String, String)>,
+    output: Option<PathBuf>,
+) -> Result<(), String> {
+    let archive = match client {
+        Some((channel_id, user_id)) => {
+            csml_engine::export_client(&Client::new(bot_id, channel_id, user_id))
+        }
+        None => csml_engine::export_bot(&bot_id),
+    }
+    .map_err(|err| format!("{:?}", err))?;
+
+    let document = serde_json::to_string_pretty(&archive).map_err(|err| err.to_string())?;
+    match output {
+        Some(path) => fs::write(path, document).map_err(|err| err.to_string()),
+        None => {
+            println!("{}", document);
+            Ok(())
+        }
+    }
+}
+
+fn import(path: PathBuf) -> Result<(), String> {
+    let document = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let archive = serde_json::from_str(&document).map_err(|err| err.to_string())?;
+
+    let imported = csml_engine::import_client(&archive).map_err(|err| format!("{:?}", err))?;
+    println!("{}", serde_json::json!(imported));
+
+    Ok(())
+}
+
+fn read_checkpoint(checkpoint: &Option<PathBuf>) -> Result<Option<MigrationCheckpoint>, String> {
+    match checkpoint {
+        Some(path) if path.exists() => {
+            let document = fs::read_to_string(path).map_err(|err| err.to_string())?;
+            Ok(Some(
+                serde_json::from_str(&document).map_err(|err| err.to_string())?,
+            ))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn save_checkpoint(
+    checkpoint: &Option<PathBuf>,
+    progress: &MigrationCheckpoint,
+) -> Result<(), EngineError> {
+    match checkpoint {
+        Some(path) => fs::write(path, serde_json::json!(progress).to_string())
+            .map_err(|err| EngineError::Manager(err.to_string())),
+        None => Ok(()),
+    }
+}
+
+fn migrate(
+    from: String,
+    to: String,
+    batch_size: u32,
+    checkpoint: Option<PathBuf>,
+) -> Result<(), String> {
+    let start = read_checkpoint(&checkpoint)?;
+
+    let report = csml_engine::migrate(&from, &to, batch_size, start, |progress| {
+        save_checkpoint(&checkpoint, progress)
+    })
+    .map_err(|err| format!("{:?}", err))?;
+
+    if let Some(path) = checkpoint {
+        fs::remove_file(path).ok();
+    }
+    println!("{}", serde_json::json!(report));
+
+    Ok(())
+}
+
+fn rotate_key(batch_size: u32, checkpoint: Option<PathBuf>) -> Result<(), String> {
+    let start = read_checkpoint(&checkpoint)?;
+
+    let report = csml_engine::rotate_encryption_key(batch_size, start, |progress| {
+        save_checkpoint(&checkpoint, progress)
+    })
+    .map_err(|err| format!("{:?}", err))?;
+
+    if let Some(path) = checkpoint {
+        fs::remove_file(path).ok();
+    }
+    println!("{}", serde_json::json!(report));
+
+    Ok(())
+}
+
+fn vendor(directory: &Path) -> Result<(), String> {
+    let file = fs::File::open(directory.join("manifest.yaml")).map_err(|err| err.to_string())?;
+    let manifest: Manifest = serde_yaml::from_reader(file).map_err(|err| err.to_string())?;
+    let registry = ModuleRegistry::from_env();
+
+    for module in manifest.modules.unwrap_or_default() {
+        let resolved = registry.resolve(&module)?;
+        let version = resolved.version.ok_or(format!(
+            "module [{}] needs an exact version to be vendored",
+            module.name
+        ))?;
+
+        let dir = directory.join("modules").join(&module.name);
+        fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+        fs::write(dir.join(format!("{}.csml", version)), &resolved.content)
+            .map_err(|err| err.to_string())?;
+
+        // only one version of each module is vendored
+        let index = vec![ModuleVersion {
+            version: version.clone(),
+            integrity: Some(resolved.integrity),
+        }];
+        fs::write(
+            dir.join("index.json"),
+            serde_json::to_string_pretty(&index).map_err(|err| err.to_string())?,
+        )
+        .map_err(|err| err.to_string())?;
+
+        println!("vendored module [{}] version [{}]", module.name, version);
+    }
+
+    Ok(())
 }
 
 fn main() {
@@ -39,6 +245,52 @@ fn main() {
     if let Some(command) = matches.command {
         match command {
             Commands::Init => interface::csml_ui(StartUI::Init).unwrap(),
+            Commands::Schedules { limit } => match csml_engine::run_due_schedules(limit) {
+                Ok(runs) => {
+                    for run in runs {
+                        println!("{}", serde_json::json!(run));
+                    }
+                }
+                Err(err) => println!("failed to run schedules: {:?}", err),
+            },
+            Commands::Export {
+                bot_id,
+                channel_id,
+                user_id,
+                output,
+            } => {
+                if let Err(err) = export(bot_id, channel_id.zip(user_id), output) {
+                    println!("failed to export: {}", err)
+                }
+            }
+            Commands::Import { path } => {
+                if let Err(err) = import(path) {
+                    println!("failed to import: {}", err)
+                }
+            }
+            Commands::Migrate {
+                from,
+                to,
+                batch_size,
+                checkpoint,
+            } => {
+                if let Err(err) = migrate(from, to, batch_size, checkpoint) {
+                    println!("failed to migrate: {}", err)
+                }
+            }
+            Commands::RotateKey {
+                batch_size,
+                checkpoint,
+            } => {
+                if let Err(err) = rotate_key(batch_size, checkpoint) {
+                    println!("failed to rotate the encryption key: {}", err)
+                }
+            }
+            Commands::Vendor => {
+                if let Err(err) = vendor(Path::new(".")) {
+                    println!("failed to vendor modules: {}", err)
+                }
+            }
             Commands::Run {
                 text,
                 flow,
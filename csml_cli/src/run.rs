@@ -2,6 +2,8 @@ use csml_engine::data::models::CsmlRequest;
 use csml_interpreter::{
     data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client},
     load_components,
+    module_registry::ModuleRegistry,
+    search_for_modules_in,
 };
 
 use crate::init_package::Manifest;
@@ -10,6 +12,7 @@ use std::error::Error;
 use serde_json::json;
 use std::fs::{self, File};
 use std::io::prelude::*;
+use std::path::Path;
 
 pub fn init_request(string: &str, metadata: Option<serde_json::Value>) -> CsmlRequest {
     CsmlRequest {
@@ -31,6 +34,8 @@ pub fn init_request(string: &str, metadata: Option<serde_json::Value>) -> CsmlRe
         ttl_duration: None,
         step_limit: None,
         low_data_mode: None,
+        debug: false,
+        random_seed: None,
     }
 }
 
@@ -54,6 +59,8 @@ pub fn init_request_flow_trigger(flow_id: &str, step_id: Option<&str>) -> CsmlRe
         ttl_duration: None,
         step_limit: None,
         low_data_mode: None,
+        debug: false,
+        random_seed: None,
     }
 }
 
@@ -86,11 +93,12 @@ pub fn load_info(directory_name: &str) -> Result<CsmlBot, Box<dyn Error>> {
                 name: file_name[0].to_owned(),
                 content: contents,
                 commands: vec![], // link commands
+                priority: 0,
             });
         }
     }
 
-    Ok(CsmlBot {
+    let mut bot = CsmlBot {
         id: manifest.name.clone(),
         name: manifest.name.clone(),
         apps_endpoint: None,
@@ -101,9 +109,25 @@ pub fn load_info(directory_name: &str) -> Result<CsmlBot, Box<dyn Error>> {
         bot_ast: None,
         no_interruption_delay: None,
         env: None,
-        modules: None,
+        modules: manifest.modules,
         multibot: None,
-    })
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    };
+
+    // vendored modules are used instead of their url, otherwise modules are downloaded
+    // when the bot is run
+    let vendor = Path::new(directory_name).join("modules");
+    if vendor.is_dir() {
+        for module in bot.modules.iter_mut().flatten() {
+            module.url = None;
+        }
+        search_for_modules_in(&mut bot, &ModuleRegistry::local(&vendor))?;
+    }
+
+    Ok(bot)
 }
 
 pub fn search_csml_bot_folders() -> Vec<(String, CsmlBot)> {
@@ -26,12 +26,14 @@ fn init_bot() -> CsmlBot {
                 name: "flow".to_owned(),
                 content: get_flow("flow").expect("error in reading flow"),
                 commands: vec!["/plop".to_owned()],
+                priority: 0,
             },
             CsmlFlow {
                 id: "2".to_owned(),
                 name: "flow2".to_owned(),
                 content: get_flow("flow2").expect("error in reading flow"),
                 commands: vec!["/random".to_owned()],
+                priority: 0,
             },
         ],
         native_components: Some(load_components().unwrap()),
@@ -42,6 +44,10 @@ fn init_bot() -> CsmlBot {
         env: None,
         modules: None,
         multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
     }
 }
 
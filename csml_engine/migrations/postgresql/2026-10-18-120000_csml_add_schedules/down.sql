@@ -0,0 +1,3 @@
+DROP INDEX schedule_run_at;
+
+DROP TABLE csml_schedules;
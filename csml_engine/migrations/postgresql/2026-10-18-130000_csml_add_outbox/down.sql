@@ -0,0 +1,3 @@
+DROP INDEX outbox_bot_id;
+
+DROP TABLE csml_outbox;
@@ -0,0 +1,16 @@
+CREATE TABLE csml_outbox (
+  id uuid PRIMARY KEY,
+  bot_id VARCHAR NOT NULL,
+  channel_id VARCHAR NOT NULL,
+  user_id VARCHAR NOT NULL,
+
+  callback_url VARCHAR NOT NULL,
+  payload VARCHAR NOT NULL,
+  signature VARCHAR DEFAULT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+
+  updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX outbox_bot_id ON csml_outbox (bot_id, created_at);
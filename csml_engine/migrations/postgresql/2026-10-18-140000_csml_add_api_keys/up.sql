@@ -0,0 +1,10 @@
+CREATE TABLE csml_api_keys (
+  id uuid PRIMARY KEY,
+  name VARCHAR NOT NULL,
+  key_hash VARCHAR NOT NULL UNIQUE,
+
+  scopes VARCHAR NOT NULL,
+  bot_ids VARCHAR DEFAULT NULL,
+
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
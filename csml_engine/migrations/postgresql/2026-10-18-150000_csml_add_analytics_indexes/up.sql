@@ -0,0 +1,2 @@
+CREATE INDEX conversation_bot_id_created_at ON csml_conversations (bot_id, created_at);
+CREATE INDEX message_conversation_created_at ON csml_messages (conversation_id, created_at);
@@ -0,0 +1,12 @@
+CREATE TABLE csml_counters (
+  bot_id VARCHAR NOT NULL,
+  channel_id VARCHAR NOT NULL,
+  user_id VARCHAR NOT NULL,
+  key VARCHAR NOT NULL,
+
+  count BIGINT NOT NULL,
+
+  expires_at TIMESTAMP DEFAULT NULL,
+
+  PRIMARY KEY (bot_id, channel_id, user_id, key)
+);
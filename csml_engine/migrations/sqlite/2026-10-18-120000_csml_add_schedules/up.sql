@@ -0,0 +1,17 @@
+CREATE TABLE csml_schedules (
+  id BINARY(128) PRIMARY KEY NOT NULL,
+  bot_id VARCHAR NOT NULL,
+  channel_id VARCHAR NOT NULL,
+  user_id VARCHAR NOT NULL,
+
+  flow_id VARCHAR NOT NULL,
+  step_id VARCHAR NOT NULL,
+  callback_url VARCHAR DEFAULT NULL,
+  bot VARCHAR DEFAULT NULL,
+
+  attempts INTEGER NOT NULL DEFAULT 0,
+  run_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX schedule_run_at ON csml_schedules (run_at);
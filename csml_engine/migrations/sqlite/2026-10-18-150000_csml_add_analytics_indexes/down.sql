@@ -0,0 +1,2 @@
+DROP INDEX message_conversation_created_at;
+DROP INDEX conversation_bot_id_created_at;
@@ -0,0 +1,69 @@
+/**
+ * Conversation analytics of a bot, over the conversations created in a period.
+ *
+ * They are computed from the saved messages, each one having the flow and step where it was
+ * saved: the input of the user (RECEIVE) at the step waiting for it, and the messages of the
+ * bot (SEND) at the step where the request stopped. Conversations in low data mode have no
+ * saved messages, and are only counted when they are ended or abandoned.
+ * - flows: conversations started in a flow (by their first message) and ended in it
+ * - holds: inputs answering the hold of a step, and open conversations abandoned at a step: without
+ *   any activity (saved message or interaction) for ANALYTICS_ABANDON_DELAY seconds (30 minutes
+ *   by default), the others may still be answered
+ * - turns: inputs of the users, and their average by conversation
+ * - transitions: changes of step between two consecutive messages of a conversation, the steps
+ *   passed through by a single request without saving a message are not seen
+ */
+use crate::data::filter::AnalyticsFilter;
+use crate::error_messages::ERROR_ANALYTICS_PERIOD;
+use crate::EngineError;
+use chrono::{DateTime, Duration, LocalResult, TimeZone, Utc};
+use std::env;
+
+const ABANDON_DELAY: i64 = 30 * 60;
+
+fn get_date(timestamp: i64) -> Result<DateTime<Utc>, EngineError> {
+    match Utc.timestamp_opt(timestamp, 0) {
+        LocalResult::Single(date) => Ok(date),
+        _ => Err(EngineError::DateTimeError(
+            "Date time is out of range".to_owned(),
+        )),
+    }
+}
+
+fn get_abandon_delay() -> Duration {
+    let delay = env::var("ANALYTICS_ABANDON_DELAY")
+        .ok()
+        .and_then(|delay| delay.parse::<i64>().ok())
+        .unwrap_or(ABANDON_DELAY);
+
+    Duration::seconds(delay.max(0))
+}
+
+/**
+ * Conversations of the bot created from `from_date` to `to_date` (timestamps in seconds),
+ * or until now
+ */
+pub fn get_filter(
+    bot_id: &str,
+    from_date: i64,
+    to_date: Option<i64>,
+) -> Result<AnalyticsFilter<'_>, EngineError> {
+    let from_date = get_date(from_date)?;
+    let to_date = match to_date {
+        Some(to_date) => get_date(to_date)?,
+        None => Utc::now(),
+    };
+
+    if from_date >= to_date {
+        return Err(EngineError::DateTimeError(
+            ERROR_ANALYTICS_PERIOD.to_owned(),
+        ));
+    }
+
+    Ok(AnalyticsFilter::builder()
+        .bot_id(bot_id)
+        .from_date(from_date)
+        .to_date(to_date)
+        .abandoned_before(Utc::now() - get_abandon_delay())
+        .build())
+}
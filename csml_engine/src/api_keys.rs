@@ -0,0 +1,59 @@
+/**
+ * API keys of the server, with scopes and an optional list of allowed bots.
+ *
+ * Keys are random and only their SHA-256 hash is saved: a key is returned once when it is
+ * created, and a request is authorized by finding the saved key with the hash of the one
+ * it comes with.
+ */
+use crate::data::models::{ApiKey, ApiKeyRequest, CreatedApiKey};
+use crate::db_connectors::api_keys;
+use crate::error_messages::{ERROR_API_KEY_BOT_IDS, ERROR_API_KEY_SCOPES};
+use crate::{Database, EngineError};
+use chrono::Utc;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const KEY_PREFIX: &str = "csml_";
+
+fn generate_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    format!("{}{}", KEY_PREFIX, hex::encode(bytes))
+}
+
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+pub fn create_api_key(
+    request: &ApiKeyRequest,
+    db: &mut Database,
+) -> Result<CreatedApiKey, EngineError> {
+    if request.scopes.is_empty() {
+        return Err(EngineError::Format(ERROR_API_KEY_SCOPES.to_owned()));
+    }
+    if let Some(bot_ids) = &request.bot_ids {
+        if bot_ids.is_empty() {
+            return Err(EngineError::Format(ERROR_API_KEY_BOT_IDS.to_owned()));
+        }
+    }
+
+    let key = generate_key();
+    let api_key = ApiKey {
+        id: Uuid::new_v4(),
+        name: request.name.to_owned(),
+        scopes: request.scopes.to_owned(),
+        bot_ids: request.bot_ids.to_owned(),
+        created_at: Utc::now(),
+    };
+
+    api_keys::create_api_key(&api_key, &hash_key(&key), db)?;
+
+    Ok(CreatedApiKey { key, api_key })
+}
+
+pub fn get_api_key(key: &str, db: &mut Database) -> Result<Option<ApiKey>, EngineError> {
+    api_keys::get_api_key(&hash_key(key), db)
+}
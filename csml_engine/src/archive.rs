@@ -0,0 +1,298 @@
+/**
+ * Export and import of the data of a client, to move it between engines or storage backends.
+ *
+ * Everything goes through the storage traits, so an archive exported from one backend
+ * (MongoDB for instance) can be imported into any other one (PostgreSQL, SQLite...).
+ * Conversations and messages keep their ids and dates, so the target backend must implement
+ * the `*_records` methods of the storage traits, as for `migrate`. Memories and state are
+ * written again, and expire with the `ttl` of the import like the conversations.
+ *
+ * The data of a client is replaced as a whole: if the import of a client fails, the clients
+ * already replaced are restored from a backup made before the import.
+ */
+use crate::data::filter::ClientMessageFilter;
+use crate::data::models::{
+    Archive, ArchiveImport, ClientArchive, Conversation, Direction, Message,
+};
+use crate::data::storage::StorageBackend;
+use crate::error_messages::ERROR_ARCHIVE_VERSION;
+use crate::{Client, EngineError};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+pub const ARCHIVE_VERSION: u32 = 1;
+
+// largest page every backend returns
+const PAGE_SIZE: u32 = 25;
+
+fn get_conversations(
+    client: &Client,
+    storage: &mut dyn StorageBackend,
+) -> Result<Vec<Conversation>, EngineError> {
+    let mut conversations = vec![];
+    let mut page = 1;
+
+    loop {
+        let paginated = storage.get_client_conversations(client, Some(PAGE_SIZE), Some(page))?;
+        conversations.extend(paginated.data);
+
+        match paginated.pagination {
+            Some(pagination) => page = pagination.page + 1,
+            None => return Ok(conversations),
+        }
+    }
+}
+
+fn get_messages(
+    client: &Client,
+    storage: &mut dyn StorageBackend,
+) -> Result<Vec<Message>, EngineError> {
+    let mut messages = vec![];
+    let mut page = 1;
+
+    loop {
+        let filter = ClientMessageFilter::builder()
+            .client(client)
+            .limit(PAGE_SIZE)
+            .pagination_key(page)
+            .build();
+        let paginated = storage.get_client_messages(filter)?;
+        messages.extend(paginated.data);
+
+        match paginated.pagination {
+            Some(pagination) => page = pagination.page + 1,
+            None => return Ok(messages),
+        }
+    }
+}
+
+fn export_client_data(
+    client: &Client,
+    storage: &mut dyn StorageBackend,
+) -> Result<ClientArchive, EngineError> {
+    let mut conversations = get_conversations(client, storage)?;
+    conversations.sort_by_key(|conversation| conversation.created_at);
+
+    let mut messages = get_messages(client, storage)?;
+    sort_messages(&mut messages, &conversations);
+
+    let memories = match storage.internal_use_get_memories(client)? {
+        serde_json::Value::Object(memories) => memories,
+        _ => serde_json::Map::new(),
+    };
+
+    let mut state = storage.get_client_state(client)?;
+    state.sort_by(|a, b| (&a._type, &a.key).cmp(&(&b._type, &b.key)));
+
+    Ok(ClientArchive {
+        client: client.to_owned(),
+        conversations,
+        messages,
+        memories,
+        state,
+    })
+}
+
+/**
+ * Messages in the order they were said: by conversation, interaction,
+ * with the messages received before the answers of the bot
+ */
+fn sort_messages(messages: &mut [Message], conversations: &[Conversation]) {
+    let positions: HashMap<Uuid, usize> = conversations
+        .iter()
+        .enumerate()
+        .map(|(position, conversation)| (conversation.id, position))
+        .collect();
+
+    messages.sort_by_key(|message| {
+        (
+            positions.get(&message.conversation_id).copied(),
+            message.interaction_order,
+            message.direction == Direction::Send,
+            message.message_order,
+        )
+    });
+}
+
+pub fn export_clients(
+    clients: &[Client],
+    db: &mut crate::Database,
+) -> Result<Archive, EngineError> {
+    let storage = db.storage()?;
+
+    let clients = clients
+        .iter()
+        .map(|client| export_client_data(client, storage))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Archive {
+        version: ARCHIVE_VERSION,
+        exported_at: chrono::Utc::now(),
+        clients,
+    })
+}
+
+pub fn get_bot_clients(bot_id: &str, db: &mut crate::Database) -> Result<Vec<Client>, EngineError> {
+    db.storage()?.get_bot_clients(bot_id)
+}
+
+/**
+ * Check that the archive of a client can be imported as it is, before any data is replaced
+ */
+fn check_client_archive(archive: &ClientArchive) -> Result<(), EngineError> {
+    let conversation_ids: HashSet<Uuid> = archive
+        .conversations
+        .iter()
+        .map(|conversation| conversation.id)
+        .collect();
+
+    match archive
+        .messages
+        .iter()
+        .find(|message| !conversation_ids.contains(&message.conversation_id))
+    {
+        Some(message) => Err(EngineError::Manager(format!(
+            "message {} belongs to conversation {} which is not in the archive",
+            message.id, message.conversation_id
+        ))),
+        None => Ok(()),
+    }
+}
+
+/**
+ * Conversations and messages are saved with their ids and dates, with the `*_records`
+ * methods that not every backend implements: check them before any data is replaced
+ */
+fn check_records_support(storage: &mut dyn StorageBackend) -> Result<(), EngineError> {
+    storage.add_conversation_records(&[])?;
+    storage.add_message_records(&[])?;
+
+    Ok(())
+}
+
+fn delete_client_data(
+    client: &Client,
+    storage: &mut dyn StorageBackend,
+) -> Result<(), EngineError> {
+    storage.delete_client_memories(client)?;
+    storage.delete_client_messages(client)?;
+    storage.delete_client_conversations(client)?;
+    storage.delete_client_state(client)
+}
+
+/**
+ * Replace the data of the client with the one of the archive
+ */
+fn import_client_data(
+    archive: &ClientArchive,
+    storage: &mut dyn StorageBackend,
+    ttl: Option<chrono::Duration>,
+    imported: &mut ArchiveImport,
+) -> Result<(), EngineError> {
+    let client = &archive.client;
+    let expires_at = ttl.map(|ttl| chrono::Utc::now() + ttl);
+
+    delete_client_data(client, storage)?;
+
+    let mut conversations: Vec<Conversation> = archive
+        .conversations
+        .iter()
+        .map(|conversation| Conversation {
+            client: client.to_owned(),
+            expires_at,
+            ..conversation.to_owned()
+        })
+        .collect();
+    conversations.sort_by_key(|conversation| conversation.created_at);
+
+    // records whose id already exists are skipped by the backend
+    let created = storage.add_conversation_records(&conversations)?;
+    if created != conversations.len() {
+        return Err(EngineError::Manager(format!(
+            "{} conversations of the archive already exist in the database",
+            conversations.len() - created
+        )));
+    }
+
+    let mut messages: Vec<Message> = archive
+        .messages
+        .iter()
+        .map(|message| Message {
+            expires_at,
+            ..message.to_owned()
+        })
+        .collect();
+    sort_messages(&mut messages, &conversations);
+
+    let created = storage.add_message_records(&messages)?;
+    if created != messages.len() {
+        return Err(EngineError::Manager(format!(
+            "{} messages of the archive already exist in the database",
+            messages.len() - created
+        )));
+    }
+
+    for (key, value) in archive.memories.iter() {
+        storage.create_client_memory(client, key, value, ttl)?;
+    }
+
+    for item in archive.state.iter() {
+        storage.set_state_items(client, &item._type, vec![(&item.key, &item.value)], ttl)?;
+    }
+
+    imported.clients += 1;
+    imported.conversations += conversations.len();
+    imported.messages += messages.len();
+    imported.memories += archive.memories.len();
+
+    Ok(())
+}
+
+/**
+ * Import the clients of the archive one by one. If one of them fails, the data of the
+ * clients replaced so far is restored from the backups made before the import.
+ */
+pub fn import_archive(
+    archive: &Archive,
+    ttl: Option<chrono::Duration>,
+    db: &mut crate::Database,
+) -> Result<ArchiveImport, EngineError> {
+    if archive.version != ARCHIVE_VERSION {
+        return Err(EngineError::Manager(format!(
+            "{} {}",
+            ERROR_ARCHIVE_VERSION, archive.version
+        )));
+    }
+
+    let storage = db.storage()?;
+    for client_archive in archive.clients.iter() {
+        check_client_archive(client_archive)?;
+    }
+    check_records_support(storage)?;
+
+    let backups = archive
+        .clients
+        .iter()
+        .map(|client_archive| export_client_data(&client_archive.client, storage))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut imported = ArchiveImport::default();
+    for (position, client_archive) in archive.clients.iter().enumerate() {
+        if let Err(err) = import_client_data(client_archive, storage, ttl, &mut imported) {
+            for backup in backups[..=position].iter() {
+                if let Err(restore_err) =
+                    import_client_data(backup, storage, ttl, &mut ArchiveImport::default())
+                {
+                    return Err(EngineError::Manager(format!(
+                        "import failed: {:?}, and client {:?} could not be restored: {:?}",
+                        err, backup.client, restore_err
+                    )));
+                }
+            }
+
+            return Err(err);
+        }
+    }
+
+    Ok(imported)
+}
@@ -0,0 +1,284 @@
+/**
+ * Process-wide cache of parsed bot ASTs.
+ *
+ * Validating and folding a bot is by far the most expensive part of a request, and
+ * its result only depends on the bot's content: the cache is keyed by a hash of the
+ * flows, resolved modules, components and default flow of the bot, so that every stored
+ * version (whether it is reached with `BotOpt::Id` or `BotOpt::BotId`) is only parsed
+ * once per process.
+ *
+ * The cache holds at most `AST_CACHE_SIZE` bots (64 by default, 0 disables it) and
+ * evicts the least recently used one when full.
+ */
+use crate::CsmlBot;
+
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+const DEFAULT_AST_CACHE_SIZE: usize = 64;
+
+static AST_CACHE: OnceLock<Mutex<AstCache>> = OnceLock::new();
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AstCacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+struct AstCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (u64, String)>,
+}
+
+impl AstCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<String> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        self.entries.get_mut(key).map(|(last_used, ast)| {
+            *last_used = tick;
+            ast.to_owned()
+        })
+    }
+
+    fn insert(&mut self, key: String, ast: String) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (last_used, _))| *last_used)
+                .map(|(key, _)| key.to_owned());
+
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.tick += 1;
+        self.entries.insert(key, (self.tick, ast));
+    }
+}
+
+fn get_capacity() -> usize {
+    match env::var("AST_CACHE_SIZE") {
+        Ok(size) => size.parse::<usize>().unwrap_or(DEFAULT_AST_CACHE_SIZE),
+        Err(_) => DEFAULT_AST_CACHE_SIZE,
+    }
+}
+
+fn cache() -> &'static Mutex<AstCache> {
+    AST_CACHE.get_or_init(|| Mutex::new(AstCache::new(get_capacity())))
+}
+
+/**
+ * Add a field to the hash, prefixed with its length so that the boundaries between fields
+ * are part of the hash
+ */
+fn update_field(hash: &mut Md5, field: &[u8]) {
+    hash.update((field.len() as u64).to_le_bytes());
+    hash.update(field);
+}
+
+fn update_json(hash: &mut Md5, value: Option<impl Serialize>) {
+    let value = serde_json::to_vec(&value).unwrap_or_default();
+
+    update_field(hash, &value);
+}
+
+/**
+ * Hash everything the AST of a bot depends on. Modules must already be downloaded, so that
+ * each one is identified by the content of the version it was resolved to.
+ */
+pub(crate) fn bot_hash(bot: &CsmlBot) -> String {
+    let mut hash = Md5::new();
+
+    update_field(&mut hash, bot.id.as_bytes());
+    update_field(&mut hash, bot.default_flow.as_bytes());
+    update_json(&mut hash, bot.native_components.as_ref());
+    update_json(&mut hash, bot.custom_components.as_ref());
+
+    update_field(&mut hash, &(bot.flows.len() as u64).to_le_bytes());
+    for flow in bot.flows.iter() {
+        update_field(&mut hash, flow.id.as_bytes());
+        update_field(&mut hash, flow.name.as_bytes());
+        update_field(&mut hash, flow.content.as_bytes());
+    }
+
+    let modules = bot.modules.as_deref().unwrap_or_default();
+    update_field(&mut hash, &(modules.len() as u64).to_le_bytes());
+    for module in modules.iter() {
+        update_field(&mut hash, module.name.as_bytes());
+
+        if let Some(flow) = &module.flow {
+            update_field(&mut hash, flow.name.as_bytes());
+            update_field(&mut hash, flow.content.as_bytes());
+        }
+    }
+
+    format!("{:x}", hash.finalize())
+}
+
+/**
+ * Return the cached AST of the bot matching this hash, if any
+ */
+pub(crate) fn get_bot_ast(key: &str) -> Option<String> {
+    let ast = match cache().lock() {
+        Ok(mut cache) => cache.get(key),
+        Err(_) => None,
+    };
+
+    match ast {
+        Some(_) => HITS.fetch_add(1, Ordering::Relaxed),
+        None => MISSES.fetch_add(1, Ordering::Relaxed),
+    };
+
+    ast
+}
+
+pub(crate) fn set_bot_ast(key: String, ast: String) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.insert(key, ast);
+    }
+}
+
+/**
+ * Return the hit/miss counters and current size of the AST cache
+ */
+pub fn ast_cache_metrics() -> AstCacheMetrics {
+    let (entries, capacity) = match cache().lock() {
+        Ok(cache) => (cache.entries.len(), cache.capacity),
+        Err(_) => (0, 0),
+    };
+
+    AstCacheMetrics {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        entries,
+        capacity,
+    }
+}
+
+/**
+ * Remove every cached AST. Hit/miss counters are kept.
+ */
+pub fn clear_ast_cache() {
+    if let Ok(mut cache) = cache().lock() {
+        cache.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csml_interpreter::data::{CsmlFlow, Module};
+
+    fn flow(name: &str, content: &str) -> CsmlFlow {
+        CsmlFlow {
+            id: name.to_owned(),
+            name: name.to_owned(),
+            content: content.to_owned(),
+            commands: vec![],
+            priority: 0,
+        }
+    }
+
+    fn bot(flows: Vec<CsmlFlow>) -> CsmlBot {
+        CsmlBot {
+            id: "cache_bot".to_owned(),
+            name: "bot".to_owned(),
+            apps_endpoint: None,
+            flows,
+            native_components: None,
+            custom_components: None,
+            default_flow: "Default".to_owned(),
+            bot_ast: None,
+            no_interruption_delay: None,
+            env: None,
+            modules: None,
+            multibot: None,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
+        }
+    }
+
+    #[test]
+    fn hash_separates_fields() {
+        let a = bot(vec![flow("Default", "start: goto end")]);
+        let b = bot(vec![flow("Defaultstart", ": goto end")]);
+
+        assert_ne!(bot_hash(&a), bot_hash(&b));
+    }
+
+    #[test]
+    fn hash_depends_on_components_and_modules() {
+        let mut bot = bot(vec![flow("Default", "start: goto end")]);
+        let hash = bot_hash(&bot);
+        assert_eq!(bot_hash(&bot.clone()), hash);
+
+        bot.custom_components = Some(serde_json::json!({"Custom": {}}));
+        let with_components = bot_hash(&bot);
+        assert_ne!(with_components, hash);
+
+        // two versions of a module resolved from the same range
+        let module = |content: &str| Module {
+            name: "utils".to_owned(),
+            url: None,
+            auth: None,
+            version: "^1.0".to_owned(),
+            integrity: None,
+            flow: Some(flow("utils", content)),
+        };
+        bot.modules = Some(vec![module("start: goto end")]);
+        let first_version = bot_hash(&bot);
+        bot.modules = Some(vec![module("start: say \"v1.1\" goto end")]);
+
+        assert_ne!(first_version, with_components);
+        assert_ne!(bot_hash(&bot), first_version);
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = AstCache::new(2);
+
+        cache.insert("a".to_owned(), "ast_a".to_owned());
+        cache.insert("b".to_owned(), "ast_b".to_owned());
+        assert_eq!(cache.get("a"), Some("ast_a".to_owned()));
+
+        cache.insert("c".to_owned(), "ast_c".to_owned());
+        assert_eq!(cache.entries.len(), 2);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some("ast_a".to_owned()));
+        assert_eq!(cache.get("c"), Some("ast_c".to_owned()));
+    }
+
+    #[test]
+    fn zero_capacity_disables_cache() {
+        let mut cache = AstCache::new(0);
+
+        cache.insert("a".to_owned(), "ast_a".to_owned());
+        assert_eq!(cache.get("a"), None);
+    }
+}
@@ -3,19 +3,26 @@ pub mod models;
 pub mod filter;
 #[cfg(feature = "async")]
 pub mod future;
+pub mod storage;
 pub mod sync;
 
+use crate::data::models::BotReference;
 use crate::{
     encrypt::{decrypt_data, encrypt_data},
+    error_messages::ERROR_DB_SETUP,
     Client, Context,
 };
-use csml_interpreter::data::{CsmlBot, CsmlFlow, Message, Module};
+use csml_interpreter::data::{
+    CsmlBot, CsmlFlow, Interruption, Message, Module, TraceEvent, TriggerMatcherConfig,
+};
 #[cfg(feature = "pooled")]
 use diesel::r2d2::{ConnectionManager, PooledConnection, R2D2Connection};
 #[cfg(any(feature = "postgresql", feature = "sqlite"))]
 use serde::de::StdError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::mpsc;
+use storage::StorageBackend;
 use uuid::Uuid;
 
 pub const DEBUG: &str = "DEBUG";
@@ -34,6 +41,18 @@ pub struct SerializeCsmlBot {
     pub no_interruption_delay: Option<i32>,
     pub env: Option<String>,
     pub modules: Option<Vec<Module>>,
+    // compiled BotArtifact, absent from versions created before it existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot_ast: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_flow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interruptions: Option<Vec<Interruption>>,
+    // encrypted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_matcher: Option<TriggerMatcherConfig>,
 }
 
 /**
@@ -47,7 +66,7 @@ pub struct SerializeCsmlBot {
 pub struct CsmlBotBincode {
     pub id: String,
     pub name: String,
-    pub flows: Vec<CsmlFlow>,
+    pub flows: Vec<CsmlFlowBincode>,
     pub native_components: Option<String>,
     // serde_json::Map<String, serde_json::Value>
     pub custom_components: Option<String>,
@@ -55,22 +74,53 @@ pub struct CsmlBotBincode {
     pub default_flow: String,
 }
 
+// bincode is not self-describing: flows must keep the fields they were encoded with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CsmlFlowBincode {
+    pub id: String,
+    pub name: String,
+    pub content: String,
+    pub commands: Vec<String>,
+}
+
 impl CsmlBotBincode {
     pub fn to_bot(self) -> SerializeCsmlBot {
         SerializeCsmlBot {
             id: self.id,
             name: self.name,
-            flows: self.flows,
+            flows: self
+                .flows
+                .into_iter()
+                .map(|flow| CsmlFlow::new(&flow.id, &flow.name, &flow.content, flow.commands))
+                .collect(),
             native_components: self.native_components,
             custom_components: self.custom_components,
             default_flow: self.default_flow,
             no_interruption_delay: None,
             env: None,
             modules: None,
+            bot_ast: None,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
         }
     }
 }
 
+fn encrypt_callback_secret(secret: &Option<String>) -> Option<String> {
+    secret
+        .as_ref()
+        .and_then(|secret| encrypt_data(&serde_json::json!(secret)).ok())
+}
+
+fn decrypt_callback_secret(secret: &Option<String>) -> Option<String> {
+    match secret.to_owned().map(decrypt_data) {
+        Some(Ok(serde_json::Value::String(secret))) => Some(secret),
+        _ => None,
+    }
+}
+
 pub fn to_serializable_bot(bot: &CsmlBot) -> SerializeCsmlBot {
     SerializeCsmlBot {
         id: bot.id.to_owned(),
@@ -93,6 +143,11 @@ pub fn to_serializable_bot(bot: &CsmlBot) -> SerializeCsmlBot {
             None => None,
         },
         modules: bot.modules.to_owned(),
+        bot_ast: bot.bot_ast.to_owned(),
+        fallback_flow: bot.fallback_flow.to_owned(),
+        interruptions: bot.interruptions.to_owned(),
+        callback_secret: encrypt_callback_secret(&bot.callback_secret),
+        trigger_matcher: bot.trigger_matcher.to_owned(),
     }
 }
 
@@ -122,7 +177,7 @@ impl SerializeCsmlBot {
                 }
             },
             default_flow: self.default_flow.to_owned(),
-            bot_ast: None,
+            bot_ast: self.bot_ast.to_owned(),
             no_interruption_delay: self.no_interruption_delay,
             env: match self.env.to_owned() {
                 Some(value) => decrypt_data(value).ok(),
@@ -130,6 +185,10 @@ impl SerializeCsmlBot {
             },
             modules: self.modules.to_owned(),
             multibot: None,
+            fallback_flow: self.fallback_flow.to_owned(),
+            interruptions: self.interruptions.to_owned(),
+            callback_secret: decrypt_callback_secret(&self.callback_secret),
+            trigger_matcher: self.trigger_matcher.to_owned(),
         }
     }
 }
@@ -142,6 +201,15 @@ pub struct DynamoBot {
     pub default_flow: String,
     pub no_interruption_delay: Option<i32>,
     pub env: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_flow: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interruptions: Option<Vec<Interruption>>,
+    // encrypted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_matcher: Option<TriggerMatcherConfig>,
 }
 
 /**
@@ -168,6 +236,10 @@ impl DynamoBotBincode {
             default_flow: self.default_flow,
             no_interruption_delay: None,
             env: None,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
         }
     }
 }
@@ -186,6 +258,10 @@ pub fn to_dynamo_bot(csml_bot: &CsmlBot) -> DynamoBot {
             Some(value) => encrypt_data(value).ok(),
             None => None,
         },
+        fallback_flow: csml_bot.fallback_flow.to_owned(),
+        interruptions: csml_bot.interruptions.to_owned(),
+        callback_secret: encrypt_callback_secret(&csml_bot.callback_secret),
+        trigger_matcher: csml_bot.trigger_matcher.to_owned(),
     }
 }
 
@@ -215,6 +291,10 @@ impl DynamoBot {
             },
             modules: Some(modules),
             multibot: None,
+            fallback_flow: self.fallback_flow.to_owned(),
+            interruptions: self.interruptions.to_owned(),
+            callback_secret: decrypt_callback_secret(&self.callback_secret),
+            trigger_matcher: self.trigger_matcher.to_owned(),
         }
     }
 }
@@ -263,14 +343,112 @@ pub enum Database<'a> {
     Postgresql(PostgresqlClient<'a>),
     #[cfg(feature = "sqlite")]
     SqLite(SqliteClient<'a>),
+    #[cfg(feature = "memory")]
+    Memory(MemoryClient),
+    Custom(Box<dyn StorageBackend + 'a>),
     None(std::marker::PhantomData<&'a ()>),
 }
 
+impl<'a> Database<'a> {
+    /**
+     * Use any implementation of `StorageBackend` as the engine database
+     */
+    pub fn custom<S: StorageBackend + 'a>(storage: S) -> Self {
+        Database::Custom(Box::new(storage))
+    }
+
+    pub fn storage(&mut self) -> Result<&mut (dyn StorageBackend + 'a), EngineError> {
+        match self {
+            #[cfg(feature = "mongo")]
+            Database::Mongo(db) => Ok(db),
+            #[cfg(feature = "dynamo")]
+            Database::Dynamodb(db) => Ok(db),
+            #[cfg(feature = "postgresql")]
+            Database::Postgresql(db) => Ok(db),
+            #[cfg(feature = "sqlite")]
+            Database::SqLite(db) => Ok(db),
+            #[cfg(feature = "memory")]
+            Database::Memory(db) => Ok(db),
+            Database::Custom(db) => Ok(db.as_mut()),
+            Database::None(_) => Err(EngineError::Manager(ERROR_DB_SETUP.to_owned())),
+        }
+    }
+
+    /**
+     * Take ownership of the storage of the database
+     */
+    pub fn into_storage(self) -> Result<Box<dyn StorageBackend + 'a>, EngineError> {
+        match self {
+            #[cfg(feature = "mongo")]
+            Database::Mongo(db) => Ok(Box::new(db)),
+            #[cfg(feature = "dynamo")]
+            Database::Dynamodb(db) => Ok(Box::new(db)),
+            #[cfg(feature = "postgresql")]
+            Database::Postgresql(db) => Ok(Box::new(db)),
+            #[cfg(feature = "sqlite")]
+            Database::SqLite(db) => Ok(Box::new(db)),
+            #[cfg(feature = "memory")]
+            Database::Memory(db) => Ok(Box::new(db)),
+            Database::Custom(db) => Ok(db),
+            Database::None(_) => Err(EngineError::Manager(ERROR_DB_SETUP.to_owned())),
+        }
+    }
+
+    /**
+     * Name of the database in the metrics and traces of its calls
+     */
+    pub fn backend(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "mongo")]
+            Database::Mongo(_) => "mongodb",
+            #[cfg(feature = "dynamo")]
+            Database::Dynamodb(_) => "dynamodb",
+            #[cfg(feature = "postgresql")]
+            Database::Postgresql(_) => "postgresql",
+            #[cfg(feature = "sqlite")]
+            Database::SqLite(_) => "sqlite",
+            #[cfg(feature = "memory")]
+            Database::Memory(_) => "memory",
+            Database::Custom(_) => "custom",
+            Database::None(_) => "none",
+        }
+    }
+}
+
+impl<'a, S: StorageBackend + 'a> From<S> for Database<'a> {
+    fn from(storage: S) -> Self {
+        Database::custom(storage)
+    }
+}
+
 #[cfg(feature = "async")]
 #[non_exhaustive]
 pub enum AsyncDatabase<'a> {
     #[cfg(feature = "postgresql-async")]
     Postgresql(AsyncPostgresqlClient<'a>),
+    #[cfg(feature = "sqlite-async")]
+    SqLite(AsyncSqliteClient),
+    #[cfg(feature = "mongo-async")]
+    Mongo(AsyncMongoDbClient),
+    None(std::marker::PhantomData<&'a ()>),
+}
+
+#[cfg(feature = "async")]
+impl<'a> AsyncDatabase<'a> {
+    /**
+     * Name of the database in the metrics and traces of its calls
+     */
+    pub fn backend(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "postgresql-async")]
+            AsyncDatabase::Postgresql(_) => "postgresql",
+            #[cfg(feature = "sqlite-async")]
+            AsyncDatabase::SqLite(_) => "sqlite",
+            #[cfg(feature = "mongo-async")]
+            AsyncDatabase::Mongo(_) => "mongodb",
+            AsyncDatabase::None(_) => "none",
+        }
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -301,6 +479,65 @@ impl PostgresqlClient<'static> {
     }
 }
 
+/**
+ * In-process storage. All the clients created with `new()` share the same store for
+ * the whole lifetime of the process, while `isolated()` creates a new empty store
+ * that is only shared with the clones of the returned client.
+ */
+#[cfg(feature = "memory")]
+#[derive(Clone)]
+pub struct MemoryClient {
+    pub(crate) store:
+        std::sync::Arc<std::sync::Mutex<crate::db_connectors::memory::models::MemoryStore>>,
+}
+
+#[cfg(feature = "memory")]
+impl MemoryClient {
+    pub fn new() -> Self {
+        Self {
+            store: crate::db_connectors::memory::shared_store(),
+        }
+    }
+
+    pub fn isolated() -> Self {
+        Self {
+            store: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "memory")]
+impl Default for MemoryClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+ * Redis connection used to store conversations, memories and state, see `SplitStorage`.
+ * All the keys written by the engine start with `prefix`.
+ */
+#[cfg(feature = "redis")]
+pub struct RedisClient {
+    pub client: redis::Connection,
+    pub prefix: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisClient {
+    pub fn new(client: redis::Connection) -> Self {
+        Self {
+            client,
+            prefix: "csml".to_owned(),
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_owned();
+        self
+    }
+}
+
 #[cfg(feature = "mongo")]
 pub struct MongoDbClient {
     pub client: mongodb::sync::Database,
@@ -313,6 +550,19 @@ impl MongoDbClient {
     }
 }
 
+#[cfg(feature = "mongo-async")]
+#[derive(Clone)]
+pub struct AsyncMongoDbClient {
+    pub client: mongodb::Database,
+}
+
+#[cfg(feature = "mongo-async")]
+impl AsyncMongoDbClient {
+    pub fn new(client: mongodb::Database) -> Self {
+        Self { client }
+    }
+}
+
 /**
  * Dynamodb runs in async by default and returns futures, that need to be awaited on.
  * The proper way to do it is by using tokio's runtime::block_on(). It is however quite costly
@@ -350,6 +600,25 @@ impl AsyncPostgresqlClient<'static> {
     }
 }
 
+/**
+ * SQLite has no async driver: the connection is shared with the blocking threads
+ * of the tokio runtime, where the queries of the sqlite connector are run.
+ */
+#[cfg(feature = "sqlite-async")]
+#[derive(Clone)]
+pub struct AsyncSqliteClient {
+    pub client: std::sync::Arc<std::sync::Mutex<diesel::prelude::SqliteConnection>>,
+}
+
+#[cfg(feature = "sqlite-async")]
+impl AsyncSqliteClient {
+    pub fn new(client: diesel::prelude::SqliteConnection) -> Self {
+        Self {
+            client: std::sync::Arc::new(std::sync::Mutex::new(client)),
+        }
+    }
+}
+
 pub struct ConversationInfo<'a> {
     pub request_id: String,
     pub conversation_id: Uuid,
@@ -360,6 +629,15 @@ pub struct ConversationInfo<'a> {
     pub messages: Vec<Message>,
     pub ttl: Option<chrono::Duration>,
     pub low_data: bool,
+    pub trace: Option<Vec<TraceEvent>>,
+    pub callback_secret: Option<String>,
+    // set once a message could not be delivered, or if older ones are still in the outbox:
+    // the next ones go straight to the outbox
+    pub callback_failed: bool,
+    // receives each message as it is sent, for streamed requests
+    pub stream: Option<mpsc::Sender<Value>>,
+    // bot of the request, saved with the schedules it plans
+    pub bot_reference: BotReference,
     pub db: Database<'a>,
 }
 
@@ -374,6 +652,15 @@ pub struct AsyncConversationInfo<'a> {
     pub messages: Vec<Message>,
     pub ttl: Option<chrono::Duration>,
     pub low_data: bool,
+    pub trace: Option<Vec<TraceEvent>>,
+    pub callback_secret: Option<String>,
+    // set once a message could not be delivered, or if older ones are still in the outbox:
+    // the next ones go straight to the outbox
+    pub callback_failed: bool,
+    // receives each message as it is sent, for streamed requests
+    pub stream: Option<mpsc::Sender<Value>>,
+    // bot of the request, saved with the schedules it plans
+    pub bot_reference: BotReference,
     pub db: AsyncDatabase<'a>,
 }
 
@@ -394,6 +681,8 @@ pub enum EngineError {
     Utf8(std::str::Utf8Error),
     Manager(String),
     Format(String),
+    // a rate limit or quota is reached, see quotas.rs
+    RateLimit(String),
     Interpreter(String),
     DateTimeError(String),
     Parring(String),
@@ -405,11 +694,11 @@ pub enum EngineError {
     Base64(base64::DecodeError),
     UUID(uuid::Error),
 
-    #[cfg(feature = "mongo")]
+    #[cfg(any(feature = "mongo", feature = "mongo-async"))]
     BsonDecoder(bson::de::Error),
-    #[cfg(feature = "mongo")]
+    #[cfg(any(feature = "mongo", feature = "mongo-async"))]
     BsonEncoder(bson::ser::Error),
-    #[cfg(feature = "mongo")]
+    #[cfg(any(feature = "mongo", feature = "mongo-async"))]
     MongoDB(mongodb::error::Error),
 
     #[cfg(feature = "dynamo")]
@@ -419,6 +708,9 @@ pub enum EngineError {
     #[cfg(feature = "dynamo")]
     S3ErrorCode(u16),
 
+    #[cfg(feature = "redis")]
+    Redis(redis::RedisError),
+
     #[cfg(any(feature = "postgresql", feature = "sqlite"))]
     SqlErrorCode(String),
     #[cfg(any(feature = "postgresql", feature = "sqlite"))]
@@ -475,27 +767,34 @@ impl From<base64::DecodeError> for EngineError {
     }
 }
 
-#[cfg(feature = "mongo")]
+#[cfg(any(feature = "mongo", feature = "mongo-async"))]
 impl From<bson::de::Error> for EngineError {
     fn from(e: bson::de::Error) -> Self {
         EngineError::BsonDecoder(e)
     }
 }
 
-#[cfg(feature = "mongo")]
+#[cfg(any(feature = "mongo", feature = "mongo-async"))]
 impl From<bson::ser::Error> for EngineError {
     fn from(e: bson::ser::Error) -> Self {
         EngineError::BsonEncoder(e)
     }
 }
 
-#[cfg(feature = "mongo")]
+#[cfg(any(feature = "mongo", feature = "mongo-async"))]
 impl From<mongodb::error::Error> for EngineError {
     fn from(e: mongodb::error::Error) -> Self {
         EngineError::MongoDB(e)
     }
 }
 
+#[cfg(feature = "redis")]
+impl From<redis::RedisError> for EngineError {
+    fn from(e: redis::RedisError) -> Self {
+        EngineError::Redis(e)
+    }
+}
+
 #[cfg(feature = "dynamo")]
 impl<E: std::error::Error + 'static> From<rusoto_core::RusotoError<E>> for EngineError {
     fn from(e: rusoto_core::RusotoError<E>) -> Self {
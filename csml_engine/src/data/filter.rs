@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use csml_interpreter::data::Client;
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
@@ -17,6 +18,18 @@ pub struct ClientMessageFilter<'a> {
     pub conversation_id: Option<Uuid>,
 }
 
+/**
+ * Conversations of a bot created between `from_date` (included) and `to_date` (excluded).
+ * Open conversations without any activity since `abandoned_before` are abandoned.
+ */
+#[derive(TypedBuilder, Debug, Clone)]
+pub struct AnalyticsFilter<'a> {
+    pub bot_id: &'a str,
+    pub from_date: DateTime<Utc>,
+    pub to_date: DateTime<Utc>,
+    pub abandoned_before: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
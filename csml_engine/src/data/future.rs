@@ -1,5 +1,6 @@
 use crate::data::models::BotOpt;
 use crate::data::{AsyncDatabase, EngineError};
+use crate::encrypt::verify_client_artifact;
 use crate::future::db_connectors;
 use csml_interpreter::data::CsmlBot;
 
@@ -9,7 +10,7 @@ impl BotOpt {
         db: &mut AsyncDatabase<'_>,
     ) -> Result<CsmlBot, EngineError> {
         match self {
-            BotOpt::CsmlBot(csml_bot) => Ok(csml_bot.to_owned()),
+            BotOpt::CsmlBot(csml_bot) => Ok(verify_client_artifact(csml_bot.to_owned())),
             BotOpt::BotId {
                 bot_id,
                 apps_endpoint,
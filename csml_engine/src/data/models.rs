@@ -1,5 +1,5 @@
 use crate::data::EngineError;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, LocalResult, TimeZone, Utc};
 use csml_interpreter::data::{Client, CsmlBot, MultiBot};
 use serde_derive::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -87,6 +87,87 @@ pub enum BotOpt {
     },
 }
 
+impl BotOpt {
+    /**
+     * Id of the bot that will run, the client of the request is saved with it
+     */
+    pub fn bot_id(&self) -> &str {
+        match self {
+            BotOpt::CsmlBot(csml_bot) => &csml_bot.id,
+            BotOpt::Id { bot_id, .. } | BotOpt::BotId { bot_id, .. } => bot_id,
+        }
+    }
+
+    /**
+     * Reference to the bot, to run it again later without the bot itself
+     */
+    pub fn to_reference(&self) -> BotReference {
+        match self {
+            BotOpt::CsmlBot(csml_bot) => BotReference {
+                version_id: None,
+                apps_endpoint: csml_bot.apps_endpoint.to_owned(),
+                multibot: csml_bot.multibot.to_owned(),
+            },
+            BotOpt::Id {
+                version_id,
+                apps_endpoint,
+                multibot,
+                ..
+            } => BotReference {
+                version_id: Some(version_id.to_owned()),
+                apps_endpoint: apps_endpoint.to_owned(),
+                multibot: multibot.to_owned(),
+            },
+            BotOpt::BotId {
+                apps_endpoint,
+                multibot,
+                ..
+            } => BotReference {
+                version_id: None,
+                apps_endpoint: apps_endpoint.to_owned(),
+                multibot: multibot.to_owned(),
+            },
+        }
+    }
+}
+
+/**
+ * Bot of a request, saved with the schedules it plans to run them with the same bot:
+ * the version of the request if it was given one, otherwise the last version of the bot.
+ * A bot sent inline with the request is not saved, its last version is used instead.
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct BotReference {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apps_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multibot: Option<Vec<MultiBot>>,
+}
+
+impl BotReference {
+    pub fn to_bot_opt(&self, bot_id: &str) -> BotOpt {
+        let bot_id = bot_id.to_owned();
+        let apps_endpoint = self.apps_endpoint.to_owned();
+        let multibot = self.multibot.to_owned();
+
+        match &self.version_id {
+            Some(version_id) => BotOpt::Id {
+                version_id: version_id.to_owned(),
+                bot_id,
+                apps_endpoint,
+                multibot,
+            },
+            None => BotOpt::BotId {
+                bot_id,
+                apps_endpoint,
+                multibot,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CsmlRequest {
     pub request_id: String,
@@ -97,6 +178,12 @@ pub struct CsmlRequest {
     pub step_limit: Option<usize>,
     pub ttl_duration: Option<serde_json::Value>,
     pub low_data_mode: Option<serde_json::Value>,
+    // return the execution trace of the request along with its messages
+    #[serde(default)]
+    pub debug: bool,
+    // seed of the random builtins (random(), shuffle(), one_of()), for reproducible runs
+    #[serde(default)]
+    pub random_seed: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -117,6 +204,457 @@ pub struct Conversation {
     pub expires_at: Option<DateTime<Utc>>,
 }
 
+/**
+ * Flow trigger planned for a client with the `Schedule()` builtin.
+ * Once `run_at` is passed, `run_due_schedules` runs it as a `flow_trigger` event, with the
+ * bot of the request that created it, and sends the messages to its `callback_url`.
+ * A run that fails is tried again later, up to `SCHEDULE_MAX_ATTEMPTS` runs in all.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Schedule {
+    pub id: Uuid,
+
+    pub client: Client,
+
+    pub flow_id: String,
+    pub step_id: String,
+    pub callback_url: Option<String>,
+    #[serde(default)]
+    pub bot: BotReference,
+
+    // number of runs started, each one moving `run_at` to the time of the next try
+    #[serde(default)]
+    pub attempts: i32,
+    pub run_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/**
+ * Number of runs of a schedule before it is dropped, if none of them succeeds
+ */
+pub const SCHEDULE_MAX_ATTEMPTS: i32 = 5;
+
+/**
+ * Delay in seconds before a schedule is run again after its first try, doubled after each try
+ */
+const SCHEDULE_RETRY_DELAY: i64 = 60;
+
+impl Schedule {
+    pub fn new(
+        client: &Client,
+        schedule: &csml_interpreter::data::Schedule,
+        callback_url: Option<String>,
+        bot: BotReference,
+    ) -> Result<Self, EngineError> {
+        let run_at = match Utc.timestamp_millis_opt(schedule.at) {
+            LocalResult::Single(run_at) => run_at,
+            _ => {
+                return Err(EngineError::DateTimeError(
+                    "Schedule date time is out of range".to_owned(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            client: client.to_owned(),
+            flow_id: schedule.flow.to_owned(),
+            step_id: schedule.step.to_owned(),
+            callback_url,
+            bot,
+            attempts: 0,
+            run_at,
+            created_at: Utc::now(),
+        })
+    }
+
+    /**
+     * When the schedule is run again if the run starting `now` fails
+     */
+    pub fn retry_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + chrono::Duration::seconds(SCHEDULE_RETRY_DELAY << self.attempts.clamp(0, 16))
+    }
+
+    /**
+     * The `flow_trigger` request running the schedule, with the bot that planned it
+     */
+    pub fn to_request(&self) -> (CsmlRequest, BotOpt) {
+        let request = CsmlRequest {
+            request_id: Uuid::new_v4().to_string(),
+            client: self.client.to_owned(),
+            callback_url: self.callback_url.to_owned(),
+            payload: serde_json::json!({
+                "content_type": "flow_trigger",
+                "content": {
+                    "flow_id": self.flow_id,
+                    "step_id": self.step_id,
+                }
+            }),
+            metadata: serde_json::json!({}),
+            step_limit: None,
+            ttl_duration: None,
+            low_data_mode: None,
+            debug: false,
+            random_seed: None,
+        };
+        let bot_opt = self.bot.to_bot_opt(&self.client.bot_id);
+
+        (request, bot_opt)
+    }
+}
+
+/**
+ * Result of a schedule run by `run_due_schedules`
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    #[serde(flatten)]
+    pub schedule: Schedule,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    // set when the run failed and the schedule is kept to be run again at this time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<DateTime<Utc>>,
+    // messages of the run, also sent to the callback_url
+    #[serde(skip)]
+    pub messages: Vec<serde_json::Value>,
+}
+
+/**
+ * Messages that could not be sent to a callback_url, kept until `redeliver_callbacks`
+ * sends them again with the same delivery id and signature.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+
+    pub client: Client,
+
+    pub callback_url: String,
+    pub payload: serde_json::Value,
+    pub signature: Option<String>,
+    pub attempts: u32,
+
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboxMessage {
+    pub fn new(
+        client: &Client,
+        callback_url: &str,
+        payload: serde_json::Value,
+        secret: Option<&str>,
+    ) -> Self {
+        let now = Utc::now();
+
+        Self {
+            id: Uuid::new_v4(),
+            client: client.to_owned(),
+            callback_url: callback_url.to_owned(),
+            signature: secret.map(|secret| crate::send::sign_payload(secret, &payload.to_string())),
+            payload,
+            attempts: 0,
+            updated_at: now,
+            created_at: now,
+        }
+    }
+}
+
+/**
+ * Result of an outbox message sent again by `redeliver_callbacks`
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallbackRedelivery {
+    pub id: Uuid,
+    pub client: Client,
+    pub callback_url: String,
+    pub attempts: u32,
+    pub delivered: bool,
+    // the endpoint refused the message, which left the outbox without being delivered
+    #[serde(default)]
+    pub dropped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/**
+ * What an API key can be used for, `admin` gives access to every route
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    // send events to bots and close conversations
+    Run,
+    // read the conversations, messages, memories and state of clients, and the analytics of bots
+    Read,
+    // create, read and delete bot versions
+    Bots,
+    Admin,
+}
+
+/**
+ * API key saved in the database. Only a hash of the key is saved with it,
+ * the key itself is returned once by `create_api_key`.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    // every bot when not set
+    pub bot_ids: Option<Vec<String>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ApiKey {
+    /**
+     * The key must have the scope (or `admin`) and be allowed for each of the bots.
+     * A key restricted to some bots can not be used for requests that are not about a bot.
+     */
+    pub fn allows(&self, scope: ApiKeyScope, bot_ids: &[&str]) -> bool {
+        let has_scope = self
+            .scopes
+            .iter()
+            .any(|granted| *granted == scope || *granted == ApiKeyScope::Admin);
+
+        let has_bots = match &self.bot_ids {
+            Some(allowed) => {
+                !bot_ids.is_empty()
+                    && bot_ids
+                        .iter()
+                        .all(|bot_id| allowed.iter().any(|id| id == bot_id))
+            }
+            None => true,
+        };
+
+        has_scope && has_bots
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    #[serde(default)]
+    pub bot_ids: Option<Vec<String>>,
+}
+
+/**
+ * A new API key, `key` can not be read again afterwards
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedApiKey {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+}
+
+/**
+ * Conversations of a flow: started in it (where their first message was saved),
+ * and ended in it (closed while it was their current flow)
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FlowAnalytics {
+    pub flow_id: String,
+    pub started: u64,
+    pub ended: u64,
+}
+
+/**
+ * Holds of a step: answered by an input of the user, or abandoned by conversations
+ * still open at this step without any recent activity
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HoldAnalytics {
+    pub flow_id: String,
+    pub step_id: String,
+    pub answered: u64,
+    pub abandoned: u64,
+}
+
+/**
+ * Inputs of the users (turns) of the conversations with saved messages
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnAnalytics {
+    pub conversations: u64,
+    pub turns: u64,
+    pub average_turns: f64,
+}
+
+impl TurnAnalytics {
+    pub fn new(conversations: u64, turns: u64) -> Self {
+        let average_turns = match conversations {
+            0 => 0.0,
+            _ => turns as f64 / conversations as f64,
+        };
+
+        Self {
+            conversations,
+            turns,
+            average_turns,
+        }
+    }
+}
+
+/**
+ * Times a conversation went from a step to another one between two saved messages
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepTransition {
+    pub from_flow: String,
+    pub from_step: String,
+    pub to_flow: String,
+    pub to_step: String,
+    pub count: u64,
+}
+
+/**
+ * Value of a state key of a client, e.g. the position of a `hold`
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateItem {
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+/**
+ * Conversations, messages, memories and state of a client
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientArchive {
+    pub client: Client,
+    pub conversations: Vec<Conversation>,
+    pub messages: Vec<Message>,
+    pub memories: serde_json::Map<String, serde_json::Value>,
+    pub state: Vec<StateItem>,
+}
+
+/**
+ * Document produced by `export_client` and `export_bot`, and read by `import_client`
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Archive {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub clients: Vec<ClientArchive>,
+}
+
+/**
+ * Number of records written by `import_client`
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveImport {
+    pub clients: usize,
+    pub conversations: usize,
+    pub messages: usize,
+    pub memories: usize,
+}
+
+/**
+ * Bot version as it is saved in the database, with the bot serialized
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BotVersionRecord {
+    pub id: Uuid,
+    pub bot_id: String,
+    pub bot: String,
+    pub engine_version: String,
+
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/**
+ * Memory of a client as it is saved in the database
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemoryRecord {
+    pub id: Uuid,
+    pub client: Client,
+    pub key: String,
+    pub value: serde_json::Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/**
+ * State key of a client as it is saved in the database
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateRecord {
+    pub id: Uuid,
+    pub client: Client,
+    #[serde(rename = "type")]
+    pub _type: String,
+    pub key: String,
+    pub value: serde_json::Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/**
+ * Kinds of records copied by `migrate`, in the order they are copied
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    BotVersions,
+    Conversations,
+    Messages,
+    Memories,
+    State,
+    Schedules,
+    Outbox,
+}
+
+/**
+ * Progress of a migration (or of a key rotation): the records of the kinds before
+ * `kind`, and the ones of `kind` up to the `after` id, are already copied
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationCheckpoint {
+    pub kind: RecordKind,
+    pub after: Option<Uuid>,
+}
+
+/**
+ * Number of records read from the source and created in the target.
+ * Records that already exist in the target are read but not created again.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MigratedRecords {
+    pub read: usize,
+    pub created: usize,
+}
+
+/**
+ * Records copied by `migrate`, by kind
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MigrationReport {
+    pub records: std::collections::BTreeMap<RecordKind, MigratedRecords>,
+}
+
+/**
+ * Records encrypted again by `rotate_encryption_key`, by kind, and the id of the key
+ * they are now encrypted with
+ */
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RotationReport {
+    pub key_id: Option<String>,
+    pub records: std::collections::BTreeMap<RecordKind, usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum Direction {
@@ -124,6 +662,15 @@ pub enum Direction {
     Receive,
 }
 
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Send => "SEND",
+            Direction::Receive => "RECEIVE",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Message {
     pub id: Uuid,
@@ -0,0 +1,1033 @@
+/**
+ * Storage interface of the engine.
+ *
+ * Every database call made by the engine goes through the traits of this module.
+ * The built-in connectors (mongodb, dynamodb, postgresql, sqlite, memory) implement
+ * them for their own client type, and any other crate can implement `StorageBackend`
+ * for its own store and use it with `Database::custom` or `start_conversation_db`,
+ * without any change to the engine itself.
+ *
+ * Expiration is expressed as a `ttl` duration: each backend is free to convert it
+ * to the representation it needs (absolute date, unix timestamp...).
+ */
+use crate::data::filter::{AnalyticsFilter, ClientMessageFilter};
+use crate::data::models::{
+    ApiKey, BotVersionRecord, Conversation, Direction, FlowAnalytics, HoldAnalytics, MemoryRecord,
+    Message, OutboxMessage, Paginated, Schedule, StateItem, StateRecord, StepTransition,
+    TurnAnalytics,
+};
+use crate::data::{Database, EngineError};
+use crate::models::BotVersion;
+use csml_interpreter::data::{Client, CsmlBot, Memory};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/**
+ * Position of the client in a conversation when a batch of messages is saved
+ */
+#[derive(Debug, Clone)]
+pub struct ConversationStep<'a> {
+    pub client: &'a Client,
+    pub conversation_id: Uuid,
+    pub flow_id: &'a str,
+    pub step_id: &'a str,
+}
+
+pub trait ConversationStorage {
+    fn create_conversation(
+        &mut self,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<Uuid, EngineError>;
+
+    fn close_conversation(&mut self, id: Uuid, client: &Client) -> Result<(), EngineError>;
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<Conversation>, EngineError>;
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: Uuid,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Not every backend is able to find a conversation without the client it belongs to
+     */
+    fn get_conversation(&mut self, _id: Uuid) -> Result<Conversation, EngineError> {
+        Err(unsupported("get_conversation"))
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<Paginated<Conversation>, EngineError>;
+
+    fn delete_client_conversations(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    /**
+     * Clients having at least one conversation with the bot, used to export all its data
+     */
+    fn get_bot_clients(&mut self, _bot_id: &str) -> Result<Vec<Client>, EngineError> {
+        Err(unsupported("get_bot_clients"))
+    }
+
+    /**
+     * Conversations of every bot and client, ordered by id and starting after the `after` id.
+     * The `*_records` methods are used by `migrate` to copy a whole database.
+     */
+    fn get_conversation_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<Conversation>, EngineError> {
+        Err(unsupported("get_conversation_records"))
+    }
+
+    /**
+     * Save conversations with their ids and dates. Those whose id already exists are
+     * skipped, so that a migration can be run again: return the number of new ones.
+     */
+    fn add_conversation_records(
+        &mut self,
+        _conversations: &[Conversation],
+    ) -> Result<usize, EngineError> {
+        Err(unsupported("add_conversation_records"))
+    }
+}
+
+pub trait MessageStorage {
+    fn add_messages_bulk(
+        &mut self,
+        step: &ConversationStep,
+        msgs: &[serde_json::Value],
+        interaction_order: i32,
+        direction: Direction,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    fn get_client_messages(
+        &mut self,
+        filter: ClientMessageFilter<'_>,
+    ) -> Result<Paginated<Message>, EngineError>;
+
+    fn delete_client_messages(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    fn get_message_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<Message>, EngineError> {
+        Err(unsupported("get_message_records"))
+    }
+
+    /**
+     * Messages whose id already exists are skipped as well. A message whose conversation
+     * is not in the backend is an error: nothing is saved.
+     */
+    fn add_message_records(&mut self, _messages: &[Message]) -> Result<usize, EngineError> {
+        Err(unsupported("add_message_records"))
+    }
+
+    /**
+     * Save again the payload of existing messages, encrypted with the current key.
+     * The `update_*_records` methods are used by `rotate_encryption_key`: return the
+     * number of records updated. Memories and state can change while the rotation runs:
+     * they are only saved again if they still hold the value that was read.
+     */
+    fn update_message_records(&mut self, _messages: &[Message]) -> Result<usize, EngineError> {
+        Err(unsupported("update_message_records"))
+    }
+}
+
+pub trait MemoryStorage {
+    fn add_memories(
+        &mut self,
+        client: &Client,
+        memories: &HashMap<String, Memory>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    fn create_client_memory(
+        &mut self,
+        client: &Client,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    /**
+     * Memories as a single `{key: value}` object, used to build the context of a
+     * conversation
+     */
+    fn internal_use_get_memories(
+        &mut self,
+        client: &Client,
+    ) -> Result<serde_json::Value, EngineError>;
+
+    fn get_memories(&mut self, client: &Client) -> Result<serde_json::Value, EngineError>;
+
+    fn get_memory(&mut self, client: &Client, key: &str) -> Result<serde_json::Value, EngineError>;
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError>;
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    fn get_memory_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<MemoryRecord>, EngineError> {
+        Err(unsupported("get_memory_records"))
+    }
+
+    fn add_memory_records(&mut self, _memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        Err(unsupported("add_memory_records"))
+    }
+
+    fn update_memory_records(&mut self, _memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        Err(unsupported("update_memory_records"))
+    }
+}
+
+pub trait StateStorage {
+    fn get_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError>;
+
+    fn get_current_state(
+        &mut self,
+        client: &Client,
+    ) -> Result<Option<serde_json::Value>, EngineError>;
+
+    fn set_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        keys_values: Vec<(&str, &serde_json::Value)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError>;
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError>;
+
+    fn delete_client_state(&mut self, client: &Client) -> Result<(), EngineError>;
+
+    /**
+     * Every state key of a client, whatever its type
+     */
+    fn get_client_state(&mut self, _client: &Client) -> Result<Vec<StateItem>, EngineError> {
+        Err(unsupported("get_client_state"))
+    }
+
+    fn get_state_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<StateRecord>, EngineError> {
+        Err(unsupported("get_state_records"))
+    }
+
+    fn add_state_records(&mut self, _state: &[StateRecord]) -> Result<usize, EngineError> {
+        Err(unsupported("add_state_records"))
+    }
+
+    fn update_state_records(&mut self, _state: &[StateRecord]) -> Result<usize, EngineError> {
+        Err(unsupported("update_state_records"))
+    }
+}
+
+pub trait BotStorage {
+    fn create_bot_version(
+        &mut self,
+        bot_id: String,
+        csml_bot: CsmlBot,
+    ) -> Result<String, EngineError>;
+
+    fn get_last_bot_version(&mut self, bot_id: &str) -> Result<Option<BotVersion>, EngineError>;
+
+    fn get_bot_by_version_id(
+        &mut self,
+        version_id: &str,
+        bot_id: &str,
+    ) -> Result<Option<BotVersion>, EngineError>;
+
+    fn get_bot_versions(
+        &mut self,
+        bot_id: &str,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<serde_json::Value, EngineError>;
+
+    fn delete_bot_version(&mut self, bot_id: &str, version_id: &str) -> Result<(), EngineError>;
+
+    fn delete_bot_versions(&mut self, bot_id: &str) -> Result<(), EngineError>;
+
+    fn get_bot_version_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<BotVersionRecord>, EngineError> {
+        Err(unsupported("get_bot_version_records"))
+    }
+
+    fn add_bot_version_records(
+        &mut self,
+        _versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        Err(unsupported("add_bot_version_records"))
+    }
+
+    fn update_bot_version_records(
+        &mut self,
+        _versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        Err(unsupported("update_bot_version_records"))
+    }
+}
+
+/**
+ * Flow triggers planned by the `Schedule()` builtin. Backends without schedules support
+ * keep the default implementation, and bots using `Schedule()` fail with an error.
+ */
+pub trait ScheduleStorage {
+    fn create_schedule(&mut self, _schedule: &Schedule) -> Result<(), EngineError> {
+        Err(unsupported("create_schedule"))
+    }
+
+    /**
+     * Schedules of all the clients whose `run_at` date is passed, the oldest first
+     */
+    fn get_due_schedules(
+        &mut self,
+        _now: chrono::DateTime<chrono::Utc>,
+        _limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        Err(unsupported("get_due_schedules"))
+    }
+
+    /**
+     * Move the schedule to `retry_at` and count one more attempt, if it was not changed
+     * since it was read (same `attempts`). Return false otherwise, for instance when
+     * another process running the same due schedules claimed it first.
+     */
+    fn claim_schedule(
+        &mut self,
+        _schedule: &Schedule,
+        _retry_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, EngineError> {
+        Err(unsupported("claim_schedule"))
+    }
+
+    /**
+     * Return false if the schedule was already deleted, for instance by another
+     * process running the same due schedules
+     */
+    fn delete_schedule(&mut self, _id: Uuid) -> Result<bool, EngineError> {
+        Err(unsupported("delete_schedule"))
+    }
+
+    fn delete_client_schedules(&mut self, _client: &Client) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn get_schedule_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        Err(unsupported("get_schedule_records"))
+    }
+
+    fn add_schedule_records(&mut self, _schedules: &[Schedule]) -> Result<usize, EngineError> {
+        Err(unsupported("add_schedule_records"))
+    }
+}
+
+/**
+ * Messages that could not be delivered to their callback_url. Backends without outbox
+ * support keep the default implementation: undelivered messages are then only logged.
+ */
+pub trait OutboxStorage {
+    fn add_outbox_message(&mut self, _message: &OutboxMessage) -> Result<(), EngineError> {
+        Err(unsupported("add_outbox_message"))
+    }
+
+    /**
+     * Outbox messages of a bot (or of all the bots), the oldest first
+     */
+    fn get_outbox_messages(
+        &mut self,
+        _bot_id: Option<&str>,
+        _limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        Err(unsupported("get_outbox_messages"))
+    }
+
+    /**
+     * Count a failed delivery of an outbox message
+     */
+    fn add_outbox_attempt(&mut self, _id: Uuid) -> Result<(), EngineError> {
+        Err(unsupported("add_outbox_attempt"))
+    }
+
+    /**
+     * Return false if the message was already deleted, for instance by another
+     * process redelivering the same messages
+     */
+    fn delete_outbox_message(&mut self, _id: Uuid) -> Result<bool, EngineError> {
+        Err(unsupported("delete_outbox_message"))
+    }
+
+    /**
+     * Whether messages of the client wait in the outbox, the next ones must follow them
+     */
+    fn has_client_outbox(&mut self, _client: &Client) -> Result<bool, EngineError> {
+        Ok(false)
+    }
+
+    fn delete_client_outbox(&mut self, _client: &Client) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn get_outbox_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        Err(unsupported("get_outbox_records"))
+    }
+
+    fn add_outbox_records(&mut self, _messages: &[OutboxMessage]) -> Result<usize, EngineError> {
+        Err(unsupported("add_outbox_records"))
+    }
+
+    fn update_outbox_records(&mut self, _messages: &[OutboxMessage]) -> Result<usize, EngineError> {
+        Err(unsupported("update_outbox_records"))
+    }
+}
+
+/**
+ * API keys of the server, found by the hash of the key. Backends without API keys
+ * support keep the default implementation: only the keys of ENGINE_SERVER_API_KEYS work.
+ */
+pub trait ApiKeyStorage {
+    fn create_api_key(&mut self, _api_key: &ApiKey, _key_hash: &str) -> Result<(), EngineError> {
+        Err(unsupported("create_api_key"))
+    }
+
+    fn get_api_key(&mut self, _key_hash: &str) -> Result<Option<ApiKey>, EngineError> {
+        Err(unsupported("get_api_key"))
+    }
+
+    fn get_api_keys(&mut self) -> Result<Vec<ApiKey>, EngineError> {
+        Err(unsupported("get_api_keys"))
+    }
+
+    /**
+     * Return false if there is no key with this id
+     */
+    fn delete_api_key(&mut self, _id: Uuid) -> Result<bool, EngineError> {
+        Err(unsupported("delete_api_key"))
+    }
+}
+
+/**
+ * Counters of the rate limits and quotas, see `quotas`. Each key is a counter of the client,
+ * and `increment_counter` adds to it and reads its new value in one atomic operation, so that
+ * concurrent requests never lose a count. Backends without counters keep the default
+ * implementation: the quotas can not be enabled with them.
+ */
+pub trait CounterStorage {
+    /**
+     * Value of the counter, 0 if it does not exist or has expired
+     */
+    fn get_counter(&mut self, _client: &Client, _key: &str) -> Result<i64, EngineError> {
+        Err(unsupported("get_counter"))
+    }
+
+    /**
+     * Add `count` to the counter (starting from 0) and return its new value. The counter
+     * expires `ttl` after its last increment.
+     */
+    fn increment_counter(
+        &mut self,
+        _client: &Client,
+        _key: &str,
+        _count: i64,
+        _ttl: Option<chrono::Duration>,
+    ) -> Result<i64, EngineError> {
+        Err(unsupported("increment_counter"))
+    }
+
+    fn delete_client_counters(&mut self, _client: &Client) -> Result<(), EngineError> {
+        Ok(())
+    }
+}
+
+/**
+ * Aggregations of the conversations of a bot and of their messages, see `AnalyticsFilter`.
+ * Backends that can not aggregate their records keep the default implementation.
+ */
+pub trait AnalyticsStorage {
+    fn get_flow_analytics(
+        &mut self,
+        _filter: &AnalyticsFilter,
+    ) -> Result<Vec<FlowAnalytics>, EngineError> {
+        Err(unsupported("get_flow_analytics"))
+    }
+
+    fn get_hold_analytics(
+        &mut self,
+        _filter: &AnalyticsFilter,
+    ) -> Result<Vec<HoldAnalytics>, EngineError> {
+        Err(unsupported("get_hold_analytics"))
+    }
+
+    fn get_turn_analytics(
+        &mut self,
+        _filter: &AnalyticsFilter,
+    ) -> Result<TurnAnalytics, EngineError> {
+        Err(unsupported("get_turn_analytics"))
+    }
+
+    fn get_step_transitions(
+        &mut self,
+        _filter: &AnalyticsFilter,
+    ) -> Result<Vec<StepTransition>, EngineError> {
+        Err(unsupported("get_step_transitions"))
+    }
+}
+
+/**
+ * Everything the engine needs to store conversations. Implement the 10 storage traits
+ * and `delete_all_bot_data` to plug a new database into the engine.
+ */
+pub trait StorageBackend:
+    ConversationStorage
+    + MessageStorage
+    + MemoryStorage
+    + StateStorage
+    + BotStorage
+    + ScheduleStorage
+    + OutboxStorage
+    + ApiKeyStorage
+    + CounterStorage
+    + AnalyticsStorage
+    + Send
+{
+    /**
+     * Remove all the data of a bot: versions, conversations, messages, memories, state,
+     * counters, schedules and outbox messages
+     */
+    fn delete_all_bot_data(&mut self, bot_id: &str) -> Result<(), EngineError>;
+
+    fn delete_client(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.delete_client_memories(client)?;
+        self.delete_client_messages(client)?;
+        self.delete_client_conversations(client)?;
+        self.delete_client_state(client)?;
+        self.delete_client_counters(client)?;
+        self.delete_client_schedules(client)?;
+        self.delete_client_outbox(client)
+    }
+
+    /**
+     * Backends without native TTL support must remove the expired data themselves
+     */
+    fn delete_expired_data(&mut self) -> Result<(), EngineError> {
+        Ok(())
+    }
+}
+
+/**
+ * Data read and written on every request: conversations, memories, state and counters.
+ * It can be kept in a faster store than messages and bot versions with `SplitStorage`.
+ */
+pub trait SessionStorage:
+    ConversationStorage + MemoryStorage + StateStorage + CounterStorage + Send
+{
+    /**
+     * Remove the conversations, memories, state and counters of all the clients of a bot
+     */
+    fn delete_all_bot_sessions(&mut self, bot_id: &str) -> Result<(), EngineError>;
+}
+
+/**
+ * Storage backend keeping conversations, memories, state and counters in `sessions`,
+ * while messages, bot versions, schedules and outbox messages stay in `storage`.
+ */
+pub struct SplitStorage<'a> {
+    sessions: Box<dyn SessionStorage + 'a>,
+    storage: Box<dyn StorageBackend + 'a>,
+}
+
+impl<'a> SplitStorage<'a> {
+    pub fn new<S: SessionStorage + 'a>(
+        sessions: S,
+        storage: Database<'a>,
+    ) -> Result<Self, EngineError> {
+        Ok(Self {
+            sessions: Box::new(sessions),
+            storage: storage.into_storage()?,
+        })
+    }
+}
+
+impl ConversationStorage for SplitStorage<'_> {
+    fn create_conversation(
+        &mut self,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<Uuid, EngineError> {
+        self.sessions
+            .create_conversation(flow_id, step_id, client, ttl)
+    }
+
+    fn close_conversation(&mut self, id: Uuid, client: &Client) -> Result<(), EngineError> {
+        self.sessions.close_conversation(id, client)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.sessions.close_all_conversations(client)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<Conversation>, EngineError> {
+        self.sessions.get_latest_open(client)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: Uuid,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        self.sessions
+            .update_conversation(conversation_id, client, flow_id, step_id)
+    }
+
+    fn get_conversation(&mut self, id: Uuid) -> Result<Conversation, EngineError> {
+        self.sessions.get_conversation(id)
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<Paginated<Conversation>, EngineError> {
+        self.sessions
+            .get_client_conversations(client, limit, pagination_key)
+    }
+
+    fn delete_client_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.sessions.delete_client_conversations(client)
+    }
+
+    fn get_bot_clients(&mut self, bot_id: &str) -> Result<Vec<Client>, EngineError> {
+        self.sessions.get_bot_clients(bot_id)
+    }
+
+    fn get_conversation_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Conversation>, EngineError> {
+        self.sessions.get_conversation_records(after, limit)
+    }
+
+    fn add_conversation_records(
+        &mut self,
+        conversations: &[Conversation],
+    ) -> Result<usize, EngineError> {
+        self.sessions.add_conversation_records(conversations)
+    }
+}
+
+impl MessageStorage for SplitStorage<'_> {
+    fn add_messages_bulk(
+        &mut self,
+        step: &ConversationStep,
+        msgs: &[serde_json::Value],
+        interaction_order: i32,
+        direction: Direction,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.storage
+            .add_messages_bulk(step, msgs, interaction_order, direction, ttl)
+    }
+
+    fn get_client_messages(
+        &mut self,
+        filter: ClientMessageFilter<'_>,
+    ) -> Result<Paginated<Message>, EngineError> {
+        self.storage.get_client_messages(filter)
+    }
+
+    fn delete_client_messages(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.storage.delete_client_messages(client)
+    }
+
+    fn get_message_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Message>, EngineError> {
+        self.storage.get_message_records(after, limit)
+    }
+
+    fn add_message_records(&mut self, messages: &[Message]) -> Result<usize, EngineError> {
+        self.storage.add_message_records(messages)
+    }
+
+    fn update_message_records(&mut self, messages: &[Message]) -> Result<usize, EngineError> {
+        self.storage.update_message_records(messages)
+    }
+}
+
+impl MemoryStorage for SplitStorage<'_> {
+    fn add_memories(
+        &mut self,
+        client: &Client,
+        memories: &HashMap<String, Memory>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.sessions.add_memories(client, memories, ttl)
+    }
+
+    fn create_client_memory(
+        &mut self,
+        client: &Client,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.sessions.create_client_memory(client, key, value, ttl)
+    }
+
+    fn internal_use_get_memories(
+        &mut self,
+        client: &Client,
+    ) -> Result<serde_json::Value, EngineError> {
+        self.sessions.internal_use_get_memories(client)
+    }
+
+    fn get_memories(&mut self, client: &Client) -> Result<serde_json::Value, EngineError> {
+        self.sessions.get_memories(client)
+    }
+
+    fn get_memory(&mut self, client: &Client, key: &str) -> Result<serde_json::Value, EngineError> {
+        self.sessions.get_memory(client, key)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        self.sessions.delete_client_memory(client, key)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.sessions.delete_client_memories(client)
+    }
+
+    fn get_memory_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<MemoryRecord>, EngineError> {
+        self.sessions.get_memory_records(after, limit)
+    }
+
+    fn add_memory_records(&mut self, memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        self.sessions.add_memory_records(memories)
+    }
+
+    fn update_memory_records(&mut self, memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        self.sessions.update_memory_records(memories)
+    }
+}
+
+impl StateStorage for SplitStorage<'_> {
+    fn get_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        self.sessions.get_state_key(client, _type, key)
+    }
+
+    fn get_current_state(
+        &mut self,
+        client: &Client,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        self.sessions.get_current_state(client)
+    }
+
+    fn set_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        keys_values: Vec<(&str, &serde_json::Value)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        self.sessions
+            .set_state_items(client, _type, keys_values, ttl)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        self.sessions.delete_state_key(client, _type, key)
+    }
+
+    fn delete_client_state(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.sessions.delete_client_state(client)
+    }
+
+    fn get_client_state(&mut self, client: &Client) -> Result<Vec<StateItem>, EngineError> {
+        self.sessions.get_client_state(client)
+    }
+
+    fn get_state_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<StateRecord>, EngineError> {
+        self.sessions.get_state_records(after, limit)
+    }
+
+    fn add_state_records(&mut self, state: &[StateRecord]) -> Result<usize, EngineError> {
+        self.sessions.add_state_records(state)
+    }
+
+    fn update_state_records(&mut self, state: &[StateRecord]) -> Result<usize, EngineError> {
+        self.sessions.update_state_records(state)
+    }
+}
+
+impl BotStorage for SplitStorage<'_> {
+    fn create_bot_version(
+        &mut self,
+        bot_id: String,
+        csml_bot: CsmlBot,
+    ) -> Result<String, EngineError> {
+        self.storage.create_bot_version(bot_id, csml_bot)
+    }
+
+    fn get_last_bot_version(&mut self, bot_id: &str) -> Result<Option<BotVersion>, EngineError> {
+        self.storage.get_last_bot_version(bot_id)
+    }
+
+    fn get_bot_by_version_id(
+        &mut self,
+        version_id: &str,
+        bot_id: &str,
+    ) -> Result<Option<BotVersion>, EngineError> {
+        self.storage.get_bot_by_version_id(version_id, bot_id)
+    }
+
+    fn get_bot_versions(
+        &mut self,
+        bot_id: &str,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<serde_json::Value, EngineError> {
+        self.storage.get_bot_versions(bot_id, limit, pagination_key)
+    }
+
+    fn delete_bot_version(&mut self, bot_id: &str, version_id: &str) -> Result<(), EngineError> {
+        self.storage.delete_bot_version(bot_id, version_id)
+    }
+
+    fn delete_bot_versions(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        self.storage.delete_bot_versions(bot_id)
+    }
+
+    fn get_bot_version_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<BotVersionRecord>, EngineError> {
+        self.storage.get_bot_version_records(after, limit)
+    }
+
+    fn add_bot_version_records(
+        &mut self,
+        versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        self.storage.add_bot_version_records(versions)
+    }
+
+    fn update_bot_version_records(
+        &mut self,
+        versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        self.storage.update_bot_version_records(versions)
+    }
+}
+
+impl ScheduleStorage for SplitStorage<'_> {
+    fn create_schedule(&mut self, schedule: &Schedule) -> Result<(), EngineError> {
+        self.storage.create_schedule(schedule)
+    }
+
+    fn get_due_schedules(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+        limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        self.storage.get_due_schedules(now, limit)
+    }
+
+    fn claim_schedule(
+        &mut self,
+        schedule: &Schedule,
+        retry_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<bool, EngineError> {
+        self.storage.claim_schedule(schedule, retry_at)
+    }
+
+    fn delete_schedule(&mut self, id: Uuid) -> Result<bool, EngineError> {
+        self.storage.delete_schedule(id)
+    }
+
+    fn delete_client_schedules(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.storage.delete_client_schedules(client)
+    }
+
+    fn get_schedule_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        self.storage.get_schedule_records(after, limit)
+    }
+
+    fn add_schedule_records(&mut self, schedules: &[Schedule]) -> Result<usize, EngineError> {
+        self.storage.add_schedule_records(schedules)
+    }
+}
+
+impl OutboxStorage for SplitStorage<'_> {
+    fn add_outbox_message(&mut self, message: &OutboxMessage) -> Result<(), EngineError> {
+        self.storage.add_outbox_message(message)
+    }
+
+    fn get_outbox_messages(
+        &mut self,
+        bot_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        self.storage.get_outbox_messages(bot_id, limit)
+    }
+
+    fn add_outbox_attempt(&mut self, id: Uuid) -> Result<(), EngineError> {
+        self.storage.add_outbox_attempt(id)
+    }
+
+    fn delete_outbox_message(&mut self, id: Uuid) -> Result<bool, EngineError> {
+        self.storage.delete_outbox_message(id)
+    }
+
+    fn has_client_outbox(&mut self, client: &Client) -> Result<bool, EngineError> {
+        self.storage.has_client_outbox(client)
+    }
+
+    fn delete_client_outbox(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.storage.delete_client_outbox(client)
+    }
+
+    fn get_outbox_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        self.storage.get_outbox_records(after, limit)
+    }
+
+    fn add_outbox_records(&mut self, messages: &[OutboxMessage]) -> Result<usize, EngineError> {
+        self.storage.add_outbox_records(messages)
+    }
+
+    fn update_outbox_records(&mut self, messages: &[OutboxMessage]) -> Result<usize, EngineError> {
+        self.storage.update_outbox_records(messages)
+    }
+}
+
+impl ApiKeyStorage for SplitStorage<'_> {
+    fn create_api_key(&mut self, api_key: &ApiKey, key_hash: &str) -> Result<(), EngineError> {
+        self.storage.create_api_key(api_key, key_hash)
+    }
+
+    fn get_api_key(&mut self, key_hash: &str) -> Result<Option<ApiKey>, EngineError> {
+        self.storage.get_api_key(key_hash)
+    }
+
+    fn get_api_keys(&mut self) -> Result<Vec<ApiKey>, EngineError> {
+        self.storage.get_api_keys()
+    }
+
+    fn delete_api_key(&mut self, id: Uuid) -> Result<bool, EngineError> {
+        self.storage.delete_api_key(id)
+    }
+}
+
+impl CounterStorage for SplitStorage<'_> {
+    fn get_counter(&mut self, client: &Client, key: &str) -> Result<i64, EngineError> {
+        self.sessions.get_counter(client, key)
+    }
+
+    fn increment_counter(
+        &mut self,
+        client: &Client,
+        key: &str,
+        count: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<i64, EngineError> {
+        self.sessions.increment_counter(client, key, count, ttl)
+    }
+
+    fn delete_client_counters(&mut self, client: &Client) -> Result<(), EngineError> {
+        self.sessions.delete_client_counters(client)
+    }
+}
+
+// the conversations and their messages are not in the same store
+impl AnalyticsStorage for SplitStorage<'_> {}
+
+impl StorageBackend for SplitStorage<'_> {
+    fn delete_all_bot_data(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        self.storage.delete_all_bot_data(bot_id)?;
+        self.sessions.delete_all_bot_sessions(bot_id)
+    }
+
+    fn delete_expired_data(&mut self) -> Result<(), EngineError> {
+        self.storage.delete_expired_data()
+    }
+}
+
+fn unsupported(method: &str) -> EngineError {
+    EngineError::Manager(format!(
+        "{} is not supported by this storage backend",
+        method
+    ))
+}
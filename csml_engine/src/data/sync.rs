@@ -1,12 +1,13 @@
 use crate::data::models::BotOpt;
 use crate::data::{Database, EngineError};
 use crate::db_connectors;
+use crate::encrypt::verify_client_artifact;
 use csml_interpreter::data::CsmlBot;
 
 impl BotOpt {
     pub fn search_bot(&self, db: &mut Database) -> Result<CsmlBot, EngineError> {
         match self {
-            BotOpt::CsmlBot(csml_bot) => Ok(csml_bot.to_owned()),
+            BotOpt::CsmlBot(csml_bot) => Ok(verify_client_artifact(csml_bot.to_owned())),
             BotOpt::BotId {
                 bot_id,
                 apps_endpoint,
@@ -0,0 +1,57 @@
+use crate::data::filter::AnalyticsFilter;
+use crate::data::models::{FlowAnalytics, HoldAnalytics, StepTransition, TurnAnalytics};
+use crate::db_connectors::db_span;
+use crate::{Database, EngineError};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+
+fn log_call(name: &str, filter: &AnalyticsFilter) {
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call get {} analytics of bot {}", name, filter.bot_id),
+        ),
+        LogLvl::Info,
+    );
+}
+
+pub fn get_flow_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut Database,
+) -> Result<Vec<FlowAnalytics>, EngineError> {
+    let _span = db_span("csml.db.get_flow_analytics", db.backend());
+    log_call("flow", filter);
+
+    db.storage()?.get_flow_analytics(filter)
+}
+
+pub fn get_hold_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut Database,
+) -> Result<Vec<HoldAnalytics>, EngineError> {
+    let _span = db_span("csml.db.get_hold_analytics", db.backend());
+    log_call("hold", filter);
+
+    db.storage()?.get_hold_analytics(filter)
+}
+
+pub fn get_turn_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut Database,
+) -> Result<TurnAnalytics, EngineError> {
+    let _span = db_span("csml.db.get_turn_analytics", db.backend());
+    log_call("turn", filter);
+
+    db.storage()?.get_turn_analytics(filter)
+}
+
+pub fn get_step_transitions(
+    filter: &AnalyticsFilter,
+    db: &mut Database,
+) -> Result<Vec<StepTransition>, EngineError> {
+    let _span = db_span("csml.db.get_step_transitions", db.backend());
+    log_call("transition", filter);
+
+    db.storage()?.get_step_transitions(filter)
+}
@@ -0,0 +1,58 @@
+use crate::data::models::ApiKey;
+use crate::db_connectors::db_span;
+use crate::{Database, EngineError};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use uuid::Uuid;
+
+pub fn create_api_key(
+    api_key: &ApiKey,
+    key_hash: &str,
+    db: &mut Database,
+) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.create_api_key", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call create api key {}", api_key.id),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.create_api_key(api_key, key_hash)
+}
+
+pub fn get_api_key(key_hash: &str, db: &mut Database) -> Result<Option<ApiKey>, EngineError> {
+    let _span = db_span("csml.db.get_api_key", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, "db call get api key".to_string()),
+        LogLvl::Info,
+    );
+
+    db.storage()?.get_api_key(key_hash)
+}
+
+pub fn get_api_keys(db: &mut Database) -> Result<Vec<ApiKey>, EngineError> {
+    let _span = db_span("csml.db.get_api_keys", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, "db call get api keys".to_string()),
+        LogLvl::Info,
+    );
+
+    db.storage()?.get_api_keys()
+}
+
+pub fn delete_api_key(id: Uuid, db: &mut Database) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.delete_api_key", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, format!("db call delete api key {}", id)),
+        LogLvl::Info,
+    );
+
+    db.storage()?.delete_api_key(id)
+}
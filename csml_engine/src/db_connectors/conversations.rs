@@ -1,19 +1,11 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
 use uuid::Uuid;
 
+use crate::db_connectors::db_span;
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
-use crate::db_connectors::{state, utils::*};
-use crate::error_messages::ERROR_DB_SETUP;
-use crate::{data, Client, ConversationInfo, Database, EngineError};
 use crate::data::models::Conversation;
+use crate::db_connectors::state;
+use crate::{data, Client, ConversationInfo, Database, EngineError};
 
 pub fn create_conversation(
     flow_id: &str,
@@ -22,6 +14,8 @@ pub fn create_conversation(
     ttl: Option<chrono::Duration>,
     db: &mut Database,
 ) -> Result<Uuid, EngineError> {
+    let _span = db_span("csml.db.create_conversation", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -47,47 +41,13 @@ pub fn create_conversation(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-
-        let expires_at = get_expires_at_for_mongodb(ttl);
-        return mongodb_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_dynamodb(ttl);
-        return dynamodb_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_postgresql(ttl);
-        return postgresql_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        let expires_at = get_expires_at_for_sqlite(ttl);
-        return sqlite_connector::conversations::create_conversation(
-            flow_id, step_id, client, expires_at, db,
-        );
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?
+        .create_conversation(flow_id, step_id, client, ttl)
 }
 
 pub fn close_conversation(id: Uuid, client: &Client, db: &mut Database) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.close_conversation", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -110,34 +70,12 @@ pub fn close_conversation(id: Uuid, client: &Client, db: &mut Database) -> Resul
     // delete previous bot info at the end of the conversation
     state::delete_state_key(client, "bot", "previous", db)?;
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::close_conversation(id, client, "CLOSED", db);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.close_conversation(id, client)
 }
 
 pub fn close_all_conversations(client: &Client, db: &mut Database) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.close_all_conversations", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -157,37 +95,15 @@ pub fn close_all_conversations(client: &Client, db: &mut Database) -> Result<(),
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::conversations::close_all_conversations(client, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::conversations::close_all_conversations(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::close_all_conversations(client, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::close_all_conversations(client, db);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.close_all_conversations(client)
 }
 
 pub fn get_latest_open(
     client: &Client,
     db: &mut Database,
 ) -> Result<Option<Conversation>, EngineError> {
+    let _span = db_span("csml.db.get_latest_open", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -207,31 +123,7 @@ pub fn get_latest_open(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::conversations::get_latest_open(client, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::conversations::get_latest_open(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::get_latest_open(client, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::get_latest_open(client, db);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.get_latest_open(client)
 }
 
 pub fn update_conversation(
@@ -239,6 +131,8 @@ pub fn update_conversation(
     flow_id: Option<String>,
     step_id: Option<String>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.update_conversation", data.db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -264,59 +158,17 @@ pub fn update_conversation(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(&data.db)?;
-        return mongodb_connector::conversations::update_conversation(
-            &data.conversation_id,
-            &data.client,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(&mut data.db)?;
-        return dynamodb_connector::conversations::update_conversation(
-            &data.conversation_id,
-            &data.client,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(&mut data.db)?;
-        return postgresql_connector::conversations::update_conversation(
-            data.conversation_id,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(&mut data.db)?;
-        return sqlite_connector::conversations::update_conversation(
-            data.conversation_id,
-            flow_id,
-            step_id,
-            db,
-        );
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    data.db
+        .storage()?
+        .update_conversation(data.conversation_id, &data.client, flow_id, step_id)
 }
 
 pub fn get_conversation(
     db: &mut Database,
     id: Uuid,
 ) -> Result<data::models::Conversation, EngineError> {
+    let _span = db_span("csml.db.get_conversation", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -327,13 +179,7 @@ pub fn get_conversation(
         LogLvl::Info,
     );
 
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::get_conversation(db, id);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.get_conversation(id)
 }
 
 pub fn get_client_conversations(
@@ -342,6 +188,8 @@ pub fn get_client_conversations(
     limit: Option<u32>,
     pagination_key: Option<u32>,
 ) -> Result<data::models::Paginated<data::models::Conversation>, EngineError> {
+    let _span = db_span("csml.db.get_client_conversations", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -364,53 +212,6 @@ pub fn get_client_conversations(
         LogLvl::Info,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        let pagination_key = mongodb_connector::get_pagination_key(pagination_key)?;
-
-        return mongodb_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let pagination_key = dynamodb_connector::get_pagination_key(pagination_key)?;
-
-        return dynamodb_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::conversations::get_client_conversations(
-            client,
-            db,
-            limit,
-            pagination_key,
-        );
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?
+        .get_client_conversations(client, limit, pagination_key)
 }
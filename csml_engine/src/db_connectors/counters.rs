@@ -0,0 +1,42 @@
+use crate::db_connectors::db_span;
+use crate::{Database, EngineError};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use csml_interpreter::data::Client;
+
+pub fn get_counter(client: &Client, key: &str, db: &mut Database) -> Result<i64, EngineError> {
+    let _span = db_span("csml.db.get_counter", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            format!("db call get counter: {:?}", key),
+        ),
+        LogLvl::Debug,
+    );
+
+    db.storage()?.get_counter(client, key)
+}
+
+pub fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    ttl: Option<chrono::Duration>,
+    db: &mut Database,
+) -> Result<i64, EngineError> {
+    let _span = db_span("csml.db.increment_counter", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            format!("db call increment counter: {:?} by {}", key, count),
+        ),
+        LogLvl::Debug,
+    );
+
+    db.storage()?.increment_counter(client, key, count, ttl)
+}
@@ -25,6 +25,8 @@ mod tests {
             step: ContextStepInfo::Normal("start".to_owned()),
             flow: "Default".to_owned(),
             previous_bot: None,
+            previous: None,
+            interrupted: None,
         }
     }
 
@@ -38,6 +40,7 @@ mod tests {
                 name: "Default".to_owned(),
                 content: "start: say \"hello\"".to_owned(),
                 commands: vec![],
+                priority: 0,
             }],
             native_components: None,
             custom_components: None,
@@ -47,6 +50,10 @@ mod tests {
             env: None,
             modules: None,
             multibot: None,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
         }
     }
 
@@ -65,6 +72,11 @@ mod tests {
             messages,
             ttl: None,
             low_data: false,
+            trace: None,
+            callback_secret: None,
+            callback_failed: false,
+            stream: None,
+            bot_reference: Default::default(),
             db,
         }
     }
@@ -176,6 +188,59 @@ mod tests {
         assert_eq!(conversations.len(), 0);
     }
 
+    #[test]
+    fn ok_counters() {
+        make_migrations().unwrap_or(());
+
+        // the other tests delete the data of their own client while this one runs
+        let client = Client::new(
+            "botid".to_owned(),
+            "counters".to_owned(),
+            "alexis".to_owned(),
+        );
+        let mut db = init_db().unwrap();
+
+        user::delete_client(&client, &mut db).unwrap();
+
+        let ttl = Some(chrono::Duration::seconds(60));
+        assert_eq!(
+            counters::get_counter(&client, "requests", &mut db).unwrap(),
+            0
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "requests", 1, ttl, &mut db).unwrap(),
+            1
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "requests", 2, ttl, &mut db).unwrap(),
+            3
+        );
+        assert_eq!(
+            counters::get_counter(&client, "requests", &mut db).unwrap(),
+            3
+        );
+
+        // an expired counter starts again from 0
+        let expired = Some(chrono::Duration::seconds(-1));
+        counters::increment_counter(&client, "steps", 5, expired, &mut db).unwrap();
+        assert_eq!(counters::get_counter(&client, "steps", &mut db).unwrap(), 0);
+        assert_eq!(
+            counters::increment_counter(&client, "steps", 1, ttl, &mut db).unwrap(),
+            1
+        );
+
+        // the counters are not part of the state of the client
+        assert!(state::get_state_key(&client, "quota", "requests", &mut db)
+            .unwrap()
+            .is_none());
+
+        user::delete_client(&client, &mut db).unwrap();
+        assert_eq!(
+            counters::get_counter(&client, "requests", &mut db).unwrap(),
+            0
+        );
+    }
+
     #[test]
     fn ok_memories() {
         make_migrations().unwrap_or(());
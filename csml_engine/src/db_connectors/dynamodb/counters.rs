@@ -0,0 +1,208 @@
+use crate::data::DynamoDbClient;
+use crate::db_connectors::dynamodb::{DynamoDbKey, State};
+use crate::{Client, EngineError};
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::*;
+use std::collections::HashMap;
+
+use crate::db_connectors::dynamodb::utils::*;
+
+fn counter_key(client: &Client, key: &str) -> DynamoDbKey {
+    DynamoDbKey::new(&State::get_hash(client), &make_range(&["counter", key]))
+}
+
+fn number(value: &str) -> AttributeValue {
+    AttributeValue {
+        n: Some(value.to_owned()),
+        ..Default::default()
+    }
+}
+
+fn get_number(item: &HashMap<String, AttributeValue>, name: &str) -> Option<i64> {
+    item.get(name)?.n.as_ref()?.parse::<i64>().ok()
+}
+
+pub fn get_counter(
+    client: &Client,
+    key: &str,
+    db: &mut DynamoDbClient,
+) -> Result<i64, EngineError> {
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&counter_key(client, key))?,
+        consistent_read: Some(true),
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let item = match db.runtime.block_on(future)?.item {
+        Some(item) => item,
+        None => return Ok(0),
+    };
+
+    // the ttl of dynamodb removes expired items in the background only
+    match get_number(&item, "expires_at") {
+        Some(expires_at) if expires_at <= chrono::Utc::now().timestamp() => Ok(0),
+        _ => Ok(get_number(&item, "count").unwrap_or(0)),
+    }
+}
+
+pub fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
+) -> Result<i64, EngineError> {
+    let item_key = serde_dynamodb::to_hashmap(&counter_key(client, key))?;
+
+    let input = DeleteItemInput {
+        table_name: get_table_name()?,
+        key: item_key.clone(),
+        condition_expression: Some("expires_at <= :now".to_owned()),
+        expression_attribute_values: Some(
+            [(
+                ":now".to_owned(),
+                number(&chrono::Utc::now().timestamp().to_string()),
+            )]
+            .iter()
+            .cloned()
+            .collect(),
+        ),
+        ..Default::default()
+    };
+
+    let future = db.client.delete_item(input);
+    match db.runtime.block_on(future) {
+        Ok(_) | Err(RusotoError::Service(DeleteItemError::ConditionalCheckFailed(_))) => {}
+        Err(err) => return Err(err.into()),
+    }
+
+    let mut expr_attr_values: HashMap<String, AttributeValue> = [
+        (":count".to_owned(), number(&count.to_string())),
+        (
+            ":class".to_owned(),
+            AttributeValue {
+                s: Some("counter".to_owned()),
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    // the class is set for the deletion of the data of the bot
+    let mut update_expr = "ADD #count :count SET #class = :class".to_owned();
+    match expires_at {
+        Some(expires_at) => {
+            update_expr = format!("{}, expires_at = :expiresAt", update_expr);
+            expr_attr_values.insert(":expiresAt".to_owned(), number(&expires_at.to_string()));
+        }
+        None => update_expr = format!("{} REMOVE expires_at", update_expr),
+    }
+
+    let expr_attr_names = [
+        ("#count".to_owned(), "count".to_owned()),
+        ("#class".to_owned(), "class".to_owned()),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = UpdateItemInput {
+        table_name: get_table_name()?,
+        key: item_key,
+        update_expression: Some(update_expr),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        return_values: Some("UPDATED_NEW".to_owned()),
+        ..Default::default()
+    };
+
+    let future = db.client.update_item(input);
+    let attributes = db.runtime.block_on(future)?.attributes;
+
+    Ok(attributes
+        .and_then(|attributes| get_number(&attributes, "count"))
+        .unwrap_or(count))
+}
+
+pub fn delete_client_counters(client: &Client, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let mut pagination_key = None;
+
+    loop {
+        let expr_attr_values = [
+            (
+                ":hashVal".to_owned(),
+                AttributeValue {
+                    s: Some(State::get_hash(client)),
+                    ..Default::default()
+                },
+            ),
+            (
+                ":rangePrefix".to_owned(),
+                AttributeValue {
+                    s: Some("counter#".to_owned()),
+                    ..Default::default()
+                },
+            ),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+
+        let input = QueryInput {
+            table_name: get_table_name()?,
+            key_condition_expression: Some(
+                "#hash = :hashVal AND begins_with(#range, :rangePrefix)".to_owned(),
+            ),
+            expression_attribute_names: Some(
+                [
+                    ("#hash".to_owned(), "hash".to_owned()),
+                    ("#range".to_owned(), "range".to_owned()),
+                ]
+                .iter()
+                .cloned()
+                .collect(),
+            ),
+            expression_attribute_values: Some(expr_attr_values),
+            projection_expression: Some("#hash, #range".to_owned()),
+            // 25 is the Maximum operations in a single request for BatchWriteItemInput
+            limit: Some(25),
+            exclusive_start_key: pagination_key,
+            ..Default::default()
+        };
+
+        let future = db.client.query(input);
+        let data = db.runtime.block_on(future)?;
+
+        let items = match data.items {
+            Some(items) if !items.is_empty() => items,
+            _ => return Ok(()),
+        };
+
+        let write_requests = items
+            .into_iter()
+            .map(|key| WriteRequest {
+                delete_request: Some(DeleteRequest { key }),
+                put_request: None,
+            })
+            .collect();
+
+        let input = BatchWriteItemInput {
+            request_items: [(get_table_name()?, write_requests)]
+                .iter()
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+
+        execute_batch_write_query(db, input)?;
+
+        pagination_key = data.last_evaluated_key;
+        if pagination_key.is_none() {
+            return Ok(());
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::data::DynamoDbClient;
-use crate::db_connectors::dynamodb::{get_db, DynamoDbKey, Memory, MemoryDeleteInfo, MemoryKeys};
-use crate::{encrypt::encrypt_data, Client, ConversationInfo, EngineError};
+use crate::db_connectors::dynamodb::{DynamoDbKey, Memory, MemoryDeleteInfo, MemoryKeys};
+use crate::{encrypt::encrypt_data, Client, EngineError};
 use csml_interpreter::data::Memory as InterpreterMemory;
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
@@ -8,7 +8,7 @@ use std::collections::HashMap;
 use crate::db_connectors::dynamodb::utils::*;
 
 fn format_memories(
-    data: &ConversationInfo,
+    client: &Client,
     memories: &HashMap<String, InterpreterMemory>,
     expires_at: Option<i64>,
 ) -> Result<Vec<Memory>, EngineError> {
@@ -16,7 +16,7 @@ fn format_memories(
 
     for (_, mem) in memories.iter() {
         res.push(Memory::new(
-            &data.client,
+            client,
             &mem.key,
             Some(encrypt_data(&mem.value)?),
             expires_at,
@@ -27,15 +27,16 @@ fn format_memories(
 }
 
 pub fn add_memories(
-    data: &mut ConversationInfo,
+    client: &Client,
     memories: &HashMap<String, InterpreterMemory>,
     expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
 ) -> Result<(), EngineError> {
     if memories.len() == 0 {
         return Ok(());
     }
 
-    let memories = format_memories(data, memories, expires_at)?;
+    let memories = format_memories(client, memories, expires_at)?;
 
     // We can only use BatchWriteItem on up to 25 items at once,
     // so we need to split the memories to write into chunks of max
@@ -61,7 +62,6 @@ pub fn add_memories(
             ..Default::default()
         };
 
-        let db = get_db(&mut data.db)?;
         let future = db.client.batch_write_item(input);
 
         db.runtime.block_on(future)?;
@@ -1,7 +1,8 @@
+use crate::data::storage::ConversationStep;
 use crate::db_connectors::dynamodb::{
-    get_db, DynamoDbClient, DynamoDbKey, Message, MessageFromDateInfo, MessageKeys,
+    DynamoDbClient, DynamoDbKey, Message, MessageFromDateInfo, MessageKeys,
 };
-use crate::{data::EngineError, encrypt::encrypt_data, Client, ConversationInfo};
+use crate::{data::EngineError, encrypt::encrypt_data, Client};
 use chrono::{DateTime, NaiveDateTime, SecondsFormat, Utc};
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
@@ -9,7 +10,7 @@ use std::collections::HashMap;
 use crate::db_connectors::dynamodb::utils::*;
 
 fn format_messages(
-    data: &ConversationInfo,
+    step: &ConversationStep,
     messages: &[serde_json::Value],
     interaction_order: i32,
     direction: &str,
@@ -19,10 +20,10 @@ fn format_messages(
 
     for (i, message) in messages.iter().enumerate() {
         res.push(Message::new(
-            &data.client,
-            &data.conversation_id,
-            &data.context.flow,
-            &data.context.step.get_step(),
+            step.client,
+            &step.conversation_id.to_string(),
+            step.flow_id,
+            step.step_id,
             direction,
             interaction_order,
             i as i32,
@@ -71,18 +72,18 @@ pub fn write_messages_batch(
 }
 
 pub fn add_messages_bulk(
-    data: &mut ConversationInfo,
+    step: &ConversationStep,
     messages: &[serde_json::Value],
     interaction_order: i32,
     direction: &str,
     expires_at: Option<i64>,
+    db: &mut DynamoDbClient,
 ) -> Result<(), EngineError> {
     if messages.len() == 0 {
         return Ok(());
     }
 
-    let messages = format_messages(data, messages, interaction_order, direction, expires_at)?;
-    let db = get_db(&mut data.db)?;
+    let messages = format_messages(step, messages, interaction_order, direction, expires_at)?;
 
     write_messages_batch(&messages, db)
 }
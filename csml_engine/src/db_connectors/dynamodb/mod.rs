@@ -9,11 +9,15 @@ use uuid::Uuid;
 pub mod aws_s3;
 pub mod bot;
 pub mod conversations;
+pub mod counters;
 pub mod memories;
 pub mod messages;
+pub mod records;
 pub mod state;
 pub mod utils;
 
+mod storage;
+
 use crate::db_connectors::dynamodb::utils::*;
 
 use rusoto_core::Region;
@@ -47,15 +51,6 @@ pub fn init() -> Result<Database<'static>, EngineError> {
     Ok(Database::Dynamodb(client))
 }
 
-pub fn get_db<'a>(db: &'a mut Database) -> Result<&'a mut DynamoDbClient, EngineError> {
-    match db {
-        Database::Dynamodb(val) => Ok(val),
-        _ => Err(EngineError::Manager(
-            "DynamoDB connector is not setup correctly".to_owned(),
-        )),
-    }
-}
-
 pub fn get_pagination_key(
     pagination_key: Option<String>,
 ) -> Result<Option<HashMap<String, AttributeValue>>, EngineError> {
@@ -0,0 +1,558 @@
+/**
+ * Records of every class, read in batches by `migrate` and `rotate_encryption_key`,
+ * which saves their encrypted fields again.
+ *
+ * A scan of DynamoDB is not ordered: each batch reads all the items of the class, and keeps
+ * the `limit` smallest ids after the `after` id page after page, which costs a full read
+ * of the class per batch. Use a large `--batch-size` to migrate from DynamoDB.
+ *
+ * Memories have no id in DynamoDB: their id is made from the md5 of their key in the table,
+ * so that it is the same each time they are read.
+ *
+ * Encrypted fields are saved again with a condition on the value that was read, so that
+ * a value written since then is not overwritten.
+ */
+use crate::data::models::{
+    BotVersionRecord, Conversation, Direction, MemoryRecord, Message, StateRecord,
+};
+use crate::data::{to_serializable_bot, DynamoDbClient};
+use crate::db_connectors::dynamodb::{self as dynamo, bot, utils::get_table_name, DynamoDbKey};
+use crate::encrypt::{decrypt_data, encrypt_data};
+use crate::{Client, EngineError};
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::{
+    AttributeValue, DynamoDb, GetItemInput, ScanInput, UpdateItemError, UpdateItemInput,
+};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/**
+ * Items of the class with the `limit` smallest ids after `after`
+ */
+fn scan_records<T: DeserializeOwned>(
+    class: &str,
+    after: Option<Uuid>,
+    limit: u32,
+    id: fn(&T) -> Result<Uuid, EngineError>,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<(Uuid, T)>, EngineError> {
+    let expr_attr_names: HashMap<String, String> =
+        [(String::from("#class"), String::from("class"))]
+            .iter()
+            .cloned()
+            .collect();
+    let expr_attr_values: HashMap<String, AttributeValue> = [(
+        String::from(":class"),
+        AttributeValue {
+            s: Some(class.to_owned()),
+            ..Default::default()
+        },
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let mut records: Vec<(Uuid, T)> = vec![];
+    let mut pagination_key = None;
+
+    loop {
+        let input = ScanInput {
+            table_name: get_table_name()?,
+            filter_expression: Some("#class = :class".to_owned()),
+            expression_attribute_names: Some(expr_attr_names.clone()),
+            expression_attribute_values: Some(expr_attr_values.clone()),
+            exclusive_start_key: pagination_key,
+            ..Default::default()
+        };
+
+        let future = db.client.scan(input);
+        let data = db.runtime.block_on(future)?;
+
+        for item in data.items.unwrap_or_default() {
+            let record: T = serde_dynamodb::from_hashmap(item)?;
+            let record_id = id(&record)?;
+
+            if after.map_or(true, |after| record_id > after) {
+                records.push((record_id, record));
+            }
+        }
+
+        // only the smallest ids are kept from one page to the next
+        records.sort_by_key(|(id, _)| *id);
+        records.truncate(limit as usize);
+
+        pagination_key = data.last_evaluated_key;
+        if pagination_key.is_none() {
+            return Ok(records);
+        }
+    }
+}
+
+fn get_item_field(
+    key: &DynamoDbKey,
+    field: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Option<String>, EngineError> {
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let data = db.runtime.block_on(future)?;
+
+    Ok(data
+        .item
+        .and_then(|item| item.get(field).and_then(|value| value.s.to_owned())))
+}
+
+/**
+ * Set the `field` of an item to `value`, only if it still holds `saved`
+ */
+fn swap_field(
+    key: &DynamoDbKey,
+    field: &str,
+    saved: &str,
+    value: String,
+    db: &mut DynamoDbClient,
+) -> Result<usize, EngineError> {
+    let expr_attr_names: HashMap<String, String> = [(String::from("#field"), field.to_owned())]
+        .iter()
+        .cloned()
+        .collect();
+    let expr_attr_values: HashMap<String, AttributeValue> = [
+        (
+            String::from(":saved"),
+            AttributeValue {
+                s: Some(saved.to_owned()),
+                ..Default::default()
+            },
+        ),
+        (
+            String::from(":value"),
+            AttributeValue {
+                s: Some(value),
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = UpdateItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(key)?,
+        condition_expression: Some("#field = :saved".to_owned()),
+        update_expression: Some("SET #field = :value".to_owned()),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        ..Default::default()
+    };
+
+    let future = db.client.update_item(input);
+    match db.runtime.block_on(future) {
+        Ok(_) => Ok(1),
+        // changed or deleted since it was read
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => Ok(0),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/**
+ * Encrypt again the `field` of an item with the current key, if it still holds `value`
+ */
+fn swap_encrypted_field(
+    key: &DynamoDbKey,
+    field: &str,
+    value: &serde_json::Value,
+    db: &mut DynamoDbClient,
+) -> Result<usize, EngineError> {
+    let saved = match get_item_field(key, field, db)? {
+        Some(saved) if decrypt_data(saved.clone())? == *value => saved,
+        _ => return Ok(0),
+    };
+
+    swap_field(key, field, &saved, encrypt_data(value)?, db)
+}
+
+fn parse_date(date: &str) -> Result<DateTime<Utc>, EngineError> {
+    match DateTime::parse_from_rfc3339(date) {
+        Ok(date) => Ok(date.with_timezone(&Utc)),
+        Err(err) => Err(EngineError::Manager(format!(
+            "Invalid date {}: {}",
+            date, err
+        ))),
+    }
+}
+
+fn parse_expires_at(expires_at: Option<i64>) -> Option<DateTime<Utc>> {
+    expires_at
+        .and_then(|timestamp| chrono::NaiveDateTime::from_timestamp_opt(timestamp, 0))
+        .map(|date| date.and_utc())
+}
+
+/**
+ * Client of an item: older items only have its bot, channel and user ids
+ */
+fn item_client(
+    client: Option<Client>,
+    bot_id: Option<String>,
+    channel_id: Option<String>,
+    user_id: Option<String>,
+) -> Result<Client, EngineError> {
+    match (client, bot_id, channel_id, user_id) {
+        (Some(client), ..) => Ok(client),
+        (None, Some(bot_id), Some(channel_id), Some(user_id)) => {
+            Ok(Client::new(bot_id, channel_id, user_id))
+        }
+        _ => Err(EngineError::Manager("Item saved without client".to_owned())),
+    }
+}
+
+pub fn get_bot_version_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<BotVersionRecord>, EngineError> {
+    let items = scan_records::<dynamo::Bot>(
+        "bot",
+        after,
+        limit,
+        |bot| Ok(Uuid::parse_str(&bot.version_id)?),
+        db,
+    )?;
+
+    let mut records = vec![];
+    for (id, item) in items {
+        // the flows and modules of the version are kept in S3
+        let version = match bot::get_bot_by_version_id(&item.version_id, &item.id, db)? {
+            Some(version) => version,
+            None => continue,
+        };
+        let created_at = parse_date(&item.created_at)?;
+
+        records.push(BotVersionRecord {
+            id,
+            bot_id: item.id,
+            bot: serde_json::json!(to_serializable_bot(&version.bot)).to_string(),
+            engine_version: item.engine_version,
+            updated_at: created_at,
+            created_at,
+        });
+    }
+
+    Ok(records)
+}
+
+pub fn update_bot_version_records(
+    versions: &[BotVersionRecord],
+    db: &mut DynamoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for version in versions {
+        let key = DynamoDbKey::new(
+            &dynamo::Bot::get_hash(&version.bot_id),
+            &dynamo::Bot::get_range(&version.id.to_string()),
+        );
+        let saved = match get_item_field(&key, "bot", db)? {
+            Some(saved) => saved,
+            None => continue,
+        };
+        // bots saved with bincode by older versions have no encrypted field
+        let mut bot: serde_json::Value = match serde_json::from_str(&saved) {
+            Ok(bot) => bot,
+            Err(_) => continue,
+        };
+
+        // the flows and modules in S3 are not encrypted
+        let rotated: serde_json::Value = serde_json::from_str(&version.bot)?;
+        for field in ["env", "callback_secret"] {
+            if let Some(value) = rotated.get(field) {
+                bot[field] = value.to_owned();
+            }
+        }
+
+        updated += swap_field(&key, "bot", &saved, bot.to_string(), db)?;
+    }
+
+    Ok(updated)
+}
+
+pub fn get_conversation_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<Conversation>, EngineError> {
+    let items = scan_records::<dynamo::Conversation>(
+        "conversation",
+        after,
+        limit,
+        |conversation| Ok(Uuid::parse_str(&conversation.id)?),
+        db,
+    )?;
+
+    items
+        .into_iter()
+        .map(|(id, item)| {
+            Ok(Conversation {
+                id,
+                client: item_client(item.client, item.bot_id, item.channel_id, item.user_id)?,
+                flow_id: item.flow_id,
+                step_id: item.step_id,
+                status: item.status,
+                last_interaction_at: parse_date(&item.last_interaction_at)?,
+                updated_at: parse_date(&item.updated_at)?,
+                created_at: parse_date(&item.created_at)?,
+                expires_at: parse_expires_at(item.expires_at),
+            })
+        })
+        .collect()
+}
+
+pub fn get_message_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<Message>, EngineError> {
+    let items = scan_records::<dynamo::Message>(
+        "message",
+        after,
+        limit,
+        |message| Ok(Uuid::parse_str(&message.id)?),
+        db,
+    )?;
+
+    items
+        .into_iter()
+        .map(|(id, item)| {
+            let created_at = parse_date(&item.created_at)?;
+            // the content type is saved as a json string
+            let content_type =
+                serde_json::from_str::<String>(&item.content_type).unwrap_or(item.content_type);
+            let direction: Direction = serde_json::from_value(serde_json::json!(item.direction))?;
+
+            Ok(Message {
+                id,
+                conversation_id: Uuid::parse_str(&item.conversation_id)?,
+                flow_id: item.flow_id,
+                step_id: item.step_id,
+                message_order: item.message_order as u32,
+                interaction_order: item.interaction_order as u32,
+                direction,
+                content_type,
+                payload: decrypt_data(item.payload)?,
+                updated_at: created_at,
+                created_at,
+                expires_at: parse_expires_at(item.expires_at),
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct MessageKey {
+    hash: String,
+    range: String,
+    id: String,
+}
+
+/**
+ * Keys of the messages with these ids: the client, part of the key, is not in the records
+ */
+fn message_keys(
+    ids: &[Uuid],
+    db: &mut DynamoDbClient,
+) -> Result<HashMap<String, DynamoDbKey>, EngineError> {
+    let expr_attr_names: HashMap<String, String> = [
+        (String::from("#class"), String::from("class")),
+        (String::from("#hash"), String::from("hash")),
+        (String::from("#range"), String::from("range")),
+        (String::from("#id"), String::from("id")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let mut keys = HashMap::new();
+    // at most 100 values in an IN condition
+    for ids in ids.chunks(100) {
+        let mut expr_attr_values = HashMap::new();
+        expr_attr_values.insert(
+            String::from(":class"),
+            AttributeValue {
+                s: Some("message".to_owned()),
+                ..Default::default()
+            },
+        );
+
+        let mut names = vec![];
+        for (index, id) in ids.iter().enumerate() {
+            let name = format!(":id{}", index);
+            expr_attr_values.insert(
+                name.to_owned(),
+                AttributeValue {
+                    s: Some(id.to_string()),
+                    ..Default::default()
+                },
+            );
+            names.push(name);
+        }
+        let filter = format!("#class = :class AND #id IN ({})", names.join(", "));
+
+        let mut pagination_key = None;
+        loop {
+            let input = ScanInput {
+                table_name: get_table_name()?,
+                filter_expression: Some(filter.to_owned()),
+                projection_expression: Some("#hash, #range, #id".to_owned()),
+                expression_attribute_names: Some(expr_attr_names.clone()),
+                expression_attribute_values: Some(expr_attr_values.clone()),
+                exclusive_start_key: pagination_key,
+                ..Default::default()
+            };
+
+            let future = db.client.scan(input);
+            let data = db.runtime.block_on(future)?;
+
+            for item in data.items.unwrap_or_default() {
+                let item: MessageKey = serde_dynamodb::from_hashmap(item)?;
+                keys.insert(item.id, DynamoDbKey::new(&item.hash, &item.range));
+            }
+
+            pagination_key = data.last_evaluated_key;
+            if pagination_key.is_none() {
+                break;
+            }
+        }
+    }
+
+    Ok(keys)
+}
+
+pub fn update_message_records(
+    messages: &[Message],
+    db: &mut DynamoDbClient,
+) -> Result<usize, EngineError> {
+    let ids: Vec<Uuid> = messages.iter().map(|message| message.id).collect();
+    let keys = message_keys(&ids, db)?;
+
+    let mut updated = 0;
+    for message in messages {
+        if let Some(key) = keys.get(&message.id.to_string()) {
+            updated += swap_encrypted_field(key, "payload", &message.payload, db)?;
+        }
+    }
+
+    Ok(updated)
+}
+
+fn memory_id(memory: &dynamo::Memory) -> Result<Uuid, EngineError> {
+    let mut hash = Md5::new();
+    hash.update(memory.hash.as_bytes());
+    hash.update(b"#");
+    hash.update(memory.range.as_bytes());
+
+    Ok(uuid::Builder::from_md5_bytes(hash.finalize().into()).into_uuid())
+}
+
+pub fn get_memory_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<MemoryRecord>, EngineError> {
+    let items = scan_records::<dynamo::Memory>("memory", after, limit, memory_id, db)?;
+
+    items
+        .into_iter()
+        .map(|(id, item)| {
+            let created_at = parse_date(&item.created_at)?;
+            let value = match item.value {
+                Some(value) => decrypt_data(value)?,
+                None => serde_json::Value::Null,
+            };
+
+            Ok(MemoryRecord {
+                id,
+                client: item_client(item.client, item.bot_id, item.channel_id, item.user_id)?,
+                key: item.key,
+                value,
+                expires_at: parse_expires_at(item.expires_at),
+                updated_at: created_at,
+                created_at,
+            })
+        })
+        .collect()
+}
+
+pub fn update_memory_records(
+    memories: &[MemoryRecord],
+    db: &mut DynamoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for memory in memories {
+        let key = DynamoDbKey::new(
+            &dynamo::Memory::get_hash(&memory.client),
+            &dynamo::Memory::get_range(&memory.key),
+        );
+        updated += swap_encrypted_field(&key, "value", &memory.value, db)?;
+    }
+
+    Ok(updated)
+}
+
+pub fn get_state_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<StateRecord>, EngineError> {
+    let items = scan_records::<dynamo::State>(
+        "state",
+        after,
+        limit,
+        |state| Ok(Uuid::parse_str(&state.id)?),
+        db,
+    )?;
+
+    items
+        .into_iter()
+        .map(|(id, item)| {
+            let created_at = parse_date(&item.created_at)?;
+
+            Ok(StateRecord {
+                id,
+                client: item_client(item.client, item.bot_id, item.channel_id, item.user_id)?,
+                _type: item._type,
+                key: item.key,
+                value: decrypt_data(item.value)?,
+                expires_at: parse_expires_at(item.expires_at),
+                updated_at: created_at,
+                created_at,
+            })
+        })
+        .collect()
+}
+
+pub fn update_state_records(
+    state: &[StateRecord],
+    db: &mut DynamoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for item in state {
+        let key = DynamoDbKey::new(
+            &dynamo::State::get_hash(&item.client),
+            &dynamo::State::get_range(&item._type, &item.key),
+        );
+        updated += swap_encrypted_field(&key, "value", &item.value, db)?;
+    }
+
+    Ok(updated)
+}
@@ -1,3 +1,4 @@
+use crate::data::models::StateItem;
 use crate::data::DynamoDbClient;
 use crate::db_connectors::dynamodb::{DynamoDbKey, StatDeleteInfo, State};
 use crate::{
@@ -64,6 +65,50 @@ pub fn get_state_key(
     }
 }
 
+/**
+ * Every state key of the client
+ */
+pub fn get_client_state(
+    client: &Client,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<StateItem>, EngineError> {
+    let mut pagination_key = None;
+    let mut state = vec![];
+    let expr_attr_names: HashMap<String, String> = [
+        (String::from("#hashKey"), String::from("hash")),
+        (String::from("#rangeKey"), String::from("range")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    loop {
+        let data = query_states(
+            client,
+            db,
+            25,
+            pagination_key,
+            None,
+            Some(expr_attr_names.clone()),
+        )?;
+
+        for item in data.items.unwrap_or_default() {
+            let item: State = serde_dynamodb::from_hashmap(item)?;
+
+            state.push(StateItem {
+                _type: item._type,
+                key: item.key,
+                value: decrypt_data(item.value)?,
+            });
+        }
+
+        pagination_key = data.last_evaluated_key;
+        if pagination_key.is_none() {
+            return Ok(state);
+        }
+    }
+}
+
 pub fn get_current_state(
     client: &Client,
     db: &mut DynamoDbClient,
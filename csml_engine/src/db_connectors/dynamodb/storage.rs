@@ -0,0 +1,392 @@
+use crate::data::filter::ClientMessageFilter;
+use crate::data::models::{
+    BotVersionRecord, Conversation, Direction, MemoryRecord, Message, OutboxMessage, Paginated,
+    Schedule, StateItem, StateRecord,
+};
+use crate::data::storage::*;
+use crate::db_connectors::utils::get_expires_at_for_dynamodb;
+use crate::models::BotVersion;
+use crate::{Client, CsmlBot, DynamoDbClient, EngineError, Memory};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{bot, conversations, counters, get_pagination_key, memories, messages, records, state};
+
+impl ConversationStorage for DynamoDbClient {
+    fn create_conversation(
+        &mut self,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<Uuid, EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+        conversations::create_conversation(flow_id, step_id, client, expires_at, self)
+    }
+
+    fn close_conversation(&mut self, id: Uuid, client: &Client) -> Result<(), EngineError> {
+        conversations::close_conversation(&id.to_string(), client, "CLOSED", self)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::close_all_conversations(client, self)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<Conversation>, EngineError> {
+        conversations::get_latest_open(client, self)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: Uuid,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        conversations::update_conversation(
+            &conversation_id.to_string(),
+            client,
+            flow_id,
+            step_id,
+            self,
+        )
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<Paginated<Conversation>, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+        conversations::get_client_conversations(client, self, limit.map(i64::from), pagination_key)
+    }
+
+    fn delete_client_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::delete_user_conversations(client, self)
+    }
+
+    fn get_conversation_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Conversation>, EngineError> {
+        records::get_conversation_records(after, limit, self)
+    }
+}
+
+impl MessageStorage for DynamoDbClient {
+    fn add_messages_bulk(
+        &mut self,
+        step: &ConversationStep,
+        msgs: &[serde_json::Value],
+        interaction_order: i32,
+        direction: Direction,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+        messages::add_messages_bulk(
+            step,
+            msgs,
+            interaction_order,
+            direction.as_str(),
+            expires_at,
+            self,
+        )
+    }
+
+    fn get_client_messages(
+        &mut self,
+        filter: ClientMessageFilter<'_>,
+    ) -> Result<Paginated<Message>, EngineError> {
+        let ClientMessageFilter {
+            client,
+            limit,
+            pagination_key,
+            from_date,
+            to_date,
+            ..
+        } = filter;
+
+        let pagination_key = get_pagination_key(pagination_key)?;
+        match from_date {
+            Some(from_date) => messages::get_client_messages_from_date(
+                self,
+                Some(i64::from(limit)),
+                pagination_key,
+                from_date,
+                to_date,
+            ),
+            None => {
+                messages::get_client_messages(client, self, Some(i64::from(limit)), pagination_key)
+            }
+        }
+    }
+
+    fn delete_client_messages(&mut self, client: &Client) -> Result<(), EngineError> {
+        messages::delete_user_messages(client, self)
+    }
+
+    fn get_message_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Message>, EngineError> {
+        records::get_message_records(after, limit, self)
+    }
+
+    fn update_message_records(&mut self, messages: &[Message]) -> Result<usize, EngineError> {
+        records::update_message_records(messages, self)
+    }
+}
+
+impl MemoryStorage for DynamoDbClient {
+    fn add_memories(
+        &mut self,
+        client: &Client,
+        memories: &HashMap<String, Memory>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+        memories::add_memories(client, memories, expires_at, self)
+    }
+
+    fn create_client_memory(
+        &mut self,
+        client: &Client,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+        memories::create_client_memory(client, key.to_owned(), value.to_owned(), expires_at, self)
+    }
+
+    fn internal_use_get_memories(
+        &mut self,
+        client: &Client,
+    ) -> Result<serde_json::Value, EngineError> {
+        memories::internal_use_get_memories(client, self)
+    }
+
+    fn get_memories(&mut self, client: &Client) -> Result<serde_json::Value, EngineError> {
+        memories::get_memories(client, self)
+    }
+
+    fn get_memory(&mut self, client: &Client, key: &str) -> Result<serde_json::Value, EngineError> {
+        memories::get_memory(client, key, self)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        memories::delete_client_memory(client, key, self)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        memories::delete_client_memories(client, self)
+    }
+
+    fn get_memory_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<MemoryRecord>, EngineError> {
+        records::get_memory_records(after, limit, self)
+    }
+
+    fn update_memory_records(&mut self, memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        records::update_memory_records(memories, self)
+    }
+}
+
+impl StateStorage for DynamoDbClient {
+    fn get_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_state_key(client, _type, key, self)
+    }
+
+    fn get_current_state(
+        &mut self,
+        client: &Client,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_current_state(client, self)
+    }
+
+    fn set_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        keys_values: Vec<(&str, &serde_json::Value)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+        state::set_state_items(client, _type, keys_values, expires_at, self)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        state::delete_state_key(client, _type, key, self)
+    }
+
+    fn delete_client_state(&mut self, client: &Client) -> Result<(), EngineError> {
+        state::delete_user_state(client, self)
+    }
+
+    fn get_client_state(&mut self, client: &Client) -> Result<Vec<StateItem>, EngineError> {
+        state::get_client_state(client, self)
+    }
+
+    fn get_state_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<StateRecord>, EngineError> {
+        records::get_state_records(after, limit, self)
+    }
+
+    fn update_state_records(&mut self, state: &[StateRecord]) -> Result<usize, EngineError> {
+        records::update_state_records(state, self)
+    }
+}
+
+impl CounterStorage for DynamoDbClient {
+    fn get_counter(&mut self, client: &Client, key: &str) -> Result<i64, EngineError> {
+        counters::get_counter(client, key, self)
+    }
+
+    fn increment_counter(
+        &mut self,
+        client: &Client,
+        key: &str,
+        count: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<i64, EngineError> {
+        let expires_at = get_expires_at_for_dynamodb(ttl);
+        counters::increment_counter(client, key, count, expires_at, self)
+    }
+
+    fn delete_client_counters(&mut self, client: &Client) -> Result<(), EngineError> {
+        counters::delete_client_counters(client, self)
+    }
+}
+
+impl BotStorage for DynamoDbClient {
+    fn create_bot_version(
+        &mut self,
+        bot_id: String,
+        csml_bot: CsmlBot,
+    ) -> Result<String, EngineError> {
+        let dynamo_bot = crate::data::to_dynamo_bot(&csml_bot);
+
+        let flows = serde_json::json!(&csml_bot.flows);
+        let flow_modules = match csml_bot.modules {
+            Some(ref modules) => serde_json::json!(&modules),
+            None => {
+                let modules: Vec<csml_interpreter::data::csml_bot::Module> = vec![];
+
+                serde_json::json!(modules)
+            }
+        };
+
+        let bot = serde_json::json!(dynamo_bot).to_string();
+
+        bot::create_bot_version(
+            bot_id,
+            bot,
+            flows.to_string(),
+            flow_modules.to_string(),
+            self,
+        )
+    }
+
+    fn get_last_bot_version(&mut self, bot_id: &str) -> Result<Option<BotVersion>, EngineError> {
+        bot::get_last_bot_version(bot_id, self)
+    }
+
+    fn get_bot_by_version_id(
+        &mut self,
+        version_id: &str,
+        bot_id: &str,
+    ) -> Result<Option<BotVersion>, EngineError> {
+        bot::get_bot_by_version_id(version_id, bot_id, self)
+    }
+
+    fn get_bot_versions(
+        &mut self,
+        bot_id: &str,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<serde_json::Value, EngineError> {
+        let pagination_key = get_pagination_key(pagination_key)?;
+        bot::get_bot_versions(bot_id, limit.map(i64::from), pagination_key, self)
+    }
+
+    fn delete_bot_version(&mut self, bot_id: &str, version_id: &str) -> Result<(), EngineError> {
+        bot::delete_bot_version(bot_id, version_id, self)
+    }
+
+    fn delete_bot_versions(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        bot::delete_bot_versions(bot_id, self)
+    }
+
+    fn get_bot_version_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<BotVersionRecord>, EngineError> {
+        records::get_bot_version_records(after, limit, self)
+    }
+
+    fn update_bot_version_records(
+        &mut self,
+        versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        records::update_bot_version_records(versions, self)
+    }
+}
+
+/**
+ * Scheduled flow triggers and the callback outbox are not supported with DynamoDB:
+ * a migration or a key rotation has none to read
+ */
+impl ScheduleStorage for DynamoDbClient {
+    fn get_schedule_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        Ok(vec![])
+    }
+}
+
+impl OutboxStorage for DynamoDbClient {
+    fn get_outbox_records(
+        &mut self,
+        _after: Option<Uuid>,
+        _limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        Ok(vec![])
+    }
+}
+
+impl ApiKeyStorage for DynamoDbClient {}
+
+impl AnalyticsStorage for DynamoDbClient {}
+
+impl StorageBackend for DynamoDbClient {
+    fn delete_all_bot_data(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        bot::delete_bot_versions(bot_id, self)?;
+        bot::delete_all_bot_data(bot_id, "memory", self)?;
+        bot::delete_all_bot_data(bot_id, "message", self)?;
+        bot::delete_all_bot_data(bot_id, "conversation", self)?;
+        bot::delete_all_bot_data(bot_id, "state", self)?;
+        bot::delete_all_bot_data(bot_id, "counter", self)
+    }
+}
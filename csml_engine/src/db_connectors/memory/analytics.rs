@@ -0,0 +1,186 @@
+use crate::data::filter::AnalyticsFilter;
+use crate::data::models::{
+    Conversation, Direction, FlowAnalytics, HoldAnalytics, Message, StepTransition, TurnAnalytics,
+};
+use crate::{EngineError, MemoryClient};
+use chrono::Utc;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{
+    lock_store,
+    models::{is_expired, MemoryStore},
+};
+
+/**
+ * Conversations of the period, each one with its messages in the order they were saved
+ */
+fn get_conversations<'a>(
+    store: &'a MemoryStore,
+    filter: &AnalyticsFilter,
+) -> Vec<(&'a Conversation, Vec<&'a Message>)> {
+    let now = Utc::now();
+
+    let conversations: Vec<&Conversation> = store
+        .conversations
+        .iter()
+        .filter(|conv| {
+            conv.client.bot_id == filter.bot_id
+                && conv.created_at >= filter.from_date
+                && conv.created_at < filter.to_date
+                && !is_expired(&conv.expires_at, &now)
+        })
+        .collect();
+
+    let mut messages: HashMap<_, Vec<&Message>> =
+        conversations.iter().map(|conv| (conv.id, vec![])).collect();
+    for saved in store.messages.iter() {
+        if is_expired(&saved.message.expires_at, &now) {
+            continue;
+        }
+
+        if let Some(conversation) = messages.get_mut(&saved.message.conversation_id) {
+            conversation.push(&saved.message);
+        }
+    }
+
+    conversations
+        .into_iter()
+        .map(|conv| (conv, messages.remove(&conv.id).unwrap_or_default()))
+        .collect()
+}
+
+pub fn get_flow_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut MemoryClient,
+) -> Result<Vec<FlowAnalytics>, EngineError> {
+    let store = lock_store(db)?;
+
+    // (started, ended) of each flow
+    let mut flows: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for (conversation, messages) in get_conversations(&store, filter) {
+        if let Some(first) = messages.first() {
+            flows.entry(first.flow_id.to_owned()).or_default().0 += 1;
+        }
+        if conversation.status == "CLOSED" {
+            flows.entry(conversation.flow_id.to_owned()).or_default().1 += 1;
+        }
+    }
+
+    Ok(flows
+        .into_iter()
+        .map(|(flow_id, (started, ended))| FlowAnalytics {
+            flow_id,
+            started,
+            ended,
+        })
+        .collect())
+}
+
+pub fn get_hold_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut MemoryClient,
+) -> Result<Vec<HoldAnalytics>, EngineError> {
+    let store = lock_store(db)?;
+
+    // (answered, abandoned) of each step
+    let mut steps: BTreeMap<(String, String), (u64, u64)> = BTreeMap::new();
+    for (conversation, messages) in get_conversations(&store, filter) {
+        // the first input of a conversation does not answer a hold
+        for message in messages.iter().skip(1) {
+            if message.direction == Direction::Receive {
+                let step = (message.flow_id.to_owned(), message.step_id.to_owned());
+                steps.entry(step).or_default().0 += 1;
+            }
+        }
+        // a conversation is abandoned once it has no activity, not while it waits for an answer
+        let last_activity = messages
+            .iter()
+            .map(|message| message.created_at)
+            .fold(conversation.last_interaction_at, |last, saved_at| {
+                last.max(saved_at)
+            });
+        if conversation.status == "OPEN" && last_activity < filter.abandoned_before {
+            let step = (
+                conversation.flow_id.to_owned(),
+                conversation.step_id.to_owned(),
+            );
+            steps.entry(step).or_default().1 += 1;
+        }
+    }
+
+    Ok(steps
+        .into_iter()
+        .map(
+            |((flow_id, step_id), (answered, abandoned))| HoldAnalytics {
+                flow_id,
+                step_id,
+                answered,
+                abandoned,
+            },
+        )
+        .collect())
+}
+
+pub fn get_turn_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut MemoryClient,
+) -> Result<TurnAnalytics, EngineError> {
+    let store = lock_store(db)?;
+
+    let (mut conversations, mut turns) = (0, 0);
+    for (_, messages) in get_conversations(&store, filter) {
+        if messages.is_empty() {
+            continue;
+        }
+
+        conversations += 1;
+        turns += messages
+            .iter()
+            .filter(|message| message.direction == Direction::Receive)
+            .count() as u64;
+    }
+
+    Ok(TurnAnalytics::new(conversations, turns))
+}
+
+pub fn get_step_transitions(
+    filter: &AnalyticsFilter,
+    db: &mut MemoryClient,
+) -> Result<Vec<StepTransition>, EngineError> {
+    let store = lock_store(db)?;
+
+    let mut transitions: BTreeMap<(&str, &str, &str, &str), u64> = BTreeMap::new();
+    for (_, messages) in get_conversations(&store, filter) {
+        for pair in messages.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+
+            if (&from.flow_id, &from.step_id) != (&to.flow_id, &to.step_id) {
+                let transition = (
+                    from.flow_id.as_str(),
+                    from.step_id.as_str(),
+                    to.flow_id.as_str(),
+                    to.step_id.as_str(),
+                );
+                *transitions.entry(transition).or_default() += 1;
+            }
+        }
+    }
+
+    let mut transitions: Vec<StepTransition> = transitions
+        .into_iter()
+        .map(
+            |((from_flow, from_step, to_flow, to_step), count)| StepTransition {
+                from_flow: from_flow.to_owned(),
+                from_step: from_step.to_owned(),
+                to_flow: to_flow.to_owned(),
+                to_step: to_step.to_owned(),
+                count,
+            },
+        )
+        .collect();
+    // most frequent first, the sort is stable so ties stay in the order of the steps
+    transitions.sort_by_key(|transition| Reverse(transition.count));
+
+    Ok(transitions)
+}
@@ -0,0 +1,49 @@
+use crate::data::models::ApiKey;
+use crate::{EngineError, MemoryClient};
+use uuid::Uuid;
+
+use super::{lock_store, models};
+
+pub fn create_api_key(
+    api_key: &ApiKey,
+    key_hash: &str,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.api_keys.push(models::ApiKey {
+        key_hash: key_hash.to_owned(),
+        api_key: api_key.to_owned(),
+    });
+
+    Ok(())
+}
+
+pub fn get_api_key(key_hash: &str, db: &mut MemoryClient) -> Result<Option<ApiKey>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(store
+        .api_keys
+        .iter()
+        .find(|saved| saved.key_hash == key_hash)
+        .map(|saved| saved.api_key.to_owned()))
+}
+
+pub fn get_api_keys(db: &mut MemoryClient) -> Result<Vec<ApiKey>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(store
+        .api_keys
+        .iter()
+        .map(|saved| saved.api_key.to_owned())
+        .collect())
+}
+
+pub fn delete_api_key(id: Uuid, db: &mut MemoryClient) -> Result<bool, EngineError> {
+    let mut store = lock_store(db)?;
+    let count = store.api_keys.len();
+
+    store.api_keys.retain(|saved| saved.api_key.id != id);
+
+    Ok(store.api_keys.len() != count)
+}
@@ -0,0 +1,207 @@
+use crate::data::models::BotVersionRecord;
+use crate::db_connectors::utils::paginate;
+use crate::{EngineError, MemoryClient, SerializeCsmlBot};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{
+    lock_store,
+    models::{self, records_after},
+};
+
+use crate::models::BotVersion;
+use std::env;
+
+pub fn create_bot_version(
+    bot_id: String,
+    bot: String,
+    db: &mut MemoryClient,
+) -> Result<String, EngineError> {
+    let mut store = lock_store(db)?;
+    let id = Uuid::new_v4();
+
+    store.bots.push(models::Bot {
+        id,
+        bot_id,
+        bot,
+        engine_version: env!("CARGO_PKG_VERSION").to_owned(),
+        created_at: Utc::now(),
+    });
+
+    Ok(id.to_string())
+}
+
+pub fn get_bot_versions(
+    bot_id: &str,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+    db: &mut MemoryClient,
+) -> Result<serde_json::Value, EngineError> {
+    let store = lock_store(db)?;
+    let pagination_key = pagination_key.unwrap_or(1);
+    let limit_per_page = limit.unwrap_or(25).min(25);
+
+    // versions are stored in creation order, newest versions come first
+    let bot_versions: Vec<&models::Bot> = store
+        .bots
+        .iter()
+        .rev()
+        .filter(|bot| bot.bot_id == bot_id)
+        .collect();
+
+    let (bot_versions, total_pages) = paginate(bot_versions, pagination_key, limit_per_page);
+
+    let mut bots = vec![];
+    for bot_version in bot_versions {
+        let csml_bot: SerializeCsmlBot = serde_json::from_str(&bot_version.bot)?;
+
+        let mut json = serde_json::json!({
+            "version_id": bot_version.id,
+            "id": csml_bot.id,
+            "name": csml_bot.name,
+            "default_flow": csml_bot.default_flow,
+            "engine_version": bot_version.engine_version,
+            "created_at": bot_version.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()
+        });
+
+        if let Some(custom_components) = csml_bot.custom_components {
+            json["custom_components"] = serde_json::json!(custom_components);
+        }
+
+        bots.push(json);
+    }
+
+    match pagination_key < total_pages {
+        true => {
+            let pagination_key = (pagination_key + 1).to_string();
+            Ok(serde_json::json!({"bots": bots, "pagination_key": pagination_key}))
+        }
+        false => Ok(serde_json::json!({ "bots": bots })),
+    }
+}
+
+fn to_bot_version(bot: &models::Bot) -> Result<BotVersion, EngineError> {
+    let csml_bot: SerializeCsmlBot = serde_json::from_str(&bot.bot)?;
+
+    Ok(BotVersion {
+        bot: csml_bot.to_bot(),
+        version_id: bot.id.to_string(),
+        engine_version: env!("CARGO_PKG_VERSION").to_owned(),
+    })
+}
+
+pub fn get_bot_by_version_id(
+    id: &str,
+    db: &mut MemoryClient,
+) -> Result<Option<BotVersion>, EngineError> {
+    let version_id = match Uuid::parse_str(id) {
+        Ok(version_id) => version_id,
+        Err(..) => return Ok(None),
+    };
+
+    let store = lock_store(db)?;
+
+    match store.bots.iter().find(|bot| bot.id == version_id) {
+        Some(bot) => Ok(Some(to_bot_version(bot)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn get_last_bot_version(
+    bot_id: &str,
+    db: &mut MemoryClient,
+) -> Result<Option<BotVersion>, EngineError> {
+    let store = lock_store(db)?;
+
+    match store.bots.iter().rev().find(|bot| bot.bot_id == bot_id) {
+        Some(bot) => Ok(Some(to_bot_version(bot)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete_bot_version(version_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let id = match Uuid::parse_str(version_id) {
+        Ok(id) => id,
+        Err(..) => return Ok(()),
+    };
+
+    let mut store = lock_store(db)?;
+    store.bots.retain(|bot| bot.id != id);
+
+    Ok(())
+}
+
+pub fn delete_bot_versions(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+    store.bots.retain(|bot| bot.bot_id != bot_id);
+
+    Ok(())
+}
+
+pub fn get_bot_version_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<BotVersionRecord>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.bots,
+        |bot| bot.id,
+        after,
+        limit,
+        |bot| BotVersionRecord {
+            id: bot.id,
+            bot_id: bot.bot_id.to_owned(),
+            bot: bot.bot.to_owned(),
+            engine_version: bot.engine_version.to_owned(),
+            updated_at: bot.created_at,
+            created_at: bot.created_at,
+        },
+    ))
+}
+
+pub fn add_bot_version_records(
+    versions: &[BotVersionRecord],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut created = 0;
+
+    for record in versions {
+        if store.bots.iter().any(|bot| bot.id == record.id) {
+            continue;
+        }
+
+        store.bots.push(models::Bot {
+            id: record.id,
+            bot_id: record.bot_id.to_owned(),
+            bot: record.bot.to_owned(),
+            engine_version: record.engine_version.to_owned(),
+            created_at: record.created_at,
+        });
+        created += 1;
+    }
+
+    // the last version of a bot is the last one of the store
+    store.bots.sort_by_key(|bot| bot.created_at);
+
+    Ok(created)
+}
+
+pub fn update_bot_version_records(
+    versions: &[BotVersionRecord],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut updated = 0;
+
+    for record in versions {
+        if let Some(bot) = store.bots.iter_mut().find(|bot| bot.id == record.id) {
+            bot.bot = record.bot.to_owned();
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,239 @@
+use crate::data::models::{Conversation, PaginationData};
+use crate::db_connectors::utils::paginate;
+use crate::{data, Client, EngineError, MemoryClient};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{
+    lock_store,
+    models::{is_expired, records_after},
+};
+
+pub fn create_conversation(
+    flow_id: &str,
+    step_id: &str,
+    client: &Client,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut MemoryClient,
+) -> Result<Uuid, EngineError> {
+    let mut store = lock_store(db)?;
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+
+    store.conversations.push(Conversation {
+        id,
+        client: client.to_owned(),
+        flow_id: flow_id.to_owned(),
+        step_id: step_id.to_owned(),
+        status: "OPEN".to_owned(),
+        last_interaction_at: now,
+        updated_at: now,
+        created_at: now,
+        expires_at,
+    });
+
+    Ok(id)
+}
+
+pub fn close_conversation(
+    id: Uuid,
+    _client: &Client,
+    status: &str,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+    let now = Utc::now();
+
+    for conversation in store.conversations.iter_mut().filter(|conv| conv.id == id) {
+        conversation.status = status.to_owned();
+        conversation.updated_at = now;
+    }
+
+    Ok(())
+}
+
+pub fn close_all_conversations(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+    let now = Utc::now();
+
+    for conversation in store
+        .conversations
+        .iter_mut()
+        .filter(|conv| conv.client == *client)
+    {
+        conversation.status = "CLOSED".to_owned();
+        conversation.updated_at = now;
+    }
+
+    Ok(())
+}
+
+pub fn get_latest_open(
+    client: &Client,
+    db: &mut MemoryClient,
+) -> Result<Option<Conversation>, EngineError> {
+    let store = lock_store(db)?;
+    let now = Utc::now();
+
+    let conversation = store
+        .conversations
+        .iter()
+        .filter(|conv| conv.client == *client && conv.status == "OPEN")
+        .filter(|conv| !is_expired(&conv.expires_at, &now))
+        .max_by_key(|conv| conv.updated_at);
+
+    Ok(conversation.cloned())
+}
+
+pub fn update_conversation(
+    conversation_id: Uuid,
+    flow_id: Option<String>,
+    step_id: Option<String>,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    if flow_id.is_none() && step_id.is_none() {
+        return Ok(());
+    }
+
+    let mut store = lock_store(db)?;
+    let now = Utc::now();
+
+    if let Some(conversation) = store
+        .conversations
+        .iter_mut()
+        .find(|conv| conv.id == conversation_id)
+    {
+        if let Some(flow_id) = flow_id {
+            conversation.flow_id = flow_id;
+        }
+        if let Some(step_id) = step_id {
+            conversation.step_id = step_id;
+        }
+
+        conversation.last_interaction_at = now;
+        conversation.updated_at = now;
+    }
+
+    Ok(())
+}
+
+pub fn delete_user_conversations(
+    client: &Client,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.conversations.retain(|conv| conv.client != *client);
+
+    Ok(())
+}
+
+pub fn get_client_conversations(
+    client: &Client,
+    db: &mut MemoryClient,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+) -> Result<data::models::Paginated<Conversation>, EngineError> {
+    let store = lock_store(db)?;
+    let now = Utc::now();
+    let pagination_key = pagination_key.unwrap_or(1);
+    let limit_per_page = limit.unwrap_or(25).min(25);
+
+    let mut conversations: Vec<Conversation> = store
+        .conversations
+        .iter()
+        .filter(|conv| conv.client == *client)
+        .filter(|conv| !is_expired(&conv.expires_at, &now))
+        .cloned()
+        .collect();
+    conversations.sort_by_key(|conv| std::cmp::Reverse(conv.updated_at));
+
+    let (convs, total_pages) = paginate(conversations, pagination_key, limit_per_page);
+
+    let pagination = (pagination_key < total_pages).then_some(PaginationData {
+        page: pagination_key,
+        total_pages,
+        per_page: limit_per_page,
+    });
+    Ok(data::models::Paginated {
+        data: convs,
+        pagination,
+    })
+}
+
+pub fn get_bot_clients(bot_id: &str, db: &mut MemoryClient) -> Result<Vec<Client>, EngineError> {
+    let store = lock_store(db)?;
+
+    let mut clients: Vec<Client> = vec![];
+    for conversation in store.conversations.iter() {
+        if conversation.client.bot_id == bot_id && !clients.contains(&conversation.client) {
+            clients.push(conversation.client.to_owned());
+        }
+    }
+
+    Ok(clients)
+}
+
+pub fn get_conversation(
+    db: &mut MemoryClient,
+    id: Uuid,
+) -> Result<data::models::Conversation, EngineError> {
+    let store = lock_store(db)?;
+
+    match store.conversations.iter().find(|conv| conv.id == id) {
+        Some(conversation) => Ok(conversation.to_owned()),
+        None => Err(EngineError::Manager(format!(
+            "conversation ({}) not found in db",
+            id
+        ))),
+    }
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .conversations
+        .retain(|conv| conv.client.bot_id != bot_id);
+
+    Ok(())
+}
+
+pub fn get_conversation_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<Conversation>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.conversations,
+        |conv| conv.id,
+        after,
+        limit,
+        Conversation::to_owned,
+    ))
+}
+
+pub fn add_conversation_records(
+    conversations: &[Conversation],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut created = 0;
+
+    for conversation in conversations {
+        if store
+            .conversations
+            .iter()
+            .any(|conv| conv.id == conversation.id)
+        {
+            continue;
+        }
+
+        store.conversations.push(conversation.to_owned());
+        created += 1;
+    }
+
+    Ok(created)
+}
@@ -0,0 +1,75 @@
+use crate::{Client, EngineError, MemoryClient};
+use chrono::{DateTime, Utc};
+
+use super::{
+    lock_store,
+    models::{self, is_expired},
+};
+
+pub fn get_counter(client: &Client, key: &str, db: &mut MemoryClient) -> Result<i64, EngineError> {
+    let store = lock_store(db)?;
+    let now = Utc::now();
+
+    let counter = store.counters.iter().find(|counter| {
+        counter.client == *client && counter.key == key && !is_expired(&counter.expires_at, &now)
+    });
+
+    Ok(counter.map_or(0, |counter| counter.count))
+}
+
+pub fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut MemoryClient,
+) -> Result<i64, EngineError> {
+    let mut store = lock_store(db)?;
+    let now = Utc::now();
+
+    // the store stays locked: no other increment can happen in the meantime
+    store.counters.retain(|counter| {
+        !(counter.client == *client && counter.key == key && is_expired(&counter.expires_at, &now))
+    });
+
+    match store
+        .counters
+        .iter_mut()
+        .find(|counter| counter.client == *client && counter.key == key)
+    {
+        Some(counter) => {
+            counter.count += count;
+            counter.expires_at = expires_at;
+
+            Ok(counter.count)
+        }
+        None => {
+            store.counters.push(models::Counter {
+                client: client.to_owned(),
+                key: key.to_owned(),
+                count,
+                expires_at,
+            });
+
+            Ok(count)
+        }
+    }
+}
+
+pub fn delete_client_counters(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.counters.retain(|counter| counter.client != *client);
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .counters
+        .retain(|counter| counter.client.bot_id != bot_id);
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::{EngineError, MemoryClient};
+
+use super::{lock_store, models::is_expired};
+
+pub fn delete_expired_data(db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+    let date_now = chrono::Utc::now();
+
+    store
+        .conversations
+        .retain(|conv| !is_expired(&conv.expires_at, &date_now));
+    store
+        .messages
+        .retain(|msg| !is_expired(&msg.message.expires_at, &date_now));
+    store
+        .memories
+        .retain(|mem| !is_expired(&mem.expires_at, &date_now));
+    store
+        .states
+        .retain(|state| !is_expired(&state.expires_at, &date_now));
+    store
+        .counters
+        .retain(|counter| !is_expired(&counter.expires_at, &date_now));
+
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use crate::data::models::MemoryRecord;
+use crate::{Client, EngineError, Memory, MemoryClient};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{
+    lock_store,
+    models::{self, is_expired, records_after},
+};
+
+pub fn add_memories(
+    client: &Client,
+    memories: &HashMap<String, Memory>,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    if memories.is_empty() {
+        return Ok(());
+    }
+
+    for (key, mem) in memories.iter() {
+        create_client_memory(client, key, &mem.value, expires_at, db)?;
+    }
+
+    Ok(())
+}
+
+pub fn create_client_memory(
+    client: &Client,
+    key: &str,
+    value: &serde_json::Value,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    match store
+        .memories
+        .iter_mut()
+        .find(|mem| mem.client == *client && mem.key == key)
+    {
+        Some(memory) => {
+            memory.value = value.to_owned();
+            memory.expires_at = expires_at;
+            memory.updated_at = Utc::now();
+        }
+        None => store.memories.push(models::Memory {
+            id: Uuid::new_v4(),
+            client: client.to_owned(),
+            key: key.to_owned(),
+            value: value.to_owned(),
+            expires_at,
+            updated_at: Utc::now(),
+            created_at: Utc::now(),
+        }),
+    }
+
+    Ok(())
+}
+
+fn client_memories<'a>(
+    client: &'a Client,
+    memories: &'a [models::Memory],
+) -> impl Iterator<Item = &'a models::Memory> {
+    let now = Utc::now();
+
+    memories
+        .iter()
+        .filter(move |mem| mem.client == *client && !is_expired(&mem.expires_at, &now))
+}
+
+fn format_memory(mem: &models::Memory) -> serde_json::Map<String, serde_json::Value> {
+    let mut memory = serde_json::Map::new();
+
+    memory.insert("key".to_owned(), serde_json::json!(mem.key));
+    memory.insert("value".to_owned(), mem.value.to_owned());
+    memory.insert(
+        "created_at".to_owned(),
+        serde_json::json!(mem.created_at.naive_utc().to_string()),
+    );
+
+    memory
+}
+
+pub fn internal_use_get_memories(
+    client: &Client,
+    db: &mut MemoryClient,
+) -> Result<serde_json::Value, EngineError> {
+    let store = lock_store(db)?;
+
+    let mut map = serde_json::Map::new();
+    for mem in client_memories(client, &store.memories) {
+        if !map.contains_key(&mem.key) {
+            map.insert(mem.key.to_owned(), mem.value.to_owned());
+        }
+    }
+
+    Ok(serde_json::json!(map))
+}
+
+pub fn get_memories(
+    client: &Client,
+    db: &mut MemoryClient,
+) -> Result<serde_json::Value, EngineError> {
+    let store = lock_store(db)?;
+
+    let vec: Vec<_> = client_memories(client, &store.memories)
+        .map(format_memory)
+        .collect();
+
+    Ok(serde_json::json!(vec))
+}
+
+pub fn get_memory(
+    client: &Client,
+    key: &str,
+    db: &mut MemoryClient,
+) -> Result<serde_json::Value, EngineError> {
+    let store = lock_store(db)?;
+
+    let mut memories = client_memories(client, &store.memories);
+    match memories.find(|mem| mem.key == key) {
+        Some(mem) => Ok(serde_json::json!(format_memory(mem))),
+        None => Err(EngineError::Manager(format!(
+            "memory ({}) not found in db",
+            key
+        ))),
+    }
+}
+
+pub fn delete_client_memory(
+    client: &Client,
+    key: &str,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .memories
+        .retain(|mem| !(mem.client == *client && mem.key == key));
+
+    Ok(())
+}
+
+pub fn delete_client_memories(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.memories.retain(|mem| mem.client != *client);
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.memories.retain(|mem| mem.client.bot_id != bot_id);
+
+    Ok(())
+}
+
+pub fn get_memory_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<MemoryRecord>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.memories,
+        |mem| mem.id,
+        after,
+        limit,
+        |mem| MemoryRecord {
+            id: mem.id,
+            client: mem.client.to_owned(),
+            key: mem.key.to_owned(),
+            value: mem.value.to_owned(),
+            expires_at: mem.expires_at,
+            updated_at: mem.updated_at,
+            created_at: mem.created_at,
+        },
+    ))
+}
+
+pub fn add_memory_records(
+    memories: &[MemoryRecord],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut created = 0;
+
+    for record in memories {
+        // a client has a single memory by key
+        if store.memories.iter().any(|mem| {
+            mem.id == record.id || (mem.client == record.client && mem.key == record.key)
+        }) {
+            continue;
+        }
+
+        store.memories.push(models::Memory {
+            id: record.id,
+            client: record.client.to_owned(),
+            key: record.key.to_owned(),
+            value: record.value.to_owned(),
+            expires_at: record.expires_at,
+            updated_at: record.updated_at,
+            created_at: record.created_at,
+        });
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+pub fn update_memory_records(
+    memories: &[MemoryRecord],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let store = lock_store(db)?;
+    let mut updated = 0;
+
+    for record in memories {
+        // values are not encrypted in memory: a memory is left as it is, and only counted
+        // if it was not changed since it was read
+        if store
+            .memories
+            .iter()
+            .any(|mem| mem.id == record.id && mem.value == record.value)
+        {
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,219 @@
+use crate::data::filter::ClientMessageFilter;
+use crate::data::models::{Direction, PaginationData};
+use crate::data::storage::ConversationStep;
+use crate::db_connectors::utils::{missing_conversation, paginate};
+use crate::{data, Client, EngineError, MemoryClient};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use uuid::Uuid;
+
+use super::{
+    lock_store,
+    models::{self, is_expired, records_after},
+};
+
+pub fn add_messages_bulk(
+    step: &ConversationStep,
+    msgs: &[serde_json::Value],
+    interaction_order: i32,
+    direction: Direction,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    if msgs.is_empty() {
+        return Ok(());
+    }
+
+    let mut store = lock_store(db)?;
+    let now = Utc::now();
+
+    for (message_order, message) in msgs.iter().enumerate() {
+        store.messages.push(models::Message {
+            client: step.client.to_owned(),
+            message: data::models::Message {
+                id: Uuid::new_v4(),
+                conversation_id: step.conversation_id,
+                flow_id: step.flow_id.to_owned(),
+                step_id: step.step_id.to_owned(),
+                message_order: message_order as u32,
+                interaction_order: interaction_order as u32,
+                direction: direction.to_owned(),
+                content_type: message["content_type"]
+                    .as_str()
+                    .unwrap_or("text")
+                    .to_owned(),
+                payload: message.to_owned(),
+                updated_at: now,
+                created_at: now,
+                expires_at,
+            },
+        });
+    }
+
+    Ok(())
+}
+
+pub fn delete_user_messages(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.messages.retain(|msg| msg.client != *client);
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.messages.retain(|msg| msg.client.bot_id != bot_id);
+
+    Ok(())
+}
+
+fn get_date(timestamp: i64) -> Result<DateTime<Utc>, EngineError> {
+    NaiveDateTime::from_timestamp_opt(timestamp, 0)
+        .map(|date| date.and_utc())
+        .ok_or(EngineError::DateTimeError(
+            "Date time is out of range".to_owned(),
+        ))
+}
+
+pub fn get_client_messages(
+    db: &mut MemoryClient,
+    filter: ClientMessageFilter,
+) -> Result<data::models::Paginated<data::models::Message>, EngineError> {
+    let ClientMessageFilter {
+        client,
+        limit,
+        pagination_key,
+        from_date,
+        to_date,
+        conversation_id,
+    } = filter;
+
+    let pagination_key = pagination_key.unwrap_or(1);
+    let now = Utc::now();
+
+    let dates = match from_date {
+        Some(from_date) => {
+            let to_date = match to_date {
+                Some(to_date) => get_date(to_date)?,
+                None => now,
+            };
+
+            Some((get_date(from_date)?, to_date))
+        }
+        None => None,
+    };
+
+    let store = lock_store(db)?;
+
+    let mut messages: Vec<data::models::Message> = store
+        .messages
+        .iter()
+        .filter(|msg| msg.client == *client)
+        .map(|msg| &msg.message)
+        .filter(|msg| !is_expired(&msg.expires_at, &now))
+        .filter(|msg| match conversation_id {
+            Some(conversation_id) => msg.conversation_id == conversation_id,
+            None => true,
+        })
+        .filter(|msg| match dates {
+            Some((from_date, to_date)) => msg.created_at >= from_date && msg.created_at <= to_date,
+            None => true,
+        })
+        .cloned()
+        .collect();
+
+    messages.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then(b.message_order.cmp(&a.message_order))
+    });
+
+    let (msgs, total_pages) = paginate(messages, pagination_key, limit);
+
+    let pagination = (pagination_key < total_pages).then_some(PaginationData {
+        page: pagination_key,
+        total_pages,
+        per_page: limit,
+    });
+    Ok(data::models::Paginated {
+        data: msgs,
+        pagination,
+    })
+}
+
+pub fn get_message_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<data::models::Message>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.messages,
+        |msg| msg.message.id,
+        after,
+        limit,
+        |msg| msg.message.to_owned(),
+    ))
+}
+
+pub fn add_message_records(
+    messages: &[data::models::Message],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut rows = vec![];
+
+    for message in messages {
+        if store
+            .messages
+            .iter()
+            .chain(rows.iter())
+            .any(|msg: &models::Message| msg.message.id == message.id)
+        {
+            continue;
+        }
+
+        // messages are saved with the client of their conversation
+        let client = match store
+            .conversations
+            .iter()
+            .find(|conv| conv.id == message.conversation_id)
+        {
+            Some(conversation) => conversation.client.to_owned(),
+            None => return Err(missing_conversation(message)),
+        };
+
+        rows.push(models::Message {
+            client,
+            message: message.to_owned(),
+        });
+    }
+
+    let created = rows.len();
+    store.messages.extend(rows);
+
+    Ok(created)
+}
+
+pub fn update_message_records(
+    messages: &[data::models::Message],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut updated = 0;
+
+    for message in messages {
+        if let Some(msg) = store
+            .messages
+            .iter_mut()
+            .find(|msg| msg.message.id == message.id)
+        {
+            msg.message.payload = message.payload.to_owned();
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
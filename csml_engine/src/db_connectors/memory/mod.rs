@@ -0,0 +1,326 @@
+/**
+ * In-process, non-persistent storage.
+ *
+ * All the data lives in a process-wide store shared by every `Database::Memory`
+ * created with `init()`, so that consecutive requests see the same conversations,
+ * memories and state. Nothing is ever written to disk: everything is lost when the
+ * process exits. This is mostly useful for tests, the CLI or embedded builds where
+ * setting up a database server or file is not an option.
+ *
+ * Expired records (see `ttl_duration`) are never returned by read operations,
+ * and are definitely removed by `delete_expired_data`.
+ */
+pub mod analytics;
+pub mod api_keys;
+pub mod bot;
+pub mod conversations;
+pub mod counters;
+pub mod memories;
+pub mod messages;
+pub mod outbox;
+pub mod schedules;
+pub mod state;
+
+pub mod models;
+
+pub mod expired_data;
+
+mod storage;
+
+use crate::{Database, EngineError, MemoryClient};
+
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+static STORE: OnceLock<Arc<Mutex<models::MemoryStore>>> = OnceLock::new();
+
+/**
+ * Return the store shared by all the memory connectors of the current process
+ */
+pub fn shared_store() -> Arc<Mutex<models::MemoryStore>> {
+    STORE
+        .get_or_init(|| Arc::new(Mutex::new(models::MemoryStore::default())))
+        .clone()
+}
+
+pub fn init() -> Result<Database<'static>, EngineError> {
+    let db = Database::Memory(MemoryClient::new());
+    Ok(db)
+}
+
+pub fn lock_store(db: &MemoryClient) -> Result<MutexGuard<'_, models::MemoryStore>, EngineError> {
+    db.store
+        .lock()
+        .map_err(|_| EngineError::Manager("Memory store is poisoned".to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+
+    fn get_client() -> Client {
+        Client {
+            bot_id: "memory_bot".to_owned(),
+            channel_id: "memory_channel".to_owned(),
+            user_id: "memory_user".to_owned(),
+        }
+    }
+
+    fn get_bot() -> crate::CsmlBot {
+        crate::CsmlBot {
+            id: "memory_bot".to_owned(),
+            name: "bot".to_owned(),
+            apps_endpoint: None,
+            flows: vec![],
+            native_components: None,
+            custom_components: None,
+            default_flow: "Default".to_owned(),
+            bot_ast: None,
+            no_interruption_delay: None,
+            env: None,
+            modules: None,
+            multibot: None,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
+        }
+    }
+
+    #[test]
+    fn ok_expired_state_and_memories() {
+        let client = get_client();
+        let mut db = MemoryClient::isolated();
+
+        let past = Some(chrono::Utc::now() - chrono::Duration::seconds(10));
+        let future = Some(chrono::Utc::now() + chrono::Duration::days(1));
+
+        state::set_state_items(
+            &client,
+            "hold",
+            vec![("position", &serde_json::json!({"index": 1}))],
+            past,
+            &mut db,
+        )
+        .unwrap();
+        state::set_state_items(
+            &client,
+            "bot",
+            vec![("previous", &serde_json::json!({"bot": "other"}))],
+            future,
+            &mut db,
+        )
+        .unwrap();
+
+        memories::create_client_memory(&client, "old", &serde_json::json!(1), past, &mut db)
+            .unwrap();
+        memories::create_client_memory(&client, "new", &serde_json::json!(2), future, &mut db)
+            .unwrap();
+
+        assert!(state::get_state_key(&client, "hold", "position", &mut db)
+            .unwrap()
+            .is_none());
+        assert!(state::get_state_key(&client, "bot", "previous", &mut db)
+            .unwrap()
+            .is_some());
+
+        let mems = memories::internal_use_get_memories(&client, &mut db).unwrap();
+        assert_eq!(mems, serde_json::json!({"new": 2}));
+
+        expired_data::delete_expired_data(&mut db).unwrap();
+
+        let store = lock_store(&db).unwrap();
+        assert_eq!(store.states.len(), 1);
+        assert_eq!(store.memories.len(), 1);
+    }
+
+    #[test]
+    fn ok_isolated_stores() {
+        let client = get_client();
+        let mut first = MemoryClient::isolated();
+        let mut second = MemoryClient::isolated();
+
+        conversations::create_conversation("Default", "start", &client, None, &mut first).unwrap();
+
+        assert!(conversations::get_latest_open(&client, &mut first)
+            .unwrap()
+            .is_some());
+        assert!(conversations::get_latest_open(&client, &mut second)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn ok_custom_backend() {
+        let client = get_client();
+        let mut db = crate::data::Database::custom(MemoryClient::isolated());
+
+        crate::db_connectors::memories::create_client_memory(
+            &client,
+            "key".to_owned(),
+            serde_json::json!("value"),
+            None,
+            &mut db,
+        )
+        .unwrap();
+
+        let memory = crate::db_connectors::memories::get_memory(&client, "key", &mut db).unwrap();
+        assert_eq!(memory["value"], serde_json::json!("value"));
+
+        db.storage().unwrap().delete_client(&client).unwrap();
+
+        let mems =
+            crate::db_connectors::memories::internal_use_get_memories(&client, &mut db).unwrap();
+        assert_eq!(mems, serde_json::json!({}));
+    }
+
+    #[test]
+    fn ok_split_storage() {
+        let client = get_client();
+        let sessions = MemoryClient::isolated();
+        let storage = MemoryClient::isolated();
+        let split = crate::data::storage::SplitStorage::new(
+            sessions.clone(),
+            Database::Memory(storage.clone()),
+        )
+        .unwrap();
+        let mut db = Database::custom(split);
+
+        crate::db_connectors::memories::create_client_memory(
+            &client,
+            "key".to_owned(),
+            serde_json::json!("value"),
+            None,
+            &mut db,
+        )
+        .unwrap();
+        db.storage()
+            .unwrap()
+            .create_bot_version("memory_bot".to_owned(), get_bot())
+            .unwrap();
+
+        assert_eq!(lock_store(&sessions).unwrap().memories.len(), 1);
+        assert_eq!(lock_store(&sessions).unwrap().bots.len(), 0);
+        assert_eq!(lock_store(&storage).unwrap().memories.len(), 0);
+        assert_eq!(lock_store(&storage).unwrap().bots.len(), 1);
+
+        db.storage()
+            .unwrap()
+            .delete_all_bot_data("memory_bot")
+            .unwrap();
+
+        assert!(lock_store(&sessions).unwrap().memories.is_empty());
+        assert!(lock_store(&storage).unwrap().bots.is_empty());
+    }
+
+    #[test]
+    fn ok_due_schedules() {
+        let client = get_client();
+        let mut db = MemoryClient::isolated();
+        let now = chrono::Utc::now();
+
+        let schedule = |minutes: i64| crate::data::models::Schedule {
+            id: uuid::Uuid::new_v4(),
+            client: client.clone(),
+            flow_id: "Default".to_owned(),
+            step_id: "start".to_owned(),
+            callback_url: None,
+            bot: Default::default(),
+            attempts: 0,
+            run_at: now + chrono::Duration::minutes(minutes),
+            created_at: now,
+        };
+        let (late, early, planned) = (schedule(-1), schedule(-10), schedule(10));
+
+        for schedule in [&late, &early, &planned] {
+            schedules::create_schedule(schedule, &mut db).unwrap();
+        }
+
+        let due = schedules::get_due_schedules(now, 10, &mut db).unwrap();
+        assert_eq!(due, vec![early.clone(), late.clone()]);
+
+        let due = schedules::get_due_schedules(now, 1, &mut db).unwrap();
+        assert_eq!(due, vec![early.clone()]);
+
+        // a schedule is claimed once for each attempt
+        let retry_at = now + chrono::Duration::minutes(5);
+        assert!(schedules::claim_schedule(&early, retry_at, &mut db).unwrap());
+        assert!(!schedules::claim_schedule(&early, retry_at, &mut db).unwrap());
+        let due = schedules::get_due_schedules(now, 10, &mut db).unwrap();
+        assert_eq!(due, vec![late.clone()]);
+
+        assert!(schedules::delete_schedule(early.id, &mut db).unwrap());
+        assert!(!schedules::delete_schedule(early.id, &mut db).unwrap());
+
+        crate::data::storage::StorageBackend::delete_client(&mut db, &client).unwrap();
+        assert!(lock_store(&db).unwrap().schedules.is_empty());
+    }
+
+    #[test]
+    fn ok_outbox() {
+        let client = get_client();
+        let mut db = MemoryClient::isolated();
+
+        let message = |text: &str| {
+            crate::data::models::OutboxMessage::new(
+                &client,
+                "http://localhost/callback",
+                serde_json::json!({ "text": text }),
+                None,
+            )
+        };
+        let (first, mut second) = (message("first"), message("second"));
+        second.created_at = first.created_at + chrono::Duration::seconds(1);
+
+        for message in [&second, &first] {
+            outbox::add_outbox_message(message, &mut db).unwrap();
+        }
+
+        let messages = outbox::get_outbox_messages(Some(&client.bot_id), 10, &mut db).unwrap();
+        assert_eq!(messages, vec![first.clone(), second.clone()]);
+        assert!(outbox::get_outbox_messages(Some("other_bot"), 10, &mut db)
+            .unwrap()
+            .is_empty());
+
+        outbox::add_outbox_attempt(first.id, &mut db).unwrap();
+        let messages = outbox::get_outbox_messages(None, 1, &mut db).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].attempts, 1);
+
+        assert!(outbox::delete_outbox_message(first.id, &mut db).unwrap());
+        assert!(!outbox::delete_outbox_message(first.id, &mut db).unwrap());
+
+        assert!(outbox::has_client_outbox(&client, &mut db).unwrap());
+        crate::data::storage::StorageBackend::delete_client(&mut db, &client).unwrap();
+        assert!(lock_store(&db).unwrap().outbox.is_empty());
+        assert!(!outbox::has_client_outbox(&client, &mut db).unwrap());
+    }
+
+    #[test]
+    fn ok_api_keys() {
+        let mut db = MemoryClient::isolated();
+
+        let api_key = crate::data::models::ApiKey {
+            id: uuid::Uuid::new_v4(),
+            name: "test".to_owned(),
+            scopes: vec![crate::data::models::ApiKeyScope::Read],
+            bot_ids: None,
+            created_at: chrono::Utc::now(),
+        };
+        api_keys::create_api_key(&api_key, "hash", &mut db).unwrap();
+
+        assert_eq!(
+            api_keys::get_api_key("hash", &mut db).unwrap(),
+            Some(api_key.clone())
+        );
+        assert_eq!(api_keys::get_api_key("other", &mut db).unwrap(), None);
+        assert_eq!(
+            api_keys::get_api_keys(&mut db).unwrap(),
+            vec![api_key.clone()]
+        );
+
+        assert!(api_keys::delete_api_key(api_key.id, &mut db).unwrap());
+        assert!(!api_keys::delete_api_key(api_key.id, &mut db).unwrap());
+        assert!(api_keys::get_api_keys(&mut db).unwrap().is_empty());
+    }
+}
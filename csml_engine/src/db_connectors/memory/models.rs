@@ -0,0 +1,95 @@
+use crate::data::models;
+use crate::Client;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    pub bots: Vec<Bot>,
+    pub conversations: Vec<models::Conversation>,
+    pub messages: Vec<Message>,
+    pub memories: Vec<Memory>,
+    pub states: Vec<State>,
+    pub counters: Vec<Counter>,
+    pub schedules: Vec<models::Schedule>,
+    pub outbox: Vec<models::OutboxMessage>,
+    pub api_keys: Vec<ApiKey>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bot {
+    pub id: Uuid,
+    pub bot_id: String,
+    pub bot: String,
+    pub engine_version: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub client: Client,
+    pub message: models::Message,
+}
+
+#[derive(Debug, Clone)]
+pub struct Memory {
+    pub id: Uuid,
+    pub client: Client,
+    pub key: String,
+    pub value: serde_json::Value,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub id: Uuid,
+    pub client: Client,
+    pub type_: String,
+    pub key: String,
+    pub value: serde_json::Value,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Counter {
+    pub client: Client,
+    pub key: String,
+    pub count: i64,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub key_hash: String,
+    pub api_key: models::ApiKey,
+}
+
+/**
+ * Up to `limit` records ordered by id, starting after the `after` id
+ */
+pub fn records_after<T, R>(
+    records: &[T],
+    id: impl Fn(&T) -> Uuid,
+    after: Option<Uuid>,
+    limit: u32,
+    into: impl Fn(&T) -> R,
+) -> Vec<R> {
+    let mut records: Vec<&T> = records
+        .iter()
+        .filter(|record| after.is_none_or(|after| id(record) > after))
+        .collect();
+    records.sort_by_key(|record| id(record));
+
+    records.into_iter().take(limit as usize).map(into).collect()
+}
+
+/**
+ * A record without expiration date never expires
+ */
+pub fn is_expired(expires_at: &Option<DateTime<Utc>>, now: &DateTime<Utc>) -> bool {
+    matches!(expires_at, Some(expires_at) if expires_at < now)
+}
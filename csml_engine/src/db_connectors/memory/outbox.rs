@@ -0,0 +1,133 @@
+use crate::data::models::OutboxMessage;
+use crate::{Client, EngineError, MemoryClient};
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::{lock_store, models::records_after};
+
+pub fn add_outbox_message(
+    message: &OutboxMessage,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.outbox.push(message.to_owned());
+
+    Ok(())
+}
+
+pub fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let store = lock_store(db)?;
+
+    let mut messages: Vec<OutboxMessage> = store
+        .outbox
+        .iter()
+        .filter(|message| bot_id.is_none() || bot_id == Some(message.client.bot_id.as_str()))
+        .cloned()
+        .collect();
+
+    messages.sort_by_key(|message| message.created_at);
+    messages.truncate(limit as usize);
+
+    Ok(messages)
+}
+
+pub fn add_outbox_attempt(id: Uuid, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    if let Some(message) = store.outbox.iter_mut().find(|message| message.id == id) {
+        message.attempts += 1;
+        message.updated_at = Utc::now();
+    }
+
+    Ok(())
+}
+
+pub fn delete_outbox_message(id: Uuid, db: &mut MemoryClient) -> Result<bool, EngineError> {
+    let mut store = lock_store(db)?;
+    let count = store.outbox.len();
+
+    store.outbox.retain(|message| message.id != id);
+
+    Ok(store.outbox.len() != count)
+}
+
+pub fn has_client_outbox(client: &Client, db: &mut MemoryClient) -> Result<bool, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(store.outbox.iter().any(|message| message.client == *client))
+}
+
+pub fn delete_client_outbox(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.outbox.retain(|message| message.client != *client);
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .outbox
+        .retain(|message| message.client.bot_id != bot_id);
+
+    Ok(())
+}
+
+pub fn get_outbox_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.outbox,
+        |message| message.id,
+        after,
+        limit,
+        OutboxMessage::to_owned,
+    ))
+}
+
+pub fn add_outbox_records(
+    messages: &[OutboxMessage],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut created = 0;
+
+    for message in messages {
+        if store.outbox.iter().any(|saved| saved.id == message.id) {
+            continue;
+        }
+
+        store.outbox.push(message.to_owned());
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+pub fn update_outbox_records(
+    messages: &[OutboxMessage],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut updated = 0;
+
+    for message in messages {
+        if let Some(saved) = store.outbox.iter_mut().find(|saved| saved.id == message.id) {
+            saved.payload = message.payload.to_owned();
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
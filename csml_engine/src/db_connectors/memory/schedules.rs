@@ -0,0 +1,121 @@
+use crate::data::models::Schedule;
+use crate::{Client, EngineError, MemoryClient};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{lock_store, models::records_after};
+
+pub fn create_schedule(schedule: &Schedule, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.schedules.push(schedule.to_owned());
+
+    Ok(())
+}
+
+pub fn get_due_schedules(
+    now: DateTime<Utc>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    let store = lock_store(db)?;
+
+    let mut schedules: Vec<Schedule> = store
+        .schedules
+        .iter()
+        .filter(|schedule| schedule.run_at <= now)
+        .cloned()
+        .collect();
+
+    schedules.sort_by_key(|schedule| schedule.run_at);
+    schedules.truncate(limit as usize);
+
+    Ok(schedules)
+}
+
+pub fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut MemoryClient,
+) -> Result<bool, EngineError> {
+    let mut store = lock_store(db)?;
+
+    let saved = store
+        .schedules
+        .iter_mut()
+        .find(|saved| saved.id == schedule.id && saved.attempts == schedule.attempts);
+
+    match saved {
+        Some(saved) => {
+            saved.attempts += 1;
+            saved.run_at = retry_at;
+
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+pub fn delete_schedule(id: Uuid, db: &mut MemoryClient) -> Result<bool, EngineError> {
+    let mut store = lock_store(db)?;
+    let count = store.schedules.len();
+
+    store.schedules.retain(|schedule| schedule.id != id);
+
+    Ok(store.schedules.len() != count)
+}
+
+pub fn delete_client_schedules(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .schedules
+        .retain(|schedule| schedule.client != *client);
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .schedules
+        .retain(|schedule| schedule.client.bot_id != bot_id);
+
+    Ok(())
+}
+
+pub fn get_schedule_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.schedules,
+        |schedule| schedule.id,
+        after,
+        limit,
+        Schedule::to_owned,
+    ))
+}
+
+pub fn add_schedule_records(
+    schedules: &[Schedule],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut created = 0;
+
+    for schedule in schedules {
+        if store.schedules.iter().any(|saved| saved.id == schedule.id) {
+            continue;
+        }
+
+        store.schedules.push(schedule.to_owned());
+        created += 1;
+    }
+
+    Ok(created)
+}
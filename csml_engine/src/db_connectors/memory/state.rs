@@ -0,0 +1,225 @@
+use crate::data::models::{StateItem, StateRecord};
+use crate::{Client, EngineError, MemoryClient};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{
+    lock_store,
+    models::{self, is_expired, records_after},
+};
+
+pub fn delete_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store
+        .states
+        .retain(|state| !(state.client == *client && state.type_ == type_ && state.key == key));
+
+    Ok(())
+}
+
+pub fn get_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut MemoryClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let store = lock_store(db)?;
+    let now = Utc::now();
+
+    let state = store.states.iter().find(|state| {
+        state.client == *client
+            && state.type_ == type_
+            && state.key == key
+            && !is_expired(&state.expires_at, &now)
+    });
+
+    Ok(state.map(|state| state.value.to_owned()))
+}
+
+/**
+ * Every state key of the client that has not expired
+ */
+pub fn get_client_state(
+    client: &Client,
+    db: &mut MemoryClient,
+) -> Result<Vec<StateItem>, EngineError> {
+    let store = lock_store(db)?;
+    let now = Utc::now();
+
+    let state = store
+        .states
+        .iter()
+        .filter(|state| state.client == *client && !is_expired(&state.expires_at, &now))
+        .map(|state| StateItem {
+            _type: state.type_.to_owned(),
+            key: state.key.to_owned(),
+            value: state.value.to_owned(),
+        })
+        .collect();
+
+    Ok(state)
+}
+
+pub fn get_current_state(
+    client: &Client,
+    db: &mut MemoryClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let store = lock_store(db)?;
+    let now = Utc::now();
+
+    let current_state = store.states.iter().find(|state| {
+        state.client == *client
+            && state.type_ == "hold"
+            && state.key == "position"
+            && !is_expired(&state.expires_at, &now)
+    });
+
+    let current_state = match current_state {
+        Some(current_state) => current_state,
+        None => return Ok(None),
+    };
+
+    let current_state = serde_json::json!({
+        "client": {
+            "bot_id": current_state.client.bot_id,
+            "channel_id": current_state.client.channel_id,
+            "user_id": current_state.client.user_id
+        },
+        "type": current_state.type_,
+        "value": current_state.value,
+        "created_at": current_state.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+    });
+
+    Ok(Some(current_state))
+}
+
+pub fn set_state_items(
+    client: &Client,
+    type_: &str,
+    keys_values: Vec<(&str, &serde_json::Value)>,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut MemoryClient,
+) -> Result<(), EngineError> {
+    if keys_values.is_empty() {
+        return Ok(());
+    }
+
+    let mut store = lock_store(db)?;
+    let now = Utc::now();
+
+    for (key, value) in keys_values.into_iter() {
+        store
+            .states
+            .retain(|state| !(state.client == *client && state.type_ == type_ && state.key == key));
+
+        store.states.push(models::State {
+            id: Uuid::new_v4(),
+            client: client.to_owned(),
+            type_: type_.to_owned(),
+            key: key.to_owned(),
+            value: value.to_owned(),
+            expires_at,
+            updated_at: now,
+            created_at: now,
+        });
+    }
+
+    Ok(())
+}
+
+pub fn delete_user_state(client: &Client, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.states.retain(|state| state.client != *client);
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut MemoryClient) -> Result<(), EngineError> {
+    let mut store = lock_store(db)?;
+
+    store.states.retain(|state| state.client.bot_id != bot_id);
+
+    Ok(())
+}
+
+pub fn get_state_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut MemoryClient,
+) -> Result<Vec<StateRecord>, EngineError> {
+    let store = lock_store(db)?;
+
+    Ok(records_after(
+        &store.states,
+        |state| state.id,
+        after,
+        limit,
+        |state| StateRecord {
+            id: state.id,
+            client: state.client.to_owned(),
+            _type: state.type_.to_owned(),
+            key: state.key.to_owned(),
+            value: state.value.to_owned(),
+            expires_at: state.expires_at,
+            updated_at: state.updated_at,
+            created_at: state.created_at,
+        },
+    ))
+}
+
+pub fn add_state_records(
+    state: &[StateRecord],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let mut store = lock_store(db)?;
+    let mut created = 0;
+
+    for record in state {
+        if store.states.iter().any(|state| state.id == record.id) {
+            continue;
+        }
+
+        store.states.push(models::State {
+            id: record.id,
+            client: record.client.to_owned(),
+            type_: record._type.to_owned(),
+            key: record.key.to_owned(),
+            value: record.value.to_owned(),
+            expires_at: record.expires_at,
+            updated_at: record.updated_at,
+            created_at: record.created_at,
+        });
+        created += 1;
+    }
+
+    Ok(created)
+}
+
+pub fn update_state_records(
+    state: &[StateRecord],
+    db: &mut MemoryClient,
+) -> Result<usize, EngineError> {
+    let store = lock_store(db)?;
+    let mut updated = 0;
+
+    for record in state {
+        // values are not encrypted in memory: a state key is left as it is, and only
+        // counted if it was not changed since it was read
+        if store
+            .states
+            .iter()
+            .any(|item| item.id == record.id && item.value == record.value)
+        {
+            updated += 1;
+        }
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,501 @@
+use crate::data::filter::{AnalyticsFilter, ClientMessageFilter};
+use crate::data::models::{
+    ApiKey, BotVersionRecord, Conversation, Direction, FlowAnalytics, HoldAnalytics, MemoryRecord,
+    Message, OutboxMessage, Paginated, Schedule, StateItem, StateRecord, StepTransition,
+    TurnAnalytics,
+};
+use crate::data::storage::*;
+use crate::db_connectors::utils::get_expires_at_for_memory;
+use crate::models::BotVersion;
+use crate::{Client, CsmlBot, EngineError, Memory, MemoryClient};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{
+    analytics, api_keys, bot, conversations, counters, expired_data, memories, messages, outbox,
+    schedules, state,
+};
+
+impl ConversationStorage for MemoryClient {
+    fn create_conversation(
+        &mut self,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<Uuid, EngineError> {
+        let expires_at = get_expires_at_for_memory(ttl);
+        conversations::create_conversation(flow_id, step_id, client, expires_at, self)
+    }
+
+    fn close_conversation(&mut self, id: Uuid, client: &Client) -> Result<(), EngineError> {
+        conversations::close_conversation(id, client, "CLOSED", self)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::close_all_conversations(client, self)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<Conversation>, EngineError> {
+        conversations::get_latest_open(client, self)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: Uuid,
+        _client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        conversations::update_conversation(conversation_id, flow_id, step_id, self)
+    }
+
+    fn get_conversation(&mut self, id: Uuid) -> Result<Conversation, EngineError> {
+        conversations::get_conversation(self, id)
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<Paginated<Conversation>, EngineError> {
+        conversations::get_client_conversations(client, self, limit, pagination_key)
+    }
+
+    fn delete_client_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::delete_user_conversations(client, self)
+    }
+
+    fn get_bot_clients(&mut self, bot_id: &str) -> Result<Vec<Client>, EngineError> {
+        conversations::get_bot_clients(bot_id, self)
+    }
+
+    fn get_conversation_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Conversation>, EngineError> {
+        conversations::get_conversation_records(after, limit, self)
+    }
+
+    fn add_conversation_records(
+        &mut self,
+        conversations: &[Conversation],
+    ) -> Result<usize, EngineError> {
+        conversations::add_conversation_records(conversations, self)
+    }
+}
+
+impl MessageStorage for MemoryClient {
+    fn add_messages_bulk(
+        &mut self,
+        step: &ConversationStep,
+        msgs: &[serde_json::Value],
+        interaction_order: i32,
+        direction: Direction,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_memory(ttl);
+        messages::add_messages_bulk(step, msgs, interaction_order, direction, expires_at, self)
+    }
+
+    fn get_client_messages(
+        &mut self,
+        filter: ClientMessageFilter<'_>,
+    ) -> Result<Paginated<Message>, EngineError> {
+        messages::get_client_messages(self, filter)
+    }
+
+    fn delete_client_messages(&mut self, client: &Client) -> Result<(), EngineError> {
+        messages::delete_user_messages(client, self)
+    }
+
+    fn get_message_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Message>, EngineError> {
+        messages::get_message_records(after, limit, self)
+    }
+
+    fn add_message_records(&mut self, messages: &[Message]) -> Result<usize, EngineError> {
+        messages::add_message_records(messages, self)
+    }
+
+    fn update_message_records(&mut self, messages: &[Message]) -> Result<usize, EngineError> {
+        messages::update_message_records(messages, self)
+    }
+}
+
+impl MemoryStorage for MemoryClient {
+    fn add_memories(
+        &mut self,
+        client: &Client,
+        memories: &HashMap<String, Memory>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_memory(ttl);
+        memories::add_memories(client, memories, expires_at, self)
+    }
+
+    fn create_client_memory(
+        &mut self,
+        client: &Client,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_memory(ttl);
+        memories::create_client_memory(client, key, value, expires_at, self)
+    }
+
+    fn internal_use_get_memories(
+        &mut self,
+        client: &Client,
+    ) -> Result<serde_json::Value, EngineError> {
+        memories::internal_use_get_memories(client, self)
+    }
+
+    fn get_memories(&mut self, client: &Client) -> Result<serde_json::Value, EngineError> {
+        memories::get_memories(client, self)
+    }
+
+    fn get_memory(&mut self, client: &Client, key: &str) -> Result<serde_json::Value, EngineError> {
+        memories::get_memory(client, key, self)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        memories::delete_client_memory(client, key, self)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        memories::delete_client_memories(client, self)
+    }
+
+    fn get_memory_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<MemoryRecord>, EngineError> {
+        memories::get_memory_records(after, limit, self)
+    }
+
+    fn add_memory_records(&mut self, memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        memories::add_memory_records(memories, self)
+    }
+
+    fn update_memory_records(&mut self, memories: &[MemoryRecord]) -> Result<usize, EngineError> {
+        memories::update_memory_records(memories, self)
+    }
+}
+
+impl StateStorage for MemoryClient {
+    fn get_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_state_key(client, _type, key, self)
+    }
+
+    fn get_current_state(
+        &mut self,
+        client: &Client,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_current_state(client, self)
+    }
+
+    fn set_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        keys_values: Vec<(&str, &serde_json::Value)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_memory(ttl);
+        state::set_state_items(client, _type, keys_values, expires_at, self)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        state::delete_state_key(client, _type, key, self)
+    }
+
+    fn delete_client_state(&mut self, client: &Client) -> Result<(), EngineError> {
+        state::delete_user_state(client, self)
+    }
+
+    fn get_client_state(&mut self, client: &Client) -> Result<Vec<StateItem>, EngineError> {
+        state::get_client_state(client, self)
+    }
+
+    fn get_state_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<StateRecord>, EngineError> {
+        state::get_state_records(after, limit, self)
+    }
+
+    fn add_state_records(&mut self, state: &[StateRecord]) -> Result<usize, EngineError> {
+        state::add_state_records(state, self)
+    }
+
+    fn update_state_records(&mut self, state: &[StateRecord]) -> Result<usize, EngineError> {
+        state::update_state_records(state, self)
+    }
+}
+
+impl BotStorage for MemoryClient {
+    fn create_bot_version(
+        &mut self,
+        bot_id: String,
+        csml_bot: CsmlBot,
+    ) -> Result<String, EngineError> {
+        let serializable_bot = crate::data::to_serializable_bot(&csml_bot);
+        let bot = serde_json::json!(serializable_bot).to_string();
+
+        bot::create_bot_version(bot_id, bot, self)
+    }
+
+    fn get_last_bot_version(&mut self, bot_id: &str) -> Result<Option<BotVersion>, EngineError> {
+        bot::get_last_bot_version(bot_id, self)
+    }
+
+    fn get_bot_by_version_id(
+        &mut self,
+        version_id: &str,
+        _bot_id: &str,
+    ) -> Result<Option<BotVersion>, EngineError> {
+        bot::get_bot_by_version_id(version_id, self)
+    }
+
+    fn get_bot_versions(
+        &mut self,
+        bot_id: &str,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<serde_json::Value, EngineError> {
+        bot::get_bot_versions(bot_id, limit, pagination_key, self)
+    }
+
+    fn delete_bot_version(&mut self, _bot_id: &str, version_id: &str) -> Result<(), EngineError> {
+        bot::delete_bot_version(version_id, self)
+    }
+
+    fn delete_bot_versions(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        bot::delete_bot_versions(bot_id, self)
+    }
+
+    fn get_bot_version_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<BotVersionRecord>, EngineError> {
+        bot::get_bot_version_records(after, limit, self)
+    }
+
+    fn add_bot_version_records(
+        &mut self,
+        versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        bot::add_bot_version_records(versions, self)
+    }
+
+    fn update_bot_version_records(
+        &mut self,
+        versions: &[BotVersionRecord],
+    ) -> Result<usize, EngineError> {
+        bot::update_bot_version_records(versions, self)
+    }
+}
+
+impl ScheduleStorage for MemoryClient {
+    fn create_schedule(&mut self, schedule: &Schedule) -> Result<(), EngineError> {
+        schedules::create_schedule(schedule, self)
+    }
+
+    fn get_due_schedules(
+        &mut self,
+        now: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        schedules::get_due_schedules(now, limit, self)
+    }
+
+    fn claim_schedule(
+        &mut self,
+        schedule: &Schedule,
+        retry_at: DateTime<Utc>,
+    ) -> Result<bool, EngineError> {
+        schedules::claim_schedule(schedule, retry_at, self)
+    }
+
+    fn delete_schedule(&mut self, id: Uuid) -> Result<bool, EngineError> {
+        schedules::delete_schedule(id, self)
+    }
+
+    fn delete_client_schedules(&mut self, client: &Client) -> Result<(), EngineError> {
+        schedules::delete_client_schedules(client, self)
+    }
+
+    fn get_schedule_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<Schedule>, EngineError> {
+        schedules::get_schedule_records(after, limit, self)
+    }
+
+    fn add_schedule_records(&mut self, schedules: &[Schedule]) -> Result<usize, EngineError> {
+        schedules::add_schedule_records(schedules, self)
+    }
+}
+
+impl OutboxStorage for MemoryClient {
+    fn add_outbox_message(&mut self, message: &OutboxMessage) -> Result<(), EngineError> {
+        outbox::add_outbox_message(message, self)
+    }
+
+    fn get_outbox_messages(
+        &mut self,
+        bot_id: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        outbox::get_outbox_messages(bot_id, limit, self)
+    }
+
+    fn add_outbox_attempt(&mut self, id: Uuid) -> Result<(), EngineError> {
+        outbox::add_outbox_attempt(id, self)
+    }
+
+    fn delete_outbox_message(&mut self, id: Uuid) -> Result<bool, EngineError> {
+        outbox::delete_outbox_message(id, self)
+    }
+
+    fn has_client_outbox(&mut self, client: &Client) -> Result<bool, EngineError> {
+        outbox::has_client_outbox(client, self)
+    }
+
+    fn delete_client_outbox(&mut self, client: &Client) -> Result<(), EngineError> {
+        outbox::delete_client_outbox(client, self)
+    }
+
+    fn get_outbox_records(
+        &mut self,
+        after: Option<Uuid>,
+        limit: u32,
+    ) -> Result<Vec<OutboxMessage>, EngineError> {
+        outbox::get_outbox_records(after, limit, self)
+    }
+
+    fn add_outbox_records(&mut self, messages: &[OutboxMessage]) -> Result<usize, EngineError> {
+        outbox::add_outbox_records(messages, self)
+    }
+
+    fn update_outbox_records(&mut self, messages: &[OutboxMessage]) -> Result<usize, EngineError> {
+        outbox::update_outbox_records(messages, self)
+    }
+}
+
+impl ApiKeyStorage for MemoryClient {
+    fn create_api_key(&mut self, api_key: &ApiKey, key_hash: &str) -> Result<(), EngineError> {
+        api_keys::create_api_key(api_key, key_hash, self)
+    }
+
+    fn get_api_key(&mut self, key_hash: &str) -> Result<Option<ApiKey>, EngineError> {
+        api_keys::get_api_key(key_hash, self)
+    }
+
+    fn get_api_keys(&mut self) -> Result<Vec<ApiKey>, EngineError> {
+        api_keys::get_api_keys(self)
+    }
+
+    fn delete_api_key(&mut self, id: Uuid) -> Result<bool, EngineError> {
+        api_keys::delete_api_key(id, self)
+    }
+}
+
+impl CounterStorage for MemoryClient {
+    fn get_counter(&mut self, client: &Client, key: &str) -> Result<i64, EngineError> {
+        counters::get_counter(client, key, self)
+    }
+
+    fn increment_counter(
+        &mut self,
+        client: &Client,
+        key: &str,
+        count: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<i64, EngineError> {
+        let expires_at = get_expires_at_for_memory(ttl);
+        counters::increment_counter(client, key, count, expires_at, self)
+    }
+
+    fn delete_client_counters(&mut self, client: &Client) -> Result<(), EngineError> {
+        counters::delete_client_counters(client, self)
+    }
+}
+
+impl AnalyticsStorage for MemoryClient {
+    fn get_flow_analytics(
+        &mut self,
+        filter: &AnalyticsFilter,
+    ) -> Result<Vec<FlowAnalytics>, EngineError> {
+        analytics::get_flow_analytics(filter, self)
+    }
+
+    fn get_hold_analytics(
+        &mut self,
+        filter: &AnalyticsFilter,
+    ) -> Result<Vec<HoldAnalytics>, EngineError> {
+        analytics::get_hold_analytics(filter, self)
+    }
+
+    fn get_turn_analytics(
+        &mut self,
+        filter: &AnalyticsFilter,
+    ) -> Result<TurnAnalytics, EngineError> {
+        analytics::get_turn_analytics(filter, self)
+    }
+
+    fn get_step_transitions(
+        &mut self,
+        filter: &AnalyticsFilter,
+    ) -> Result<Vec<StepTransition>, EngineError> {
+        analytics::get_step_transitions(filter, self)
+    }
+}
+
+impl StorageBackend for MemoryClient {
+    fn delete_all_bot_data(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        bot::delete_bot_versions(bot_id, self)?;
+        conversations::delete_all_bot_data(bot_id, self)?;
+        messages::delete_all_bot_data(bot_id, self)?;
+        memories::delete_all_bot_data(bot_id, self)?;
+        state::delete_all_bot_data(bot_id, self)?;
+        counters::delete_all_bot_data(bot_id, self)?;
+        schedules::delete_all_bot_data(bot_id, self)?;
+        outbox::delete_all_bot_data(bot_id, self)
+    }
+
+    fn delete_expired_data(&mut self) -> Result<(), EngineError> {
+        expired_data::delete_expired_data(self)
+    }
+}
+
+impl SessionStorage for MemoryClient {
+    fn delete_all_bot_sessions(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        conversations::delete_all_bot_data(bot_id, self)?;
+        memories::delete_all_bot_data(bot_id, self)?;
+        state::delete_all_bot_data(bot_id, self)?;
+        counters::delete_all_bot_data(bot_id, self)
+    }
+}
@@ -1,16 +1,7 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
-
 use crate::data::filter::ClientMessageFilter;
 use crate::data::models::{Direction, Message, Paginated};
-use crate::db_connectors::utils::*;
-use crate::error_messages::ERROR_DB_SETUP;
+use crate::data::storage::ConversationStep;
+use crate::db_connectors::db_span;
 use crate::{ConversationInfo, Database, EngineError};
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
@@ -20,6 +11,8 @@ pub fn add_messages_bulk(
     interaction_order: i32,
     direction: Direction,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_messages_bulk", data.db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -39,65 +32,24 @@ pub fn add_messages_bulk(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let expires_at = get_expires_at_for_mongodb(data.ttl);
-
-        return mongodb_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
-            interaction_order,
-            direction,
-            expires_at,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let expires_at = get_expires_at_for_dynamodb(data.ttl);
-
-        return dynamodb_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
-            interaction_order,
-            direction,
-            expires_at,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let expires_at = get_expires_at_for_postgresql(data.ttl);
-
-        return postgresql_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
-            interaction_order,
-            direction.into(),
-            expires_at,
-        );
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let expires_at = get_expires_at_for_sqlite(data.ttl);
-
-        return sqlite_connector::messages::add_messages_bulk(
-            data,
-            &msgs,
-            interaction_order,
-            direction.into(),
-            expires_at,
-        );
-    }
+    let step = ConversationStep {
+        client: &data.client,
+        conversation_id: data.conversation_id,
+        flow_id: &data.context.flow,
+        step_id: data.context.step.get_step_ref(),
+    };
 
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    data.db
+        .storage()?
+        .add_messages_bulk(&step, &msgs, interaction_order, direction, data.ttl)
 }
 
 pub fn get_client_messages(
     db: &mut Database,
     filter: ClientMessageFilter<'_>,
 ) -> Result<Paginated<Message>, EngineError> {
+    let _span = db_span("csml.db.get_client_messages", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call get messages".to_string()),
         LogLvl::Info,
@@ -108,60 +60,5 @@ pub fn get_client_messages(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        let pagination_key = mongodb_connector::get_pagination_key(pagination_key)?;
-
-        return mongodb_connector::messages::get_client_messages(
-            client,
-            db,
-            limit,
-            pagination_key,
-            from_date,
-            to_date,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        let pagination_key = dynamodb_connector::get_pagination_key(pagination_key)?;
-
-        match from_date {
-            Some(from_date) => {
-                return dynamodb_connector::messages::get_client_messages_from_date(
-                    db,
-                    limit,
-                    pagination_key,
-                    from_date,
-                    to_date,
-                );
-            }
-            None => {
-                return dynamodb_connector::messages::get_client_messages(
-                    client,
-                    db,
-                    limit,
-                    pagination_key,
-                )
-            }
-        }
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-
-        return postgresql_connector::messages::get_client_messages(db, filter);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-
-        return sqlite_connector::messages::get_client_messages(db, filter);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.get_client_messages(filter)
 }
@@ -25,28 +25,49 @@
  *   - AWS_S3_ENDPOINT optional, defaults to the S3 endpoint for the given region
  * Both AWS_REGION AND AWS_DYNAMODB_ENDPOINT must be set to use a custom dynamodb-compatible DB.
  *
+ * - `memory`: in-process storage, no additional variable required. Data is shared by all the
+ *   requests handled by the current process and is lost when it exits.
+ *
  * If the ENGINE_DB_TYPE env var is not set, mongodb is used by default.
  *
- * To add a new DB type, please use one of the existing templates implementations.
- * Each method of each module must be fully reimplemented in order to extend the "generic"
- * implementation at the root of db_connectors directory.
+ * With the `redis` feature, conversations, memories and state can be moved to redis
+ * while the database above keeps messages and bot versions, by setting:
+ *   - REDIS_URL, for example `redis://127.0.0.1:6379`
+ *   - REDIS_KEY_PREFIX optional, defaults to `csml`
+ *
+ * To add a new DB type, implement the traits of `data::storage` (see `StorageBackend`)
+ * for the client of this DB. The existing connectors can be used as templates.
+ * A backend defined outside of this crate can be used directly with `Database::custom`
+ * and `start_conversation_db`, without being registered here.
  */
 use crate::data::{Database, EngineError};
-use crate::error_messages::ERROR_DB_SETUP;
+use crate::error_messages::{ERROR_DB_SETUP, ERROR_DB_URL};
+use csml_interpreter::data::csml_metrics::{self, Metric};
+use csml_interpreter::data::csml_otel;
+use std::time::Instant;
 
 #[cfg(feature = "dynamo")]
 use self::dynamodb as dynamodb_connector;
+#[cfg(feature = "memory")]
+use self::memory as memory_connector;
 #[cfg(feature = "mongo")]
 use self::mongodb as mongodb_connector;
 #[cfg(feature = "postgresql")]
 use self::postgresql as postgresql_connector;
+#[cfg(feature = "redis")]
+use self::redis as redis_connector;
 #[cfg(feature = "sqlite")]
 use self::sqlite as sqlite_connector;
 
+pub mod analytics;
+pub mod api_keys;
 pub mod bot;
 pub mod conversations;
+pub mod counters;
 pub mod memories;
 pub mod messages;
+pub mod outbox;
+pub mod schedules;
 pub mod state;
 
 pub mod clean_db;
@@ -57,17 +78,53 @@ pub mod db_test;
 
 #[cfg(feature = "dynamo")]
 mod dynamodb;
+#[cfg(feature = "memory")]
+pub(crate) mod memory;
 #[cfg(feature = "mongo")]
 mod mongodb;
 #[cfg(feature = "postgresql")]
 pub(crate) mod postgresql;
+#[cfg(feature = "redis")]
+mod redis;
 
 #[cfg(feature = "sqlite")]
-mod sqlite;
+pub(crate) mod sqlite;
 
 #[cfg(any(feature = "sqlite", feature = "postgresql"))]
 pub mod diesel;
 
+/**
+ * Span of a database call, recording its duration in the `Db` metric when dropped
+ */
+pub(crate) struct DbSpan {
+    _span: csml_otel::Span,
+    backend: &'static str,
+    operation: &'static str,
+    start: Instant,
+}
+
+impl Drop for DbSpan {
+    fn drop(&mut self) {
+        csml_metrics::record(Metric::Db {
+            backend: self.backend,
+            operation: self.operation,
+            duration: self.start.elapsed(),
+        });
+    }
+}
+
+/**
+ * Start the span of a database call: `name` is the span name, "csml.db.<operation>"
+ */
+pub(crate) fn db_span(name: &'static str, backend: &'static str) -> DbSpan {
+    DbSpan {
+        _span: csml_otel::span(name, &[("db.system", backend)]),
+        backend,
+        operation: name.trim_start_matches("csml.db."),
+        start: Instant::now(),
+    }
+}
+
 #[cfg(feature = "mongo")]
 pub fn is_mongodb() -> bool {
     // If the env var is not set at all, use mongodb by default
@@ -101,7 +158,31 @@ pub fn is_sqlite() -> bool {
     }
 }
 
+#[cfg(feature = "memory")]
+pub fn is_memory() -> bool {
+    match std::env::var("ENGINE_DB_TYPE") {
+        Ok(val) => val == *"memory",
+        Err(_) => false,
+    }
+}
+
+#[cfg(feature = "redis")]
+pub fn is_redis() -> bool {
+    std::env::var("REDIS_URL").is_ok()
+}
+
 pub fn init_db() -> Result<Database<'static>, EngineError> {
+    let db = init_storage_db()?;
+
+    #[cfg(feature = "redis")]
+    if is_redis() {
+        return redis_connector::init(db);
+    }
+
+    Ok(db)
+}
+
+fn init_storage_db() -> Result<Database<'static>, EngineError> {
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         return mongodb_connector::init();
@@ -122,9 +203,53 @@ pub fn init_db() -> Result<Database<'static>, EngineError> {
         return sqlite_connector::init();
     }
 
+    #[cfg(feature = "memory")]
+    if is_memory() {
+        return memory_connector::init();
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
+/**
+ * Connect to the database of an url rather than the one of the env vars, for instance
+ * to copy a database to another one with `migrate`. The SQL tables are created if needed.
+ *
+ * - `sqlite://<path>`
+ * - `postgresql://<user>:<password>@<host>/<database>` (or `postgres://`)
+ * - `memory://`: the in-process store of the current process
+ * - `mongodb://<user>:<password>@<host>:<port>/<database>`
+ * - `dynamodb://`: the DynamoDB table and S3 bucket of the AWS_* env vars
+ */
+pub fn init_db_from_url(url: &str) -> Result<Database<'static>, EngineError> {
+    #[cfg(feature = "mongo")]
+    if url.starts_with("mongodb://") || url.starts_with("mongodb+srv://") {
+        return mongodb_connector::init_from_url(url);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if url == "dynamodb://" {
+        return dynamodb_connector::init();
+    }
+
+    #[cfg(feature = "sqlite")]
+    if let Some(path) = url.strip_prefix("sqlite://") {
+        return sqlite_connector::init_from_url(path);
+    }
+
+    #[cfg(feature = "postgresql")]
+    if url.starts_with("postgresql://") || url.starts_with("postgres://") {
+        return postgresql_connector::init_from_url(url);
+    }
+
+    #[cfg(feature = "memory")]
+    if url == "memory://" {
+        return memory_connector::init();
+    }
+
+    Err(EngineError::Manager(format!("{}: {}", ERROR_DB_URL, url)))
+}
+
 pub fn make_migrations() -> Result<(), EngineError> {
     #[cfg(feature = "postgresql")]
     if is_postgresql() {
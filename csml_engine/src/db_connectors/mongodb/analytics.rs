@@ -0,0 +1,272 @@
+use crate::data::filter::AnalyticsFilter;
+use crate::data::models::{FlowAnalytics, HoldAnalytics, StepTransition, TurnAnalytics};
+use crate::{EngineError, MongoDbClient};
+use bson::{doc, Bson, Document};
+
+/**
+ * Conversations of the period, each one with its messages in the order they were saved
+ */
+fn conversations_pipeline(filter: &AnalyticsFilter) -> Vec<Document> {
+    vec![
+        doc! {
+            "$match": {
+                "client.bot_id": filter.bot_id,
+                "created_at": {
+                    "$gte": bson::DateTime::from_chrono(filter.from_date),
+                    "$lt": bson::DateTime::from_chrono(filter.to_date),
+                },
+            }
+        },
+        doc! {
+            "$lookup": {
+                "from": "message",
+                "let": { "conversation_id": { "$toString": "$_id" } },
+                "pipeline": [
+                    { "$match": { "$expr": { "$eq": ["$conversation_id", "$$conversation_id"] } } },
+                    { "$sort": { "created_at": 1, "_id": 1 } },
+                    { "$project": { "_id": 0, "flow_id": 1, "step_id": 1, "direction": 1, "created_at": 1 } },
+                ],
+                "as": "messages",
+            }
+        },
+    ]
+}
+
+/**
+ * Every message of the conversation but the first one
+ */
+fn next_messages() -> Bson {
+    Bson::Document(doc! {
+        "$slice": ["$messages", 1, { "$max": [{ "$size": "$messages" }, 1] }]
+    })
+}
+
+fn aggregate(
+    filter: &AnalyticsFilter,
+    stages: Vec<Document>,
+    db: &MongoDbClient,
+) -> Result<Vec<Document>, EngineError> {
+    let collection = db.client.collection::<Document>("conversation");
+
+    let mut pipeline = conversations_pipeline(filter);
+    pipeline.extend(stages);
+
+    let cursor = collection.aggregate(pipeline, None)?;
+
+    let mut documents = vec![];
+    for doc in cursor {
+        documents.push(doc?);
+    }
+
+    Ok(documents)
+}
+
+fn get_count(doc: &Document, key: &str) -> u64 {
+    match doc.get(key) {
+        Some(Bson::Int32(count)) => *count as u64,
+        Some(Bson::Int64(count)) => *count as u64,
+        Some(Bson::Double(count)) => *count as u64,
+        _ => 0,
+    }
+}
+
+fn get_string(doc: &Document, key: &str) -> String {
+    doc.get_str(key).unwrap_or_default().to_owned()
+}
+
+pub fn get_flow_analytics(
+    filter: &AnalyticsFilter,
+    db: &MongoDbClient,
+) -> Result<Vec<FlowAnalytics>, EngineError> {
+    let stages = vec![
+        doc! {
+            "$project": {
+                "flows": { "$concatArrays": [
+                    { "$cond": [
+                        { "$gt": [{ "$size": "$messages" }, 0] },
+                        [{
+                            "flow_id": { "$arrayElemAt": ["$messages.flow_id", 0] },
+                            "started": 1,
+                            "ended": 0,
+                        }],
+                        [],
+                    ] },
+                    { "$cond": [
+                        { "$eq": ["$status", "CLOSED"] },
+                        [{ "flow_id": "$flow_id", "started": 0, "ended": 1 }],
+                        [],
+                    ] },
+                ] }
+            }
+        },
+        doc! { "$unwind": "$flows" },
+        doc! {
+            "$group": {
+                "_id": "$flows.flow_id",
+                "started": { "$sum": "$flows.started" },
+                "ended": { "$sum": "$flows.ended" },
+            }
+        },
+        doc! { "$sort": { "_id": 1 } },
+    ];
+
+    Ok(aggregate(filter, stages, db)?
+        .iter()
+        .map(|doc| FlowAnalytics {
+            flow_id: get_string(doc, "_id"),
+            started: get_count(doc, "started"),
+            ended: get_count(doc, "ended"),
+        })
+        .collect())
+}
+
+pub fn get_hold_analytics(
+    filter: &AnalyticsFilter,
+    db: &MongoDbClient,
+) -> Result<Vec<HoldAnalytics>, EngineError> {
+    let next_messages = next_messages();
+    let stages = vec![
+        doc! {
+            "$project": {
+                "holds": { "$concatArrays": [
+                    // the first input of a conversation does not answer a hold
+                    { "$map": {
+                        "input": { "$filter": {
+                            "input": next_messages,
+                            "cond": { "$eq": ["$$this.direction", "RECEIVE"] },
+                        } },
+                        "in": {
+                            "flow_id": "$$this.flow_id",
+                            "step_id": "$$this.step_id",
+                            "answered": 1,
+                            "abandoned": 0,
+                        },
+                    } },
+                    // a conversation is abandoned once it has no activity, not while it waits
+                    // for an answer
+                    { "$cond": [
+                        { "$and": [
+                            { "$eq": ["$status", "OPEN"] },
+                            { "$lt": [
+                                { "$max": ["$last_interaction_at", { "$max": "$messages.created_at" }] },
+                                bson::DateTime::from_chrono(filter.abandoned_before),
+                            ] },
+                        ] },
+                        [{ "flow_id": "$flow_id", "step_id": "$step_id", "answered": 0, "abandoned": 1 }],
+                        [],
+                    ] },
+                ] }
+            }
+        },
+        doc! { "$unwind": "$holds" },
+        doc! {
+            "$group": {
+                "_id": { "flow_id": "$holds.flow_id", "step_id": "$holds.step_id" },
+                "answered": { "$sum": "$holds.answered" },
+                "abandoned": { "$sum": "$holds.abandoned" },
+            }
+        },
+        doc! { "$sort": { "_id.flow_id": 1, "_id.step_id": 1 } },
+    ];
+
+    Ok(aggregate(filter, stages, db)?
+        .iter()
+        .map(|doc| {
+            let id = doc.get_document("_id").cloned().unwrap_or_default();
+
+            HoldAnalytics {
+                flow_id: get_string(&id, "flow_id"),
+                step_id: get_string(&id, "step_id"),
+                answered: get_count(doc, "answered"),
+                abandoned: get_count(doc, "abandoned"),
+            }
+        })
+        .collect())
+}
+
+pub fn get_turn_analytics(
+    filter: &AnalyticsFilter,
+    db: &MongoDbClient,
+) -> Result<TurnAnalytics, EngineError> {
+    let stages = vec![
+        doc! { "$match": { "messages.0": { "$exists": true } } },
+        doc! {
+            "$group": {
+                "_id": Bson::Null,
+                "conversations": { "$sum": 1 },
+                "turns": { "$sum": { "$size": { "$filter": {
+                    "input": "$messages",
+                    "cond": { "$eq": ["$$this.direction", "RECEIVE"] },
+                } } } },
+            }
+        },
+    ];
+
+    Ok(match aggregate(filter, stages, db)?.first() {
+        Some(doc) => TurnAnalytics::new(get_count(doc, "conversations"), get_count(doc, "turns")),
+        None => TurnAnalytics::new(0, 0),
+    })
+}
+
+pub fn get_step_transitions(
+    filter: &AnalyticsFilter,
+    db: &MongoDbClient,
+) -> Result<Vec<StepTransition>, EngineError> {
+    let next_messages = next_messages();
+    let stages = vec![
+        // each message with the next one
+        doc! {
+            "$project": {
+                "pairs": { "$zip": { "inputs": ["$messages", next_messages] } }
+            }
+        },
+        doc! { "$unwind": "$pairs" },
+        doc! {
+            "$project": {
+                "from": { "$arrayElemAt": ["$pairs", 0] },
+                "to": { "$arrayElemAt": ["$pairs", 1] },
+            }
+        },
+        doc! {
+            "$match": { "$expr": { "$or": [
+                { "$ne": ["$from.flow_id", "$to.flow_id"] },
+                { "$ne": ["$from.step_id", "$to.step_id"] },
+            ] } }
+        },
+        doc! {
+            "$group": {
+                "_id": {
+                    "from_flow": "$from.flow_id",
+                    "from_step": "$from.step_id",
+                    "to_flow": "$to.flow_id",
+                    "to_step": "$to.step_id",
+                },
+                "count": { "$sum": 1 },
+            }
+        },
+        doc! {
+            "$sort": {
+                "count": -1,
+                "_id.from_flow": 1,
+                "_id.from_step": 1,
+                "_id.to_flow": 1,
+                "_id.to_step": 1,
+            }
+        },
+    ];
+
+    Ok(aggregate(filter, stages, db)?
+        .iter()
+        .map(|doc| {
+            let id = doc.get_document("_id").cloned().unwrap_or_default();
+
+            StepTransition {
+                from_flow: get_string(&id, "from_flow"),
+                from_step: get_string(&id, "from_step"),
+                to_flow: get_string(&id, "to_flow"),
+                to_step: get_string(&id, "to_step"),
+                count: get_count(doc, "count"),
+            }
+        })
+        .collect())
+}
@@ -21,6 +21,19 @@ fn format_bot_struct(bot: bson::document::Document) -> Result<DbBot, EngineError
     })
 }
 
+/**
+ * Bots are saved as JSON, or as base64 encoded bincode by older versions
+ */
+pub(super) fn parse_bot(bot: &str) -> Result<SerializeCsmlBot, EngineError> {
+    match base64::engine::general_purpose::STANDARD.decode(bot) {
+        Ok(base64decoded) => match bincode::deserialize::<CsmlBotBincode>(&base64decoded[..]) {
+            Ok(bot) => Ok(bot.to_bot()),
+            Err(_) => Ok(serde_json::from_str(bot)?),
+        },
+        Err(_) => Ok(serde_json::from_str(bot)?),
+    }
+}
+
 pub fn create_bot_version(
     bot_id: String,
     bot: String,
@@ -80,16 +93,7 @@ pub fn get_bot_versions(
             Ok(bot_doc) => {
                 let bot_version = format_bot_struct(bot_doc)?;
 
-                let csml_bot: SerializeCsmlBot =
-                    match base64::engine::general_purpose::STANDARD.decode(&bot_version.bot) {
-                        Ok(base64decoded) => {
-                            match bincode::deserialize::<CsmlBotBincode>(&base64decoded[..]) {
-                                Ok(bot) => bot.to_bot(),
-                                Err(_) => serde_json::from_str(&bot_version.bot).unwrap(),
-                            }
-                        }
-                        Err(_) => serde_json::from_str(&bot_version.bot).unwrap(),
-                    };
+                let csml_bot = parse_bot(&bot_version.bot)?;
 
                 let mut json = serde_json::json!({
                     "version_id": bot_version.id,
@@ -147,16 +151,7 @@ pub fn get_bot_by_version_id(
         Some(bot) => {
             let bot = format_bot_struct(bot)?;
 
-            let csml_bot: SerializeCsmlBot =
-                match base64::engine::general_purpose::STANDARD.decode(&bot.bot) {
-                    Ok(base64decoded) => {
-                        match bincode::deserialize::<CsmlBotBincode>(&base64decoded[..]) {
-                            Ok(bot) => bot.to_bot(),
-                            Err(_) => serde_json::from_str(&bot.bot).unwrap(),
-                        }
-                    }
-                    Err(_) => serde_json::from_str(&bot.bot).unwrap(),
-                };
+            let csml_bot = parse_bot(&bot.bot)?;
 
             Ok(Some(BotVersion {
                 bot: csml_bot.to_bot(),
@@ -188,16 +183,7 @@ pub fn get_last_bot_version(
         Some(bot) => {
             let bot = format_bot_struct(bot)?;
 
-            let csml_bot: SerializeCsmlBot =
-                match base64::engine::general_purpose::STANDARD.decode(&bot.bot) {
-                    Ok(base64decoded) => {
-                        match bincode::deserialize::<CsmlBotBincode>(&base64decoded[..]) {
-                            Ok(bot) => bot.to_bot(),
-                            Err(_) => serde_json::from_str(&bot.bot).unwrap(),
-                        }
-                    }
-                    Err(_) => serde_json::from_str(&bot.bot).unwrap(),
-                };
+            let csml_bot = parse_bot(&bot.bot)?;
 
             Ok(Some(BotVersion {
                 bot: csml_bot.to_bot(),
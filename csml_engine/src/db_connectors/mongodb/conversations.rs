@@ -184,6 +184,29 @@ pub fn delete_user_conversations(client: &Client, db: &MongoDbClient) -> Result<
     Ok(())
 }
 
+pub fn get_bot_clients(bot_id: &str, db: &MongoDbClient) -> Result<Vec<Client>, EngineError> {
+    let collection = db.client.collection::<Document>("conversation");
+
+    let filter = doc! {
+        "client.bot_id": bot_id,
+    };
+    let find_options = mongodb::options::FindOptions::builder()
+        .projection(doc! { "client": 1 })
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut clients: Vec<Client> = vec![];
+    for doc in cursor {
+        let client: Client = bson::from_bson(doc?.get("client").unwrap().to_owned())?;
+
+        if !clients.contains(&client) {
+            clients.push(client);
+        }
+    }
+
+    Ok(clients)
+}
+
 pub fn get_client_conversations(
     client: &Client,
     db: &MongoDbClient,
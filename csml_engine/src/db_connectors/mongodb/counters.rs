@@ -0,0 +1,78 @@
+use crate::{EngineError, MongoDbClient};
+use bson::{doc, Document};
+use csml_interpreter::data::Client;
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+
+fn counter_filter(client: &Client, key: &str) -> Document {
+    doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "key": key,
+    }
+}
+
+pub fn get_counter(client: &Client, key: &str, db: &MongoDbClient) -> Result<i64, EngineError> {
+    let collection = db.client.collection::<Document>("counter");
+
+    let mut filter = counter_filter(client, key);
+    filter.insert(
+        "$or",
+        vec![
+            doc! { "expires_at": null },
+            doc! { "expires_at": { "$gt": bson::DateTime::now() } },
+        ],
+    );
+
+    match collection.find_one(filter, None)? {
+        Some(counter) => Ok(counter.get_i64("count").unwrap_or(0)),
+        None => Ok(0),
+    }
+}
+
+pub fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<bson::DateTime>,
+    db: &MongoDbClient,
+) -> Result<i64, EngineError> {
+    let collection = db.client.collection::<Document>("counter");
+
+    // the ttl index removes expired counters in the background only
+    let mut expired = counter_filter(client, key);
+    expired.insert("expires_at", doc! { "$lt": bson::DateTime::now() });
+    collection.delete_one(expired, None)?;
+
+    let update = doc! {
+        "$inc": { "count": count },
+        "$set": { "expires_at": expires_at },
+        "$setOnInsert": {
+            "client": bson::to_bson(client)?,
+            "key": key,
+        },
+    };
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .build();
+
+    match collection.find_one_and_update(counter_filter(client, key), update, options)? {
+        Some(counter) => Ok(counter.get_i64("count").unwrap_or(0)),
+        None => Ok(count),
+    }
+}
+
+pub fn delete_client_counters(client: &Client, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection::<Document>("counter");
+
+    let filter = doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+    };
+
+    collection.delete_many(filter, None)?;
+
+    Ok(())
+}
@@ -1,17 +1,16 @@
 use crate::{
-    db_connectors::mongodb::get_db,
     encrypt::{decrypt_data, encrypt_data},
-    Client, ConversationInfo, EngineError, Memory, MongoDbClient,
+    Client, EngineError, Memory, MongoDbClient,
 };
 use bson::{doc, Bson, Document};
 use std::collections::HashMap;
 
 fn format_memories(
-    data: &mut ConversationInfo,
+    client: &Client,
     memories: &HashMap<String, Memory>,
     expires_at: Option<bson::DateTime>,
 ) -> Result<Vec<bson::Document>, EngineError> {
-    let client = bson::to_bson(&data.client)?;
+    let client = bson::to_bson(client)?;
 
     memories.iter().fold(Ok(vec![]), |vec, (_, mem)| {
         let time = bson::DateTime::from_chrono(chrono::Utc::now());
@@ -33,16 +32,16 @@ fn format_memories(
 }
 
 pub fn add_memories(
-    data: &mut ConversationInfo,
+    client: &Client,
     memories: &HashMap<String, Memory>,
     expires_at: Option<bson::DateTime>,
+    db: &MongoDbClient,
 ) -> Result<(), EngineError> {
     if memories.is_empty() {
         return Ok(());
     }
 
-    let mem = format_memories(data, memories, expires_at)?;
-    let db = get_db(&data.db)?;
+    let mem = format_memories(client, memories, expires_at)?;
 
     let collection = db.client.collection::<Document>("memory");
     collection.insert_many(mem, None)?;
@@ -1,13 +1,14 @@
+use crate::data::storage::ConversationStep;
 use crate::models::DbMessage;
 use crate::{
-    db_connectors::mongodb::get_db, encrypt::decrypt_data, Client, ConversationInfo, EngineError,
-    MongoDbClient,
+    encrypt::{decrypt_data, encrypt_data},
+    Client, EngineError, MongoDbClient,
 };
 use bson::{doc, Document};
 use chrono::SecondsFormat;
 
 fn format_messages(
-    data: &ConversationInfo,
+    step: &ConversationStep,
     messages: &[serde_json::Value],
     interaction_order: i32,
     direction: &str,
@@ -18,7 +19,7 @@ fn format_messages(
         .enumerate()
         .map(|(i, var)| {
             format_message(
-                data,
+                step,
                 var.clone(),
                 i as i32,
                 interaction_order,
@@ -30,7 +31,7 @@ fn format_messages(
 }
 
 fn format_message(
-    data: &ConversationInfo,
+    step: &ConversationStep,
     message: serde_json::Value,
     msg_order: i32,
     interaction_order: i32,
@@ -39,10 +40,10 @@ fn format_message(
 ) -> Result<Document, EngineError> {
     let time = bson::DateTime::from_chrono(chrono::Utc::now());
     let doc = doc! {
-        "client": bson::to_bson(&data.client)?,
-        "conversation_id": &data.conversation_id,
-        "flow_id": &data.context.flow,
-        "step_id": &data.context.step.get_step(),
+        "client": bson::to_bson(step.client)?,
+        "conversation_id": step.conversation_id.to_string(),
+        "flow_id": step.flow_id,
+        "step_id": step.step_id,
         "message_order": msg_order,
         "interaction_order": interaction_order,
         "direction": direction,
@@ -77,17 +78,17 @@ fn format_message_struct(message: bson::document::Document) -> Result<DbMessage,
 }
 
 pub fn add_messages_bulk(
-    data: &ConversationInfo,
+    step: &ConversationStep,
     msgs: &[serde_json::Value],
     interaction_order: i32,
     direction: &str,
     expires_at: Option<bson::DateTime>,
+    db: &MongoDbClient,
 ) -> Result<(), EngineError> {
     if msgs.len() == 0 {
         return Ok(());
     }
-    let docs = format_messages(data, msgs, interaction_order, direction, expires_at)?;
-    let db = get_db(&data.db)?;
+    let docs = format_messages(step, msgs, interaction_order, direction, expires_at)?;
 
     let message = db.client.collection::<Document>("message");
 
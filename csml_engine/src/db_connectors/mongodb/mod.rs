@@ -1,9 +1,16 @@
+pub mod analytics;
 pub mod bot;
 pub mod conversations;
+pub mod counters;
 pub mod memories;
 pub mod messages;
+pub mod outbox;
+pub mod records;
+pub mod schedules;
 pub mod state;
 
+mod storage;
+
 use crate::{Database, EngineError, MongoDbClient};
 use base64::Engine;
 use bson::{doc, Document};
@@ -58,19 +65,34 @@ pub fn init() -> Result<Database<'static>, EngineError> {
     let mongodb_client = MongoDbClient::new(client.database(&dbname));
     create_ttl_indexes(&mongodb_client);
     create_client_indexes(&mongodb_client);
+    create_analytics_indexes(&mongodb_client);
 
     let db = Database::Mongo(mongodb_client);
 
     Ok(db)
 }
 
-pub fn get_db<'a>(db: &'a Database) -> Result<&'a MongoDbClient, EngineError> {
-    match db {
-        Database::Mongo(db) => Ok(db),
-        _ => Err(EngineError::Manager(
-            "MongoDB connector is not setup correctly".to_owned(),
-        )),
-    }
+/**
+ * Connect to the database of a `mongodb://` url, which must name the database
+ */
+pub fn init_from_url(url: &str) -> Result<Database<'static>, EngineError> {
+    let client = mongodb::sync::Client::with_uri_str(url)?;
+    let database = match client.default_database() {
+        Some(database) => database,
+        None => {
+            return Err(EngineError::Manager(format!(
+                "Missing database name in {}",
+                url
+            )))
+        }
+    };
+
+    let mongodb_client = MongoDbClient::new(database);
+    create_ttl_indexes(&mongodb_client);
+    create_client_indexes(&mongodb_client);
+    create_analytics_indexes(&mongodb_client);
+
+    Ok(Database::Mongo(mongodb_client))
 }
 
 pub fn get_pagination_key(pagination_key: Option<String>) -> Result<Option<String>, EngineError> {
@@ -149,6 +171,20 @@ fn create_ttl_indexes(db: &MongoDbClient) {
         ))
         .build();
     state.create_index(index, None).ok();
+
+    // create index expires_at for counter
+    let counter = db.client.collection::<Document>("counter");
+    let index: IndexModel = IndexModel::builder()
+        .keys(doc! {
+            "expires_at": 1
+        })
+        .options(Some(
+            IndexOptions::builder()
+                .expire_after(CoreDuration::new(0, 0))
+                .build(),
+        ))
+        .build();
+    counter.create_index(index, None).ok();
 }
 
 fn create_client_indexes(db: &MongoDbClient) {
@@ -195,4 +231,39 @@ fn create_client_indexes(db: &MongoDbClient) {
         })
         .build();
     state.create_index(index, None).ok();
+
+    // create unique client and key index for counter, counters are incremented with upserts
+    let counter = db.client.collection::<Document>("counter");
+    let index: IndexModel = IndexModel::builder()
+        .keys(doc! {
+            "client.bot_id": 1,
+            "client.channel_id": 1,
+            "client.user_id": 1,
+            "key": 1
+        })
+        .options(Some(IndexOptions::builder().unique(true).build()))
+        .build();
+    counter.create_index(index, None).ok();
+}
+
+fn create_analytics_indexes(db: &MongoDbClient) {
+    // conversations of a bot over a period
+    let conversation = db.client.collection::<Document>("conversation");
+    let index: IndexModel = IndexModel::builder()
+        .keys(doc! {
+            "client.bot_id": 1,
+            "created_at": 1
+        })
+        .build();
+    conversation.create_index(index, None).ok();
+
+    // messages of a conversation in the order they were saved
+    let message = db.client.collection::<Document>("message");
+    let index: IndexModel = IndexModel::builder()
+        .keys(doc! {
+            "conversation_id": 1,
+            "created_at": 1
+        })
+        .build();
+    message.create_index(index, None).ok();
 }
@@ -0,0 +1,128 @@
+use crate::data::models::OutboxMessage;
+use crate::{
+    encrypt::{decrypt_data, encrypt_data},
+    Client, EngineError, MongoDbClient,
+};
+use bson::{doc, Document};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+struct OutboxDocument {
+    #[serde(rename = "_id")]
+    id: String,
+    client: Client,
+    callback_url: String,
+    payload: String, // encrypted
+    signature: Option<String>,
+    attempts: i32,
+    updated_at: bson::DateTime,
+    created_at: bson::DateTime,
+}
+
+pub(super) fn format_outbox_message(doc: Document) -> Result<OutboxMessage, EngineError> {
+    let message: OutboxDocument = bson::from_document(doc)?;
+
+    Ok(OutboxMessage {
+        id: Uuid::parse_str(&message.id)?,
+        client: message.client,
+        callback_url: message.callback_url,
+        payload: decrypt_data(message.payload)?,
+        signature: message.signature,
+        attempts: message.attempts as u32,
+        updated_at: message.updated_at.to_chrono(),
+        created_at: message.created_at.to_chrono(),
+    })
+}
+
+pub fn add_outbox_message(message: &OutboxMessage, db: &MongoDbClient) -> Result<(), EngineError> {
+    let message = doc! {
+        "_id": message.id.to_string(),
+        "client": bson::to_bson(&message.client)?,
+        "callback_url": &message.callback_url,
+        "payload": encrypt_data(&message.payload)?, // encrypted
+        "signature": &message.signature,
+        "attempts": message.attempts as i32,
+        "updated_at": bson::DateTime::from_chrono(message.updated_at),
+        "created_at": bson::DateTime::from_chrono(message.created_at),
+    };
+
+    let collection = db.client.collection::<Document>("outbox");
+    collection.insert_one(message, None)?;
+
+    Ok(())
+}
+
+pub fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let collection = db.client.collection::<Document>("outbox");
+
+    let filter = match bot_id {
+        Some(bot_id) => doc! { "client.bot_id": bot_id },
+        None => doc! {},
+    };
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .limit(i64::from(limit))
+        .build();
+
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut messages = vec![];
+    for doc in cursor {
+        messages.push(format_outbox_message(doc?)?);
+    }
+
+    Ok(messages)
+}
+
+pub fn add_outbox_attempt(id: Uuid, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection::<Document>("outbox");
+
+    collection.update_one(
+        doc! { "_id": id.to_string() },
+        doc! {
+            "$inc": { "attempts": 1 },
+            "$set": { "updated_at": bson::DateTime::from_chrono(chrono::Utc::now()) },
+        },
+        None,
+    )?;
+
+    Ok(())
+}
+
+pub fn delete_outbox_message(id: Uuid, db: &MongoDbClient) -> Result<bool, EngineError> {
+    let collection = db.client.collection::<Document>("outbox");
+    let result = collection.delete_one(doc! { "_id": id.to_string() }, None)?;
+
+    Ok(result.deleted_count > 0)
+}
+
+pub fn has_client_outbox(client: &Client, db: &MongoDbClient) -> Result<bool, EngineError> {
+    let collection = db.client.collection::<Document>("outbox");
+
+    let filter = doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+    };
+    let message = collection.find_one(filter, None)?;
+
+    Ok(message.is_some())
+}
+
+pub fn delete_client_outbox(client: &Client, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection::<Document>("outbox");
+
+    let filter = doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+    };
+    collection.delete_many(filter, None)?;
+
+    Ok(())
+}
@@ -0,0 +1,445 @@
+/**
+ * Records of every collection, read in batches by `migrate` and `rotate_encryption_key`,
+ * which saves their encrypted fields again.
+ *
+ * The engine saves its own uuids as `_id`, but the documents written by older versions,
+ * and the memories, state and bot versions, have an ObjectId created by MongoDB. An ObjectId
+ * is read as a version 8 uuid holding its 12 bytes, so that its order is kept and the
+ * `after` id of the next batch can be turned back into it.
+ *
+ * Documents are read ordered by `_id`: MongoDB sorts the uuid strings before the ObjectIds.
+ */
+use crate::data::models::{
+    BotVersionRecord, Conversation, Direction, MemoryRecord, Message, OutboxMessage, Schedule,
+    StateRecord,
+};
+use crate::encrypt::{decrypt_data, encrypt_data};
+use crate::{Client, EngineError, MongoDbClient};
+use bson::{doc, oid::ObjectId, Bson, Document};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{bot, outbox, schedules};
+
+fn object_id_to_uuid(id: &ObjectId) -> Uuid {
+    let id = id.bytes();
+    let mut bytes = [0; 16];
+
+    bytes[..6].copy_from_slice(&id[..6]);
+    bytes[9..15].copy_from_slice(&id[6..]);
+    // version 8 and RFC 4122 variant, which uuids made by the engine (v4, v7) never have
+    bytes[6] = 0x80;
+    bytes[8] = 0x80;
+
+    Uuid::from_bytes(bytes)
+}
+
+fn uuid_to_object_id(id: &Uuid) -> Option<ObjectId> {
+    if id.get_version_num() != 8 {
+        return None;
+    }
+
+    let id = id.as_bytes();
+    let mut bytes = [0; 12];
+    bytes[..6].copy_from_slice(&id[..6]);
+    bytes[6..].copy_from_slice(&id[9..15]);
+
+    Some(ObjectId::from_bytes(bytes))
+}
+
+fn parse_id(id: &str) -> Result<Uuid, EngineError> {
+    match ObjectId::parse_str(id) {
+        Ok(id) => Ok(object_id_to_uuid(&id)),
+        Err(_) => Ok(Uuid::parse_str(id)?),
+    }
+}
+
+fn record_id(id: &Bson) -> Result<Uuid, EngineError> {
+    match id {
+        Bson::String(id) => parse_id(id),
+        Bson::ObjectId(id) => Ok(object_id_to_uuid(id)),
+        _ => Err(EngineError::Manager(format!(
+            "Unsupported document id {}",
+            id
+        ))),
+    }
+}
+
+/**
+ * Filter on the `_id` of a record, saved as an ObjectId or as a uuid string
+ */
+fn id_filter(id: &Uuid) -> Document {
+    match uuid_to_object_id(id) {
+        Some(id) => doc! { "_id": id },
+        None => doc! { "_id": id.to_string() },
+    }
+}
+
+fn update_field(
+    collection: &str,
+    id: &Uuid,
+    field: &str,
+    value: String,
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let collection = db.client.collection::<Document>(collection);
+
+    let result = collection.update_one(id_filter(id), doc! { "$set": { field: value } }, None)?;
+
+    Ok(result.matched_count as usize)
+}
+
+/**
+ * Encrypt again the `field` of a document, only if it still holds `value`: a value
+ * written since it was read is not overwritten
+ */
+fn swap_encrypted_field(
+    collection: &str,
+    id: &Uuid,
+    field: &str,
+    value: &serde_json::Value,
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let collection = db.client.collection::<Document>(collection);
+
+    let saved = collection
+        .find_one(id_filter(id), None)?
+        .and_then(|doc| doc.get_str(field).ok().map(str::to_owned));
+    let saved = match saved {
+        Some(saved) if decrypt_data(saved.clone())? == *value => saved,
+        _ => return Ok(0),
+    };
+
+    let mut filter = id_filter(id);
+    filter.insert(field, saved);
+    let result = collection.update_one(
+        filter,
+        doc! { "$set": { field: encrypt_data(value)? } },
+        None,
+    )?;
+
+    Ok(result.matched_count as usize)
+}
+
+/**
+ * Documents of the collection ordered by `_id`, starting after the `after` id
+ */
+fn find_records<T: DeserializeOwned>(
+    collection: &str,
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<(Uuid, T)>, EngineError> {
+    let collection = db.client.collection::<Document>(collection);
+
+    let filter = match after {
+        None => doc! {},
+        Some(after) => match uuid_to_object_id(&after) {
+            Some(after) => doc! { "_id": { "$gt": after } },
+            // every ObjectId comes after the uuid strings
+            None => doc! {
+                "$or": [
+                    { "_id": { "$gt": after.to_string() } },
+                    { "_id": { "$type": "objectId" } },
+                ]
+            },
+        },
+    };
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .limit(limit as i64)
+        .build();
+
+    let mut records = vec![];
+    for doc in collection.find(filter, find_options)? {
+        let doc = doc?;
+        let id = record_id(doc.get("_id").unwrap_or(&Bson::Null))?;
+
+        records.push((id, bson::from_document(doc)?));
+    }
+
+    Ok(records)
+}
+
+#[derive(Deserialize)]
+struct BotDocument {
+    bot_id: String,
+    bot: String,
+    engine_version: String,
+    updated_at: Option<bson::DateTime>,
+    created_at: bson::DateTime,
+}
+
+pub fn get_bot_version_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<BotVersionRecord>, EngineError> {
+    let records = find_records::<BotDocument>("bot", after, limit, db)?;
+
+    records
+        .into_iter()
+        .map(|(id, version)| {
+            Ok(BotVersionRecord {
+                id,
+                bot_id: version.bot_id,
+                // saved as JSON, whatever the format it was saved with
+                bot: serde_json::json!(bot::parse_bot(&version.bot)?).to_string(),
+                engine_version: version.engine_version,
+                updated_at: version.updated_at.unwrap_or(version.created_at).to_chrono(),
+                created_at: version.created_at.to_chrono(),
+            })
+        })
+        .collect()
+}
+
+pub fn update_bot_version_records(
+    versions: &[BotVersionRecord],
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for version in versions {
+        updated += update_field("bot", &version.id, "bot", version.bot.to_owned(), db)?;
+    }
+
+    Ok(updated)
+}
+
+#[derive(Deserialize)]
+struct ConversationDocument {
+    client: Client,
+    flow_id: String,
+    step_id: String,
+    status: String,
+    last_interaction_at: bson::DateTime,
+    updated_at: Option<bson::DateTime>,
+    created_at: bson::DateTime,
+    expires_at: Option<bson::DateTime>,
+}
+
+pub fn get_conversation_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<Conversation>, EngineError> {
+    let records = find_records::<ConversationDocument>("conversation", after, limit, db)?;
+
+    Ok(records
+        .into_iter()
+        .map(|(id, conversation)| Conversation {
+            id,
+            client: conversation.client,
+            flow_id: conversation.flow_id,
+            step_id: conversation.step_id,
+            status: conversation.status,
+            last_interaction_at: conversation.last_interaction_at.to_chrono(),
+            updated_at: conversation
+                .updated_at
+                .unwrap_or(conversation.last_interaction_at)
+                .to_chrono(),
+            created_at: conversation.created_at.to_chrono(),
+            expires_at: conversation.expires_at.map(bson::DateTime::to_chrono),
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct MessageDocument {
+    conversation_id: String,
+    flow_id: String,
+    step_id: String,
+    message_order: i32,
+    interaction_order: i32,
+    direction: Direction,
+    content_type: Option<String>,
+    payload: String, // encrypted
+    updated_at: Option<bson::DateTime>,
+    created_at: bson::DateTime,
+    expires_at: Option<bson::DateTime>,
+}
+
+pub fn get_message_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<Message>, EngineError> {
+    let records = find_records::<MessageDocument>("message", after, limit, db)?;
+
+    records
+        .into_iter()
+        .map(|(id, message)| {
+            let payload = decrypt_data(message.payload)?;
+            // the content type was only saved in the payload by older versions
+            let content_type = match message.content_type {
+                Some(content_type) => content_type,
+                None => payload["content_type"].as_str().unwrap_or("").to_owned(),
+            };
+
+            Ok(Message {
+                id,
+                conversation_id: parse_id(&message.conversation_id)?,
+                flow_id: message.flow_id,
+                step_id: message.step_id,
+                message_order: message.message_order as u32,
+                interaction_order: message.interaction_order as u32,
+                direction: message.direction,
+                content_type,
+                payload,
+                updated_at: message.updated_at.unwrap_or(message.created_at).to_chrono(),
+                created_at: message.created_at.to_chrono(),
+                expires_at: message.expires_at.map(bson::DateTime::to_chrono),
+            })
+        })
+        .collect()
+}
+
+pub fn update_message_records(
+    messages: &[Message],
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for message in messages {
+        let payload = encrypt_data(&message.payload)?;
+        updated += update_field("message", &message.id, "payload", payload, db)?;
+    }
+
+    Ok(updated)
+}
+
+#[derive(Deserialize)]
+struct MemoryDocument {
+    client: Client,
+    key: String,
+    value: String, // encrypted
+    updated_at: Option<bson::DateTime>,
+    created_at: bson::DateTime,
+    expires_at: Option<bson::DateTime>,
+}
+
+pub fn get_memory_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<MemoryRecord>, EngineError> {
+    let records = find_records::<MemoryDocument>("memory", after, limit, db)?;
+
+    records
+        .into_iter()
+        .map(|(id, memory)| {
+            Ok(MemoryRecord {
+                id,
+                client: memory.client,
+                key: memory.key,
+                value: decrypt_data(memory.value)?,
+                expires_at: memory.expires_at.map(bson::DateTime::to_chrono),
+                updated_at: memory.updated_at.unwrap_or(memory.created_at).to_chrono(),
+                created_at: memory.created_at.to_chrono(),
+            })
+        })
+        .collect()
+}
+
+pub fn update_memory_records(
+    memories: &[MemoryRecord],
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for memory in memories {
+        updated += swap_encrypted_field("memory", &memory.id, "value", &memory.value, db)?;
+    }
+
+    Ok(updated)
+}
+
+#[derive(Deserialize)]
+struct StateDocument {
+    client: Client,
+    #[serde(rename = "type")]
+    type_: String,
+    key: String,
+    value: String, // encrypted
+    updated_at: Option<bson::DateTime>,
+    created_at: bson::DateTime,
+    expires_at: Option<bson::DateTime>,
+}
+
+pub fn get_state_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<StateRecord>, EngineError> {
+    let records = find_records::<StateDocument>("state", after, limit, db)?;
+
+    records
+        .into_iter()
+        .map(|(id, state)| {
+            Ok(StateRecord {
+                id,
+                client: state.client,
+                _type: state.type_,
+                key: state.key,
+                value: decrypt_data(state.value)?,
+                expires_at: state.expires_at.map(bson::DateTime::to_chrono),
+                updated_at: state.updated_at.unwrap_or(state.created_at).to_chrono(),
+                created_at: state.created_at.to_chrono(),
+            })
+        })
+        .collect()
+}
+
+pub fn update_state_records(
+    state: &[StateRecord],
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for item in state {
+        updated += swap_encrypted_field("state", &item.id, "value", &item.value, db)?;
+    }
+
+    Ok(updated)
+}
+
+pub fn get_schedule_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    let records = find_records::<Document>("schedule", after, limit, db)?;
+
+    records
+        .into_iter()
+        .map(|(_, doc)| schedules::format_schedule(doc))
+        .collect()
+}
+
+pub fn get_outbox_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let records = find_records::<Document>("outbox", after, limit, db)?;
+
+    records
+        .into_iter()
+        .map(|(_, doc)| outbox::format_outbox_message(doc))
+        .collect()
+}
+
+pub fn update_outbox_records(
+    messages: &[OutboxMessage],
+    db: &MongoDbClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for message in messages {
+        let payload = encrypt_data(&message.payload)?;
+        updated += update_field("outbox", &message.id, "payload", payload, db)?;
+    }
+
+    Ok(updated)
+}
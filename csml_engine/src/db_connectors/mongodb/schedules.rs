@@ -0,0 +1,124 @@
+use crate::data::models::{BotReference, Schedule};
+use crate::{Client, EngineError, MongoDbClient};
+use bson::{doc, Document};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+struct ScheduleDocument {
+    #[serde(rename = "_id")]
+    id: String,
+    client: Client,
+    flow_id: String,
+    step_id: String,
+    callback_url: Option<String>,
+    #[serde(default)]
+    bot: BotReference,
+    #[serde(default)]
+    attempts: i32,
+    run_at: bson::DateTime,
+    created_at: bson::DateTime,
+}
+
+pub(super) fn format_schedule(doc: Document) -> Result<Schedule, EngineError> {
+    let schedule: ScheduleDocument = bson::from_document(doc)?;
+
+    Ok(Schedule {
+        id: Uuid::parse_str(&schedule.id)?,
+        client: schedule.client,
+        flow_id: schedule.flow_id,
+        step_id: schedule.step_id,
+        callback_url: schedule.callback_url,
+        bot: schedule.bot,
+        attempts: schedule.attempts,
+        run_at: schedule.run_at.to_chrono(),
+        created_at: schedule.created_at.to_chrono(),
+    })
+}
+
+pub fn create_schedule(schedule: &Schedule, db: &MongoDbClient) -> Result<(), EngineError> {
+    let schedule = doc! {
+        "_id": schedule.id.to_string(),
+        "client": bson::to_bson(&schedule.client)?,
+        "flow_id": &schedule.flow_id,
+        "step_id": &schedule.step_id,
+        "callback_url": &schedule.callback_url,
+        "bot": bson::to_bson(&schedule.bot)?,
+        "attempts": schedule.attempts,
+        "run_at": bson::DateTime::from_chrono(schedule.run_at),
+        "created_at": bson::DateTime::from_chrono(schedule.created_at),
+    };
+
+    let collection = db.client.collection::<Document>("schedule");
+    collection.insert_one(schedule, None)?;
+
+    Ok(())
+}
+
+pub fn get_due_schedules(
+    now: DateTime<Utc>,
+    limit: u32,
+    db: &MongoDbClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    let collection = db.client.collection::<Document>("schedule");
+
+    let filter = doc! {
+        "run_at": { "$lte": bson::DateTime::from_chrono(now) },
+    };
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "run_at": 1 })
+        .limit(i64::from(limit))
+        .build();
+
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut schedules = vec![];
+    for doc in cursor {
+        schedules.push(format_schedule(doc?)?);
+    }
+
+    Ok(schedules)
+}
+
+pub fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &MongoDbClient,
+) -> Result<bool, EngineError> {
+    let collection = db.client.collection::<Document>("schedule");
+
+    let filter = doc! {
+        "_id": schedule.id.to_string(),
+        "attempts": schedule.attempts,
+    };
+    let update = doc! {
+        "$set": {
+            "attempts": schedule.attempts + 1,
+            "run_at": bson::DateTime::from_chrono(retry_at),
+        }
+    };
+    let result = collection.update_one(filter, update, None)?;
+
+    Ok(result.modified_count > 0)
+}
+
+pub fn delete_schedule(id: Uuid, db: &MongoDbClient) -> Result<bool, EngineError> {
+    let collection = db.client.collection::<Document>("schedule");
+    let result = collection.delete_one(doc! { "_id": id.to_string() }, None)?;
+
+    Ok(result.deleted_count > 0)
+}
+
+pub fn delete_client_schedules(client: &Client, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection::<Document>("schedule");
+
+    let filter = doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+    };
+    collection.delete_many(filter, None)?;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::data::models::StateItem;
 use crate::{
     encrypt::{decrypt_data, encrypt_data},
     EngineError, MongoDbClient,
@@ -75,6 +76,37 @@ pub fn get_state_key(
     }
 }
 
+/**
+ * Every state key of the client
+ */
+pub fn get_client_state(
+    client: &Client,
+    db: &MongoDbClient,
+) -> Result<Vec<StateItem>, EngineError> {
+    let collection = db.client.collection::<Document>("state");
+
+    let filter = doc! {
+        "client.bot_id": client.bot_id.to_owned(),
+        "client.user_id": client.user_id.to_owned(),
+        "client.channel_id": client.channel_id.to_owned(),
+    };
+    let cursor = collection.find(filter, None)?;
+
+    let mut state = vec![];
+    for doc in cursor {
+        let item: serde_json::Value = bson::from_bson(bson::Bson::Document(doc?))?;
+        let value = item["value"].as_str().unwrap_or_default().to_owned();
+
+        state.push(StateItem {
+            _type: item["type"].as_str().unwrap_or_default().to_owned(),
+            key: item["key"].as_str().unwrap_or_default().to_owned(),
+            value: decrypt_data(value)?,
+        });
+    }
+
+    Ok(state)
+}
+
 pub fn get_current_state(
     client: &Client,
     db: &MongoDbClient,
@@ -0,0 +1,84 @@
+use crate::data::models::OutboxMessage;
+use crate::db_connectors::db_span;
+use crate::{Client, Database, EngineError};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use uuid::Uuid;
+
+pub fn add_outbox_message(message: &OutboxMessage, db: &mut Database) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_outbox_message", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&message.client),
+            None,
+            None,
+            format!("db call add outbox message {}", message.id),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.add_outbox_message(message)
+}
+
+pub fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &mut Database,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let _span = db_span("csml.db.get_outbox_messages", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, "db call get outbox messages".to_string()),
+        LogLvl::Info,
+    );
+
+    db.storage()?.get_outbox_messages(bot_id, limit)
+}
+
+pub fn has_client_outbox(client: &Client, db: &mut Database) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.has_client_outbox", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            "db call has client outbox".to_string(),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.has_client_outbox(client)
+}
+
+pub fn add_outbox_attempt(id: Uuid, db: &mut Database) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_outbox_attempt", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call add outbox attempt {}", id),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.add_outbox_attempt(id)
+}
+
+pub fn delete_outbox_message(id: Uuid, db: &mut Database) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.delete_outbox_message", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call delete outbox message {}", id),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.delete_outbox_message(id)
+}
@@ -1,10 +1,12 @@
 use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 
+use crate::data::models::BotVersionRecord;
 use crate::{BotVersion, EngineError, PostgresqlClient, SerializeCsmlBot};
 
 use super::{models, pagination::*, schema::cmsl_bot_versions};
 
 use std::env;
+use uuid::Uuid;
 
 pub fn create_bot_version(
     bot_id: String,
@@ -140,3 +142,68 @@ pub fn delete_bot_versions(bot_id: &str, db: &mut PostgresqlClient) -> Result<()
 
     Ok(())
 }
+
+pub fn get_bot_version_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<BotVersionRecord>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::Bot> = cmsl_bot_versions::table
+        .filter(cmsl_bot_versions::id.gt(after.unwrap_or_default()))
+        .order_by(cmsl_bot_versions::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    Ok(records
+        .into_iter()
+        .map(|bot| BotVersionRecord {
+            id: bot.id,
+            bot_id: bot.bot_id,
+            bot: bot.bot,
+            engine_version: bot.engine_version,
+            updated_at: bot.updated_at.and_utc(),
+            created_at: bot.created_at.and_utc(),
+        })
+        .collect())
+}
+
+pub fn add_bot_version_records(
+    versions: &[BotVersionRecord],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let rows: Vec<models::Bot> = versions
+        .iter()
+        .map(|version| models::Bot {
+            id: version.id,
+            bot_id: version.bot_id.to_owned(),
+            bot: version.bot.to_owned(),
+            engine_version: version.engine_version.to_owned(),
+            updated_at: version.updated_at.naive_utc(),
+            created_at: version.created_at.naive_utc(),
+        })
+        .collect();
+
+    let created = diesel::insert_into(cmsl_bot_versions::table)
+        .values(&rows)
+        .on_conflict_do_nothing()
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
+
+pub fn update_bot_version_records(
+    versions: &[BotVersionRecord],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for version in versions {
+        updated +=
+            diesel::update(cmsl_bot_versions::table.filter(cmsl_bot_versions::id.eq(version.id)))
+                .set(cmsl_bot_versions::bot.eq(&version.bot))
+                .execute(db.client.as_mut())?;
+    }
+
+    Ok(updated)
+}
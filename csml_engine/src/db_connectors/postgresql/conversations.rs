@@ -38,7 +38,6 @@ pub fn close_conversation(
     status: &str,
     db: &mut PostgresqlClient,
 ) -> Result<(), EngineError> {
-
     diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(id)))
         .set(csml_conversations::status.eq(status))
         .execute(db.client.as_mut())?;
@@ -91,25 +90,30 @@ pub fn update_conversation(
     step_id: Option<String>,
     db: &mut PostgresqlClient,
 ) -> Result<(), EngineError> {
-
     match (flow_id, step_id) {
         (Some(flow_id), Some(step_id)) => {
-            diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(&conversation_id)))
-                .set((
-                    csml_conversations::flow_id.eq(flow_id.as_str()),
-                    csml_conversations::step_id.eq(step_id.as_str()),
-                ))
-                .execute(db.client.as_mut())?;
+            diesel::update(
+                csml_conversations::table.filter(csml_conversations::id.eq(&conversation_id)),
+            )
+            .set((
+                csml_conversations::flow_id.eq(flow_id.as_str()),
+                csml_conversations::step_id.eq(step_id.as_str()),
+            ))
+            .execute(db.client.as_mut())?;
         }
         (Some(flow_id), _) => {
-            diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(&conversation_id)))
-                .set(csml_conversations::flow_id.eq(flow_id.as_str()))
-                .get_result::<models::Conversation>(db.client.as_mut())?;
+            diesel::update(
+                csml_conversations::table.filter(csml_conversations::id.eq(&conversation_id)),
+            )
+            .set(csml_conversations::flow_id.eq(flow_id.as_str()))
+            .get_result::<models::Conversation>(db.client.as_mut())?;
         }
         (_, Some(step_id)) => {
-            diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(&conversation_id)))
-                .set(csml_conversations::step_id.eq(step_id.as_str()))
-                .get_result::<models::Conversation>(db.client.as_mut())?;
+            diesel::update(
+                csml_conversations::table.filter(csml_conversations::id.eq(&conversation_id)),
+            )
+            .set(csml_conversations::step_id.eq(step_id.as_str()))
+            .get_result::<models::Conversation>(db.client.as_mut())?;
         }
         _ => return Ok(()),
     };
@@ -167,6 +171,23 @@ pub fn get_client_conversations(
     })
 }
 
+pub fn get_bot_clients(
+    bot_id: &str,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<Client>, EngineError> {
+    let clients: Vec<(String, String)> = csml_conversations::table
+        .select((csml_conversations::channel_id, csml_conversations::user_id))
+        .filter(csml_conversations::bot_id.eq(bot_id))
+        .order_by((csml_conversations::channel_id, csml_conversations::user_id))
+        .distinct()
+        .load(db.client.as_mut())?;
+
+    Ok(clients
+        .into_iter()
+        .map(|(channel_id, user_id)| Client::new(bot_id.to_owned(), channel_id, user_id))
+        .collect())
+}
+
 pub fn delete_all_bot_data(bot_id: &str, db: &mut PostgresqlClient) -> Result<(), EngineError> {
     diesel::delete(csml_conversations::table.filter(csml_conversations::bot_id.eq(bot_id)))
         .execute(db.client.as_mut())
@@ -174,3 +195,47 @@ pub fn delete_all_bot_data(bot_id: &str, db: &mut PostgresqlClient) -> Result<()
 
     Ok(())
 }
+
+pub fn get_conversation_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<Conversation>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::Conversation> = csml_conversations::table
+        .filter(csml_conversations::id.gt(after.unwrap_or_default()))
+        .order_by(csml_conversations::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    Ok(records.into_iter().map(Into::into).collect())
+}
+
+pub fn add_conversation_records(
+    conversations: &[Conversation],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let rows: Vec<models::Conversation> = conversations
+        .iter()
+        .map(|conversation| models::Conversation {
+            id: conversation.id,
+            bot_id: conversation.client.bot_id.to_owned(),
+            channel_id: conversation.client.channel_id.to_owned(),
+            user_id: conversation.client.user_id.to_owned(),
+            flow_id: conversation.flow_id.to_owned(),
+            step_id: conversation.step_id.to_owned(),
+            status: conversation.status.to_owned(),
+            last_interaction_at: conversation.last_interaction_at.naive_utc(),
+            updated_at: conversation.updated_at.naive_utc(),
+            created_at: conversation.created_at.naive_utc(),
+            expires_at: conversation.expires_at.map(|date| date.naive_utc()),
+        })
+        .collect();
+
+    let created = diesel::insert_into(csml_conversations::table)
+        .values(&rows)
+        .on_conflict_do_nothing()
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
@@ -2,8 +2,8 @@ use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 use std::convert::TryInto;
 
 use crate::{
-    data, db_connectors::postgresql::get_db, encrypt::encrypt_data, Client, ConversationInfo,
-    EngineError, PostgresqlClient,
+    data, data::storage::ConversationStep, encrypt::encrypt_data, Client, EngineError,
+    PostgresqlClient,
 };
 
 use super::{
@@ -14,32 +14,32 @@ use super::{
 use crate::data::filter::ClientMessageFilter;
 use crate::data::models::PaginationData;
 use crate::db_connectors::diesel::Direction;
+use crate::db_connectors::utils::missing_conversation;
 use chrono::NaiveDateTime;
 use uuid::Uuid;
 
 pub fn add_messages_bulk(
-    data: &mut ConversationInfo,
+    step: &ConversationStep,
     msgs: &[serde_json::Value],
     interaction_order: i32,
     direction: Direction,
     expires_at: Option<NaiveDateTime>,
+    db: &mut PostgresqlClient,
 ) -> Result<(), EngineError> {
     if msgs.is_empty() {
         return Ok(());
     }
 
-    let db = get_db(&mut data.db)?;
-
     let mut new_messages = vec![];
     for (message_order, message) in msgs.iter().enumerate() {
-        let conversation_id = data.conversation_id;
+        let conversation_id = step.conversation_id;
 
         let msg = models::NewMessages {
             id: uuid::Uuid::new_v4(),
             conversation_id,
 
-            flow_id: &data.context.flow,
-            step_id: data.context.step.get_step_ref(),
+            flow_id: step.flow_id,
+            step_id: step.step_id,
             direction,
             payload: encrypt_data(message)?,
             content_type: message["content_type"].as_str().unwrap_or("text"),
@@ -244,3 +244,77 @@ fn get_messages_with_conversation_filter(
     };
     Ok(res)
 }
+
+pub fn get_message_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<data::models::Message>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::Message> = csml_messages::table
+        .filter(csml_messages::id.gt(after.unwrap_or_default()))
+        .order_by(csml_messages::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    records.into_iter().map(TryInto::try_into).collect()
+}
+
+pub fn add_message_records(
+    messages: &[data::models::Message],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let conversation_ids: Vec<Uuid> = messages
+        .iter()
+        .map(|message| message.conversation_id)
+        .collect();
+    let conversations: Vec<Uuid> = csml_conversations::table
+        .select(csml_conversations::id)
+        .filter(csml_conversations::id.eq_any(&conversation_ids))
+        .load(db.client.as_mut())?;
+
+    let mut rows = vec![];
+    for message in messages {
+        // messages are only saved in a conversation of the database
+        if !conversations.contains(&message.conversation_id) {
+            return Err(missing_conversation(message));
+        }
+
+        rows.push(models::Message {
+            id: message.id,
+            conversation_id: message.conversation_id,
+            flow_id: message.flow_id.to_owned(),
+            step_id: message.step_id.to_owned(),
+            direction: message.direction.to_owned().into(),
+            payload: encrypt_data(&message.payload)?,
+            content_type: message.content_type.to_owned(),
+            message_order: message.message_order as i32,
+            interaction_order: message.interaction_order as i32,
+            updated_at: message.updated_at.naive_utc(),
+            created_at: message.created_at.naive_utc(),
+            expires_at: message.expires_at.map(|date| date.naive_utc()),
+        });
+    }
+
+    let created = diesel::insert_into(csml_messages::table)
+        .values(&rows)
+        .on_conflict_do_nothing()
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
+
+pub fn update_message_records(
+    messages: &[data::models::Message],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for message in messages {
+        updated += diesel::update(csml_messages::table.filter(csml_messages::id.eq(message.id)))
+            .set(csml_messages::payload.eq(encrypt_data(&message.payload)?))
+            .execute(db.client.as_mut())?;
+    }
+
+    Ok(updated)
+}
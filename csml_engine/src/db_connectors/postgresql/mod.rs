@@ -1,7 +1,12 @@
+pub mod analytics;
+pub mod api_keys;
 pub mod bot;
 pub mod conversations;
+pub mod counters;
 pub mod memories;
 pub mod messages;
+pub mod outbox;
+pub mod schedules;
 pub mod state;
 
 pub mod pagination;
@@ -11,6 +16,8 @@ pub mod schema;
 
 pub mod expired_data;
 
+mod storage;
+
 use crate::{Database, EngineError, PostgresqlClient};
 
 use diesel::prelude::{Connection, PgConnection};
@@ -31,6 +38,18 @@ pub fn init() -> Result<Database<'static>, EngineError> {
     Ok(db)
 }
 
+/**
+ * Connect to the database of the `uri`, and create its tables if needed
+ */
+pub fn init_from_url(uri: &str) -> Result<Database<'static>, EngineError> {
+    let mut pg_connection = PgConnection::establish(uri)
+        .map_err(|err| EngineError::Manager(format!("Error connecting to {}: {}", uri, err)))?;
+
+    pg_connection.run_pending_migrations(MIGRATIONS)?;
+
+    Ok(Database::Postgresql(PostgresqlClient::new(pg_connection)))
+}
+
 pub fn make_migrations() -> Result<(), EngineError> {
     let uri = match std::env::var("POSTGRESQL_URL") {
         Ok(var) => var,
@@ -45,14 +64,3 @@ pub fn make_migrations() -> Result<(), EngineError> {
 
     Ok(())
 }
-
-pub fn get_db<'a, 'b>(
-    db: &'a mut Database<'b>,
-) -> Result<&'a mut PostgresqlClient<'b>, EngineError> {
-    match db {
-        Database::Postgresql(db) => Ok(db),
-        _ => Err(EngineError::Manager(
-            "Postgresql connector is not setup correctly".to_owned(),
-        )),
-    }
-}
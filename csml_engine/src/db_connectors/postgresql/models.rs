@@ -9,7 +9,7 @@ use diesel::{Associations, Identifiable, Insertable, Queryable};
 use std::convert::TryFrom;
 use uuid::Uuid;
 
-#[derive(Identifiable, Queryable, PartialEq, Debug)]
+#[derive(Identifiable, Insertable, Queryable, PartialEq, Debug)]
 #[diesel(table_name = cmsl_bot_versions)]
 pub struct Bot {
     pub id: Uuid,
@@ -31,7 +31,7 @@ pub struct NewBot<'a> {
     pub engine_version: &'a str,
 }
 
-#[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
+#[derive(Identifiable, Insertable, Queryable, Associations, PartialEq, Debug)]
 #[diesel(table_name = csml_conversations, belongs_to(Bot))]
 pub struct Conversation {
     pub id: Uuid,
@@ -86,7 +86,7 @@ pub struct NewConversation<'a> {
     pub expires_at: Option<NaiveDateTime>,
 }
 
-#[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
+#[derive(Identifiable, Insertable, Queryable, Associations, PartialEq, Debug)]
 #[diesel(table_name = csml_memories, belongs_to(Bot))]
 pub struct Memory {
     pub id: Uuid,
@@ -116,7 +116,7 @@ pub struct NewMemory<'a> {
     pub expires_at: Option<NaiveDateTime>,
 }
 
-#[derive(Identifiable, Queryable, Associations, PartialEq, Debug)]
+#[derive(Identifiable, Insertable, Queryable, Associations, PartialEq, Debug)]
 #[diesel(table_name = csml_messages, belongs_to(Conversation))]
 pub struct Message {
     pub id: Uuid,
@@ -209,6 +209,196 @@ pub struct NewState<'a> {
     pub expires_at: Option<NaiveDateTime>,
 }
 
+#[derive(Insertable, PartialEq, Debug)]
+#[diesel(table_name = csml_counters)]
+pub struct NewCounter<'a> {
+    pub bot_id: &'a str,
+    pub channel_id: &'a str,
+    pub user_id: &'a str,
+    pub key: &'a str,
+
+    pub count: i64,
+
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Identifiable, Queryable, PartialEq, Debug)]
+#[diesel(table_name = csml_outbox)]
+pub struct OutboxMessage {
+    pub id: Uuid,
+
+    pub bot_id: String,
+    pub channel_id: String,
+    pub user_id: String,
+
+    pub callback_url: String,
+    pub payload: String, // encrypted
+    pub signature: Option<String>,
+    pub attempts: i32,
+
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl TryFrom<OutboxMessage> for data::models::OutboxMessage {
+    type Error = EngineError;
+
+    fn try_from(value: OutboxMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            client: Client {
+                bot_id: value.bot_id,
+                channel_id: value.channel_id,
+                user_id: value.user_id,
+            },
+            callback_url: value.callback_url,
+            payload: decrypt_data(value.payload)?,
+            signature: value.signature,
+            attempts: value.attempts as u32,
+            updated_at: value.updated_at.and_utc(),
+            created_at: value.created_at.and_utc(),
+        })
+    }
+}
+
+#[derive(Insertable, PartialEq, Debug)]
+#[diesel(table_name = csml_outbox)]
+pub struct NewOutboxMessage<'a> {
+    pub id: Uuid,
+    pub bot_id: &'a str,
+    pub channel_id: &'a str,
+    pub user_id: &'a str,
+
+    pub callback_url: &'a str,
+    pub payload: String,
+    pub signature: Option<&'a str>,
+    pub attempts: i32,
+
+    pub updated_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Identifiable, Queryable, PartialEq, Debug)]
+#[diesel(table_name = csml_schedules)]
+pub struct Schedule {
+    pub id: Uuid,
+
+    pub bot_id: String,
+    pub channel_id: String,
+    pub user_id: String,
+
+    pub flow_id: String,
+    pub step_id: String,
+    pub callback_url: Option<String>,
+    pub bot: Option<String>, // json object
+
+    pub attempts: i32,
+    pub run_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<Schedule> for data::models::Schedule {
+    fn from(value: Schedule) -> Self {
+        Self {
+            id: value.id,
+            client: Client {
+                bot_id: value.bot_id,
+                channel_id: value.channel_id,
+                user_id: value.user_id,
+            },
+            flow_id: value.flow_id,
+            step_id: value.step_id,
+            callback_url: value.callback_url,
+            bot: value
+                .bot
+                .and_then(|bot| serde_json::from_str(&bot).ok())
+                .unwrap_or_default(),
+            attempts: value.attempts,
+            run_at: value.run_at.and_utc(),
+            created_at: value.created_at.and_utc(),
+        }
+    }
+}
+
+#[derive(Insertable, PartialEq, Debug)]
+#[diesel(table_name = csml_schedules)]
+pub struct NewSchedule<'a> {
+    pub id: Uuid,
+    pub bot_id: &'a str,
+    pub channel_id: &'a str,
+    pub user_id: &'a str,
+
+    pub flow_id: &'a str,
+    pub step_id: &'a str,
+    pub callback_url: Option<&'a str>,
+    pub bot: Option<String>,
+
+    pub attempts: i32,
+    pub run_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl<'a> From<&'a data::models::Schedule> for NewSchedule<'a> {
+    fn from(schedule: &'a data::models::Schedule) -> Self {
+        Self {
+            id: schedule.id,
+            bot_id: &schedule.client.bot_id,
+            channel_id: &schedule.client.channel_id,
+            user_id: &schedule.client.user_id,
+            flow_id: &schedule.flow_id,
+            step_id: &schedule.step_id,
+            callback_url: schedule.callback_url.as_deref(),
+            bot: serde_json::to_string(&schedule.bot).ok(),
+            attempts: schedule.attempts,
+            run_at: schedule.run_at.naive_utc(),
+            created_at: schedule.created_at.naive_utc(),
+        }
+    }
+}
+
+#[derive(Identifiable, Queryable, PartialEq, Debug)]
+#[diesel(table_name = csml_api_keys)]
+pub struct ApiKey {
+    pub id: Uuid,
+    pub name: String,
+    pub key_hash: String,
+
+    pub scopes: String,          // json array
+    pub bot_ids: Option<String>, // json array
+
+    pub created_at: NaiveDateTime,
+}
+
+impl TryFrom<ApiKey> for data::models::ApiKey {
+    type Error = EngineError;
+
+    fn try_from(value: ApiKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.id,
+            name: value.name,
+            scopes: serde_json::from_str(&value.scopes)?,
+            bot_ids: match value.bot_ids {
+                Some(bot_ids) => Some(serde_json::from_str(&bot_ids)?),
+                None => None,
+            },
+            created_at: value.created_at.and_utc(),
+        })
+    }
+}
+
+#[derive(Insertable, PartialEq, Debug)]
+#[diesel(table_name = csml_api_keys)]
+pub struct NewApiKey<'a> {
+    pub id: Uuid,
+    pub name: &'a str,
+    pub key_hash: &'a str,
+
+    pub scopes: String,
+    pub bot_ids: Option<String>,
+
+    pub created_at: NaiveDateTime,
+}
+
 // use serde::{ Deserializer};
 // use serde_derive::{Serialize,Deserialize};
 
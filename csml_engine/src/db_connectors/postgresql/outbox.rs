@@ -0,0 +1,160 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use std::convert::TryFrom;
+
+use crate::data::models::OutboxMessage;
+use crate::{encrypt::encrypt_data, Client, EngineError, PostgresqlClient};
+
+use super::{models, schema::csml_outbox};
+use chrono::Utc;
+use uuid::Uuid;
+
+pub fn add_outbox_message(
+    message: &OutboxMessage,
+    db: &mut PostgresqlClient,
+) -> Result<(), EngineError> {
+    let new_message = models::NewOutboxMessage {
+        id: message.id,
+        bot_id: &message.client.bot_id,
+        channel_id: &message.client.channel_id,
+        user_id: &message.client.user_id,
+        callback_url: &message.callback_url,
+        payload: encrypt_data(&message.payload)?,
+        signature: message.signature.as_deref(),
+        attempts: message.attempts as i32,
+        updated_at: message.updated_at.naive_utc(),
+        created_at: message.created_at.naive_utc(),
+    };
+
+    diesel::insert_into(csml_outbox::table)
+        .values(&new_message)
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let mut query = csml_outbox::table
+        .order_by(csml_outbox::created_at.asc())
+        .limit(limit as i64)
+        .into_boxed();
+
+    if let Some(bot_id) = bot_id {
+        query = query.filter(csml_outbox::bot_id.eq(bot_id));
+    }
+
+    let messages: Vec<models::OutboxMessage> = query.load(db.client.as_mut())?;
+
+    messages.into_iter().map(OutboxMessage::try_from).collect()
+}
+
+pub fn add_outbox_attempt(id: Uuid, db: &mut PostgresqlClient) -> Result<(), EngineError> {
+    diesel::update(csml_outbox::table.filter(csml_outbox::id.eq(id)))
+        .set((
+            csml_outbox::attempts.eq(csml_outbox::attempts + 1),
+            csml_outbox::updated_at.eq(Utc::now().naive_utc()),
+        ))
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn delete_outbox_message(id: Uuid, db: &mut PostgresqlClient) -> Result<bool, EngineError> {
+    let count = diesel::delete(csml_outbox::table.filter(csml_outbox::id.eq(id)))
+        .execute(db.client.as_mut())?;
+
+    Ok(count > 0)
+}
+
+pub fn has_client_outbox(client: &Client, db: &mut PostgresqlClient) -> Result<bool, EngineError> {
+    let count: i64 = csml_outbox::table
+        .filter(csml_outbox::bot_id.eq(&client.bot_id))
+        .filter(csml_outbox::channel_id.eq(&client.channel_id))
+        .filter(csml_outbox::user_id.eq(&client.user_id))
+        .count()
+        .get_result(db.client.as_mut())?;
+
+    Ok(count > 0)
+}
+
+pub fn delete_client_outbox(client: &Client, db: &mut PostgresqlClient) -> Result<(), EngineError> {
+    diesel::delete(
+        csml_outbox::table
+            .filter(csml_outbox::bot_id.eq(&client.bot_id))
+            .filter(csml_outbox::channel_id.eq(&client.channel_id))
+            .filter(csml_outbox::user_id.eq(&client.user_id)),
+    )
+    .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut PostgresqlClient) -> Result<(), EngineError> {
+    diesel::delete(csml_outbox::table.filter(csml_outbox::bot_id.eq(bot_id)))
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn get_outbox_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::OutboxMessage> = csml_outbox::table
+        .filter(csml_outbox::id.gt(after.unwrap_or_default()))
+        .order_by(csml_outbox::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    records.into_iter().map(OutboxMessage::try_from).collect()
+}
+
+pub fn add_outbox_records(
+    messages: &[OutboxMessage],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let rows = messages
+        .iter()
+        .map(|message| {
+            Ok(models::NewOutboxMessage {
+                id: message.id,
+                bot_id: &message.client.bot_id,
+                channel_id: &message.client.channel_id,
+                user_id: &message.client.user_id,
+                callback_url: &message.callback_url,
+                payload: encrypt_data(&message.payload)?,
+                signature: message.signature.as_deref(),
+                attempts: message.attempts as i32,
+                updated_at: message.updated_at.naive_utc(),
+                created_at: message.created_at.naive_utc(),
+            })
+        })
+        .collect::<Result<Vec<_>, EngineError>>()?;
+
+    let created = diesel::insert_into(csml_outbox::table)
+        .values(&rows)
+        .on_conflict_do_nothing()
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
+
+pub fn update_outbox_records(
+    messages: &[OutboxMessage],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for message in messages {
+        updated += diesel::update(csml_outbox::table.filter(csml_outbox::id.eq(message.id)))
+            .set(csml_outbox::payload.eq(encrypt_data(&message.payload)?))
+            .execute(db.client.as_mut())?;
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,109 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+
+use crate::data::models::Schedule;
+use crate::{Client, EngineError, PostgresqlClient};
+
+use super::{models, schema::csml_schedules};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub fn create_schedule(schedule: &Schedule, db: &mut PostgresqlClient) -> Result<(), EngineError> {
+    let new_schedule = models::NewSchedule::from(schedule);
+
+    diesel::insert_into(csml_schedules::table)
+        .values(&new_schedule)
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn get_due_schedules(
+    now: DateTime<Utc>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    let schedules: Vec<models::Schedule> = csml_schedules::table
+        .filter(csml_schedules::run_at.le(now.naive_utc()))
+        .order_by(csml_schedules::run_at.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    Ok(schedules.into_iter().map(Schedule::from).collect())
+}
+
+pub fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut PostgresqlClient,
+) -> Result<bool, EngineError> {
+    let count = diesel::update(
+        csml_schedules::table
+            .filter(csml_schedules::id.eq(schedule.id))
+            .filter(csml_schedules::attempts.eq(schedule.attempts)),
+    )
+    .set((
+        csml_schedules::attempts.eq(schedule.attempts + 1),
+        csml_schedules::run_at.eq(retry_at.naive_utc()),
+    ))
+    .execute(db.client.as_mut())?;
+
+    Ok(count > 0)
+}
+
+pub fn delete_schedule(id: Uuid, db: &mut PostgresqlClient) -> Result<bool, EngineError> {
+    let count = diesel::delete(csml_schedules::table.filter(csml_schedules::id.eq(id)))
+        .execute(db.client.as_mut())?;
+
+    Ok(count > 0)
+}
+
+pub fn delete_client_schedules(
+    client: &Client,
+    db: &mut PostgresqlClient,
+) -> Result<(), EngineError> {
+    diesel::delete(
+        csml_schedules::table
+            .filter(csml_schedules::bot_id.eq(&client.bot_id))
+            .filter(csml_schedules::channel_id.eq(&client.channel_id))
+            .filter(csml_schedules::user_id.eq(&client.user_id)),
+    )
+    .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut PostgresqlClient) -> Result<(), EngineError> {
+    diesel::delete(csml_schedules::table.filter(csml_schedules::bot_id.eq(bot_id)))
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn get_schedule_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut PostgresqlClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::Schedule> = csml_schedules::table
+        .filter(csml_schedules::id.gt(after.unwrap_or_default()))
+        .order_by(csml_schedules::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    Ok(records.into_iter().map(Schedule::from).collect())
+}
+
+pub fn add_schedule_records(
+    schedules: &[Schedule],
+    db: &mut PostgresqlClient,
+) -> Result<usize, EngineError> {
+    let rows: Vec<models::NewSchedule> = schedules.iter().map(models::NewSchedule::from).collect();
+
+    let created = diesel::insert_into(csml_schedules::table)
+        .values(&rows)
+        .on_conflict_do_nothing()
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
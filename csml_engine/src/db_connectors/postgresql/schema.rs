@@ -9,6 +9,17 @@ table! {
     }
 }
 
+table! {
+    csml_api_keys (id) {
+        id -> Uuid,
+        name -> Varchar,
+        key_hash -> Varchar,
+        scopes -> Varchar,
+        bot_ids -> Nullable<Varchar>,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     csml_conversations (id) {
         id -> Uuid,
@@ -25,6 +36,17 @@ table! {
     }
 }
 
+table! {
+    csml_counters (bot_id, channel_id, user_id, key) {
+        bot_id -> Varchar,
+        channel_id -> Varchar,
+        user_id -> Varchar,
+        key -> Varchar,
+        count -> Int8,
+        expires_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     csml_memories (id) {
         id -> Uuid,
@@ -72,12 +94,47 @@ table! {
     }
 }
 
+table! {
+    csml_outbox (id) {
+        id -> Uuid,
+        bot_id -> Varchar,
+        channel_id -> Varchar,
+        user_id -> Varchar,
+        callback_url -> Varchar,
+        payload -> Varchar,
+        signature -> Nullable<Varchar>,
+        attempts -> Int4,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    csml_schedules (id) {
+        id -> Uuid,
+        bot_id -> Varchar,
+        channel_id -> Varchar,
+        user_id -> Varchar,
+        flow_id -> Varchar,
+        step_id -> Varchar,
+        callback_url -> Nullable<Varchar>,
+        bot -> Nullable<Varchar>,
+        attempts -> Int4,
+        run_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 joinable!(csml_messages -> csml_conversations (conversation_id));
 
 allow_tables_to_appear_in_same_query!(
     cmsl_bot_versions,
+    csml_api_keys,
     csml_conversations,
+    csml_counters,
     csml_memories,
     csml_messages,
+    csml_outbox,
+    csml_schedules,
     csml_states,
 );
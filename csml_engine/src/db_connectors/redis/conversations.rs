@@ -0,0 +1,316 @@
+use crate::data::models::{Conversation, PaginationData};
+use crate::db_connectors::utils::paginate;
+use crate::{data, Client, EngineError, RedisClient};
+use ::redis::Commands;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::{client_key, delete_keys, scan_keys, write_with_expiration};
+
+/**
+ * Set of the ids of all the conversations of a client
+ */
+const KIND: &str = "conversations";
+
+/**
+ * Set of the ids of the open conversations of a client, so that finding or closing them
+ * does not read the closed ones
+ */
+const OPEN_KIND: &str = "open_conversations";
+
+/**
+ * Each conversation is stored in its own key, which expires with the conversation
+ */
+fn conversation_key(id: &str, db: &RedisClient) -> String {
+    format!("{}:conversation:{}", db.prefix, id)
+}
+
+fn is_expired(conversation: &Conversation, now: &DateTime<Utc>) -> bool {
+    matches!(conversation.expires_at, Some(expires_at) if expires_at < *now)
+}
+
+/**
+ * Add the write of the conversation to the pipeline, the key keeping the expiration date
+ * of the conversation
+ */
+fn set_conversation(
+    pipe: &mut ::redis::Pipeline,
+    conversation: &Conversation,
+    db: &RedisClient,
+) -> Result<(), EngineError> {
+    let key = conversation_key(&conversation.id.to_string(), db);
+    let value = serde_json::to_string(conversation)?;
+
+    match conversation.expires_at {
+        Some(expires_at) => {
+            let ttl = (expires_at - Utc::now()).num_seconds().max(1) as usize;
+            pipe.set_ex(key, value, ttl)
+        }
+        None => pipe.set(key, value),
+    }
+    .ignore();
+
+    Ok(())
+}
+
+/**
+ * Conversations of the set `set_key` that are not expired. The ids of the expired ones
+ * are removed from the set.
+ */
+fn get_conversations(
+    set_key: &str,
+    db: &mut RedisClient,
+) -> Result<Vec<Conversation>, EngineError> {
+    let ids: Vec<String> = db.client.smembers(set_key)?;
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let keys: Vec<String> = ids.iter().map(|id| conversation_key(id, db)).collect();
+    let values: Vec<Option<String>> = ::redis::cmd("MGET").arg(&keys).query(&mut db.client)?;
+    let now = Utc::now();
+
+    let mut conversations = Vec::with_capacity(values.len());
+    let mut expired = vec![];
+    for (id, value) in ids.into_iter().zip(values) {
+        let conversation: Option<Conversation> = match value {
+            Some(value) => Some(serde_json::from_str(&value)?),
+            None => None,
+        };
+
+        match conversation {
+            Some(conversation) if !is_expired(&conversation, &now) => {
+                conversations.push(conversation)
+            }
+            _ => expired.push(id),
+        }
+    }
+
+    if !expired.is_empty() {
+        db.client.srem::<_, _, ()>(set_key, expired)?;
+    }
+
+    Ok(conversations)
+}
+
+pub fn create_conversation(
+    flow_id: &str,
+    step_id: &str,
+    client: &Client,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut RedisClient,
+) -> Result<Uuid, EngineError> {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+
+    let conversation = Conversation {
+        id,
+        client: client.to_owned(),
+        flow_id: flow_id.to_owned(),
+        step_id: step_id.to_owned(),
+        status: "OPEN".to_owned(),
+        last_interaction_at: now,
+        updated_at: now,
+        created_at: now,
+        expires_at,
+    };
+
+    let mut pipe = ::redis::pipe();
+    pipe.atomic();
+    set_conversation(&mut pipe, &conversation, db)?;
+    for kind in [KIND, OPEN_KIND] {
+        let set_key = client_key(client, kind, db);
+        write_with_expiration(&mut pipe, &set_key, "SADD", id.to_string(), expires_at);
+    }
+
+    pipe.query::<()>(&mut db.client)?;
+
+    Ok(id)
+}
+
+fn find_conversation(id: Uuid, db: &mut RedisClient) -> Result<Option<Conversation>, EngineError> {
+    let conversation: Option<String> = db.client.get(conversation_key(&id.to_string(), db))?;
+
+    match conversation {
+        Some(conversation) => {
+            let conversation: Conversation = serde_json::from_str(&conversation)?;
+
+            Ok((!is_expired(&conversation, &Utc::now())).then_some(conversation))
+        }
+        None => Ok(None),
+    }
+}
+
+fn get_client_conversation(
+    id: Uuid,
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<Option<Conversation>, EngineError> {
+    let conversation = find_conversation(id, db)?;
+
+    Ok(conversation.filter(|conversation| conversation.client == *client))
+}
+
+pub fn close_conversation(
+    id: Uuid,
+    client: &Client,
+    status: &str,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    let mut pipe = ::redis::pipe();
+    pipe.atomic();
+
+    if let Some(mut conversation) = get_client_conversation(id, client, db)? {
+        conversation.status = status.to_owned();
+        conversation.updated_at = Utc::now();
+
+        set_conversation(&mut pipe, &conversation, db)?;
+    }
+    pipe.srem(client_key(client, OPEN_KIND, db), id.to_string())
+        .ignore();
+
+    pipe.query::<()>(&mut db.client)?;
+
+    Ok(())
+}
+
+pub fn close_all_conversations(client: &Client, db: &mut RedisClient) -> Result<(), EngineError> {
+    let open_key = client_key(client, OPEN_KIND, db);
+    let now = Utc::now();
+
+    let mut pipe = ::redis::pipe();
+    pipe.atomic();
+
+    for mut conversation in get_conversations(&open_key, db)? {
+        conversation.status = "CLOSED".to_owned();
+        conversation.updated_at = now;
+
+        set_conversation(&mut pipe, &conversation, db)?;
+    }
+    pipe.del(open_key).ignore();
+
+    pipe.query::<()>(&mut db.client)?;
+
+    Ok(())
+}
+
+pub fn get_latest_open(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<Option<Conversation>, EngineError> {
+    let open_key = client_key(client, OPEN_KIND, db);
+
+    let conversation = get_conversations(&open_key, db)?
+        .into_iter()
+        .filter(|conv| conv.status == "OPEN")
+        .max_by_key(|conv| conv.updated_at);
+
+    Ok(conversation)
+}
+
+pub fn update_conversation(
+    conversation_id: Uuid,
+    client: &Client,
+    flow_id: Option<String>,
+    step_id: Option<String>,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    if flow_id.is_none() && step_id.is_none() {
+        return Ok(());
+    }
+
+    let mut conversation = match get_client_conversation(conversation_id, client, db)? {
+        Some(conversation) => conversation,
+        None => return Ok(()),
+    };
+
+    let now = Utc::now();
+    if let Some(flow_id) = flow_id {
+        conversation.flow_id = flow_id;
+    }
+    if let Some(step_id) = step_id {
+        conversation.step_id = step_id;
+    }
+    conversation.last_interaction_at = now;
+    conversation.updated_at = now;
+
+    let mut pipe = ::redis::pipe();
+    set_conversation(&mut pipe, &conversation, db)?;
+
+    pipe.query::<()>(&mut db.client)?;
+
+    Ok(())
+}
+
+pub fn get_conversation(
+    db: &mut RedisClient,
+    id: Uuid,
+) -> Result<data::models::Conversation, EngineError> {
+    match find_conversation(id, db)? {
+        Some(conversation) => Ok(conversation),
+        None => Err(EngineError::Manager(format!(
+            "conversation ({}) not found in db",
+            id
+        ))),
+    }
+}
+
+pub fn get_client_conversations(
+    client: &Client,
+    db: &mut RedisClient,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+) -> Result<data::models::Paginated<Conversation>, EngineError> {
+    let key = client_key(client, KIND, db);
+    let pagination_key = pagination_key.unwrap_or(1);
+    let limit_per_page = limit.unwrap_or(25).min(25);
+
+    let mut conversations = get_conversations(&key, db)?;
+    conversations.sort_by_key(|conv| std::cmp::Reverse(conv.updated_at));
+
+    let (convs, total_pages) = paginate(conversations, pagination_key, limit_per_page);
+
+    let pagination = (pagination_key < total_pages).then_some(PaginationData {
+        page: pagination_key,
+        total_pages,
+        per_page: limit_per_page,
+    });
+    Ok(data::models::Paginated {
+        data: convs,
+        pagination,
+    })
+}
+
+/**
+ * Remove the sets of conversations `keys` of a kind, with the conversations they hold
+ */
+fn delete_conversations(keys: Vec<String>, db: &mut RedisClient) -> Result<(), EngineError> {
+    let mut to_delete = vec![];
+    for key in keys {
+        let ids: Vec<String> = db.client.smembers(&key)?;
+
+        to_delete.extend(ids.iter().map(|id| conversation_key(id, db)));
+        to_delete.push(key);
+    }
+
+    delete_keys(&to_delete, db)
+}
+
+pub fn delete_user_conversations(client: &Client, db: &mut RedisClient) -> Result<(), EngineError> {
+    let keys = vec![
+        client_key(client, KIND, db),
+        client_key(client, OPEN_KIND, db),
+    ];
+
+    delete_conversations(keys, db)
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut RedisClient) -> Result<(), EngineError> {
+    let mut keys = vec![];
+    for kind in [KIND, OPEN_KIND] {
+        let pattern = format!("{}:*:{}", super::bot_key(bot_id, db), kind);
+        keys.extend(scan_keys(&pattern, db)?);
+    }
+
+    delete_conversations(keys, db)
+}
@@ -0,0 +1,56 @@
+use crate::{Client, EngineError, RedisClient};
+use ::redis::Commands;
+use chrono::{DateTime, Utc};
+
+use super::{bot_key, client_key, delete_keys, scan_keys};
+
+const KIND: &str = "counter";
+
+/**
+ * Each counter has its own key, so that redis increments it and expires it on its own
+ */
+fn counter_key(client: &Client, key: &str, db: &RedisClient) -> String {
+    format!("{}:{}", client_key(client, KIND, db), key)
+}
+
+pub fn get_counter(client: &Client, key: &str, db: &mut RedisClient) -> Result<i64, EngineError> {
+    let count: Option<i64> = db.client.get(counter_key(client, key, db))?;
+
+    Ok(count.unwrap_or(0))
+}
+
+pub fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut RedisClient,
+) -> Result<i64, EngineError> {
+    let key = counter_key(client, key, db);
+
+    let mut pipe = ::redis::pipe();
+    pipe.atomic().cmd("INCRBY").arg(&key).arg(count);
+    match expires_at {
+        Some(expires_at) => pipe.cmd("EXPIREAT").arg(&key).arg(expires_at.timestamp()),
+        None => pipe.cmd("PERSIST").arg(&key),
+    }
+    .ignore();
+
+    let (count,): (i64,) = pipe.query(&mut db.client)?;
+
+    Ok(count)
+}
+
+pub fn delete_client_counters(client: &Client, db: &mut RedisClient) -> Result<(), EngineError> {
+    let pattern = format!("{}:*", client_key(client, KIND, db));
+    let keys = scan_keys(&pattern, db)?;
+
+    delete_keys(&keys, db)
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut RedisClient) -> Result<(), EngineError> {
+    let pattern = format!("{}:*:{}:*", bot_key(bot_id, db), KIND);
+    let keys = scan_keys(&pattern, db)?;
+
+    delete_keys(&keys, db)
+}
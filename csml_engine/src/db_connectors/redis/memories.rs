@@ -0,0 +1,163 @@
+use crate::{
+    encrypt::{decrypt_data, encrypt_data},
+    Client, EngineError, Memory, RedisClient,
+};
+use ::redis::Commands;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use super::{client_key, delete_fields, delete_keys, models::Record, write_fields};
+
+const KIND: &str = "memories";
+
+pub fn add_memories(
+    client: &Client,
+    memories: &HashMap<String, Memory>,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    if memories.is_empty() {
+        return Ok(());
+    }
+
+    let mut fields = Vec::with_capacity(memories.len());
+    for (key, mem) in memories.iter() {
+        let record = Record::new(encrypt_data(&mem.value)?, expires_at);
+        fields.push((key.to_owned(), serde_json::to_string(&record)?));
+    }
+
+    let key = client_key(client, KIND, db);
+    write_fields(&key, &fields, expires_at, db)
+}
+
+pub fn create_client_memory(
+    client: &Client,
+    key: &str,
+    value: &serde_json::Value,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    let record = Record::new(encrypt_data(value)?, expires_at);
+    let fields = [(key.to_owned(), serde_json::to_string(&record)?)];
+
+    let key = client_key(client, KIND, db);
+    write_fields(&key, &fields, expires_at, db)
+}
+
+/**
+ * Memories of the client that are not expired, with their decrypted value.
+ * The expired ones are removed from the hash.
+ */
+fn get_client_memories(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<Vec<(String, serde_json::Value, Record)>, EngineError> {
+    let hash_key = client_key(client, KIND, db);
+    let fields: HashMap<String, String> = db.client.hgetall(&hash_key)?;
+    let now = Utc::now();
+
+    let mut memories = vec![];
+    let mut expired = vec![];
+    for (key, record) in fields {
+        let record: Record = serde_json::from_str(&record)?;
+        if record.is_expired(&now) {
+            expired.push(key);
+            continue;
+        }
+
+        let value = decrypt_data(record.value.to_owned())?;
+        memories.push((key, value, record));
+    }
+    delete_fields(&hash_key, &expired, db)?;
+
+    Ok(memories)
+}
+
+fn format_memory(
+    key: String,
+    value: serde_json::Value,
+    record: &Record,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut memory = serde_json::Map::new();
+
+    memory.insert("key".to_owned(), serde_json::json!(key));
+    memory.insert("value".to_owned(), value);
+    memory.insert(
+        "created_at".to_owned(),
+        serde_json::json!(record.created_at.naive_utc().to_string()),
+    );
+
+    memory
+}
+
+pub fn internal_use_get_memories(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<serde_json::Value, EngineError> {
+    let mut map = serde_json::Map::new();
+    for (key, value, _) in get_client_memories(client, db)? {
+        map.insert(key, value);
+    }
+
+    Ok(serde_json::json!(map))
+}
+
+pub fn get_memories(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<serde_json::Value, EngineError> {
+    let vec: Vec<_> = get_client_memories(client, db)?
+        .into_iter()
+        .map(|(key, value, record)| format_memory(key, value, &record))
+        .collect();
+
+    Ok(serde_json::json!(vec))
+}
+
+pub fn get_memory(
+    client: &Client,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<serde_json::Value, EngineError> {
+    let record: Option<String> = db.client.hget(client_key(client, KIND, db), key)?;
+    let record: Option<Record> = match record {
+        Some(record) => Some(serde_json::from_str(&record)?),
+        None => None,
+    };
+
+    match record {
+        Some(record) if !record.is_expired(&Utc::now()) => {
+            let value = decrypt_data(record.value.to_owned())?;
+            Ok(serde_json::json!(format_memory(
+                key.to_owned(),
+                value,
+                &record
+            )))
+        }
+        _ => Err(EngineError::Manager(format!(
+            "memory ({}) not found in db",
+            key
+        ))),
+    }
+}
+
+pub fn delete_client_memory(
+    client: &Client,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    db.client
+        .hdel::<_, _, ()>(client_key(client, KIND, db), key)?;
+
+    Ok(())
+}
+
+pub fn delete_client_memories(client: &Client, db: &mut RedisClient) -> Result<(), EngineError> {
+    let key = client_key(client, KIND, db);
+
+    delete_keys(&[key], db)
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut RedisClient) -> Result<(), EngineError> {
+    super::delete_all_bot_data(bot_id, KIND, db)
+}
@@ -0,0 +1,385 @@
+/**
+ * Redis storage for conversations, memories, state and counters.
+ *
+ * Messages and bot versions are not stored in redis: when REDIS_URL is set, the
+ * database selected with ENGINE_DB_TYPE keeps them and this connector handles
+ * everything that is read and written on each request (see `SplitStorage`).
+ *
+ * Memories and state of a client are kept in one redis hash per kind of data, under the key
+ * `{prefix}:{md5(bot_id)}:{md5(channel_id, user_id)}:{kind}`. Each record of a hash carries
+ * its own expiration date: expired records are never returned and are removed when they
+ * are read. The expiration of the hash itself is only pushed back, to the latest expiration
+ * of its records, so that writing a record with a short `ttl_duration` never removes the
+ * ones written before with a longer one or without any.
+ *
+ * Each conversation has its own key, expiring with the conversation, and the client keeps
+ * the sets of its conversations and of its open ones (see `conversations`).
+ *
+ * Each counter also has its own key, `{client key of kind counter}:{counter key}`, incremented
+ * with INCRBY and expiring with its window (see `counters`).
+ */
+pub mod conversations;
+pub mod counters;
+pub mod memories;
+pub mod models;
+pub mod state;
+
+mod storage;
+
+use crate::data::storage::SplitStorage;
+use crate::{Client, Database, EngineError, RedisClient};
+use chrono::{DateTime, Utc};
+use md5::{Digest, Md5};
+use std::env;
+
+pub fn init(db: Database<'static>) -> Result<Database<'static>, EngineError> {
+    let url = match env::var("REDIS_URL") {
+        Ok(url) => url,
+        Err(_) => return Err(EngineError::Manager("REDIS_URL is not set".to_owned())),
+    };
+
+    let client = ::redis::Client::open(url)?;
+    let mut redis = RedisClient::new(client.get_connection()?);
+    if let Ok(prefix) = env::var("REDIS_KEY_PREFIX") {
+        redis = redis.with_prefix(&prefix);
+    }
+
+    Ok(Database::custom(SplitStorage::new(redis, db)?))
+}
+
+fn hash(parts: &[&str]) -> String {
+    let mut hash = Md5::new();
+    hash.update(serde_json::json!(parts).to_string().as_bytes());
+
+    hex::encode(hash.finalize())
+}
+
+fn bot_key(bot_id: &str, db: &RedisClient) -> String {
+    format!("{}:{}", db.prefix, hash(&[bot_id]))
+}
+
+/**
+ * Key of the hash holding the records of type `kind` of the client
+ */
+pub fn client_key(client: &Client, kind: &str, db: &RedisClient) -> String {
+    format!(
+        "{}:{}:{}",
+        bot_key(&client.bot_id, db),
+        hash(&[&client.channel_id, &client.user_id]),
+        kind
+    )
+}
+
+/**
+ * KEYS[1]: key to write, ARGV[1]: expiration timestamp of the written value (empty if it
+ * never expires), ARGV[2..]: write command and its arguments.
+ * The ttl is read before the write, to tell a key without expiration from a new one.
+ */
+const WRITE_WITH_EXPIRATION: &str = r"
+local ttl = redis.call('TTL', KEYS[1])
+redis.call(ARGV[2], KEYS[1], unpack(ARGV, 3))
+if ARGV[1] == '' then
+    redis.call('PERSIST', KEYS[1])
+elseif ttl ~= -1 then
+    local expires_at = tonumber(ARGV[1])
+    if ttl == -2 or expires_at > tonumber(redis.call('TIME')[1]) + ttl then
+        redis.call('EXPIREAT', KEYS[1], expires_at)
+    end
+end
+";
+
+/**
+ * Add to the pipeline a write `command` on `key`, keeping the key at least until `expires_at`.
+ * The expiration of the key is never brought forward, and a value without expiration
+ * makes the key persistent.
+ */
+pub fn write_with_expiration<A: ::redis::ToRedisArgs>(
+    pipe: &mut ::redis::Pipeline,
+    key: &str,
+    command: &str,
+    args: A,
+    expires_at: Option<DateTime<Utc>>,
+) {
+    let expires_at = match expires_at {
+        Some(expires_at) => expires_at.timestamp().max(0).to_string(),
+        None => String::new(),
+    };
+
+    pipe.cmd("EVAL")
+        .arg(WRITE_WITH_EXPIRATION)
+        .arg(1)
+        .arg(key)
+        .arg(expires_at)
+        .arg(command)
+        .arg(args)
+        .ignore();
+}
+
+/**
+ * Write the fields of a hash, each field expiring at `expires_at`
+ */
+pub fn write_fields(
+    key: &str,
+    fields: &[(String, String)],
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    let mut pipe = ::redis::pipe();
+    write_with_expiration(&mut pipe, key, "HSET", fields, expires_at);
+
+    pipe.query::<()>(&mut db.client)?;
+
+    Ok(())
+}
+
+/**
+ * Remove the fields of a hash, once their records have expired
+ */
+pub fn delete_fields(
+    key: &str,
+    fields: &[String],
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    ::redis::Commands::hdel::<_, _, ()>(&mut db.client, key, fields)?;
+
+    Ok(())
+}
+
+pub fn scan_keys(pattern: &str, db: &mut RedisClient) -> Result<Vec<String>, EngineError> {
+    let keys = ::redis::Commands::scan_match(&mut db.client, pattern)?.collect();
+
+    Ok(keys)
+}
+
+pub fn delete_keys(keys: &[String], db: &mut RedisClient) -> Result<(), EngineError> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+
+    ::redis::Commands::del::<_, ()>(&mut db.client, keys)?;
+
+    Ok(())
+}
+
+/**
+ * Remove the hashes of type `kind` of all the clients of a bot
+ */
+pub fn delete_all_bot_data(
+    bot_id: &str,
+    kind: &str,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    let pattern = format!("{}:*:{}", bot_key(bot_id, db), kind);
+    let keys = scan_keys(&pattern, db)?;
+
+    delete_keys(&keys, db)
+}
+
+/**
+ * These tests need a running redis-server:
+ * `REDIS_URL=redis://127.0.0.1:6379 cargo test --features redis`
+ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /**
+     * The tests run in parallel, each one with its own bot
+     */
+    fn get_client(bot_id: &str) -> Client {
+        Client {
+            bot_id: bot_id.to_owned(),
+            channel_id: "redis_channel".to_owned(),
+            user_id: "redis_user".to_owned(),
+        }
+    }
+
+    fn get_db() -> RedisClient {
+        let client = ::redis::Client::open(env::var("REDIS_URL").unwrap()).unwrap();
+
+        RedisClient::new(client.get_connection().unwrap()).with_prefix("csml_test")
+    }
+
+    fn get_ttl(key: &str, db: &mut RedisClient) -> i64 {
+        ::redis::Commands::ttl(&mut db.client, key).unwrap()
+    }
+
+    #[test]
+    fn ok_conversations() {
+        let client = get_client("redis_conversations_bot");
+        let mut db = get_db();
+        conversations::delete_user_conversations(&client, &mut db).unwrap();
+
+        let id =
+            conversations::create_conversation("Default", "start", &client, None, &mut db).unwrap();
+        conversations::update_conversation(id, &client, None, Some("end".to_owned()), &mut db)
+            .unwrap();
+
+        let conversation = conversations::get_latest_open(&client, &mut db)
+            .unwrap()
+            .unwrap();
+        assert_eq!(conversation.id, id);
+        assert_eq!(conversation.step_id, "end");
+        assert_eq!(conversations::get_conversation(&mut db, id).unwrap().id, id);
+
+        conversations::close_all_conversations(&client, &mut db).unwrap();
+        assert!(conversations::get_latest_open(&client, &mut db)
+            .unwrap()
+            .is_none());
+
+        // closed conversations are no longer read to find the open one
+        let open_key = client_key(&client, "open_conversations", &db);
+        let open: usize = ::redis::Commands::scard(&mut db.client, open_key).unwrap();
+        assert_eq!(open, 0);
+        let closed = conversations::get_conversation(&mut db, id).unwrap();
+        assert_eq!(closed.status, "CLOSED");
+        let listed = conversations::get_client_conversations(&client, &mut db, None, None).unwrap();
+        assert_eq!(listed.data.len(), 1);
+
+        conversations::delete_user_conversations(&client, &mut db).unwrap();
+        assert!(conversations::get_conversation(&mut db, id).is_err());
+    }
+
+    #[test]
+    fn ok_native_expiry() {
+        let client = get_client("redis_expiry_bot");
+        let mut db = get_db();
+        memories::delete_client_memories(&client, &mut db).unwrap();
+        state::delete_user_state(&client, &mut db).unwrap();
+
+        let past = Some(Utc::now() - chrono::Duration::seconds(10));
+        let future = Some(Utc::now() + chrono::Duration::days(1));
+
+        memories::create_client_memory(&client, "kept", &serde_json::json!(1), future, &mut db)
+            .unwrap();
+        assert_eq!(
+            memories::internal_use_get_memories(&client, &mut db).unwrap(),
+            serde_json::json!({"kept": 1})
+        );
+
+        state::set_state_items(
+            &client,
+            "hold",
+            vec![("position", &serde_json::json!({"index": 1}))],
+            past,
+            &mut db,
+        )
+        .unwrap();
+
+        let key = client_key(&client, "state", &db);
+        let exists: bool = ::redis::Commands::exists(&mut db.client, key).unwrap();
+        assert!(!exists);
+        assert!(state::get_current_state(&client, &mut db)
+            .unwrap()
+            .is_none());
+
+        memories::delete_all_bot_data(&client.bot_id, &mut db).unwrap();
+        assert_eq!(
+            memories::internal_use_get_memories(&client, &mut db).unwrap(),
+            serde_json::json!({})
+        );
+    }
+
+    #[test]
+    fn ok_expiry_per_record() {
+        let client = get_client("redis_records_bot");
+        let mut db = get_db();
+        memories::delete_client_memories(&client, &mut db).unwrap();
+        state::delete_user_state(&client, &mut db).unwrap();
+
+        let soon = Some(Utc::now() + chrono::Duration::seconds(60));
+        let later = Some(Utc::now() + chrono::Duration::days(1));
+        let past = Some(Utc::now() - chrono::Duration::seconds(10));
+
+        // a record with a shorter ttl does not bring the expiration of the hash forward
+        let hold = serde_json::json!({"index": 1});
+        state::set_state_items(&client, "hold", vec![("position", &hold)], None, &mut db).unwrap();
+        state::set_state_items(&client, "delay", vec![("content", &hold)], soon, &mut db).unwrap();
+        state::set_state_items(&client, "other", vec![("expired", &hold)], past, &mut db).unwrap();
+
+        let key = client_key(&client, "state", &db);
+        assert_eq!(get_ttl(&key, &mut db), -1);
+        assert_eq!(
+            state::get_state_key(&client, "hold", "position", &mut db).unwrap(),
+            Some(hold.clone())
+        );
+        assert!(state::get_state_key(&client, "delay", "content", &mut db)
+            .unwrap()
+            .is_some());
+
+        // expired records are filtered out and removed when they are read
+        assert!(state::get_state_key(&client, "other", "expired", &mut db)
+            .unwrap()
+            .is_none());
+        let exists: bool =
+            ::redis::Commands::hexists(&mut db.client, &key, "other:expired").unwrap();
+        assert!(!exists);
+
+        // the expiration of the hash is pushed back to the latest record
+        memories::create_client_memory(&client, "soon", &serde_json::json!(1), soon, &mut db)
+            .unwrap();
+        memories::create_client_memory(&client, "later", &serde_json::json!(2), later, &mut db)
+            .unwrap();
+        memories::create_client_memory(&client, "again", &serde_json::json!(3), soon, &mut db)
+            .unwrap();
+
+        let key = client_key(&client, "memories", &db);
+        assert!(get_ttl(&key, &mut db) > 3600);
+        assert_eq!(
+            memories::internal_use_get_memories(&client, &mut db).unwrap(),
+            serde_json::json!({"soon": 1, "later": 2, "again": 3})
+        );
+
+        memories::delete_client_memories(&client, &mut db).unwrap();
+        state::delete_user_state(&client, &mut db).unwrap();
+    }
+
+    #[test]
+    fn ok_counters() {
+        let client = get_client("redis_counters_bot");
+        let mut db = get_db();
+        counters::delete_client_counters(&client, &mut db).unwrap();
+
+        let soon = Some(Utc::now() + chrono::Duration::seconds(60));
+        assert_eq!(
+            counters::get_counter(&client, "requests:0", &mut db).unwrap(),
+            0
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "requests:0", 1, soon, &mut db).unwrap(),
+            1
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "requests:0", 2, soon, &mut db).unwrap(),
+            3
+        );
+        assert_eq!(
+            counters::get_counter(&client, "requests:0", &mut db).unwrap(),
+            3
+        );
+
+        let key = format!("{}:requests:0", client_key(&client, "counter", &db));
+        assert!(get_ttl(&key, &mut db) > 0);
+
+        // counters are kept apart from the state of the client
+        state::delete_user_state(&client, &mut db).unwrap();
+        assert_eq!(
+            counters::get_counter(&client, "requests:0", &mut db).unwrap(),
+            3
+        );
+
+        counters::delete_all_bot_data(&client.bot_id, &mut db).unwrap();
+        assert_eq!(
+            counters::get_counter(&client, "requests:0", &mut db).unwrap(),
+            0
+        );
+    }
+}
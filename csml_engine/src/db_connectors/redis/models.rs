@@ -0,0 +1,30 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/**
+ * Memory or state value, stored encrypted in the field of a client hash
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub value: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Record {
+    pub fn new(value: String, expires_at: Option<DateTime<Utc>>) -> Self {
+        Self {
+            value,
+            created_at: Utc::now(),
+            expires_at,
+        }
+    }
+
+    /**
+     * A record without expiration date never expires
+     */
+    pub fn is_expired(&self, now: &DateTime<Utc>) -> bool {
+        matches!(&self.expires_at, Some(expires_at) if expires_at < now)
+    }
+}
@@ -0,0 +1,151 @@
+use crate::data::models::StateItem;
+use crate::{
+    encrypt::{decrypt_data, encrypt_data},
+    Client, EngineError, RedisClient,
+};
+use ::redis::Commands;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use super::{client_key, delete_fields, delete_keys, models::Record, write_fields};
+
+const KIND: &str = "state";
+
+fn field(type_: &str, key: &str) -> String {
+    format!("{}:{}", type_, key)
+}
+
+fn get_record(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<Option<Record>, EngineError> {
+    let hash_key = client_key(client, KIND, db);
+    let field = field(type_, key);
+
+    let record: Option<String> = db.client.hget(&hash_key, &field)?;
+    let record: Record = match record {
+        Some(record) => serde_json::from_str(&record)?,
+        None => return Ok(None),
+    };
+
+    if record.is_expired(&Utc::now()) {
+        delete_fields(&hash_key, &[field], db)?;
+        return Ok(None);
+    }
+
+    Ok(Some(record))
+}
+
+pub fn delete_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    db.client
+        .hdel::<_, _, ()>(client_key(client, KIND, db), field(type_, key))?;
+
+    Ok(())
+}
+
+pub fn get_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut RedisClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    match get_record(client, type_, key, db)? {
+        Some(record) => Ok(Some(decrypt_data(record.value)?)),
+        None => Ok(None),
+    }
+}
+
+/**
+ * Every state key of the client that has not expired. The expired ones are removed
+ * from the hash.
+ */
+pub fn get_client_state(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<Vec<StateItem>, EngineError> {
+    let hash_key = client_key(client, KIND, db);
+    let fields: HashMap<String, String> = db.client.hgetall(&hash_key)?;
+    let now = Utc::now();
+
+    let mut state = vec![];
+    let mut expired = vec![];
+    for (field, record) in fields {
+        let record: Record = serde_json::from_str(&record)?;
+        if record.is_expired(&now) {
+            expired.push(field);
+            continue;
+        }
+
+        // the type of the state never contains a colon, unlike its key
+        let (type_, key) = field.split_once(':').unwrap_or((&field, ""));
+        state.push(StateItem {
+            _type: type_.to_owned(),
+            key: key.to_owned(),
+            value: decrypt_data(record.value)?,
+        });
+    }
+    delete_fields(&hash_key, &expired, db)?;
+
+    Ok(state)
+}
+
+pub fn get_current_state(
+    client: &Client,
+    db: &mut RedisClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let current_state = match get_record(client, "hold", "position", db)? {
+        Some(current_state) => current_state,
+        None => return Ok(None),
+    };
+
+    let current_state = serde_json::json!({
+        "client": {
+            "bot_id": client.bot_id,
+            "channel_id": client.channel_id,
+            "user_id": client.user_id
+        },
+        "type": "hold",
+        "value": decrypt_data(current_state.value)?,
+        "created_at": current_state.created_at.format("%Y-%m-%dT%H:%M:%S%.fZ").to_string(),
+    });
+
+    Ok(Some(current_state))
+}
+
+pub fn set_state_items(
+    client: &Client,
+    type_: &str,
+    keys_values: Vec<(&str, &serde_json::Value)>,
+    expires_at: Option<DateTime<Utc>>,
+    db: &mut RedisClient,
+) -> Result<(), EngineError> {
+    if keys_values.is_empty() {
+        return Ok(());
+    }
+
+    let mut fields = Vec::with_capacity(keys_values.len());
+    for (key, value) in keys_values.into_iter() {
+        let record = Record::new(encrypt_data(value)?, expires_at);
+        fields.push((field(type_, key), serde_json::to_string(&record)?));
+    }
+
+    let key = client_key(client, KIND, db);
+    write_fields(&key, &fields, expires_at, db)
+}
+
+pub fn delete_user_state(client: &Client, db: &mut RedisClient) -> Result<(), EngineError> {
+    let key = client_key(client, KIND, db);
+
+    delete_keys(&[key], db)
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut RedisClient) -> Result<(), EngineError> {
+    super::delete_all_bot_data(bot_id, KIND, db)
+}
@@ -0,0 +1,182 @@
+use crate::data::models::{Conversation, Paginated, StateItem};
+use crate::data::storage::*;
+use crate::db_connectors::utils::get_expires_at_for_redis;
+use crate::{Client, EngineError, Memory, RedisClient};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{conversations, counters, memories, state};
+
+impl ConversationStorage for RedisClient {
+    fn create_conversation(
+        &mut self,
+        flow_id: &str,
+        step_id: &str,
+        client: &Client,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<Uuid, EngineError> {
+        let expires_at = get_expires_at_for_redis(ttl);
+        conversations::create_conversation(flow_id, step_id, client, expires_at, self)
+    }
+
+    fn close_conversation(&mut self, id: Uuid, client: &Client) -> Result<(), EngineError> {
+        conversations::close_conversation(id, client, "CLOSED", self)
+    }
+
+    fn close_all_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::close_all_conversations(client, self)
+    }
+
+    fn get_latest_open(&mut self, client: &Client) -> Result<Option<Conversation>, EngineError> {
+        conversations::get_latest_open(client, self)
+    }
+
+    fn update_conversation(
+        &mut self,
+        conversation_id: Uuid,
+        client: &Client,
+        flow_id: Option<String>,
+        step_id: Option<String>,
+    ) -> Result<(), EngineError> {
+        conversations::update_conversation(conversation_id, client, flow_id, step_id, self)
+    }
+
+    fn get_conversation(&mut self, id: Uuid) -> Result<Conversation, EngineError> {
+        conversations::get_conversation(self, id)
+    }
+
+    fn get_client_conversations(
+        &mut self,
+        client: &Client,
+        limit: Option<u32>,
+        pagination_key: Option<u32>,
+    ) -> Result<Paginated<Conversation>, EngineError> {
+        conversations::get_client_conversations(client, self, limit, pagination_key)
+    }
+
+    fn delete_client_conversations(&mut self, client: &Client) -> Result<(), EngineError> {
+        conversations::delete_user_conversations(client, self)
+    }
+}
+
+impl MemoryStorage for RedisClient {
+    fn add_memories(
+        &mut self,
+        client: &Client,
+        memories: &HashMap<String, Memory>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_redis(ttl);
+        memories::add_memories(client, memories, expires_at, self)
+    }
+
+    fn create_client_memory(
+        &mut self,
+        client: &Client,
+        key: &str,
+        value: &serde_json::Value,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_redis(ttl);
+        memories::create_client_memory(client, key, value, expires_at, self)
+    }
+
+    fn internal_use_get_memories(
+        &mut self,
+        client: &Client,
+    ) -> Result<serde_json::Value, EngineError> {
+        memories::internal_use_get_memories(client, self)
+    }
+
+    fn get_memories(&mut self, client: &Client) -> Result<serde_json::Value, EngineError> {
+        memories::get_memories(client, self)
+    }
+
+    fn get_memory(&mut self, client: &Client, key: &str) -> Result<serde_json::Value, EngineError> {
+        memories::get_memory(client, key, self)
+    }
+
+    fn delete_client_memory(&mut self, client: &Client, key: &str) -> Result<(), EngineError> {
+        memories::delete_client_memory(client, key, self)
+    }
+
+    fn delete_client_memories(&mut self, client: &Client) -> Result<(), EngineError> {
+        memories::delete_client_memories(client, self)
+    }
+}
+
+impl StateStorage for RedisClient {
+    fn get_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_state_key(client, _type, key, self)
+    }
+
+    fn get_current_state(
+        &mut self,
+        client: &Client,
+    ) -> Result<Option<serde_json::Value>, EngineError> {
+        state::get_current_state(client, self)
+    }
+
+    fn set_state_items(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        keys_values: Vec<(&str, &serde_json::Value)>,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<(), EngineError> {
+        let expires_at = get_expires_at_for_redis(ttl);
+        state::set_state_items(client, _type, keys_values, expires_at, self)
+    }
+
+    fn delete_state_key(
+        &mut self,
+        client: &Client,
+        _type: &str,
+        key: &str,
+    ) -> Result<(), EngineError> {
+        state::delete_state_key(client, _type, key, self)
+    }
+
+    fn delete_client_state(&mut self, client: &Client) -> Result<(), EngineError> {
+        state::delete_user_state(client, self)
+    }
+
+    fn get_client_state(&mut self, client: &Client) -> Result<Vec<StateItem>, EngineError> {
+        state::get_client_state(client, self)
+    }
+}
+
+impl CounterStorage for RedisClient {
+    fn get_counter(&mut self, client: &Client, key: &str) -> Result<i64, EngineError> {
+        counters::get_counter(client, key, self)
+    }
+
+    fn increment_counter(
+        &mut self,
+        client: &Client,
+        key: &str,
+        count: i64,
+        ttl: Option<chrono::Duration>,
+    ) -> Result<i64, EngineError> {
+        let expires_at = get_expires_at_for_redis(ttl);
+        counters::increment_counter(client, key, count, expires_at, self)
+    }
+
+    fn delete_client_counters(&mut self, client: &Client) -> Result<(), EngineError> {
+        counters::delete_client_counters(client, self)
+    }
+}
+
+impl SessionStorage for RedisClient {
+    fn delete_all_bot_sessions(&mut self, bot_id: &str) -> Result<(), EngineError> {
+        conversations::delete_all_bot_data(bot_id, self)?;
+        memories::delete_all_bot_data(bot_id, self)?;
+        state::delete_all_bot_data(bot_id, self)?;
+        counters::delete_all_bot_data(bot_id, self)
+    }
+}
@@ -0,0 +1,64 @@
+use crate::data::models::Schedule;
+use crate::db_connectors::db_span;
+use crate::{Database, EngineError};
+use chrono::{DateTime, Utc};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use uuid::Uuid;
+
+pub fn create_schedule(schedule: &Schedule, db: &mut Database) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.create_schedule", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&schedule.client),
+            Some(schedule.flow_id.to_owned()),
+            None,
+            format!("db call create schedule at {}", schedule.run_at),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.create_schedule(schedule)
+}
+
+pub fn get_due_schedules(limit: u32, db: &mut Database) -> Result<Vec<Schedule>, EngineError> {
+    let _span = db_span("csml.db.get_due_schedules", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, "db call get due schedules".to_string()),
+        LogLvl::Info,
+    );
+
+    db.storage()?.get_due_schedules(Utc::now(), limit)
+}
+
+pub fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut Database,
+) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.claim_schedule", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&schedule.client),
+            Some(schedule.flow_id.to_owned()),
+            None,
+            format!("db call claim schedule {}", schedule.id),
+        ),
+        LogLvl::Info,
+    );
+
+    db.storage()?.claim_schedule(schedule, retry_at)
+}
+
+pub fn delete_schedule(id: Uuid, db: &mut Database) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.delete_schedule", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, format!("db call delete schedule {}", id)),
+        LogLvl::Info,
+    );
+
+    db.storage()?.delete_schedule(id)
+}
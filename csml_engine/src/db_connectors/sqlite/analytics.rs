@@ -0,0 +1,200 @@
+use diesel::sql_types::{BigInt, Text, Timestamp};
+use diesel::{sql_query, QueryableByName, RunQueryDsl};
+
+use crate::data::filter::AnalyticsFilter;
+use crate::data::models::{FlowAnalytics, HoldAnalytics, StepTransition, TurnAnalytics};
+use crate::{EngineError, SqliteClient};
+
+/**
+ * Conversations of the period, and their messages numbered in the order they were saved
+ * (created_at only has a precision of one second, the rowid orders the messages of a request)
+ * with the position of the previous message of the conversation
+ */
+const CONVERSATIONS: &str = "
+    WITH conversations AS (
+        SELECT id, flow_id, step_id, status, last_interaction_at FROM csml_conversations
+        WHERE bot_id = ? AND created_at >= ? AND created_at < ?
+    ),
+    messages AS (
+        SELECT m.conversation_id, m.flow_id, m.step_id, m.direction, m.created_at,
+            ROW_NUMBER() OVER saved AS position,
+            LAG(m.flow_id) OVER saved AS previous_flow_id,
+            LAG(m.step_id) OVER saved AS previous_step_id
+        FROM csml_messages m JOIN conversations c ON c.id = m.conversation_id
+        WINDOW saved AS (PARTITION BY m.conversation_id ORDER BY m.created_at, m.rowid)
+    )
+";
+
+#[derive(QueryableByName)]
+struct FlowRow {
+    #[diesel(sql_type = Text)]
+    flow_id: String,
+    #[diesel(sql_type = BigInt)]
+    started: i64,
+    #[diesel(sql_type = BigInt)]
+    ended: i64,
+}
+
+#[derive(QueryableByName)]
+struct HoldRow {
+    #[diesel(sql_type = Text)]
+    flow_id: String,
+    #[diesel(sql_type = Text)]
+    step_id: String,
+    #[diesel(sql_type = BigInt)]
+    answered: i64,
+    #[diesel(sql_type = BigInt)]
+    abandoned: i64,
+}
+
+#[derive(QueryableByName)]
+struct TurnRow {
+    #[diesel(sql_type = BigInt)]
+    conversations: i64,
+    #[diesel(sql_type = BigInt)]
+    turns: i64,
+}
+
+#[derive(QueryableByName)]
+struct TransitionRow {
+    #[diesel(sql_type = Text)]
+    from_flow: String,
+    #[diesel(sql_type = Text)]
+    from_step: String,
+    #[diesel(sql_type = Text)]
+    to_flow: String,
+    #[diesel(sql_type = Text)]
+    to_step: String,
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+fn load<T: QueryableByName<diesel::sqlite::Sqlite> + 'static>(
+    query: &str,
+    filter: &AnalyticsFilter,
+    db: &mut SqliteClient,
+) -> Result<Vec<T>, EngineError> {
+    let rows = sql_query(format!("{} {}", CONVERSATIONS, query))
+        .bind::<Text, _>(filter.bot_id)
+        .bind::<Timestamp, _>(filter.from_date.naive_utc())
+        .bind::<Timestamp, _>(filter.to_date.naive_utc())
+        .load::<T>(db.client.as_mut())?;
+
+    Ok(rows)
+}
+
+pub fn get_flow_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut SqliteClient,
+) -> Result<Vec<FlowAnalytics>, EngineError> {
+    let rows: Vec<FlowRow> = load(
+        "
+        SELECT flow_id, SUM(started) AS started, SUM(ended) AS ended FROM (
+            SELECT flow_id, 1 AS started, 0 AS ended FROM messages WHERE position = 1
+            UNION ALL
+            SELECT flow_id, 0 AS started, 1 AS ended FROM conversations WHERE status = 'CLOSED'
+        ) AS flows
+        GROUP BY flow_id
+        ORDER BY flow_id
+        ",
+        filter,
+        db,
+    )?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| FlowAnalytics {
+            flow_id: row.flow_id,
+            started: row.started as u64,
+            ended: row.ended as u64,
+        })
+        .collect())
+}
+
+pub fn get_hold_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut SqliteClient,
+) -> Result<Vec<HoldAnalytics>, EngineError> {
+    // a conversation is abandoned once it has no activity, not while it waits for an answer
+    let query = "
+        SELECT flow_id, step_id, SUM(answered) AS answered, SUM(abandoned) AS abandoned FROM (
+            SELECT flow_id, step_id, 1 AS answered, 0 AS abandoned FROM messages
+            WHERE direction = 'RECEIVE' AND position > 1
+            UNION ALL
+            SELECT c.flow_id, c.step_id, 0 AS answered, 1 AS abandoned FROM conversations c
+            LEFT JOIN (
+                SELECT conversation_id, MAX(created_at) AS saved_at FROM messages
+                GROUP BY conversation_id
+            ) s ON s.conversation_id = c.id
+            WHERE c.status = 'OPEN'
+                AND MAX(c.last_interaction_at, COALESCE(s.saved_at, c.last_interaction_at)) < ?
+        ) AS holds
+        GROUP BY flow_id, step_id
+        ORDER BY flow_id, step_id
+    ";
+    let rows = sql_query(format!("{} {}", CONVERSATIONS, query))
+        .bind::<Text, _>(filter.bot_id)
+        .bind::<Timestamp, _>(filter.from_date.naive_utc())
+        .bind::<Timestamp, _>(filter.to_date.naive_utc())
+        .bind::<Timestamp, _>(filter.abandoned_before.naive_utc())
+        .load::<HoldRow>(db.client.as_mut())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| HoldAnalytics {
+            flow_id: row.flow_id,
+            step_id: row.step_id,
+            answered: row.answered as u64,
+            abandoned: row.abandoned as u64,
+        })
+        .collect())
+}
+
+pub fn get_turn_analytics(
+    filter: &AnalyticsFilter,
+    db: &mut SqliteClient,
+) -> Result<TurnAnalytics, EngineError> {
+    let rows: Vec<TurnRow> = load(
+        "
+        SELECT COUNT(DISTINCT conversation_id) AS conversations,
+            COUNT(CASE WHEN direction = 'RECEIVE' THEN 1 END) AS turns
+        FROM messages
+        ",
+        filter,
+        db,
+    )?;
+
+    Ok(match rows.first() {
+        Some(row) => TurnAnalytics::new(row.conversations as u64, row.turns as u64),
+        None => TurnAnalytics::new(0, 0),
+    })
+}
+
+pub fn get_step_transitions(
+    filter: &AnalyticsFilter,
+    db: &mut SqliteClient,
+) -> Result<Vec<StepTransition>, EngineError> {
+    let rows: Vec<TransitionRow> = load(
+        "
+        SELECT previous_flow_id AS from_flow, previous_step_id AS from_step,
+            flow_id AS to_flow, step_id AS to_step, COUNT(*) AS count
+        FROM messages
+        WHERE position > 1 AND (previous_flow_id <> flow_id OR previous_step_id <> step_id)
+        GROUP BY previous_flow_id, previous_step_id, flow_id, step_id
+        ORDER BY count DESC, from_flow, from_step, to_flow, to_step
+        ",
+        filter,
+        db,
+    )?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| StepTransition {
+            from_flow: row.from_flow,
+            from_step: row.from_step,
+            to_flow: row.to_flow,
+            to_step: row.to_step,
+            count: row.count as u64,
+        })
+        .collect())
+}
@@ -0,0 +1,58 @@
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use std::convert::TryFrom;
+
+use crate::data::models::ApiKey;
+use crate::{EngineError, SqliteClient};
+
+use super::{models, schema::csml_api_keys};
+use uuid::Uuid;
+
+pub fn create_api_key(
+    api_key: &ApiKey,
+    key_hash: &str,
+    db: &mut SqliteClient,
+) -> Result<(), EngineError> {
+    let bot_ids = match &api_key.bot_ids {
+        Some(bot_ids) => Some(serde_json::to_string(bot_ids)?),
+        None => None,
+    };
+
+    let new_api_key = models::NewApiKey {
+        id: models::UUID(api_key.id),
+        name: &api_key.name,
+        key_hash,
+        scopes: serde_json::to_string(&api_key.scopes)?,
+        bot_ids,
+        created_at: api_key.created_at.naive_utc(),
+    };
+
+    diesel::insert_into(csml_api_keys::table)
+        .values(&new_api_key)
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn get_api_key(key_hash: &str, db: &mut SqliteClient) -> Result<Option<ApiKey>, EngineError> {
+    let api_key: Option<models::ApiKey> = csml_api_keys::table
+        .filter(csml_api_keys::key_hash.eq(key_hash))
+        .first(db.client.as_mut())
+        .optional()?;
+
+    api_key.map(ApiKey::try_from).transpose()
+}
+
+pub fn get_api_keys(db: &mut SqliteClient) -> Result<Vec<ApiKey>, EngineError> {
+    let api_keys: Vec<models::ApiKey> = csml_api_keys::table
+        .order_by(csml_api_keys::created_at.asc())
+        .load(db.client.as_mut())?;
+
+    api_keys.into_iter().map(ApiKey::try_from).collect()
+}
+
+pub fn delete_api_key(id: Uuid, db: &mut SqliteClient) -> Result<bool, EngineError> {
+    let count = diesel::delete(csml_api_keys::table.filter(csml_api_keys::id.eq(models::UUID(id))))
+        .execute(db.client.as_mut())?;
+
+    Ok(count > 0)
+}
@@ -0,0 +1,98 @@
+use diesel::upsert::excluded;
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl, RunQueryDsl};
+
+use crate::{Client, EngineError, SqliteClient};
+
+use super::{models, schema::csml_counters};
+use chrono::NaiveDateTime;
+
+pub fn get_counter(client: &Client, key: &str, db: &mut SqliteClient) -> Result<i64, EngineError> {
+    let date_now = chrono::Utc::now().naive_utc();
+
+    let count: Option<i64> = csml_counters::table
+        .filter(csml_counters::bot_id.eq(&client.bot_id))
+        .filter(csml_counters::channel_id.eq(&client.channel_id))
+        .filter(csml_counters::user_id.eq(&client.user_id))
+        .filter(csml_counters::key.eq(key))
+        .filter(
+            csml_counters::expires_at
+                .is_null()
+                .or(csml_counters::expires_at.gt(date_now)),
+        )
+        .select(csml_counters::count)
+        .first(db.client.as_mut())
+        .ok();
+
+    Ok(count.unwrap_or(0))
+}
+
+pub fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<NaiveDateTime>,
+    db: &mut SqliteClient,
+) -> Result<i64, EngineError> {
+    let date_now = chrono::Utc::now().naive_utc();
+    let counter = csml_counters::table
+        .filter(csml_counters::bot_id.eq(&client.bot_id))
+        .filter(csml_counters::channel_id.eq(&client.channel_id))
+        .filter(csml_counters::user_id.eq(&client.user_id))
+        .filter(csml_counters::key.eq(key));
+
+    let new_counter = models::NewCounter {
+        bot_id: &client.bot_id,
+        channel_id: &client.channel_id,
+        user_id: &client.user_id,
+        key,
+        count,
+        expires_at,
+    };
+
+    // the write lock is taken when the transaction begins: the new count is read before
+    // any other increment
+    let count = db.client.as_mut().immediate_transaction(|connection| {
+        diesel::delete(counter.filter(csml_counters::expires_at.lt(date_now)))
+            .execute(connection)?;
+
+        diesel::insert_into(csml_counters::table)
+            .values(&new_counter)
+            .on_conflict((
+                csml_counters::bot_id,
+                csml_counters::channel_id,
+                csml_counters::user_id,
+                csml_counters::key,
+            ))
+            .do_update()
+            .set((
+                csml_counters::count.eq(csml_counters::count + excluded(csml_counters::count)),
+                csml_counters::expires_at.eq(excluded(csml_counters::expires_at)),
+            ))
+            .execute(connection)?;
+
+        counter
+            .select(csml_counters::count)
+            .first::<i64>(connection)
+    })?;
+
+    Ok(count)
+}
+
+pub fn delete_client_counters(client: &Client, db: &mut SqliteClient) -> Result<(), EngineError> {
+    diesel::delete(
+        csml_counters::table
+            .filter(csml_counters::bot_id.eq(&client.bot_id))
+            .filter(csml_counters::channel_id.eq(&client.channel_id))
+            .filter(csml_counters::user_id.eq(&client.user_id)),
+    )
+    .execute(db.client.as_mut())?;
+
+    Ok(())
+}
+
+pub fn delete_all_bot_data(bot_id: &str, db: &mut SqliteClient) -> Result<(), EngineError> {
+    diesel::delete(csml_counters::table.filter(csml_counters::bot_id.eq(bot_id)))
+        .execute(db.client.as_mut())?;
+
+    Ok(())
+}
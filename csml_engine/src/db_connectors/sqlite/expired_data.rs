@@ -2,7 +2,7 @@ use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 
 use crate::{EngineError, SqliteClient};
 
-use super::schema::{csml_conversations, csml_memories, csml_states};
+use super::schema::{csml_conversations, csml_counters, csml_memories, csml_states};
 
 pub fn delete_expired_data(db: &mut SqliteClient) -> Result<(), EngineError> {
     let date_now = chrono::Utc::now().naive_utc();
@@ -19,5 +19,9 @@ pub fn delete_expired_data(db: &mut SqliteClient) -> Result<(), EngineError> {
         .execute(db.client.as_mut())
         .ok();
 
+    diesel::delete(csml_counters::table.filter(csml_counters::expires_at.lt(date_now)))
+        .execute(db.client.as_mut())
+        .ok();
+
     Ok(())
 }
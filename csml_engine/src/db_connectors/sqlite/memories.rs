@@ -1,31 +1,31 @@
 use diesel::sql_query;
 use diesel::sql_types;
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 
+use crate::data::models::MemoryRecord;
 use crate::{
-    db_connectors::sqlite::get_db,
     encrypt::{decrypt_data, encrypt_data},
-    Client, ConversationInfo, EngineError, Memory, SqliteClient,
+    Client, EngineError, Memory, SqliteClient,
 };
 
 use super::{models, schema::csml_memories};
 
 use chrono::NaiveDateTime;
 use std::collections::HashMap;
+use uuid::Uuid;
 
 pub fn add_memories(
-    data: &mut ConversationInfo,
+    client: &Client,
     memories: &HashMap<String, Memory>,
     expires_at: Option<NaiveDateTime>,
+    db: &mut SqliteClient,
 ) -> Result<(), EngineError> {
     if memories.is_empty() {
         return Ok(());
     }
 
-    let db = get_db(&mut data.db)?;
-
     for (key, mem) in memories.iter() {
-        create_client_memory(&data.client, key, &mem.value, expires_at, db)?;
+        create_client_memory(client, key, &mem.value, expires_at, db)?;
     }
 
     Ok(())
@@ -197,3 +197,90 @@ pub fn delete_all_bot_data(bot_id: &str, db: &mut SqliteClient) -> Result<(), En
 
     Ok(())
 }
+
+pub fn get_memory_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut SqliteClient,
+) -> Result<Vec<MemoryRecord>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::Memory> = csml_memories::table
+        .filter(csml_memories::id.gt(models::UUID(after.unwrap_or_default())))
+        .order_by(csml_memories::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    records
+        .into_iter()
+        .map(|mem| {
+            Ok(MemoryRecord {
+                id: mem.id.0,
+                client: Client::new(mem.bot_id, mem.channel_id, mem.user_id),
+                key: mem.key,
+                value: decrypt_data(mem.value)?,
+                expires_at: mem.expires_at.as_ref().map(NaiveDateTime::and_utc),
+                updated_at: mem.updated_at.and_utc(),
+                created_at: mem.created_at.and_utc(),
+            })
+        })
+        .collect()
+}
+
+pub fn add_memory_records(
+    memories: &[MemoryRecord],
+    db: &mut SqliteClient,
+) -> Result<usize, EngineError> {
+    let rows = memories
+        .iter()
+        .map(|mem| {
+            Ok(models::Memory {
+                id: models::UUID(mem.id),
+                bot_id: mem.client.bot_id.to_owned(),
+                channel_id: mem.client.channel_id.to_owned(),
+                user_id: mem.client.user_id.to_owned(),
+                key: mem.key.to_owned(),
+                value: encrypt_data(&mem.value)?,
+                expires_at: mem.expires_at.map(|date| date.naive_utc()),
+                updated_at: mem.updated_at.naive_utc(),
+                created_at: mem.created_at.naive_utc(),
+            })
+        })
+        .collect::<Result<Vec<_>, EngineError>>()?;
+
+    let created = diesel::insert_or_ignore_into(csml_memories::table)
+        .values(&rows)
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
+
+pub fn update_memory_records(
+    memories: &[MemoryRecord],
+    db: &mut SqliteClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for memory in memories {
+        // saved again only if it was not changed since it was read, not to overwrite a
+        // value written in the meantime
+        let saved: Option<String> = csml_memories::table
+            .filter(csml_memories::id.eq(models::UUID(memory.id)))
+            .select(csml_memories::value)
+            .first(db.client.as_mut())
+            .optional()?;
+        let saved = match saved {
+            Some(saved) if decrypt_data(saved.clone())? == memory.value => saved,
+            _ => continue,
+        };
+
+        updated += diesel::update(
+            csml_memories::table
+                .filter(csml_memories::id.eq(models::UUID(memory.id)))
+                .filter(csml_memories::value.eq(saved)),
+        )
+        .set(csml_memories::value.eq(encrypt_data(&memory.value)?))
+        .execute(db.client.as_mut())?;
+    }
+
+    Ok(updated)
+}
@@ -2,8 +2,7 @@ use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 use std::convert::TryInto;
 
 use crate::{
-    data, db_connectors::sqlite::get_db, encrypt::encrypt_data, Client, ConversationInfo,
-    EngineError, SqliteClient,
+    data, data::storage::ConversationStep, encrypt::encrypt_data, Client, EngineError, SqliteClient,
 };
 
 use super::{
@@ -14,32 +13,32 @@ use super::{
 use crate::data::filter::ClientMessageFilter;
 use crate::data::models::PaginationData;
 use crate::db_connectors::diesel::Direction;
+use crate::db_connectors::utils::missing_conversation;
 use chrono::NaiveDateTime;
 use uuid::Uuid;
 
 pub fn add_messages_bulk(
-    data: &mut ConversationInfo,
+    step: &ConversationStep,
     msgs: &[serde_json::Value],
     interaction_order: i32,
     direction: Direction,
     expires_at: Option<NaiveDateTime>,
+    db: &mut SqliteClient,
 ) -> Result<(), EngineError> {
     if msgs.is_empty() {
         return Ok(());
     }
 
-    let db = get_db(&mut data.db)?;
-
     let mut new_messages = vec![];
     for (message_order, message) in msgs.iter().enumerate() {
-        let conversation_id = models::UUID(data.conversation_id);
+        let conversation_id = models::UUID(step.conversation_id);
 
         let msg = models::NewMessages {
             id: models::UUID::new_v4(),
             conversation_id,
 
-            flow_id: &data.context.flow,
-            step_id: data.context.step.get_step_ref(),
+            flow_id: step.flow_id,
+            step_id: step.step_id,
             direction,
             payload: encrypt_data(message)?,
             content_type: message["content_type"].as_str().unwrap_or("text"),
@@ -244,3 +243,78 @@ fn get_messages_with_conversation_filter(
     };
     Ok(res)
 }
+
+pub fn get_message_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut SqliteClient,
+) -> Result<Vec<data::models::Message>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::Message> = csml_messages::table
+        .filter(csml_messages::id.gt(models::UUID(after.unwrap_or_default())))
+        .order_by(csml_messages::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    records.into_iter().map(TryInto::try_into).collect()
+}
+
+pub fn add_message_records(
+    messages: &[data::models::Message],
+    db: &mut SqliteClient,
+) -> Result<usize, EngineError> {
+    let conversation_ids: Vec<models::UUID> = messages
+        .iter()
+        .map(|message| models::UUID(message.conversation_id))
+        .collect();
+    let conversations: Vec<models::UUID> = csml_conversations::table
+        .select(csml_conversations::id)
+        .filter(csml_conversations::id.eq_any(&conversation_ids))
+        .load(db.client.as_mut())?;
+
+    let mut rows = vec![];
+    for message in messages {
+        // messages are only saved in a conversation of the database
+        if !conversations.contains(&models::UUID(message.conversation_id)) {
+            return Err(missing_conversation(message));
+        }
+
+        rows.push(models::Message {
+            id: models::UUID(message.id),
+            conversation_id: models::UUID(message.conversation_id),
+            flow_id: message.flow_id.to_owned(),
+            step_id: message.step_id.to_owned(),
+            direction: message.direction.to_owned().into(),
+            payload: encrypt_data(&message.payload)?,
+            content_type: message.content_type.to_owned(),
+            message_order: message.message_order as i32,
+            interaction_order: message.interaction_order as i32,
+            updated_at: message.updated_at.naive_utc(),
+            created_at: message.created_at.naive_utc(),
+            expires_at: message.expires_at.map(|date| date.naive_utc()),
+        });
+    }
+
+    let created = diesel::insert_or_ignore_into(csml_messages::table)
+        .values(&rows)
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
+
+pub fn update_message_records(
+    messages: &[data::models::Message],
+    db: &mut SqliteClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for message in messages {
+        updated += diesel::update(
+            csml_messages::table.filter(csml_messages::id.eq(models::UUID(message.id))),
+        )
+        .set(csml_messages::payload.eq(encrypt_data(&message.payload)?))
+        .execute(db.client.as_mut())?;
+    }
+
+    Ok(updated)
+}
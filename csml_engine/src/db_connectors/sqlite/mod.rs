@@ -1,7 +1,12 @@
+pub mod analytics;
+pub mod api_keys;
 pub mod bot;
 pub mod conversations;
+pub mod counters;
 pub mod memories;
 pub mod messages;
+pub mod outbox;
+pub mod schedules;
 pub mod state;
 
 pub mod pagination;
@@ -12,6 +17,8 @@ pub mod schema;
 
 pub mod expired_data;
 
+mod storage;
+
 use crate::{Database, EngineError, SqliteClient};
 
 use diesel::prelude::*;
@@ -32,6 +39,18 @@ pub fn init() -> Result<Database<'static>, EngineError> {
     Ok(db)
 }
 
+/**
+ * Connect to the database file of the `uri`, and create its tables if needed
+ */
+pub fn init_from_url(uri: &str) -> Result<Database<'static>, EngineError> {
+    let mut sqlite_connection = SqliteConnection::establish(uri)
+        .map_err(|err| EngineError::Manager(format!("Error connecting to {}: {}", uri, err)))?;
+
+    sqlite_connection.run_pending_migrations(MIGRATIONS)?;
+
+    Ok(Database::SqLite(SqliteClient::new(sqlite_connection)))
+}
+
 pub fn make_migrations() -> Result<(), EngineError> {
     let uri = match std::env::var("SQLITE_URL") {
         Ok(var) => var,
@@ -46,12 +65,3 @@ pub fn make_migrations() -> Result<(), EngineError> {
 
     Ok(())
 }
-
-pub fn get_db<'a, 'b>(db: &'a mut Database<'b>) -> Result<&'a mut SqliteClient<'b>, EngineError> {
-    match db {
-        Database::SqLite(db) => Ok(db),
-        _ => Err(EngineError::Manager(
-            "SqLite connector is not setup correctly".to_owned(),
-        )),
-    }
-}
@@ -9,6 +9,17 @@ table! {
     }
 }
 
+table! {
+    csml_api_keys (id) {
+        id -> Binary,
+        name -> Text,
+        key_hash -> Text,
+        scopes -> Text,
+        bot_ids -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
 table! {
     csml_conversations (id) {
         id -> Binary,
@@ -25,6 +36,17 @@ table! {
     }
 }
 
+table! {
+    csml_counters (bot_id, channel_id, user_id, key) {
+        bot_id -> Text,
+        channel_id -> Text,
+        user_id -> Text,
+        key -> Text,
+        count -> BigInt,
+        expires_at -> Nullable<Timestamp>,
+    }
+}
+
 table! {
     csml_memories (id) {
         id -> Binary,
@@ -72,12 +94,47 @@ table! {
     }
 }
 
+table! {
+    csml_outbox (id) {
+        id -> Binary,
+        bot_id -> Text,
+        channel_id -> Text,
+        user_id -> Text,
+        callback_url -> Text,
+        payload -> Text,
+        signature -> Nullable<Text>,
+        attempts -> Integer,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    csml_schedules (id) {
+        id -> Binary,
+        bot_id -> Text,
+        channel_id -> Text,
+        user_id -> Text,
+        flow_id -> Text,
+        step_id -> Text,
+        callback_url -> Nullable<Text>,
+        bot -> Nullable<Text>,
+        attempts -> Integer,
+        run_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 joinable!(csml_messages -> csml_conversations (conversation_id));
 
 allow_tables_to_appear_in_same_query!(
     cmsl_bot_versions,
+    csml_api_keys,
     csml_conversations,
+    csml_counters,
     csml_memories,
     csml_messages,
+    csml_outbox,
+    csml_schedules,
     csml_states,
 );
@@ -1,5 +1,6 @@
-use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 
+use crate::data::models::{StateItem, StateRecord};
 use crate::{
     encrypt::{decrypt_data, encrypt_data},
     Client, EngineError, SqliteClient,
@@ -7,6 +8,7 @@ use crate::{
 
 use super::{models, schema::csml_states};
 use chrono::NaiveDateTime;
+use uuid::Uuid;
 
 pub fn delete_state_key(
     client: &Client,
@@ -50,6 +52,31 @@ pub fn get_state_key(
     }
 }
 
+/**
+ * Every state key of the client
+ */
+pub fn get_client_state(
+    client: &Client,
+    db: &mut SqliteClient,
+) -> Result<Vec<StateItem>, EngineError> {
+    let states: Vec<models::State> = csml_states::table
+        .filter(csml_states::bot_id.eq(&client.bot_id))
+        .filter(csml_states::channel_id.eq(&client.channel_id))
+        .filter(csml_states::user_id.eq(&client.user_id))
+        .load(db.client.as_mut())?;
+
+    states
+        .into_iter()
+        .map(|state| {
+            Ok(StateItem {
+                _type: state.type_,
+                key: state.key,
+                value: decrypt_data(state.value)?,
+            })
+        })
+        .collect()
+}
+
 pub fn get_current_state(
     client: &Client,
     db: &mut SqliteClient,
@@ -133,3 +160,92 @@ pub fn delete_all_bot_data(bot_id: &str, db: &mut SqliteClient) -> Result<(), En
 
     Ok(())
 }
+
+pub fn get_state_records(
+    after: Option<Uuid>,
+    limit: u32,
+    db: &mut SqliteClient,
+) -> Result<Vec<StateRecord>, EngineError> {
+    // the nil uuid is before any id
+    let records: Vec<models::State> = csml_states::table
+        .filter(csml_states::id.gt(models::UUID(after.unwrap_or_default())))
+        .order_by(csml_states::id.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())?;
+
+    records
+        .into_iter()
+        .map(|state| {
+            Ok(StateRecord {
+                id: state.id.0,
+                client: Client::new(state.bot_id, state.channel_id, state.user_id),
+                _type: state.type_,
+                key: state.key,
+                value: decrypt_data(state.value)?,
+                expires_at: state.expires_at.as_ref().map(NaiveDateTime::and_utc),
+                updated_at: state.updated_at.and_utc(),
+                created_at: state.created_at.and_utc(),
+            })
+        })
+        .collect()
+}
+
+pub fn add_state_records(
+    state: &[StateRecord],
+    db: &mut SqliteClient,
+) -> Result<usize, EngineError> {
+    let rows = state
+        .iter()
+        .map(|state| {
+            Ok(models::State {
+                id: models::UUID(state.id),
+                bot_id: state.client.bot_id.to_owned(),
+                channel_id: state.client.channel_id.to_owned(),
+                user_id: state.client.user_id.to_owned(),
+                type_: state._type.to_owned(),
+                key: state.key.to_owned(),
+                value: encrypt_data(&state.value)?,
+                expires_at: state.expires_at.map(|date| date.naive_utc()),
+                updated_at: state.updated_at.naive_utc(),
+                created_at: state.created_at.naive_utc(),
+            })
+        })
+        .collect::<Result<Vec<_>, EngineError>>()?;
+
+    let created = diesel::insert_or_ignore_into(csml_states::table)
+        .values(&rows)
+        .execute(db.client.as_mut())?;
+
+    Ok(created)
+}
+
+pub fn update_state_records(
+    state: &[StateRecord],
+    db: &mut SqliteClient,
+) -> Result<usize, EngineError> {
+    let mut updated = 0;
+
+    for item in state {
+        // saved again only if it was not changed since it was read, not to overwrite a
+        // value written in the meantime
+        let saved: Option<String> = csml_states::table
+            .filter(csml_states::id.eq(models::UUID(item.id)))
+            .select(csml_states::value)
+            .first(db.client.as_mut())
+            .optional()?;
+        let saved = match saved {
+            Some(saved) if decrypt_data(saved.clone())? == item.value => saved,
+            _ => continue,
+        };
+
+        updated += diesel::update(
+            csml_states::table
+                .filter(csml_states::id.eq(models::UUID(item.id)))
+                .filter(csml_states::value.eq(saved)),
+        )
+        .set(csml_states::value.eq(encrypt_data(&item.value)?))
+        .execute(db.client.as_mut())?;
+    }
+
+    Ok(updated)
+}
@@ -1,14 +1,4 @@
-#[cfg(feature = "dynamo")]
-use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
-#[cfg(feature = "mongo")]
-use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
-#[cfg(feature = "postgresql")]
-use crate::db_connectors::{is_postgresql, postgresql_connector};
-#[cfg(feature = "sqlite")]
-use crate::db_connectors::{is_sqlite, sqlite_connector};
-
-use crate::db_connectors::utils::*;
-use crate::error_messages::ERROR_DB_SETUP;
+use crate::db_connectors::db_span;
 use crate::{Database, EngineError};
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 use csml_interpreter::data::Client;
@@ -19,6 +9,8 @@ pub fn delete_state_key(
     key: &str,
     db: &mut Database,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_state_key", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -38,45 +30,23 @@ pub fn delete_state_key(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::state::delete_state_key(client, _type, key, db);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.delete_state_key(client, _type, key)
 }
 
 pub fn get_state_key(
     client: &Client,
     _type: &str,
-    _key: &str,
+    key: &str,
     db: &mut Database,
 ) -> Result<Option<serde_json::Value>, EngineError> {
+    let _span = db_span("csml.db.get_state_key", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
             None,
             None,
-            format!("db call get state key: {:?}, type: {:?}", _key, _type),
+            format!("db call get state key: {:?}, type: {:?}", key, _type),
         ),
         LogLvl::Info,
     );
@@ -85,42 +55,20 @@ pub fn get_state_key(
             Some(client),
             None,
             None,
-            format!("db call get state key: {:?}, type: {:?}", _key, _type),
+            format!("db call get state key: {:?}, type: {:?}", key, _type),
         ),
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::state::get_state_key(client, _type, _key, db);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.get_state_key(client, _type, key)
 }
 
 pub fn get_current_state(
     client: &Client,
     db: &mut Database,
 ) -> Result<Option<serde_json::Value>, EngineError> {
+    let _span = db_span("csml.db.get_current_state", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call get current state".to_string()),
         LogLvl::Info,
@@ -135,40 +83,18 @@ pub fn get_current_state(
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::state::get_current_state(client, db); // "hold", "position"
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::state::get_current_state(client, db);
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(db)?;
-        return postgresql_connector::state::get_current_state(client, db);
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(db)?;
-        return sqlite_connector::state::get_current_state(client, db);
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?.get_current_state(client)
 }
 
 pub fn set_state_items(
-    _client: &Client,
+    client: &Client,
     _type: &str,
-    _keys_values: Vec<(&str, &serde_json::Value)>,
+    keys_values: Vec<(&str, &serde_json::Value)>,
     ttl: Option<chrono::Duration>,
-    _db: &mut Database,
+    db: &mut Database,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.set_state_items", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -176,81 +102,26 @@ pub fn set_state_items(
             None,
             format!(
                 "db call set state type: {:?}, keys and values {:?}",
-                _type, _keys_values
+                _type, keys_values
             ),
         ),
         LogLvl::Info,
     );
     csml_logger(
         CsmlLog::new(
-            Some(_client),
+            Some(client),
             None,
             None,
             format!(
                 "db call set state type: {:?}, keys and values {:?}",
-                _type, _keys_values
+                _type, keys_values
             ),
         ),
         LogLvl::Debug,
     );
 
-    #[cfg(feature = "mongo")]
-    if is_mongodb() {
-        let db = mongodb_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_mongodb(ttl);
-
-        return mongodb_connector::state::set_state_items(
-            _client,
-            _type,
-            _keys_values,
-            expires_at,
-            &db,
-        );
-    }
-
-    #[cfg(feature = "dynamo")]
-    if is_dynamodb() {
-        let db = dynamodb_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_dynamodb(ttl);
-
-        return dynamodb_connector::state::set_state_items(
-            _client,
-            _type,
-            _keys_values,
-            expires_at,
-            db,
-        );
-    }
-
-    #[cfg(feature = "postgresql")]
-    if is_postgresql() {
-        let db = postgresql_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_postgresql(ttl);
-
-        return postgresql_connector::state::set_state_items(
-            _client,
-            _type,
-            _keys_values,
-            expires_at,
-            db,
-        );
-    }
-
-    #[cfg(feature = "sqlite")]
-    if is_sqlite() {
-        let db = sqlite_connector::get_db(_db)?;
-        let expires_at = get_expires_at_for_sqlite(ttl);
-
-        return sqlite_connector::state::set_state_items(
-            _client,
-            _type,
-            _keys_values,
-            expires_at,
-            db,
-        );
-    }
-
-    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+    db.storage()?
+        .set_state_items(client, _type, keys_values, ttl)
 }
 
 #[cfg(test)]
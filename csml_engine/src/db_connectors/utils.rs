@@ -47,3 +47,45 @@ pub fn get_expires_at_for_sqlite(ttl: Option<chrono::Duration>) -> Option<chrono
         None => None,
     }
 }
+
+#[cfg(feature = "memory")]
+pub fn get_expires_at_for_memory(
+    ttl: Option<chrono::Duration>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    ttl.map(|ttl| chrono::Utc::now() + ttl)
+}
+
+#[cfg(feature = "redis")]
+pub fn get_expires_at_for_redis(
+    ttl: Option<chrono::Duration>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    ttl.map(|ttl| chrono::Utc::now() + ttl)
+}
+
+/**
+ * Split an already sorted list of items in pages of `per_page` items,
+ * starting at page 1, and return the requested page with the total number of pages.
+ */
+#[cfg(any(feature = "memory", feature = "redis"))]
+pub fn paginate<T>(items: Vec<T>, page: u32, per_page: u32) -> (Vec<T>, u32) {
+    let per_page = per_page.max(1) as usize;
+    let total_pages = items.len().div_ceil(per_page) as u32;
+    let offset = (page.max(1) as usize - 1) * per_page;
+
+    let page = items.into_iter().skip(offset).take(per_page).collect();
+
+    (page, total_pages)
+}
+
+/**
+ * Error of `add_message_records` for a message whose conversation is not in the database
+ */
+#[cfg(any(feature = "memory", feature = "sqlite", feature = "postgresql"))]
+pub fn missing_conversation(message: &crate::data::models::Message) -> crate::EngineError {
+    crate::EngineError::Manager(format!(
+        "{}: message {}, conversation {}",
+        crate::error_messages::ERROR_MESSAGE_CONVERSATION,
+        message.id,
+        message.conversation_id
+    ))
+}
@@ -6,12 +6,21 @@
  * To automatically setup encryption/decryption of data, you must set an ENCRYPTION_SECRET
  * environment variable with a complex enough string.
  *
+ * To be able to change of secret, set ENCRYPTION_SECRETS instead to a comma-separated list of
+ * `key_id:secret` pairs. Data is encrypted with the secret of ENCRYPTION_KEY_ID (the first one
+ * by default) and saved as `key_id:ciphertext`, so that it can be decrypted with any secret of
+ * the list. Data saved without key id is still decrypted with ENCRYPTION_SECRET.
+ *
  * Encrypt: Data is JSON-stringified before encryption, and is returned as an encrypted string.
  * Decrypt: Data is decrypted from an encrypted string and is returned as a JSON Value.
  *
  * The encryption algorithm used is AES-256-GCM.
  */
+use crate::error_messages::{
+    ERROR_ENCRYPTION_KEY_ID, ERROR_ENCRYPTION_SECRET, ERROR_ENCRYPTION_SECRETS,
+};
 use crate::EngineError;
+use csml_interpreter::data::{BotArtifact, CsmlBot};
 
 #[cfg(all(feature = "openssl", not(feature = "rustls")))]
 use openssl::{
@@ -45,17 +54,100 @@ static PBKDF2_ALG: pbkdf2::Algorithm = pbkdf2::PBKDF2_HMAC_SHA512;
 #[cfg(feature = "rustls")]
 type Aes256Gcm16 = AesGcm<Aes256, U16>;
 
-fn get_encryption_secret() -> String {
-    match env::var("ENCRYPTION_SECRET") {
-        Ok(var) => var,
-        _ => panic!("No ENCRYPTION_SECRET value in env"),
+/**
+ * Secret used to encrypt new data, with its key id if it comes from ENCRYPTION_SECRETS
+ */
+struct Secret {
+    key_id: Option<String>,
+    secret: String,
+}
+
+fn is_key_id(key_id: &str) -> bool {
+    !key_id.is_empty()
+        && key_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/**
+ * `key_id:secret` pairs of ENCRYPTION_SECRETS
+ */
+fn parse_secrets(secrets: &str) -> Result<Vec<(String, String)>, EngineError> {
+    secrets
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once(':') {
+            Some((key_id, secret)) if is_key_id(key_id) && !secret.is_empty() => {
+                Ok((key_id.to_owned(), secret.to_owned()))
+            }
+            _ => Err(EngineError::Manager(ERROR_ENCRYPTION_SECRETS.to_owned())),
+        })
+        .collect()
+}
+
+fn get_versioned_secrets() -> Result<Vec<(String, String)>, EngineError> {
+    match env::var("ENCRYPTION_SECRETS") {
+        Ok(secrets) => parse_secrets(&secrets),
+        Err(_) => Ok(vec![]),
     }
 }
 
-#[cfg(all(feature = "openssl", not(feature = "rustls")))]
-fn get_key(salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
-    let pass = get_encryption_secret();
+fn get_current_secret() -> Result<Option<Secret>, EngineError> {
+    let secrets = get_versioned_secrets()?;
+    if secrets.is_empty() {
+        return Ok(env::var("ENCRYPTION_SECRET").ok().map(|secret| Secret {
+            key_id: None,
+            secret,
+        }));
+    }
+
+    let (key_id, secret) = match env::var("ENCRYPTION_KEY_ID") {
+        Ok(current) => secrets
+            .into_iter()
+            .find(|(key_id, _)| *key_id == current)
+            .ok_or_else(|| {
+                EngineError::Manager(format!("{}: {}", ERROR_ENCRYPTION_KEY_ID, current))
+            })?,
+        Err(_) => secrets.into_iter().next().unwrap(),
+    };
+
+    Ok(Some(Secret {
+        key_id: Some(key_id),
+        secret,
+    }))
+}
+
+/**
+ * Secret that encrypted data saved with this key id, or without key id
+ */
+fn get_decryption_secret(key_id: Option<&str>) -> Result<String, EngineError> {
+    match key_id {
+        Some(key_id) => get_versioned_secrets()?
+            .into_iter()
+            .find(|(id, _)| id == key_id)
+            .map(|(_, secret)| secret)
+            .ok_or_else(|| {
+                EngineError::Manager(format!("{}: {}", ERROR_ENCRYPTION_KEY_ID, key_id))
+            }),
+        None => env::var("ENCRYPTION_SECRET")
+            .map_err(|_| EngineError::Manager(ERROR_ENCRYPTION_SECRET.to_owned())),
+    }
+}
+
+pub fn is_encryption_enabled() -> bool {
+    env::var("ENCRYPTION_SECRETS").is_ok() || env::var("ENCRYPTION_SECRET").is_ok()
+}
 
+/**
+ * Id of the key used to encrypt new data, if encryption uses ENCRYPTION_SECRETS
+ */
+pub fn get_current_key_id() -> Result<Option<String>, EngineError> {
+    Ok(get_current_secret()?.and_then(|secret| secret.key_id))
+}
+
+#[cfg(all(feature = "openssl", not(feature = "rustls")))]
+fn get_key(pass: &str, salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
     pbkdf2_hmac(
         pass.as_bytes(),
         salt,
@@ -68,9 +160,7 @@ fn get_key(salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
 }
 
 #[cfg(feature = "rustls")]
-fn get_key(salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
-    let pass = get_encryption_secret();
-
+fn get_key(pass: &str, salt: &[u8], key: &mut [u8]) -> Result<(), EngineError> {
     pbkdf2::derive(
         PBKDF2_ALG,
         NonZeroU32::new(10000).unwrap(),
@@ -100,7 +190,7 @@ fn decode(text: &str) -> Result<Vec<u8>, EngineError> {
 }
 
 #[cfg(all(feature = "openssl", not(feature = "rustls")))]
-fn encrypt(text: &[u8]) -> Result<String, EngineError> {
+fn encrypt(text: &[u8], secret: &str) -> Result<String, EngineError> {
     let cipher = Cipher::aes_256_gcm();
 
     let mut tag = vec![0; 16];
@@ -109,7 +199,7 @@ fn encrypt(text: &[u8]) -> Result<String, EngineError> {
     let mut salt = vec![0; 64];
     rand_bytes(&mut salt)?;
     let mut key = [0; 32];
-    get_key(&salt, &mut key)?;
+    get_key(secret, &salt, &mut key)?;
 
     let encrypted = encrypt_aead(cipher, &key, Some(&iv), &[], text, &mut tag)?;
 
@@ -117,11 +207,11 @@ fn encrypt(text: &[u8]) -> Result<String, EngineError> {
 }
 
 #[cfg(feature = "rustls")]
-fn encrypt(text: &[u8]) -> Result<String, EngineError> {
+fn encrypt(text: &[u8], secret: &str) -> Result<String, EngineError> {
     let mut key = [0; 32];
     let mut salt = vec![0; 64];
     OsRng.fill_bytes(&mut salt);
-    get_key(&salt, &mut key)?;
+    get_key(secret, &salt, &mut key)?;
 
     let cipher = Aes256Gcm16::new(&key.into());
     let nonce = Aes256Gcm16::generate_nonce(&mut OsRng);
@@ -138,15 +228,26 @@ fn encrypt(text: &[u8]) -> Result<String, EngineError> {
 }
 
 pub fn encrypt_data(value: &serde_json::Value) -> Result<String, EngineError> {
-    match env::var("ENCRYPTION_SECRET") {
-        Ok(..) => encrypt(value.to_string().as_bytes()),
-        _ => Ok(value.to_string()),
+    match get_current_secret()? {
+        Some(Secret {
+            key_id: Some(key_id),
+            secret,
+        }) => Ok(format!(
+            "{}:{}",
+            key_id,
+            encrypt(value.to_string().as_bytes(), &secret)?
+        )),
+        Some(Secret {
+            key_id: None,
+            secret,
+        }) => encrypt(value.to_string().as_bytes(), &secret),
+        None => Ok(value.to_string()),
     }
 }
 
 #[cfg(all(feature = "openssl", not(feature = "rustls")))]
-fn decrypt(text: String) -> Result<String, EngineError> {
-    let ciphertext = decode(&text)?;
+fn decrypt(text: &str, secret: &str) -> Result<String, EngineError> {
+    let ciphertext = decode(text)?;
     let cipher = Cipher::aes_256_gcm();
 
     let iv_length = 16;
@@ -161,7 +262,7 @@ fn decrypt(text: String) -> Result<String, EngineError> {
     let encrypted: &[u8] = &ciphertext[encrypted_position..];
 
     let mut key = [0; 32];
-    get_key(salt, &mut key)?;
+    get_key(secret, salt, &mut key)?;
 
     let value = decrypt_aead(cipher, &key, Some(iv), &[], encrypted, tag)?;
 
@@ -169,8 +270,8 @@ fn decrypt(text: String) -> Result<String, EngineError> {
 }
 
 #[cfg(feature = "rustls")]
-fn decrypt(text: String) -> Result<String, EngineError> {
-    let ciphertext = decode(&text)?;
+fn decrypt(text: &str, secret: &str) -> Result<String, EngineError> {
+    let ciphertext = decode(text)?;
 
     let nonce_length = 16;
     let salt_length = 64;
@@ -184,7 +285,7 @@ fn decrypt(text: String) -> Result<String, EngineError> {
     let mut buffer = ciphertext[encrypted_position..].to_vec();
 
     let mut key = [0; 32];
-    get_key(salt, &mut key)?;
+    get_key(secret, salt, &mut key)?;
 
     let cipher = Aes256Gcm16::new(&key.into());
     // Unrolled version of let encrypted = cipher.decrypt(...)?;
@@ -194,16 +295,57 @@ fn decrypt(text: String) -> Result<String, EngineError> {
 }
 
 pub fn decrypt_data(value: String) -> Result<serde_json::Value, EngineError> {
-    match env::var("ENCRYPTION_SECRET") {
-        Ok(..) => {
-            let value: serde_json::Value = serde_json::from_str(&decrypt(value)?)?;
-            Ok(value)
-        }
-        _ => {
-            let value: serde_json::Value = serde_json::from_str(&value)?;
-            Ok(value)
-        }
+    if !is_encryption_enabled() {
+        let value: serde_json::Value = serde_json::from_str(&value)?;
+        return Ok(value);
+    }
+
+    // base64 and hex ciphertexts have no ':', only the prefix of the key id
+    let decrypted = match value.split_once(':') {
+        Some((key_id, encrypted)) => decrypt(encrypted, &get_decryption_secret(Some(key_id))?)?,
+        None => decrypt(&value, &get_decryption_secret(None)?)?,
+    };
+
+    let value: serde_json::Value = serde_json::from_str(&decrypted)?;
+    Ok(value)
+}
+
+/**
+ * Secret signing the bot artifacts returned by `compile_bot`, set with BOT_ARTIFACT_SECRET
+ */
+fn get_artifact_secret() -> Option<String> {
+    env::var("BOT_ARTIFACT_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty())
+}
+
+/**
+ * Encode a compiled bot (see `compile_bot`) to be sent as the `bot_ast` of the bot. The artifact
+ * is signed with BOT_ARTIFACT_SECRET, as bots sent with an artifact that is not signed are
+ * compiled again from their flows.
+ */
+pub fn encode_bot_artifact(artifact: &BotArtifact) -> String {
+    match get_artifact_secret() {
+        Some(secret) => artifact.encode_signed(secret.as_bytes()),
+        None => artifact.encode(),
+    }
+}
+
+/**
+ * The `bot_ast` of a bot sent with a request is only used if it was signed by this engine,
+ * otherwise it is removed and the bot is compiled from its flows
+ */
+pub fn verify_client_artifact(mut bot: CsmlBot) -> CsmlBot {
+    let verified = match (&bot.bot_ast, get_artifact_secret()) {
+        (Some(ast), Some(secret)) => BotArtifact::verify(ast, Some(secret.as_bytes())).is_ok(),
+        _ => false,
+    };
+
+    if !verified {
+        bot.bot_ast = None;
     }
+
+    bot
 }
 
 #[cfg(test)]
@@ -217,7 +359,7 @@ mod tests {
         let key = b"key123".to_vec();
         let mut encrypted = key.clone();
 
-        get_key(salt, encrypted.as_mut_slice())?;
+        get_key("test", salt, encrypted.as_mut_slice())?;
 
         assert_eq!(encrypted, [35, 47, 70, 158, 170, 34]);
         Ok(())
@@ -228,9 +370,26 @@ mod tests {
         env::set_var("ENCRYPTION_SECRET", "test");
         let text = "text".to_owned();
 
-        let encrypted = encrypt(text.as_bytes()).unwrap();
-        let decrypted = decrypt(encrypted).unwrap();
+        let encrypted = encrypt(text.as_bytes(), "test").unwrap();
+        let decrypted = decrypt(&encrypted, "test").unwrap();
 
         assert_eq!(text, decrypted);
+        assert!(decrypt(&encrypted, "other").is_err());
+    }
+
+    #[test]
+    fn test_parse_secrets() {
+        let secrets = parse_secrets("2024-02:new:secret, 2023:old,").unwrap();
+
+        assert_eq!(
+            secrets,
+            vec![
+                ("2024-02".to_owned(), "new:secret".to_owned()),
+                ("2023".to_owned(), "old".to_owned()),
+            ]
+        );
+        assert!(parse_secrets("secret").is_err());
+        assert!(parse_secrets("key id:secret").is_err());
+        assert!(parse_secrets("2024:").is_err());
     }
 }
@@ -1 +1,19 @@
 pub const ERROR_DB_SETUP: &str = "Database connector is not setup correctly";
+pub const ERROR_CALLBACK_ORDER: &str =
+    "Not sent to keep the order after an earlier message of this client failed";
+pub const ERROR_ARCHIVE_VERSION: &str = "Unsupported archive version";
+#[cfg(any(feature = "memory", feature = "sqlite", feature = "postgresql"))]
+pub const ERROR_MESSAGE_CONVERSATION: &str = "Message saved without its conversation";
+pub const ERROR_DB_URL: &str =
+    "Unsupported database url, expected sqlite://, postgresql://, mongodb://, dynamodb:// or memory://";
+pub const ERROR_ENCRYPTION_SECRETS: &str =
+    "ENCRYPTION_SECRETS must be a comma-separated list of key_id:secret, with key ids made of letters, digits, - or _";
+pub const ERROR_ENCRYPTION_KEY_ID: &str = "Unknown encryption key id";
+pub const ERROR_ENCRYPTION_SECRET: &str =
+    "No ENCRYPTION_SECRET to decrypt data saved without key id";
+pub const ERROR_ENCRYPTION_DISABLED: &str =
+    "Set ENCRYPTION_SECRETS (or ENCRYPTION_SECRET) to rotate the encryption key";
+pub const ERROR_API_KEY_SCOPES: &str = "An API key needs at least one scope";
+pub const ERROR_API_KEY_BOT_IDS: &str =
+    "bot_ids must list at least one bot, or be left out to allow every bot";
+pub const ERROR_ANALYTICS_PERIOD: &str = "from_date must be before to_date";
@@ -1,7 +1,12 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 
 use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::models::BotVersion;
 use crate::{CsmlBot, EngineError};
@@ -12,6 +17,8 @@ pub async fn create_bot_version(
     csml_bot: CsmlBot,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<String, EngineError> {
+    let _span = db_span("csml.db.create_bot_version", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -46,6 +53,30 @@ pub async fn create_bot_version(
         return Ok(version_id);
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+
+        let serializable_bot = crate::data::to_serializable_bot(&csml_bot);
+        let bot = serde_json::json!(serializable_bot).to_string();
+
+        let version_id = sqlite_connector::bot::create_bot_version(bot_id, bot, db).await?;
+
+        return Ok(version_id);
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+
+        let serializable_bot = crate::data::to_serializable_bot(&csml_bot);
+        let bot = serde_json::json!(serializable_bot).to_string();
+
+        let version_id = mongodb_connector::bot::create_bot_version(bot_id, bot, db).await?;
+
+        return Ok(version_id);
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -53,6 +84,8 @@ pub async fn get_last_bot_version(
     bot_id: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<Option<BotVersion>, EngineError> {
+    let _span = db_span("csml.db.get_last_bot_version", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -69,6 +102,18 @@ pub async fn get_last_bot_version(
         return postgresql_connector::bot::get_last_bot_version(bot_id, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::bot::get_last_bot_version(bot_id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot::get_last_bot_version(bot_id, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -77,6 +122,8 @@ pub async fn get_by_version_id(
     _bot_id: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<Option<BotVersion>, EngineError> {
+    let _span = db_span("csml.db.get_by_version_id", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -105,6 +152,18 @@ pub async fn get_by_version_id(
         return postgresql_connector::bot::get_bot_by_version_id(version_id, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::bot::get_bot_by_version_id(version_id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot::get_bot_by_version_id(version_id, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -114,6 +173,8 @@ pub async fn get_bot_versions(
     pagination_key: Option<u32>,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<serde_json::Value, EngineError> {
+    let _span = db_span("csml.db.get_bot_versions", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -143,6 +204,18 @@ pub async fn get_bot_versions(
             .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::bot::get_bot_versions(bot_id, limit, pagination_key, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot::get_bot_versions(bot_id, limit, pagination_key, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -151,6 +224,8 @@ pub async fn delete_bot_version(
     version_id: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_bot_version", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -176,6 +251,18 @@ pub async fn delete_bot_version(
         return postgresql_connector::bot::delete_bot_version(version_id, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::bot::delete_bot_version(version_id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot::delete_bot_version(version_id, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -183,6 +270,8 @@ pub async fn delete_bot_versions(
     bot_id: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_bot_versions", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call delete bot versions".to_string()),
         LogLvl::Info,
@@ -203,6 +292,18 @@ pub async fn delete_bot_versions(
         return postgresql_connector::bot::delete_bot_versions(bot_id, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::bot::delete_bot_versions(bot_id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot::delete_bot_versions(bot_id, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -210,6 +311,8 @@ pub async fn delete_all_bot_data(
     bot_id: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_all_bot_data", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call delete all bot data".to_string()),
         LogLvl::Info,
@@ -233,6 +336,40 @@ pub async fn delete_all_bot_data(
         postgresql_connector::conversations::delete_all_bot_data(bot_id, db).await?;
         postgresql_connector::memories::delete_all_bot_data(bot_id, db).await?;
         postgresql_connector::state::delete_all_bot_data(bot_id, db).await?;
+        postgresql_connector::counters::delete_all_bot_data(bot_id, db).await?;
+        postgresql_connector::schedules::delete_all_bot_data(bot_id, db).await?;
+        postgresql_connector::outbox::delete_all_bot_data(bot_id, db).await?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        delete_bot_versions(bot_id, db).await?;
+
+        let db = sqlite_connector::get_db(db)?;
+
+        sqlite_connector::conversations::delete_all_bot_data(bot_id, db).await?;
+        sqlite_connector::memories::delete_all_bot_data(bot_id, db).await?;
+        sqlite_connector::state::delete_all_bot_data(bot_id, db).await?;
+        sqlite_connector::counters::delete_all_bot_data(bot_id, db).await?;
+        sqlite_connector::schedules::delete_all_bot_data(bot_id, db).await?;
+        sqlite_connector::outbox::delete_all_bot_data(bot_id, db).await?;
+        return Ok(());
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        delete_bot_versions(bot_id, db).await?;
+
+        let db = mongodb_connector::get_db(db)?;
+
+        mongodb_connector::conversations::delete_all_bot_data(bot_id, db).await?;
+        mongodb_connector::memories::delete_all_bot_data(bot_id, db).await?;
+        mongodb_connector::messages::delete_all_bot_data(bot_id, db).await?;
+        mongodb_connector::state::delete_all_bot_data(bot_id, db).await?;
+        mongodb_connector::counters::delete_all_bot_data(bot_id, db).await?;
+        mongodb_connector::schedules::delete_all_bot_data(bot_id, db).await?;
+        mongodb_connector::outbox::delete_all_bot_data(bot_id, db).await?;
         return Ok(());
     }
 
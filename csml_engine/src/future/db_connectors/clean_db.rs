@@ -1,11 +1,18 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 
 use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::EngineError;
 
 pub async fn delete_expired_data(_db: &mut AsyncDatabase<'_>) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_expired_data", _db.backend());
+
     #[cfg(feature = "postgresql-async")]
     if is_postgresql() {
         let db = postgresql_connector::get_db(_db)?;
@@ -15,5 +22,23 @@ pub async fn delete_expired_data(_db: &mut AsyncDatabase<'_>) -> Result<(), Engi
         return Ok(());
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(_db)?;
+
+        sqlite_connector::expired_data::delete_expired_data(db).await?;
+
+        return Ok(());
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(_db)?;
+
+        mongodb_connector::expired_data::delete_expired_data(db).await?;
+
+        return Ok(());
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
@@ -1,13 +1,18 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 use uuid::Uuid;
 
+use crate::db_connectors::db_span;
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
+use crate::data::models::Conversation;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::future::db_connectors::{state, utils::*};
 use crate::{data, AsyncConversationInfo, AsyncDatabase, Client, EngineError};
-use crate::data::models::Conversation;
 
 pub async fn create_conversation(
     flow_id: &str,
@@ -16,6 +21,8 @@ pub async fn create_conversation(
     ttl: Option<chrono::Duration>,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<Uuid, EngineError> {
+    let _span = db_span("csml.db.create_conversation", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -51,6 +58,26 @@ pub async fn create_conversation(
         .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_sqlite(ttl);
+        return sqlite_connector::conversations::create_conversation(
+            flow_id, step_id, client, expires_at, db,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_mongodb(ttl);
+        return mongodb_connector::conversations::create_conversation(
+            flow_id, step_id, client, expires_at, db,
+        )
+        .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -59,6 +86,8 @@ pub async fn close_conversation(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.close_conversation", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -88,6 +117,19 @@ pub async fn close_conversation(
             .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::conversations::close_conversation(id, client, "CLOSED", db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::close_conversation(id, client, "CLOSED", db)
+            .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -95,6 +137,8 @@ pub async fn close_all_conversations(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.close_all_conversations", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -120,6 +164,18 @@ pub async fn close_all_conversations(
         return postgresql_connector::conversations::close_all_conversations(client, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::conversations::close_all_conversations(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::close_all_conversations(client, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -127,6 +183,8 @@ pub async fn get_latest_open(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<Option<Conversation>, EngineError> {
+    let _span = db_span("csml.db.get_latest_open", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -152,6 +210,18 @@ pub async fn get_latest_open(
         return postgresql_connector::conversations::get_latest_open(client, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::conversations::get_latest_open(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::get_latest_open(client, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -160,6 +230,8 @@ pub async fn update_conversation(
     flow_id: Option<String>,
     step_id: Option<String>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.update_conversation", data.db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -197,6 +269,30 @@ pub async fn update_conversation(
         .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(&mut data.db)?;
+        return sqlite_connector::conversations::update_conversation(
+            data.conversation_id,
+            flow_id,
+            step_id,
+            db,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(&mut data.db)?;
+        return mongodb_connector::conversations::update_conversation(
+            data.conversation_id,
+            flow_id,
+            step_id,
+            db,
+        )
+        .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -204,6 +300,8 @@ pub async fn get_conversation(
     db: &mut AsyncDatabase<'_>,
     id: Uuid,
 ) -> Result<data::models::Conversation, EngineError> {
+    let _span = db_span("csml.db.get_conversation", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, format!("db call get client conversation")),
         LogLvl::Info,
@@ -215,6 +313,18 @@ pub async fn get_conversation(
         return postgresql_connector::conversations::get_conversation(db, id).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::conversations::get_conversation(db, id).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::get_conversation(db, id).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -224,6 +334,8 @@ pub async fn get_client_conversations(
     limit: Option<u32>,
     pagination_key: Option<u32>,
 ) -> Result<data::models::Paginated<data::models::Conversation>, EngineError> {
+    let _span = db_span("csml.db.get_client_conversations", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -258,5 +370,29 @@ pub async fn get_client_conversations(
         .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::conversations::get_client_conversations(
+            client,
+            db,
+            limit,
+            pagination_key,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::get_client_conversations(
+            client,
+            db,
+            limit,
+            pagination_key,
+        )
+        .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
@@ -0,0 +1,103 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
+#[cfg(feature = "postgresql-async")]
+use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
+
+use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::future::db_connectors::utils::*;
+use crate::EngineError;
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use csml_interpreter::data::Client;
+
+pub async fn get_counter(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<i64, EngineError> {
+    let _span = db_span("csml.db.get_counter", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            format!("db call get counter: {:?}", key),
+        ),
+        LogLvl::Debug,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::counters::get_counter(client, key, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::counters::get_counter(client, key, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::counters::get_counter(client, key, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    ttl: Option<chrono::Duration>,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<i64, EngineError> {
+    let _span = db_span("csml.db.increment_counter", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            format!("db call increment counter: {:?} by {}", key, count),
+        ),
+        LogLvl::Debug,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_postgresql(ttl);
+
+        return postgresql_connector::counters::increment_counter(
+            client, key, count, expires_at, db,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_sqlite(ttl);
+
+        return sqlite_connector::counters::increment_counter(client, key, count, expires_at, db)
+            .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_mongodb(ttl);
+
+        return mongodb_connector::counters::increment_counter(client, key, count, expires_at, db)
+            .await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
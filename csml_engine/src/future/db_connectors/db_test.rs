@@ -5,7 +5,8 @@ mod tests {
     use uuid::Uuid;
 
     use crate::data::filter::ClientMessageFilter;
-    use crate::data::models::Direction;
+    use crate::data::models::{BotOpt, CsmlRequest, Direction};
+    use crate::future::start_conversation_stream;
     use crate::{
         future::db_connectors::init_db, future::db_connectors::*, make_migrations,
         AsyncConversationInfo, Client, Context,
@@ -28,6 +29,8 @@ mod tests {
             step: ContextStepInfo::Normal("start".to_owned()),
             flow: "Default".to_owned(),
             previous_bot: None,
+            previous: None,
+            interrupted: None,
         }
     }
 
@@ -41,6 +44,7 @@ mod tests {
                 name: "Default".to_owned(),
                 content: "start: say \"hello\"".to_owned(),
                 commands: vec![],
+                priority: 0,
             }],
             native_components: None,
             custom_components: None,
@@ -50,6 +54,10 @@ mod tests {
             env: None,
             modules: None,
             multibot: None,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
         }
     }
 
@@ -68,6 +76,11 @@ mod tests {
             messages,
             ttl: None,
             low_data: false,
+            trace: None,
+            callback_secret: None,
+            callback_failed: false,
+            stream: None,
+            bot_reference: Default::default(),
             db,
         }
     }
@@ -207,6 +220,79 @@ mod tests {
         assert_eq!(conversations.len(), 0);
     }
 
+    #[tokio::test]
+    async fn ok_counters() {
+        make_migrations().unwrap_or(());
+
+        // the other tests delete the data of their own client while this one runs
+        let client = Client::new(
+            "botid".to_owned(),
+            "counters".to_owned(),
+            "alexis".to_owned(),
+        );
+        let mut db = init_db().await.unwrap();
+
+        user::delete_client(&client, &mut db).await.unwrap();
+
+        let ttl = Some(chrono::Duration::seconds(60));
+        assert_eq!(
+            counters::get_counter(&client, "requests", &mut db)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "requests", 1, ttl, &mut db)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "requests", 2, ttl, &mut db)
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            counters::get_counter(&client, "requests", &mut db)
+                .await
+                .unwrap(),
+            3
+        );
+
+        // an expired counter starts again from 0
+        let expired = Some(chrono::Duration::seconds(-1));
+        counters::increment_counter(&client, "steps", 5, expired, &mut db)
+            .await
+            .unwrap();
+        assert_eq!(
+            counters::get_counter(&client, "steps", &mut db)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            counters::increment_counter(&client, "steps", 1, ttl, &mut db)
+                .await
+                .unwrap(),
+            1
+        );
+
+        // the counters are not part of the state of the client
+        assert!(state::get_state_key(&client, "quota", "requests", &mut db)
+            .await
+            .unwrap()
+            .is_none());
+
+        user::delete_client(&client, &mut db).await.unwrap();
+        assert_eq!(
+            counters::get_counter(&client, "requests", &mut db)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
     #[tokio::test]
     async fn ok_memories() {
         make_migrations().unwrap_or(());
@@ -365,4 +451,43 @@ mod tests {
             value => panic!("bad format => {:?}", value),
         }
     }
+
+    #[tokio::test]
+    async fn ok_conversation_stream() {
+        make_migrations().unwrap_or(());
+
+        let mut bot = init_bot();
+        bot.flows[0].content = "start:\n  say \"hello\"\n  say \"bye\"\n  goto end".to_owned();
+        let mut client = get_client();
+        client.user_id = "stream".to_owned();
+
+        let request = CsmlRequest {
+            request_id: "1234".to_owned(),
+            client,
+            callback_url: None,
+            payload: gen_message("hi"),
+            metadata: serde_json::json!({}),
+            step_limit: None,
+            ttl_duration: None,
+            low_data_mode: None,
+            debug: false,
+            random_seed: None,
+        };
+
+        // each message is handled while the interpreter runs on a blocking thread
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let response = start_conversation_stream(request, BotOpt::CsmlBot(bot), sender)
+            .await
+            .unwrap();
+
+        let streamed: Vec<serde_json::Value> = receiver
+            .iter()
+            .map(|message| message["payload"]["content"]["text"].clone())
+            .collect();
+        assert_eq!(
+            streamed,
+            vec![serde_json::json!("hello"), serde_json::json!("bye")]
+        );
+        assert_eq!(response["messages"].as_array().unwrap().len(), 2);
+    }
 }
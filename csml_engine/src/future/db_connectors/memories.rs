@@ -1,6 +1,11 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 
+use crate::db_connectors::db_span;
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
 use crate::error_messages::ERROR_DB_SETUP;
@@ -12,6 +17,8 @@ pub async fn add_memories(
     data: &mut AsyncConversationInfo<'_>,
     memories: &HashMap<String, Memory>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_memories", data.db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -37,6 +44,18 @@ pub async fn add_memories(
         return postgresql_connector::memories::add_memories(data, memories, expires_at).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let expires_at = get_expires_at_for_sqlite(data.ttl);
+        return sqlite_connector::memories::add_memories(data, memories, expires_at).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let expires_at = get_expires_at_for_mongodb(data.ttl);
+        return mongodb_connector::memories::add_memories(data, memories, expires_at).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -47,6 +66,8 @@ pub async fn create_client_memory(
     ttl: Option<chrono::Duration>,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.create_client_memory", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, format!("db call save memory {:?}", key)),
         LogLvl::Info,
@@ -71,6 +92,26 @@ pub async fn create_client_memory(
         .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_sqlite(ttl);
+        return sqlite_connector::memories::create_client_memory(
+            client, &key, &value, expires_at, db,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        let expires_at = get_expires_at_for_mongodb(ttl);
+        return mongodb_connector::memories::create_client_memory(
+            client, &key, &value, expires_at, db,
+        )
+        .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -78,6 +119,8 @@ pub async fn internal_use_get_memories(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<serde_json::Value, EngineError> {
+    let _span = db_span("csml.db.internal_use_get_memories", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call get memories".to_string()),
         LogLvl::Info,
@@ -93,6 +136,18 @@ pub async fn internal_use_get_memories(
         return postgresql_connector::memories::internal_use_get_memories(client, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::memories::internal_use_get_memories(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::memories::internal_use_get_memories(client, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -103,6 +158,8 @@ pub async fn get_memories(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<serde_json::Value, EngineError> {
+    let _span = db_span("csml.db.get_memories", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call get memories client".to_string()),
         LogLvl::Info,
@@ -123,6 +180,18 @@ pub async fn get_memories(
         return postgresql_connector::memories::get_memories(client, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::memories::get_memories(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::memories::get_memories(client, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -134,6 +203,8 @@ pub async fn get_memory(
     key: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<serde_json::Value, EngineError> {
+    let _span = db_span("csml.db.get_memory", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, format!("db call get memory {:?}", key)),
         LogLvl::Info,
@@ -154,6 +225,18 @@ pub async fn get_memory(
         return postgresql_connector::memories::get_memory(client, key, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::memories::get_memory(client, key, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::memories::get_memory(client, key, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -162,6 +245,8 @@ pub async fn delete_client_memory(
     key: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_client_memory", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, format!("db call delete memory {:?}", key)),
         LogLvl::Info,
@@ -182,6 +267,18 @@ pub async fn delete_client_memory(
         return postgresql_connector::memories::delete_client_memory(client, key, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::memories::delete_client_memory(client, key, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::memories::delete_client_memory(client, key, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -189,6 +286,8 @@ pub async fn delete_client_memories(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_client_memories", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call delete memories".to_string()),
         LogLvl::Info,
@@ -209,5 +308,17 @@ pub async fn delete_client_memories(
         return postgresql_connector::memories::delete_client_memories(client, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::memories::delete_client_memories(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::memories::delete_client_memories(client, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
@@ -1,8 +1,13 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 
 use crate::data::filter::ClientMessageFilter;
 use crate::data::models::{Direction, Message, Paginated};
+use crate::db_connectors::db_span;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::future::db_connectors::utils::*;
 use crate::{AsyncConversationInfo, AsyncDatabase, EngineError};
@@ -14,6 +19,8 @@ pub async fn add_messages_bulk(
     interaction_order: i32,
     direction: Direction,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_messages_bulk", data.db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -47,6 +54,34 @@ pub async fn add_messages_bulk(
         .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let expires_at = get_expires_at_for_sqlite(data.ttl);
+
+        return sqlite_connector::messages::add_messages_bulk(
+            data,
+            &msgs,
+            interaction_order,
+            direction,
+            expires_at,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let expires_at = get_expires_at_for_mongodb(data.ttl);
+
+        return mongodb_connector::messages::add_messages_bulk(
+            data,
+            &msgs,
+            interaction_order,
+            direction,
+            expires_at,
+        )
+        .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -54,6 +89,8 @@ pub async fn get_client_messages<'conn, 'a: 'conn, 'b: 'conn>(
     db: &'a mut AsyncDatabase<'conn>,
     filter: ClientMessageFilter<'b>,
 ) -> Result<Paginated<Message>, EngineError> {
+    let _span = db_span("csml.db.get_client_messages", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call get messages".to_string()),
         LogLvl::Info,
@@ -71,5 +108,19 @@ pub async fn get_client_messages<'conn, 'a: 'conn, 'b: 'conn>(
         return postgresql_connector::messages::get_client_messages(db, filter).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+
+        return sqlite_connector::messages::get_client_messages(db, filter).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+
+        return mongodb_connector::messages::get_client_messages(db, filter).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
@@ -27,6 +27,9 @@
  *
  * If the ENGINE_DB_TYPE env var is not set, mongodb is used by default.
  *
+ * Only `postgresql` (`postgresql-async` feature), `sqlite` (`sqlite-async` feature)
+ * and `mongodb` (`mongo-async` feature) are available in async mode.
+ *
  * To add a new DB type, please use one of the existing templates implementations.
  * Each method of each module must be fully reimplemented in order to extend the "generic"
  * implementation at the root of db_connectors directory.
@@ -34,13 +37,20 @@
 use crate::data::{AsyncDatabase, EngineError};
 use crate::error_messages::ERROR_DB_SETUP;
 
+#[cfg(feature = "mongo-async")]
+use self::mongodb as mongodb_connector;
 #[cfg(feature = "postgresql-async")]
 use self::postgresql as postgresql_connector;
+#[cfg(feature = "sqlite-async")]
+use self::sqlite as sqlite_connector;
 
 pub mod bot;
 pub mod conversations;
+pub mod counters;
 pub mod memories;
 pub mod messages;
+pub mod outbox;
+pub mod schedules;
 pub mod state;
 
 pub mod clean_db;
@@ -49,8 +59,12 @@ pub mod utils;
 
 pub mod db_test;
 
+#[cfg(feature = "mongo-async")]
+pub(crate) mod mongodb;
 #[cfg(feature = "postgresql-async")]
 pub(crate) mod postgresql;
+#[cfg(feature = "sqlite-async")]
+pub(crate) mod sqlite;
 
 #[cfg(feature = "postgresql-async")]
 pub fn is_postgresql() -> bool {
@@ -60,11 +74,38 @@ pub fn is_postgresql() -> bool {
     }
 }
 
+#[cfg(feature = "sqlite-async")]
+pub fn is_sqlite() -> bool {
+    match std::env::var("ENGINE_DB_TYPE") {
+        Ok(val) => val == *"sqlite",
+        Err(_) => false,
+    }
+}
+
+#[cfg(feature = "mongo-async")]
+pub fn is_mongodb() -> bool {
+    // If the env var is not set at all, use mongodb by default
+    match std::env::var("ENGINE_DB_TYPE") {
+        Ok(val) => val == *"mongodb",
+        Err(_) => true,
+    }
+}
+
 pub async fn init_db() -> Result<AsyncDatabase<'static>, EngineError> {
     #[cfg(feature = "postgresql-async")]
     if is_postgresql() {
         return postgresql_connector::init().await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        return sqlite_connector::init().await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        return mongodb_connector::init().await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
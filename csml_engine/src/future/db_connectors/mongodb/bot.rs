@@ -0,0 +1,166 @@
+use crate::data::{CsmlBotBincode, SerializeCsmlBot};
+use crate::models::BotVersion;
+use crate::{AsyncMongoDbClient, EngineError};
+use base64::Engine;
+use bson::{doc, oid::ObjectId};
+use chrono::SecondsFormat;
+use mongodb::options::FindOneOptions;
+
+use super::{find_page, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::Bot> {
+    db.client.collection::<models::Bot>("bot")
+}
+
+/**
+ * Bots saved by older engine versions may be stored as base64 encoded bincode
+ */
+fn deserialize_bot(bot: &str) -> Result<SerializeCsmlBot, EngineError> {
+    if let Ok(base64decoded) = base64::engine::general_purpose::STANDARD.decode(bot) {
+        if let Ok(bot) = bincode::deserialize::<CsmlBotBincode>(&base64decoded[..]) {
+            return Ok(bot.to_bot());
+        }
+    }
+
+    Ok(serde_json::from_str(bot)?)
+}
+
+fn format_bot_version(bot: models::Bot) -> Result<BotVersion, EngineError> {
+    let csml_bot = deserialize_bot(&bot.bot)?;
+
+    Ok(BotVersion {
+        bot: csml_bot.to_bot(),
+        version_id: bot.id.map(|id| id.to_hex()).unwrap_or_default(),
+        engine_version: env!("CARGO_PKG_VERSION").to_owned(),
+    })
+}
+
+pub async fn create_bot_version(
+    bot_id: String,
+    bot: String,
+    db: &mut AsyncMongoDbClient,
+) -> Result<String, EngineError> {
+    let bot = models::Bot {
+        id: None,
+        bot_id,
+        bot,
+        engine_version: env!("CARGO_PKG_VERSION").to_owned(),
+        created_at: bson::DateTime::now(),
+    };
+
+    let inserted = collection(db).insert_one(bot, None).await?;
+
+    match inserted.inserted_id.as_object_id() {
+        Some(id) => Ok(id.to_hex()),
+        None => Err(EngineError::Manager(
+            "MongoDB returned an invalid bot version id".to_owned(),
+        )),
+    }
+}
+
+pub async fn get_bot_versions(
+    bot_id: &str,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let pagination_key = pagination_key.unwrap_or(1);
+    let limit_per_page = limit.unwrap_or(25).min(25);
+
+    let (bot_versions, total_pages) = find_page(
+        &collection(db),
+        doc! { "bot_id": bot_id },
+        doc! { "created_at": -1 },
+        pagination_key,
+        limit_per_page,
+    )
+    .await?;
+
+    let mut bots = vec![];
+    for bot_version in bot_versions {
+        let csml_bot = deserialize_bot(&bot_version.bot)?;
+
+        let mut json = serde_json::json!({
+            "version_id": bot_version.id.map(|id| id.to_hex()),
+            "id": csml_bot.id,
+            "name": csml_bot.name,
+            "default_flow": csml_bot.default_flow,
+            "engine_version": bot_version.engine_version,
+            "created_at": bot_version
+                .created_at
+                .to_chrono()
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+        });
+
+        if let Some(custom_components) = csml_bot.custom_components {
+            json["custom_components"] = serde_json::json!(custom_components);
+        }
+
+        bots.push(json);
+    }
+
+    match pagination_key < total_pages {
+        true => {
+            let pagination_key = (pagination_key + 1).to_string();
+            Ok(serde_json::json!({"bots": bots, "pagination_key": pagination_key}))
+        }
+        false => Ok(serde_json::json!({ "bots": bots })),
+    }
+}
+
+pub async fn get_bot_by_version_id(
+    id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Option<BotVersion>, EngineError> {
+    let id = match ObjectId::parse_str(id) {
+        Ok(id) => id,
+        Err(..) => return Ok(None),
+    };
+
+    match collection(db).find_one(doc! { "_id": id }, None).await? {
+        Some(bot) => Ok(Some(format_bot_version(bot)?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn get_last_bot_version(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Option<BotVersion>, EngineError> {
+    let find_options = FindOneOptions::builder()
+        .sort(doc! { "created_at": -1 })
+        .build();
+
+    match collection(db)
+        .find_one(doc! { "bot_id": bot_id }, find_options)
+        .await?
+    {
+        Some(bot) => Ok(Some(format_bot_version(bot)?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn delete_bot_version(
+    version_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    let id = match ObjectId::parse_str(version_id) {
+        Ok(id) => id,
+        Err(..) => return Ok(()),
+    };
+
+    collection(db).delete_one(doc! { "_id": id }, None).await?;
+
+    Ok(())
+}
+
+pub async fn delete_bot_versions(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use crate::data::models::{Conversation, Paginated, PaginationData};
+use crate::{AsyncMongoDbClient, Client, EngineError};
+use bson::doc;
+use mongodb::options::FindOneOptions;
+use std::convert::TryInto;
+use uuid::Uuid;
+
+use super::{client_filter, find_page, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::Conversation> {
+    db.client.collection::<models::Conversation>("conversation")
+}
+
+pub async fn create_conversation(
+    flow_id: &str,
+    step_id: &str,
+    client: &Client,
+    expires_at: Option<bson::DateTime>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Uuid, EngineError> {
+    let id = Uuid::new_v4();
+    let time = bson::DateTime::now();
+
+    let conversation = models::Conversation {
+        id: id.to_string(),
+        client: client.to_owned(),
+        flow_id: flow_id.to_owned(),
+        step_id: step_id.to_owned(),
+        status: "OPEN".to_owned(),
+        last_interaction_at: time,
+        updated_at: time,
+        created_at: time,
+        expires_at,
+    };
+
+    collection(db).insert_one(conversation, None).await?;
+
+    Ok(id)
+}
+
+pub async fn close_conversation(
+    id: Uuid,
+    client: &Client,
+    status: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    let mut filter = client_filter(client);
+    filter.insert("_id", id.to_string());
+
+    collection(db)
+        .update_one(
+            filter,
+            doc! {
+                "$set": { "status": status },
+                "$currentDate": { "last_interaction_at": true, "updated_at": true }
+            },
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn close_all_conversations(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .update_many(
+            client_filter(client),
+            doc! {
+                "$set": { "status": "CLOSED" },
+                "$currentDate": { "last_interaction_at": true, "updated_at": true }
+            },
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_latest_open(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Option<Conversation>, EngineError> {
+    let mut filter = client_filter(client);
+    filter.insert("status", "OPEN");
+
+    let find_options = FindOneOptions::builder()
+        .sort(doc! { "updated_at": -1 })
+        .build();
+
+    match collection(db).find_one(filter, find_options).await? {
+        Some(conversation) => Ok(Some(conversation.try_into()?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn update_conversation(
+    conversation_id: Uuid,
+    flow_id: Option<String>,
+    step_id: Option<String>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    let set = match (flow_id, step_id) {
+        (Some(flow_id), Some(step_id)) => doc! { "flow_id": flow_id, "step_id": step_id },
+        (Some(flow_id), None) => doc! { "flow_id": flow_id },
+        (None, Some(step_id)) => doc! { "step_id": step_id },
+        (None, None) => return Ok(()),
+    };
+
+    collection(db)
+        .update_one(
+            doc! { "_id": conversation_id.to_string() },
+            doc! {
+                "$set": set,
+                "$currentDate": { "last_interaction_at": true, "updated_at": true }
+            },
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_user_conversations(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_conversation(
+    db: &mut AsyncMongoDbClient,
+    id: Uuid,
+) -> Result<Conversation, EngineError> {
+    match collection(db)
+        .find_one(doc! { "_id": id.to_string() }, None)
+        .await?
+    {
+        Some(conversation) => conversation.try_into(),
+        None => Err(EngineError::Manager(format!(
+            "Conversation {} not found",
+            id
+        ))),
+    }
+}
+
+pub async fn get_client_conversations(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+) -> Result<Paginated<Conversation>, EngineError> {
+    let pagination_key = pagination_key.unwrap_or(1);
+    let limit_per_page = limit.unwrap_or(25).min(25);
+
+    let (conversations, total_pages) = find_page(
+        &collection(db),
+        client_filter(client),
+        doc! { "updated_at": -1 },
+        pagination_key,
+        limit_per_page,
+    )
+    .await?;
+
+    let data = conversations
+        .into_iter()
+        .map(TryInto::try_into)
+        .collect::<Result<Vec<Conversation>, EngineError>>()?;
+
+    let pagination = (pagination_key < total_pages).then_some(PaginationData {
+        page: pagination_key,
+        total_pages,
+        per_page: limit_per_page,
+    });
+    Ok(Paginated { data, pagination })
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use crate::{AsyncMongoDbClient, Client, EngineError};
+use bson::{doc, Document};
+use mongodb::options::{FindOneAndUpdateOptions, ReturnDocument};
+
+use super::client_filter;
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<Document> {
+    db.client.collection::<Document>("counter")
+}
+
+fn counter_filter(client: &Client, key: &str) -> Document {
+    let mut filter = client_filter(client);
+    filter.insert("key", key);
+
+    filter
+}
+
+pub async fn get_counter(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<i64, EngineError> {
+    let mut filter = counter_filter(client, key);
+    filter.insert(
+        "$or",
+        vec![
+            doc! { "expires_at": null },
+            doc! { "expires_at": { "$gt": bson::DateTime::now() } },
+        ],
+    );
+
+    match collection(db).find_one(filter, None).await? {
+        Some(counter) => Ok(counter.get_i64("count").unwrap_or(0)),
+        None => Ok(0),
+    }
+}
+
+pub async fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<bson::DateTime>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<i64, EngineError> {
+    let collection = collection(db);
+
+    // the ttl index removes expired counters in the background only
+    let mut expired = counter_filter(client, key);
+    expired.insert("expires_at", doc! { "$lt": bson::DateTime::now() });
+    collection.delete_one(expired, None).await?;
+
+    let update = doc! {
+        "$inc": { "count": count },
+        "$set": { "expires_at": expires_at },
+        "$setOnInsert": {
+            "client": bson::to_bson(client)?,
+            "key": key,
+        },
+    };
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(true)
+        .return_document(ReturnDocument::After)
+        .build();
+
+    match collection
+        .find_one_and_update(counter_filter(client, key), update, options)
+        .await?
+    {
+        Some(counter) => Ok(counter.get_i64("count").unwrap_or(0)),
+        None => Ok(count),
+    }
+}
+
+pub async fn delete_client_counters(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::{AsyncMongoDbClient, EngineError};
+use bson::{doc, Document};
+
+use super::COLLECTIONS;
+
+/**
+ * The ttl indexes created by `init` already remove expired documents; this
+ * lets callers purge them without waiting for the mongodb background task.
+ */
+pub async fn delete_expired_data(db: &mut AsyncMongoDbClient) -> Result<(), EngineError> {
+    let filter = doc! { "expires_at": { "$lt": bson::DateTime::now() } };
+
+    for name in COLLECTIONS {
+        db.client
+            .collection::<Document>(name)
+            .delete_many(filter.clone(), None)
+            .await
+            .ok();
+    }
+
+    Ok(())
+}
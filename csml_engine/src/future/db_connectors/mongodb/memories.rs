@@ -0,0 +1,172 @@
+use crate::{
+    encrypt::{decrypt_data, encrypt_data},
+    future::db_connectors::mongodb::get_db,
+    AsyncConversationInfo, AsyncMongoDbClient, Client, EngineError, Memory,
+};
+use bson::doc;
+use chrono::SecondsFormat;
+use futures::TryStreamExt;
+use mongodb::options::{FindOptions, UpdateOptions};
+use std::collections::HashMap;
+
+use super::{client_filter, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::Memory> {
+    db.client.collection::<models::Memory>("memory")
+}
+
+fn format_memory(memory: models::Memory) -> Result<serde_json::Value, EngineError> {
+    Ok(serde_json::json!({
+        "key": memory.key,
+        "value": decrypt_data(memory.value)?,
+        "created_at": memory
+            .created_at
+            .to_chrono()
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    }))
+}
+
+async fn find_memories(
+    client: &Client,
+    db: &AsyncMongoDbClient,
+) -> Result<Vec<models::Memory>, EngineError> {
+    let find_options = FindOptions::builder()
+        .sort(doc! { "updated_at": -1 })
+        .build();
+
+    let memories = collection(db)
+        .find(client_filter(client), find_options)
+        .await?
+        .try_collect()
+        .await?;
+
+    Ok(memories)
+}
+
+pub async fn add_memories(
+    data: &mut AsyncConversationInfo<'_>,
+    memories: &HashMap<String, Memory>,
+    expires_at: Option<bson::DateTime>,
+) -> Result<(), EngineError> {
+    if memories.is_empty() {
+        return Ok(());
+    }
+
+    let db = get_db(&mut data.db)?;
+
+    for (key, mem) in memories.iter() {
+        create_client_memory(&data.client, key, &mem.value, expires_at, db).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn create_client_memory(
+    client: &Client,
+    key: &str,
+    value: &serde_json::Value,
+    expires_at: Option<bson::DateTime>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    let mut filter = client_filter(client);
+    filter.insert("key", key);
+
+    let time = bson::DateTime::now();
+    let update = doc! {
+        "$set": {
+            "value": encrypt_data(value)?, // encrypted
+            "expires_at": expires_at,
+            "updated_at": time,
+        },
+        "$setOnInsert": {
+            "client": bson::to_bson(client)?,
+            "key": key,
+            "created_at": time,
+        }
+    };
+    let update_options = UpdateOptions::builder().upsert(true).build();
+
+    collection(db)
+        .update_one(filter, update, update_options)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn internal_use_get_memories(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let mut map = serde_json::Map::new();
+
+    for memory in find_memories(client, db).await? {
+        if !map.contains_key(&memory.key) {
+            let value: serde_json::Value = decrypt_data(memory.value)?;
+            map.insert(memory.key, value);
+        }
+    }
+
+    Ok(serde_json::json!(map))
+}
+
+pub async fn get_memories(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let memories = find_memories(client, db)
+        .await?
+        .into_iter()
+        .map(format_memory)
+        .collect::<Result<Vec<_>, EngineError>>()?;
+
+    Ok(serde_json::json!(memories))
+}
+
+pub async fn get_memory(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let mut filter = client_filter(client);
+    filter.insert("key", key);
+
+    match collection(db).find_one(filter, None).await? {
+        Some(memory) => format_memory(memory),
+        None => Ok(serde_json::Value::Null),
+    }
+}
+
+pub async fn delete_client_memory(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    let mut filter = client_filter(client);
+    filter.insert("key", key);
+
+    collection(db).delete_many(filter, None).await?;
+
+    Ok(())
+}
+
+pub async fn delete_client_memories(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
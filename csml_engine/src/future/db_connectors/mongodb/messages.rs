@@ -0,0 +1,139 @@
+use crate::data::filter::ClientMessageFilter;
+use crate::data::models::{Direction, Message, Paginated, PaginationData};
+use crate::encrypt::encrypt_data;
+use crate::future::db_connectors::mongodb::get_db;
+use crate::{AsyncConversationInfo, AsyncMongoDbClient, Client, EngineError};
+use bson::doc;
+use std::convert::TryInto;
+use uuid::Uuid;
+
+use super::{client_filter, find_page, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::Message> {
+    db.client.collection::<models::Message>("message")
+}
+
+fn get_date(timestamp: i64) -> Result<bson::DateTime, EngineError> {
+    timestamp
+        .checked_mul(1000)
+        .map(bson::DateTime::from_millis)
+        .ok_or(EngineError::DateTimeError(
+            "Date time is out of range".to_owned(),
+        ))
+}
+
+pub async fn add_messages_bulk(
+    data: &mut AsyncConversationInfo<'_>,
+    msgs: &[serde_json::Value],
+    interaction_order: i32,
+    direction: Direction,
+    expires_at: Option<bson::DateTime>,
+) -> Result<(), EngineError> {
+    if msgs.is_empty() {
+        return Ok(());
+    }
+
+    let time = bson::DateTime::now();
+
+    let mut new_messages = vec![];
+    for (message_order, message) in msgs.iter().enumerate() {
+        new_messages.push(models::Message {
+            id: Uuid::new_v4().to_string(),
+            client: data.client.to_owned(),
+            conversation_id: data.conversation_id.to_string(),
+            flow_id: data.context.flow.to_owned(),
+            step_id: data.context.step.get_step(),
+            message_order: message_order as i32,
+            interaction_order,
+            direction: direction.to_owned(),
+            content_type: message["content_type"]
+                .as_str()
+                .unwrap_or("text")
+                .to_owned(),
+            payload: encrypt_data(message)?,
+            updated_at: time,
+            created_at: time,
+            expires_at,
+        });
+    }
+
+    let db = get_db(&mut data.db)?;
+    collection(db).insert_many(new_messages, None).await?;
+
+    Ok(())
+}
+
+pub async fn delete_user_messages(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_client_messages(
+    db: &mut AsyncMongoDbClient,
+    filter: ClientMessageFilter<'_>,
+) -> Result<Paginated<Message>, EngineError> {
+    let ClientMessageFilter {
+        client,
+        limit,
+        pagination_key,
+        from_date,
+        to_date,
+        conversation_id,
+    } = filter;
+
+    let pagination_key = pagination_key.unwrap_or(1);
+
+    let mut query = client_filter(client);
+    if let Some(conversation_id) = conversation_id {
+        query.insert("conversation_id", conversation_id.to_string());
+    }
+    if let Some(from_date) = from_date {
+        let to_date = match to_date {
+            Some(to_date) => get_date(to_date)?,
+            None => bson::DateTime::now(),
+        };
+
+        query.insert(
+            "created_at",
+            doc! { "$gte": get_date(from_date)?, "$lte": to_date },
+        );
+    }
+
+    let (messages, total_pages) = find_page(
+        &collection(db),
+        query,
+        doc! { "created_at": -1, "message_order": -1 },
+        pagination_key,
+        limit,
+    )
+    .await?;
+
+    let data = messages
+        .into_iter()
+        .map(TryInto::try_into)
+        .collect::<Result<Vec<Message>, EngineError>>()?;
+
+    let pagination = (pagination_key < total_pages).then_some(PaginationData {
+        page: pagination_key,
+        total_pages,
+        per_page: limit,
+    });
+    Ok(Paginated { data, pagination })
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+/**
+ * Async mongodb connector, built on the non-blocking API of the `mongodb` crate.
+ *
+ * Documents are stored in the same collections as the sync connector
+ * (bot, conversation, counter, memory, message, schedule, state).
+ */
+pub mod bot;
+pub mod conversations;
+pub mod counters;
+pub mod memories;
+pub mod messages;
+pub mod outbox;
+pub mod schedules;
+pub mod state;
+
+pub mod expired_data;
+
+mod models;
+
+use crate::{AsyncDatabase, AsyncMongoDbClient, Client, EngineError};
+use bson::{doc, Document};
+use core::time::Duration as CoreDuration;
+use futures::TryStreamExt;
+use mongodb::{
+    options::{FindOptions, IndexOptions},
+    Collection, IndexModel,
+};
+use serde::de::DeserializeOwned;
+
+const COLLECTIONS: [&str; 5] = ["conversation", "counter", "memory", "message", "state"];
+
+fn create_mongodb_uri() -> Result<String, EngineError> {
+    let mut uri = "mongodb://".to_owned();
+
+    match (
+        std::env::var("MONGODB_USERNAME"),
+        std::env::var("MONGODB_PASSWORD"),
+    ) {
+        (Ok(username), Ok(password)) if !username.is_empty() && !password.is_empty() => {
+            uri = format!("{}{}:{}@", uri, username, password)
+        }
+        _ => {}
+    }
+
+    match std::env::var("MONGODB_HOST") {
+        Ok(host) => uri = format!("{}{}", uri, host),
+        _ => {
+            return Err(EngineError::Manager(
+                "Missing MONGODB_HOST in env".to_owned(),
+            ))
+        }
+    }
+
+    if let Ok(var) = std::env::var("MONGODB_PORT") {
+        match var.parse::<u16>() {
+            Ok(port) => uri = format!("{}:{}", uri, port),
+            Err(err) => return Err(EngineError::Manager(err.to_string())),
+        }
+    }
+
+    Ok(uri)
+}
+
+pub async fn init() -> Result<AsyncDatabase<'static>, EngineError> {
+    let dbname = match std::env::var("MONGODB_DATABASE") {
+        Ok(var) => var,
+        _ => {
+            return Err(EngineError::Manager(
+                "Missing MONGODB_DATABASE in env".to_owned(),
+            ))
+        }
+    };
+
+    let uri = match std::env::var("MONGODB_URI") {
+        Ok(var) => var,
+        _ => create_mongodb_uri()?,
+    };
+
+    let client = mongodb::Client::with_uri_str(&uri).await?;
+    let mongodb_client = AsyncMongoDbClient::new(client.database(&dbname));
+    create_indexes(&mongodb_client).await;
+
+    Ok(AsyncDatabase::Mongo(mongodb_client))
+}
+
+pub fn get_db<'a>(
+    db: &'a mut AsyncDatabase<'_>,
+) -> Result<&'a mut AsyncMongoDbClient, EngineError> {
+    match db {
+        AsyncDatabase::Mongo(db) => Ok(db),
+        _ => Err(EngineError::Manager(
+            "MongoDB connector is not setup correctly".to_owned(),
+        )),
+    }
+}
+
+/**
+ * Create the ttl index on expires_at and the compound client index of every collection
+ * holding client data. Failures are ignored, as with the sync connector.
+ */
+async fn create_indexes(db: &AsyncMongoDbClient) {
+    for name in COLLECTIONS {
+        let collection = db.client.collection::<Document>(name);
+
+        let ttl_index = IndexModel::builder()
+            .keys(doc! { "expires_at": 1 })
+            .options(Some(
+                IndexOptions::builder()
+                    .expire_after(CoreDuration::new(0, 0))
+                    .build(),
+            ))
+            .build();
+        collection.create_index(ttl_index, None).await.ok();
+
+        let client_index = IndexModel::builder()
+            .keys(doc! {
+                "client.bot_id": 1,
+                "client.channel_id": 1,
+                "client.user_id": 1
+            })
+            .build();
+        collection.create_index(client_index, None).await.ok();
+    }
+
+    // counters are incremented with upserts, which need a unique index to stay single
+    let counter_index = IndexModel::builder()
+        .keys(doc! {
+            "client.bot_id": 1,
+            "client.channel_id": 1,
+            "client.user_id": 1,
+            "key": 1
+        })
+        .options(Some(IndexOptions::builder().unique(true).build()))
+        .build();
+    db.client
+        .collection::<Document>("counter")
+        .create_index(counter_index, None)
+        .await
+        .ok();
+}
+
+pub(crate) fn client_filter(client: &Client) -> Document {
+    doc! {
+        "client.bot_id": &client.bot_id,
+        "client.channel_id": &client.channel_id,
+        "client.user_id": &client.user_id,
+    }
+}
+
+/**
+ * Load the documents of page `page` (starting at 1) matching `filter`,
+ * along with the total number of pages
+ */
+pub(crate) async fn find_page<T>(
+    collection: &Collection<T>,
+    filter: Document,
+    sort: Document,
+    page: u32,
+    per_page: u32,
+) -> Result<(Vec<T>, u32), EngineError>
+where
+    T: DeserializeOwned + Unpin + Send + Sync,
+{
+    let per_page = per_page.max(1);
+    let total = collection.count_documents(filter.clone(), None).await?;
+
+    let find_options = FindOptions::builder()
+        .sort(sort)
+        .skip(u64::from(page.saturating_sub(1)) * u64::from(per_page))
+        .limit(i64::from(per_page))
+        .build();
+
+    let documents = collection
+        .find(filter, find_options)
+        .await?
+        .try_collect()
+        .await?;
+    let total_pages = total.div_ceil(u64::from(per_page)) as u32;
+
+    Ok((documents, total_pages))
+}
@@ -0,0 +1,215 @@
+use crate::data::models::Direction;
+use crate::encrypt::{decrypt_data, encrypt_data};
+use crate::{data, Client, EngineError};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Conversation {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub client: Client,
+
+    pub flow_id: String,
+    pub step_id: String,
+    pub status: String,
+
+    pub last_interaction_at: bson::DateTime,
+    pub updated_at: bson::DateTime,
+    pub created_at: bson::DateTime,
+    pub expires_at: Option<bson::DateTime>,
+}
+
+impl TryFrom<Conversation> for data::models::Conversation {
+    type Error = EngineError;
+
+    fn try_from(conversation: Conversation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: uuid::Uuid::parse_str(&conversation.id)?,
+            client: conversation.client,
+            flow_id: conversation.flow_id,
+            step_id: conversation.step_id,
+            status: conversation.status,
+            last_interaction_at: conversation.last_interaction_at.to_chrono(),
+            updated_at: conversation.updated_at.to_chrono(),
+            created_at: conversation.created_at.to_chrono(),
+            expires_at: conversation.expires_at.map(bson::DateTime::to_chrono),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Message {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub client: Client,
+    pub conversation_id: String,
+
+    pub flow_id: String,
+    pub step_id: String,
+    pub message_order: i32,
+    pub interaction_order: i32,
+
+    pub direction: Direction,
+    pub content_type: String,
+    pub payload: String, // encrypted
+
+    pub updated_at: bson::DateTime,
+    pub created_at: bson::DateTime,
+    pub expires_at: Option<bson::DateTime>,
+}
+
+impl TryFrom<Message> for data::models::Message {
+    type Error = EngineError;
+
+    fn try_from(message: Message) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: uuid::Uuid::parse_str(&message.id)?,
+            conversation_id: uuid::Uuid::parse_str(&message.conversation_id)?,
+            flow_id: message.flow_id,
+            step_id: message.step_id,
+            message_order: message.message_order as u32,
+            interaction_order: message.interaction_order as u32,
+            direction: message.direction,
+            content_type: message.content_type,
+            payload: decrypt_data(message.payload)?,
+            updated_at: message.updated_at.to_chrono(),
+            created_at: message.created_at.to_chrono(),
+            expires_at: message.expires_at.map(bson::DateTime::to_chrono),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Memory {
+    pub client: Client,
+    pub key: String,
+    pub value: String, // encrypted
+
+    pub updated_at: bson::DateTime,
+    pub created_at: bson::DateTime,
+    pub expires_at: Option<bson::DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct State {
+    pub client: Client,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub key: String,
+    pub value: String, // encrypted
+
+    pub created_at: bson::DateTime,
+    pub expires_at: Option<bson::DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Bot {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<bson::oid::ObjectId>,
+    pub bot_id: String,
+    pub bot: String,
+    pub engine_version: String,
+    pub created_at: bson::DateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Schedule {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub client: Client,
+
+    pub flow_id: String,
+    pub step_id: String,
+    pub callback_url: Option<String>,
+    #[serde(default)]
+    pub bot: data::models::BotReference,
+
+    #[serde(default)]
+    pub attempts: i32,
+    pub run_at: bson::DateTime,
+    pub created_at: bson::DateTime,
+}
+
+impl From<&data::models::Schedule> for Schedule {
+    fn from(schedule: &data::models::Schedule) -> Self {
+        Self {
+            id: schedule.id.to_string(),
+            client: schedule.client.to_owned(),
+            flow_id: schedule.flow_id.to_owned(),
+            step_id: schedule.step_id.to_owned(),
+            callback_url: schedule.callback_url.to_owned(),
+            bot: schedule.bot.to_owned(),
+            attempts: schedule.attempts,
+            run_at: bson::DateTime::from_chrono(schedule.run_at),
+            created_at: bson::DateTime::from_chrono(schedule.created_at),
+        }
+    }
+}
+
+impl TryFrom<Schedule> for data::models::Schedule {
+    type Error = EngineError;
+
+    fn try_from(schedule: Schedule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: uuid::Uuid::parse_str(&schedule.id)?,
+            client: schedule.client,
+            flow_id: schedule.flow_id,
+            step_id: schedule.step_id,
+            callback_url: schedule.callback_url,
+            bot: schedule.bot,
+            attempts: schedule.attempts,
+            run_at: schedule.run_at.to_chrono(),
+            created_at: schedule.created_at.to_chrono(),
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub client: Client,
+
+    pub callback_url: String,
+    pub payload: String, // encrypted
+    pub signature: Option<String>,
+    pub attempts: i32,
+
+    pub updated_at: bson::DateTime,
+    pub created_at: bson::DateTime,
+}
+
+impl TryFrom<&data::models::OutboxMessage> for OutboxMessage {
+    type Error = EngineError;
+
+    fn try_from(message: &data::models::OutboxMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: message.id.to_string(),
+            client: message.client.to_owned(),
+            callback_url: message.callback_url.to_owned(),
+            payload: encrypt_data(&message.payload)?,
+            signature: message.signature.to_owned(),
+            attempts: message.attempts as i32,
+            updated_at: bson::DateTime::from_chrono(message.updated_at),
+            created_at: bson::DateTime::from_chrono(message.created_at),
+        })
+    }
+}
+
+impl TryFrom<OutboxMessage> for data::models::OutboxMessage {
+    type Error = EngineError;
+
+    fn try_from(message: OutboxMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: uuid::Uuid::parse_str(&message.id)?,
+            client: message.client,
+            callback_url: message.callback_url,
+            payload: decrypt_data(message.payload)?,
+            signature: message.signature,
+            attempts: message.attempts as u32,
+            updated_at: message.updated_at.to_chrono(),
+            created_at: message.created_at.to_chrono(),
+        })
+    }
+}
@@ -0,0 +1,105 @@
+use crate::data::models::OutboxMessage;
+use crate::{AsyncMongoDbClient, Client, EngineError};
+use bson::doc;
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::options::FindOptions;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+use super::{client_filter, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::OutboxMessage> {
+    db.client.collection::<models::OutboxMessage>("outbox")
+}
+
+pub async fn add_outbox_message(
+    message: &OutboxMessage,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .insert_one(models::OutboxMessage::try_from(message)?, None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let filter = match bot_id {
+        Some(bot_id) => doc! { "client.bot_id": bot_id },
+        None => doc! {},
+    };
+    let find_options = FindOptions::builder()
+        .sort(doc! { "created_at": 1 })
+        .limit(i64::from(limit))
+        .build();
+
+    let messages: Vec<models::OutboxMessage> = collection(db)
+        .find(filter, find_options)
+        .await?
+        .try_collect()
+        .await?;
+
+    messages.into_iter().map(OutboxMessage::try_from).collect()
+}
+
+pub async fn add_outbox_attempt(id: Uuid, db: &mut AsyncMongoDbClient) -> Result<(), EngineError> {
+    collection(db)
+        .update_one(
+            doc! { "_id": id.to_string() },
+            doc! {
+                "$inc": { "attempts": 1 },
+                "$set": { "updated_at": bson::DateTime::from_chrono(Utc::now()) },
+            },
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_outbox_message(
+    id: Uuid,
+    db: &mut AsyncMongoDbClient,
+) -> Result<bool, EngineError> {
+    let result = collection(db)
+        .delete_one(doc! { "_id": id.to_string() }, None)
+        .await?;
+
+    Ok(result.deleted_count > 0)
+}
+
+pub async fn has_client_outbox(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<bool, EngineError> {
+    let message = collection(db).find_one(client_filter(client), None).await?;
+
+    Ok(message.is_some())
+}
+
+pub async fn delete_client_outbox(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use crate::data::models::Schedule;
+use crate::{AsyncMongoDbClient, Client, EngineError};
+use bson::doc;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use mongodb::options::FindOptions;
+use std::convert::TryFrom;
+use uuid::Uuid;
+
+use super::{client_filter, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::Schedule> {
+    db.client.collection::<models::Schedule>("schedule")
+}
+
+pub async fn create_schedule(
+    schedule: &Schedule,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .insert_one(models::Schedule::from(schedule), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_due_schedules(
+    now: DateTime<Utc>,
+    limit: u32,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    let find_options = FindOptions::builder()
+        .sort(doc! { "run_at": 1 })
+        .limit(i64::from(limit))
+        .build();
+
+    let schedules: Vec<models::Schedule> = collection(db)
+        .find(
+            doc! { "run_at": { "$lte": bson::DateTime::from_chrono(now) } },
+            find_options,
+        )
+        .await?
+        .try_collect()
+        .await?;
+
+    schedules.into_iter().map(Schedule::try_from).collect()
+}
+
+pub async fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<bool, EngineError> {
+    let filter = doc! {
+        "_id": schedule.id.to_string(),
+        "attempts": schedule.attempts,
+    };
+    let update = doc! {
+        "$set": {
+            "attempts": schedule.attempts + 1,
+            "run_at": bson::DateTime::from_chrono(retry_at),
+        }
+    };
+    let result = collection(db).update_one(filter, update, None).await?;
+
+    Ok(result.modified_count > 0)
+}
+
+pub async fn delete_schedule(id: Uuid, db: &mut AsyncMongoDbClient) -> Result<bool, EngineError> {
+    let result = collection(db)
+        .delete_one(doc! { "_id": id.to_string() }, None)
+        .await?;
+
+    Ok(result.deleted_count > 0)
+}
+
+pub async fn delete_client_schedules(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use crate::{
+    encrypt::{decrypt_data, encrypt_data},
+    AsyncMongoDbClient, Client, EngineError,
+};
+use bson::doc;
+use chrono::SecondsFormat;
+use mongodb::options::UpdateOptions;
+
+use super::{client_filter, models};
+
+fn collection(db: &AsyncMongoDbClient) -> mongodb::Collection<models::State> {
+    db.client.collection::<models::State>("state")
+}
+
+fn state_filter(client: &Client, type_: &str, key: &str) -> bson::Document {
+    let mut filter = client_filter(client);
+    filter.insert("type", type_);
+    filter.insert("key", key);
+
+    filter
+}
+
+pub async fn delete_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(state_filter(client, type_, key), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    match collection(db)
+        .find_one(state_filter(client, type_, key), None)
+        .await?
+    {
+        Some(state) => Ok(Some(decrypt_data(state.value)?)),
+        None => Ok(None),
+    }
+}
+
+pub async fn get_current_state(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let state = match collection(db)
+        .find_one(state_filter(client, "hold", "position"), None)
+        .await?
+    {
+        Some(state) => state,
+        None => return Ok(None),
+    };
+
+    let current_state = serde_json::json!({
+        "client": state.client,
+        "type": state.type_,
+        "value": decrypt_data(state.value)?,
+        "created_at": state
+            .created_at
+            .to_chrono()
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    });
+
+    Ok(Some(current_state))
+}
+
+pub async fn set_state_items(
+    client: &Client,
+    type_: &str,
+    keys_values: Vec<(&str, &serde_json::Value)>,
+    expires_at: Option<bson::DateTime>,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    if keys_values.is_empty() {
+        return Ok(());
+    }
+
+    let collection = collection(db);
+    let client_bson = bson::to_bson(client)?;
+    let update_options = UpdateOptions::builder().upsert(true).build();
+
+    for (key, value) in keys_values {
+        let update = doc! {
+            "$set": {
+                "value": encrypt_data(value)?, // encrypted
+                "expires_at": expires_at,
+            },
+            "$setOnInsert": {
+                "client": client_bson.clone(),
+                "type": type_,
+                "key": key,
+            },
+            "$currentDate": { "created_at": true }
+        };
+
+        collection
+            .update_one(
+                state_filter(client, type_, key),
+                update,
+                update_options.clone(),
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn delete_user_state(
+    client: &Client,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(client_filter(client), None)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncMongoDbClient,
+) -> Result<(), EngineError> {
+    collection(db)
+        .delete_many(doc! { "client.bot_id": bot_id }, None)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,192 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
+#[cfg(feature = "postgresql-async")]
+use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
+
+use crate::data::models::OutboxMessage;
+use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::{Client, EngineError};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use uuid::Uuid;
+
+pub async fn add_outbox_message(
+    message: &OutboxMessage,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_outbox_message", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&message.client),
+            None,
+            None,
+            format!("db call add outbox message {}", message.id),
+        ),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::outbox::add_outbox_message(message, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::outbox::add_outbox_message(message, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::outbox::add_outbox_message(message, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let _span = db_span("csml.db.get_outbox_messages", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, "db call get outbox messages".to_string()),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::outbox::get_outbox_messages(bot_id, limit, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::outbox::get_outbox_messages(bot_id, limit, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::outbox::get_outbox_messages(bot_id, limit, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn has_client_outbox(
+    client: &Client,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.has_client_outbox", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(client),
+            None,
+            None,
+            "db call has client outbox".to_string(),
+        ),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::outbox::has_client_outbox(client, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::outbox::has_client_outbox(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::outbox::has_client_outbox(client, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn add_outbox_attempt(id: Uuid, db: &mut AsyncDatabase<'_>) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.add_outbox_attempt", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call add outbox attempt {}", id),
+        ),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::outbox::add_outbox_attempt(id, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::outbox::add_outbox_attempt(id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::outbox::add_outbox_attempt(id, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn delete_outbox_message(
+    id: Uuid,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.delete_outbox_message", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            None,
+            None,
+            None,
+            format!("db call delete outbox message {}", id),
+        ),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::outbox::delete_outbox_message(id, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::outbox::delete_outbox_message(id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::outbox::delete_outbox_message(id, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
@@ -96,28 +96,33 @@ pub async fn update_conversation(
     step_id: Option<String>,
     db: &mut AsyncPostgresqlClient<'_>,
 ) -> Result<(), EngineError> {
-
     match (flow_id, step_id) {
         (Some(flow_id), Some(step_id)) => {
-            diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(conversation_id)))
-                .set((
-                    csml_conversations::flow_id.eq(flow_id.as_str()),
-                    csml_conversations::step_id.eq(step_id.as_str()),
-                ))
-                .execute(db.client.as_mut())
-                .await?;
+            diesel::update(
+                csml_conversations::table.filter(csml_conversations::id.eq(conversation_id)),
+            )
+            .set((
+                csml_conversations::flow_id.eq(flow_id.as_str()),
+                csml_conversations::step_id.eq(step_id.as_str()),
+            ))
+            .execute(db.client.as_mut())
+            .await?;
         }
         (Some(flow_id), _) => {
-            diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(conversation_id)))
-                .set(csml_conversations::flow_id.eq(flow_id.as_str()))
-                .get_result::<models::Conversation>(db.client.as_mut())
-                .await?;
+            diesel::update(
+                csml_conversations::table.filter(csml_conversations::id.eq(conversation_id)),
+            )
+            .set(csml_conversations::flow_id.eq(flow_id.as_str()))
+            .get_result::<models::Conversation>(db.client.as_mut())
+            .await?;
         }
         (_, Some(step_id)) => {
-            diesel::update(csml_conversations::table.filter(csml_conversations::id.eq(conversation_id)))
-                .set(csml_conversations::step_id.eq(step_id.as_str()))
-                .get_result::<models::Conversation>(db.client.as_mut())
-                .await?;
+            diesel::update(
+                csml_conversations::table.filter(csml_conversations::id.eq(conversation_id)),
+            )
+            .set(csml_conversations::step_id.eq(step_id.as_str()))
+            .get_result::<models::Conversation>(db.client.as_mut())
+            .await?;
         }
         _ => return Ok(()),
     };
@@ -0,0 +1,110 @@
+use diesel::upsert::excluded;
+use diesel::{BoolExpressionMethods, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+use crate::{AsyncPostgresqlClient, Client, EngineError};
+
+use crate::db_connectors::postgresql::{models, schema::csml_counters};
+use chrono::NaiveDateTime;
+
+pub async fn get_counter(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<i64, EngineError> {
+    let date_now = chrono::Utc::now().naive_utc();
+
+    let count: Option<i64> = csml_counters::table
+        .filter(csml_counters::bot_id.eq(&client.bot_id))
+        .filter(csml_counters::channel_id.eq(&client.channel_id))
+        .filter(csml_counters::user_id.eq(&client.user_id))
+        .filter(csml_counters::key.eq(key))
+        .filter(
+            csml_counters::expires_at
+                .is_null()
+                .or(csml_counters::expires_at.gt(date_now)),
+        )
+        .select(csml_counters::count)
+        .first(db.client.as_mut())
+        .await
+        .ok();
+
+    Ok(count.unwrap_or(0))
+}
+
+pub async fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<NaiveDateTime>,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<i64, EngineError> {
+    let date_now = chrono::Utc::now().naive_utc();
+
+    diesel::delete(
+        csml_counters::table
+            .filter(csml_counters::bot_id.eq(&client.bot_id))
+            .filter(csml_counters::channel_id.eq(&client.channel_id))
+            .filter(csml_counters::user_id.eq(&client.user_id))
+            .filter(csml_counters::key.eq(key))
+            .filter(csml_counters::expires_at.lt(date_now)),
+    )
+    .execute(db.client.as_mut())
+    .await?;
+
+    let new_counter = models::NewCounter {
+        bot_id: &client.bot_id,
+        channel_id: &client.channel_id,
+        user_id: &client.user_id,
+        key,
+        count,
+        expires_at,
+    };
+
+    // the row is locked by the upsert, which returns the count it wrote
+    let count = diesel::insert_into(csml_counters::table)
+        .values(&new_counter)
+        .on_conflict((
+            csml_counters::bot_id,
+            csml_counters::channel_id,
+            csml_counters::user_id,
+            csml_counters::key,
+        ))
+        .do_update()
+        .set((
+            csml_counters::count.eq(csml_counters::count + excluded(csml_counters::count)),
+            csml_counters::expires_at.eq(excluded(csml_counters::expires_at)),
+        ))
+        .returning(csml_counters::count)
+        .get_result(db.client.as_mut())
+        .await?;
+
+    Ok(count)
+}
+
+pub async fn delete_client_counters(
+    client: &Client,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<(), EngineError> {
+    diesel::delete(
+        csml_counters::table
+            .filter(csml_counters::bot_id.eq(&client.bot_id))
+            .filter(csml_counters::channel_id.eq(&client.channel_id))
+            .filter(csml_counters::user_id.eq(&client.user_id)),
+    )
+    .execute(db.client.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<(), EngineError> {
+    diesel::delete(csml_counters::table.filter(csml_counters::bot_id.eq(bot_id)))
+        .execute(db.client.as_mut())
+        .await?;
+
+    Ok(())
+}
@@ -3,7 +3,9 @@ use diesel_async::RunQueryDsl;
 
 use crate::{AsyncPostgresqlClient, EngineError};
 
-use crate::db_connectors::postgresql::schema::{csml_conversations, csml_memories, csml_states};
+use crate::db_connectors::postgresql::schema::{
+    csml_conversations, csml_counters, csml_memories, csml_states,
+};
 
 pub async fn delete_expired_data(db: &mut AsyncPostgresqlClient<'_>) -> Result<(), EngineError> {
     let date_now = chrono::Utc::now().naive_utc();
@@ -23,5 +25,10 @@ pub async fn delete_expired_data(db: &mut AsyncPostgresqlClient<'_>) -> Result<(
         .await
         .ok();
 
+    diesel::delete(csml_counters::table.filter(csml_counters::expires_at.lt(date_now)))
+        .execute(db.client.as_mut())
+        .await
+        .ok();
+
     Ok(())
 }
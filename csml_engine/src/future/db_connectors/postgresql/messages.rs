@@ -3,10 +3,8 @@ use diesel_async::RunQueryDsl;
 use std::convert::TryInto;
 
 use crate::{
-    data,
-    encrypt::{encrypt_data},
-    future::db_connectors::postgresql::get_db,
-    AsyncConversationInfo, AsyncPostgresqlClient, Client, EngineError,
+    data, encrypt::encrypt_data, future::db_connectors::postgresql::get_db, AsyncConversationInfo,
+    AsyncPostgresqlClient, Client, EngineError,
 };
 
 use super::pagination::*;
@@ -0,0 +1,96 @@
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+use crate::data::models::Schedule;
+use crate::{AsyncPostgresqlClient, Client, EngineError};
+
+use crate::db_connectors::postgresql::{models, schema::csml_schedules};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub async fn create_schedule(
+    schedule: &Schedule,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<(), EngineError> {
+    let new_schedule = models::NewSchedule::from(schedule);
+
+    diesel::insert_into(csml_schedules::table)
+        .values(&new_schedule)
+        .execute(db.client.as_mut())
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_due_schedules(
+    now: DateTime<Utc>,
+    limit: u32,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<Vec<Schedule>, EngineError> {
+    let schedules: Vec<models::Schedule> = csml_schedules::table
+        .filter(csml_schedules::run_at.le(now.naive_utc()))
+        .order_by(csml_schedules::run_at.asc())
+        .limit(limit as i64)
+        .load(db.client.as_mut())
+        .await?;
+
+    Ok(schedules.into_iter().map(Schedule::from).collect())
+}
+
+pub async fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<bool, EngineError> {
+    let count = diesel::update(
+        csml_schedules::table
+            .filter(csml_schedules::id.eq(schedule.id))
+            .filter(csml_schedules::attempts.eq(schedule.attempts)),
+    )
+    .set((
+        csml_schedules::attempts.eq(schedule.attempts + 1),
+        csml_schedules::run_at.eq(retry_at.naive_utc()),
+    ))
+    .execute(db.client.as_mut())
+    .await?;
+
+    Ok(count > 0)
+}
+
+pub async fn delete_schedule(
+    id: Uuid,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<bool, EngineError> {
+    let count = diesel::delete(csml_schedules::table.filter(csml_schedules::id.eq(id)))
+        .execute(db.client.as_mut())
+        .await?;
+
+    Ok(count > 0)
+}
+
+pub async fn delete_client_schedules(
+    client: &Client,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<(), EngineError> {
+    diesel::delete(
+        csml_schedules::table
+            .filter(csml_schedules::bot_id.eq(&client.bot_id))
+            .filter(csml_schedules::channel_id.eq(&client.channel_id))
+            .filter(csml_schedules::user_id.eq(&client.user_id)),
+    )
+    .execute(db.client.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncPostgresqlClient<'_>,
+) -> Result<(), EngineError> {
+    diesel::delete(csml_schedules::table.filter(csml_schedules::bot_id.eq(bot_id)))
+        .execute(db.client.as_mut())
+        .await?;
+
+    Ok(())
+}
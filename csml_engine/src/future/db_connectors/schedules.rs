@@ -0,0 +1,153 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
+#[cfg(feature = "postgresql-async")]
+use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
+
+use crate::data::models::Schedule;
+use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::EngineError;
+use chrono::{DateTime, Utc};
+use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
+use uuid::Uuid;
+
+pub async fn create_schedule(
+    schedule: &Schedule,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.create_schedule", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&schedule.client),
+            Some(schedule.flow_id.to_owned()),
+            None,
+            format!("db call create schedule at {}", schedule.run_at),
+        ),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::schedules::create_schedule(schedule, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::schedules::create_schedule(schedule, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::schedules::create_schedule(schedule, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn get_due_schedules(
+    limit: u32,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<Vec<Schedule>, EngineError> {
+    let _span = db_span("csml.db.get_due_schedules", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, "db call get due schedules".to_string()),
+        LogLvl::Info,
+    );
+
+    let now = Utc::now();
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::schedules::get_due_schedules(now, limit, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::schedules::get_due_schedules(now, limit, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::schedules::get_due_schedules(now, limit, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.claim_schedule", db.backend());
+
+    csml_logger(
+        CsmlLog::new(
+            Some(&schedule.client),
+            Some(schedule.flow_id.to_owned()),
+            None,
+            format!("db call claim schedule {}", schedule.id),
+        ),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::schedules::claim_schedule(schedule, retry_at, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::schedules::claim_schedule(schedule, retry_at, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::schedules::claim_schedule(schedule, retry_at, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub async fn delete_schedule(id: Uuid, db: &mut AsyncDatabase<'_>) -> Result<bool, EngineError> {
+    let _span = db_span("csml.db.delete_schedule", db.backend());
+
+    csml_logger(
+        CsmlLog::new(None, None, None, format!("db call delete schedule {}", id)),
+        LogLvl::Info,
+    );
+
+    #[cfg(feature = "postgresql-async")]
+    if is_postgresql() {
+        let db = postgresql_connector::get_db(db)?;
+        return postgresql_connector::schedules::delete_schedule(id, db).await;
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::schedules::delete_schedule(id, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::schedules::delete_schedule(id, db).await;
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
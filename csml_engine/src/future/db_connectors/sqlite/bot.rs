@@ -0,0 +1,63 @@
+use crate::db_connectors::sqlite::bot;
+use crate::models::BotVersion;
+use crate::{AsyncSqliteClient, EngineError};
+
+use super::run;
+
+pub async fn create_bot_version(
+    bot_id: String,
+    bot: String,
+    db: &mut AsyncSqliteClient,
+) -> Result<String, EngineError> {
+    run(db, move |db| bot::create_bot_version(bot_id, bot, db)).await
+}
+
+pub async fn get_bot_versions(
+    bot_id: &str,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+    db: &mut AsyncSqliteClient,
+) -> Result<serde_json::Value, EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| {
+        bot::get_bot_versions(&bot_id, limit, pagination_key, db)
+    })
+    .await
+}
+
+pub async fn get_bot_by_version_id(
+    id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<Option<BotVersion>, EngineError> {
+    let id = id.to_owned();
+
+    run(db, move |db| bot::get_bot_by_version_id(&id, db)).await
+}
+
+pub async fn get_last_bot_version(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<Option<BotVersion>, EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| bot::get_last_bot_version(&bot_id, db)).await
+}
+
+pub async fn delete_bot_version(
+    version_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let version_id = version_id.to_owned();
+
+    run(db, move |db| bot::delete_bot_version(&version_id, db)).await
+}
+
+pub async fn delete_bot_versions(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| bot::delete_bot_versions(&bot_id, db)).await
+}
@@ -0,0 +1,114 @@
+use crate::data::models::{Conversation, Paginated};
+use crate::db_connectors::sqlite::conversations;
+use crate::{AsyncSqliteClient, Client, EngineError};
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use super::run;
+
+pub async fn create_conversation(
+    flow_id: &str,
+    step_id: &str,
+    client: &Client,
+    expires_at: Option<NaiveDateTime>,
+    db: &mut AsyncSqliteClient,
+) -> Result<Uuid, EngineError> {
+    let (flow_id, step_id, client) = (flow_id.to_owned(), step_id.to_owned(), client.to_owned());
+
+    run(db, move |db| {
+        conversations::create_conversation(&flow_id, &step_id, &client, expires_at, db)
+    })
+    .await
+}
+
+pub async fn close_conversation(
+    id: Uuid,
+    client: &Client,
+    status: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let (client, status) = (client.to_owned(), status.to_owned());
+
+    run(db, move |db| {
+        conversations::close_conversation(id, &client, &status, db)
+    })
+    .await
+}
+
+pub async fn close_all_conversations(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| {
+        conversations::close_all_conversations(&client, db)
+    })
+    .await
+}
+
+pub async fn get_latest_open(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<Option<Conversation>, EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| conversations::get_latest_open(&client, db)).await
+}
+
+pub async fn update_conversation(
+    conversation_id: Uuid,
+    flow_id: Option<String>,
+    step_id: Option<String>,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    run(db, move |db| {
+        conversations::update_conversation(conversation_id, flow_id, step_id, db)
+    })
+    .await
+}
+
+pub async fn delete_user_conversations(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| {
+        conversations::delete_user_conversations(&client, db)
+    })
+    .await
+}
+
+pub async fn get_conversation(
+    db: &mut AsyncSqliteClient,
+    id: Uuid,
+) -> Result<Conversation, EngineError> {
+    run(db, move |db| conversations::get_conversation(db, id)).await
+}
+
+pub async fn get_client_conversations(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+    limit: Option<u32>,
+    pagination_key: Option<u32>,
+) -> Result<Paginated<Conversation>, EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| {
+        conversations::get_client_conversations(&client, db, limit, pagination_key)
+    })
+    .await
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| {
+        conversations::delete_all_bot_data(&bot_id, db)
+    })
+    .await
+}
@@ -0,0 +1,48 @@
+use crate::db_connectors::sqlite::counters;
+use crate::{AsyncSqliteClient, Client, EngineError};
+use chrono::NaiveDateTime;
+
+use super::run;
+
+pub async fn get_counter(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<i64, EngineError> {
+    let (client, key) = (client.to_owned(), key.to_owned());
+
+    run(db, move |db| counters::get_counter(&client, &key, db)).await
+}
+
+pub async fn increment_counter(
+    client: &Client,
+    key: &str,
+    count: i64,
+    expires_at: Option<NaiveDateTime>,
+    db: &mut AsyncSqliteClient,
+) -> Result<i64, EngineError> {
+    let (client, key) = (client.to_owned(), key.to_owned());
+
+    run(db, move |db| {
+        counters::increment_counter(&client, &key, count, expires_at, db)
+    })
+    .await
+}
+
+pub async fn delete_client_counters(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| counters::delete_client_counters(&client, db)).await
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| counters::delete_all_bot_data(&bot_id, db)).await
+}
@@ -0,0 +1,8 @@
+use crate::db_connectors::sqlite::expired_data;
+use crate::{AsyncSqliteClient, EngineError};
+
+use super::run;
+
+pub async fn delete_expired_data(db: &mut AsyncSqliteClient) -> Result<(), EngineError> {
+    run(db, expired_data::delete_expired_data).await
+}
@@ -0,0 +1,102 @@
+use crate::db_connectors::sqlite::memories;
+use crate::future::db_connectors::sqlite::get_db;
+use crate::{AsyncConversationInfo, AsyncSqliteClient, Client, EngineError, Memory};
+use chrono::NaiveDateTime;
+use std::collections::HashMap;
+
+use super::run;
+
+pub async fn add_memories(
+    data: &mut AsyncConversationInfo<'_>,
+    memories: &HashMap<String, Memory>,
+    expires_at: Option<NaiveDateTime>,
+) -> Result<(), EngineError> {
+    if memories.is_empty() {
+        return Ok(());
+    }
+
+    let (client, memories) = (data.client.to_owned(), memories.to_owned());
+    let db = get_db(&mut data.db)?;
+
+    run(db, move |db| {
+        memories::add_memories(&client, &memories, expires_at, db)
+    })
+    .await
+}
+
+pub async fn create_client_memory(
+    client: &Client,
+    key: &str,
+    value: &serde_json::Value,
+    expires_at: Option<NaiveDateTime>,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let (client, key, value) = (client.to_owned(), key.to_owned(), value.to_owned());
+
+    run(db, move |db| {
+        memories::create_client_memory(&client, &key, &value, expires_at, db)
+    })
+    .await
+}
+
+pub async fn internal_use_get_memories(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<serde_json::Value, EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| {
+        memories::internal_use_get_memories(&client, db)
+    })
+    .await
+}
+
+pub async fn get_memories(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<serde_json::Value, EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| memories::get_memories(&client, db)).await
+}
+
+pub async fn get_memory(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<serde_json::Value, EngineError> {
+    let (client, key) = (client.to_owned(), key.to_owned());
+
+    run(db, move |db| memories::get_memory(&client, &key, db)).await
+}
+
+pub async fn delete_client_memory(
+    client: &Client,
+    key: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let (client, key) = (client.to_owned(), key.to_owned());
+
+    run(db, move |db| {
+        memories::delete_client_memory(&client, &key, db)
+    })
+    .await
+}
+
+pub async fn delete_client_memories(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| memories::delete_client_memories(&client, db)).await
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| memories::delete_all_bot_data(&bot_id, db)).await
+}
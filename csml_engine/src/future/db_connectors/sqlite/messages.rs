@@ -0,0 +1,85 @@
+use crate::data::filter::ClientMessageFilter;
+use crate::data::models::{Direction, Message, Paginated};
+use crate::data::storage::ConversationStep;
+use crate::db_connectors::sqlite::messages;
+use crate::future::db_connectors::sqlite::get_db;
+use crate::{AsyncConversationInfo, AsyncSqliteClient, Client, EngineError};
+use chrono::NaiveDateTime;
+
+use super::run;
+
+pub async fn add_messages_bulk(
+    data: &mut AsyncConversationInfo<'_>,
+    msgs: &[serde_json::Value],
+    interaction_order: i32,
+    direction: Direction,
+    expires_at: Option<NaiveDateTime>,
+) -> Result<(), EngineError> {
+    if msgs.is_empty() {
+        return Ok(());
+    }
+
+    let client = data.client.to_owned();
+    let conversation_id = data.conversation_id;
+    let flow_id = data.context.flow.to_owned();
+    let step_id = data.context.step.get_step();
+    let msgs = msgs.to_owned();
+    let db = get_db(&mut data.db)?;
+
+    run(db, move |db| {
+        let step = ConversationStep {
+            client: &client,
+            conversation_id,
+            flow_id: &flow_id,
+            step_id: &step_id,
+        };
+
+        messages::add_messages_bulk(
+            &step,
+            &msgs,
+            interaction_order,
+            direction.into(),
+            expires_at,
+            db,
+        )
+    })
+    .await
+}
+
+pub async fn delete_user_messages(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| messages::delete_user_messages(&client, db)).await
+}
+
+pub async fn get_client_messages(
+    db: &mut AsyncSqliteClient,
+    filter: ClientMessageFilter<'_>,
+) -> Result<Paginated<Message>, EngineError> {
+    let ClientMessageFilter {
+        client,
+        limit,
+        pagination_key,
+        from_date,
+        to_date,
+        conversation_id,
+    } = filter;
+    let client = client.to_owned();
+
+    run(db, move |db| {
+        let filter = ClientMessageFilter {
+            client: &client,
+            limit,
+            pagination_key,
+            from_date,
+            to_date,
+            conversation_id,
+        };
+
+        messages::get_client_messages(db, filter)
+    })
+    .await
+}
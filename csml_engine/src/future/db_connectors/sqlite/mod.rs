@@ -0,0 +1,69 @@
+/**
+ * Async wrapper of the sqlite connector: every call runs the matching query of
+ * `db_connectors::sqlite` on the blocking thread pool of the tokio runtime.
+ */
+pub mod bot;
+pub mod conversations;
+pub mod counters;
+pub mod memories;
+pub mod messages;
+pub mod outbox;
+pub mod schedules;
+pub mod state;
+
+pub mod expired_data;
+
+use crate::data::Connections;
+use crate::{AsyncDatabase, AsyncSqliteClient, EngineError, SqliteClient};
+
+use diesel::prelude::*;
+
+pub async fn init() -> Result<AsyncDatabase<'static>, EngineError> {
+    let uri = match std::env::var("SQLITE_URL") {
+        Ok(var) => var,
+        _ => "".to_owned(),
+    };
+
+    let sqlite_connection =
+        SqliteConnection::establish(&uri).unwrap_or_else(|_| panic!("Error connecting to {}", uri));
+
+    let db = AsyncDatabase::SqLite(AsyncSqliteClient::new(sqlite_connection));
+    Ok(db)
+}
+
+pub fn get_db<'a>(db: &'a mut AsyncDatabase<'_>) -> Result<&'a mut AsyncSqliteClient, EngineError> {
+    match db {
+        AsyncDatabase::SqLite(db) => Ok(db),
+        _ => Err(EngineError::Manager(
+            "Sqlite connector is not setup correctly".to_owned(),
+        )),
+    }
+}
+
+/**
+ * Run `query` with the connection of `db` without blocking the async runtime
+ */
+pub async fn run<R, F>(db: &mut AsyncSqliteClient, query: F) -> Result<R, EngineError>
+where
+    F: FnOnce(&mut SqliteClient) -> Result<R, EngineError> + Send + 'static,
+    R: Send + 'static,
+{
+    let connection = db.client.clone();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let mut connection = connection
+            .lock()
+            .map_err(|_| EngineError::Manager("Sqlite connection is poisoned".to_owned()))?;
+        let mut db = SqliteClient {
+            client: Connections::Reference(&mut connection),
+        };
+
+        query(&mut db)
+    })
+    .await;
+
+    match result {
+        Ok(result) => result,
+        Err(e) => Err(EngineError::Manager(format!("Sqlite query failed: {}", e))),
+    }
+}
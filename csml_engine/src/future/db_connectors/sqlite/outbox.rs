@@ -0,0 +1,66 @@
+use crate::data::models::OutboxMessage;
+use crate::db_connectors::sqlite::outbox;
+use crate::{AsyncSqliteClient, Client, EngineError};
+use uuid::Uuid;
+
+use super::run;
+
+pub async fn add_outbox_message(
+    message: &OutboxMessage,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let message = message.to_owned();
+
+    run(db, move |db| outbox::add_outbox_message(&message, db)).await
+}
+
+pub async fn get_outbox_messages(
+    bot_id: Option<&str>,
+    limit: u32,
+    db: &mut AsyncSqliteClient,
+) -> Result<Vec<OutboxMessage>, EngineError> {
+    let bot_id = bot_id.map(str::to_owned);
+
+    run(db, move |db| {
+        outbox::get_outbox_messages(bot_id.as_deref(), limit, db)
+    })
+    .await
+}
+
+pub async fn add_outbox_attempt(id: Uuid, db: &mut AsyncSqliteClient) -> Result<(), EngineError> {
+    run(db, move |db| outbox::add_outbox_attempt(id, db)).await
+}
+
+pub async fn delete_outbox_message(
+    id: Uuid,
+    db: &mut AsyncSqliteClient,
+) -> Result<bool, EngineError> {
+    run(db, move |db| outbox::delete_outbox_message(id, db)).await
+}
+
+pub async fn has_client_outbox(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<bool, EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| outbox::has_client_outbox(&client, db)).await
+}
+
+pub async fn delete_client_outbox(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| outbox::delete_client_outbox(&client, db)).await
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| outbox::delete_all_bot_data(&bot_id, db)).await
+}
@@ -0,0 +1,62 @@
+use crate::data::models::Schedule;
+use crate::db_connectors::sqlite::schedules;
+use crate::{AsyncSqliteClient, Client, EngineError};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::run;
+
+pub async fn create_schedule(
+    schedule: &Schedule,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let schedule = schedule.to_owned();
+
+    run(db, move |db| schedules::create_schedule(&schedule, db)).await
+}
+
+pub async fn get_due_schedules(
+    now: DateTime<Utc>,
+    limit: u32,
+    db: &mut AsyncSqliteClient,
+) -> Result<Vec<Schedule>, EngineError> {
+    run(db, move |db| schedules::get_due_schedules(now, limit, db)).await
+}
+
+pub async fn claim_schedule(
+    schedule: &Schedule,
+    retry_at: DateTime<Utc>,
+    db: &mut AsyncSqliteClient,
+) -> Result<bool, EngineError> {
+    let schedule = schedule.to_owned();
+
+    run(db, move |db| {
+        schedules::claim_schedule(&schedule, retry_at, db)
+    })
+    .await
+}
+
+pub async fn delete_schedule(id: Uuid, db: &mut AsyncSqliteClient) -> Result<bool, EngineError> {
+    run(db, move |db| schedules::delete_schedule(id, db)).await
+}
+
+pub async fn delete_client_schedules(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| {
+        schedules::delete_client_schedules(&client, db)
+    })
+    .await
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| schedules::delete_all_bot_data(&bot_id, db)).await
+}
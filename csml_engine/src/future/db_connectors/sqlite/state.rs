@@ -0,0 +1,84 @@
+use crate::db_connectors::sqlite::state;
+use crate::{AsyncSqliteClient, Client, EngineError};
+use chrono::NaiveDateTime;
+
+use super::run;
+
+pub async fn delete_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let (client, type_, key) = (client.to_owned(), type_.to_owned(), key.to_owned());
+
+    run(db, move |db| {
+        state::delete_state_key(&client, &type_, &key, db)
+    })
+    .await
+}
+
+pub async fn get_state_key(
+    client: &Client,
+    type_: &str,
+    key: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let (client, type_, key) = (client.to_owned(), type_.to_owned(), key.to_owned());
+
+    run(db, move |db| {
+        state::get_state_key(&client, &type_, &key, db)
+    })
+    .await
+}
+
+pub async fn get_current_state(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| state::get_current_state(&client, db)).await
+}
+
+pub async fn set_state_items(
+    client: &Client,
+    type_: &str,
+    keys_values: Vec<(&str, &serde_json::Value)>,
+    expires_at: Option<NaiveDateTime>,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let (client, type_) = (client.to_owned(), type_.to_owned());
+    let keys_values: Vec<(String, serde_json::Value)> = keys_values
+        .into_iter()
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+
+    run(db, move |db| {
+        let keys_values = keys_values
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+            .collect();
+
+        state::set_state_items(&client, &type_, keys_values, expires_at, db)
+    })
+    .await
+}
+
+pub async fn delete_user_state(
+    client: &Client,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let client = client.to_owned();
+
+    run(db, move |db| state::delete_user_state(&client, db)).await
+}
+
+pub async fn delete_all_bot_data(
+    bot_id: &str,
+    db: &mut AsyncSqliteClient,
+) -> Result<(), EngineError> {
+    let bot_id = bot_id.to_owned();
+
+    run(db, move |db| state::delete_all_bot_data(&bot_id, db)).await
+}
@@ -1,7 +1,12 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 
 use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::future::db_connectors::utils::*;
 use crate::EngineError;
@@ -14,6 +19,8 @@ pub async fn delete_state_key(
     key: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_state_key", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -39,6 +46,18 @@ pub async fn delete_state_key(
         return postgresql_connector::state::delete_state_key(client, _type, key, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::state::delete_state_key(client, _type, key, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::state::delete_state_key(client, _type, key, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -48,6 +67,8 @@ pub async fn get_state_key(
     _key: &str,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<Option<serde_json::Value>, EngineError> {
+    let _span = db_span("csml.db.get_state_key", db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -73,6 +94,18 @@ pub async fn get_state_key(
         return postgresql_connector::state::get_state_key(client, _type, _key, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::state::get_state_key(client, _type, _key, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::state::get_state_key(client, _type, _key, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -80,6 +113,8 @@ pub async fn get_current_state(
     client: &Client,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<Option<serde_json::Value>, EngineError> {
+    let _span = db_span("csml.db.get_current_state", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call get current state".to_string()),
         LogLvl::Info,
@@ -100,6 +135,18 @@ pub async fn get_current_state(
         return postgresql_connector::state::get_current_state(client, db).await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+        return sqlite_connector::state::get_current_state(client, db).await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::state::get_current_state(client, db).await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
@@ -110,6 +157,8 @@ pub async fn set_state_items(
     ttl: Option<chrono::Duration>,
     _db: &mut AsyncDatabase<'_>,
 ) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.set_state_items", _db.backend());
+
     csml_logger(
         CsmlLog::new(
             None,
@@ -150,6 +199,36 @@ pub async fn set_state_items(
         .await;
     }
 
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(_db)?;
+        let expires_at = get_expires_at_for_sqlite(ttl);
+
+        return sqlite_connector::state::set_state_items(
+            _client,
+            _type,
+            _keys_values,
+            expires_at,
+            db,
+        )
+        .await;
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(_db)?;
+        let expires_at = get_expires_at_for_mongodb(ttl);
+
+        return mongodb_connector::state::set_state_items(
+            _client,
+            _type,
+            _keys_values,
+            expires_at,
+            db,
+        )
+        .await;
+    }
+
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
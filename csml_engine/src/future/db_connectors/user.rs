@@ -1,12 +1,19 @@
+#[cfg(feature = "mongo-async")]
+use crate::future::db_connectors::{is_mongodb, mongodb_connector};
 #[cfg(feature = "postgresql-async")]
 use crate::future::db_connectors::{is_postgresql, postgresql_connector};
+#[cfg(feature = "sqlite-async")]
+use crate::future::db_connectors::{is_sqlite, sqlite_connector};
 
 use crate::data::AsyncDatabase;
+use crate::db_connectors::db_span;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Client, EngineError};
 use csml_interpreter::data::csml_logs::{csml_logger, CsmlLog, LogLvl};
 
 pub async fn delete_client(client: &Client, db: &mut AsyncDatabase<'_>) -> Result<(), EngineError> {
+    let _span = db_span("csml.db.delete_client", db.backend());
+
     csml_logger(
         CsmlLog::new(None, None, None, "db call delete client".to_string()),
         LogLvl::Info,
@@ -29,6 +36,39 @@ pub async fn delete_client(client: &Client, db: &mut AsyncDatabase<'_>) -> Resul
         postgresql_connector::memories::delete_client_memories(client, db).await?;
         postgresql_connector::messages::delete_user_messages(client, db).await?;
         postgresql_connector::state::delete_user_state(client, db).await?;
+        postgresql_connector::counters::delete_client_counters(client, db).await?;
+        postgresql_connector::schedules::delete_client_schedules(client, db).await?;
+        postgresql_connector::outbox::delete_client_outbox(client, db).await?;
+
+        return Ok(());
+    }
+
+    #[cfg(feature = "sqlite-async")]
+    if is_sqlite() {
+        let db = sqlite_connector::get_db(db)?;
+
+        sqlite_connector::conversations::delete_user_conversations(client, db).await?;
+        sqlite_connector::memories::delete_client_memories(client, db).await?;
+        sqlite_connector::messages::delete_user_messages(client, db).await?;
+        sqlite_connector::state::delete_user_state(client, db).await?;
+        sqlite_connector::counters::delete_client_counters(client, db).await?;
+        sqlite_connector::schedules::delete_client_schedules(client, db).await?;
+        sqlite_connector::outbox::delete_client_outbox(client, db).await?;
+
+        return Ok(());
+    }
+
+    #[cfg(feature = "mongo-async")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+
+        mongodb_connector::conversations::delete_user_conversations(client, db).await?;
+        mongodb_connector::memories::delete_client_memories(client, db).await?;
+        mongodb_connector::messages::delete_user_messages(client, db).await?;
+        mongodb_connector::state::delete_user_state(client, db).await?;
+        mongodb_connector::counters::delete_client_counters(client, db).await?;
+        mongodb_connector::schedules::delete_client_schedules(client, db).await?;
+        mongodb_connector::outbox::delete_client_outbox(client, db).await?;
 
         return Ok(());
     }
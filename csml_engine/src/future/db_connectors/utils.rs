@@ -11,3 +11,27 @@ pub fn get_expires_at_for_postgresql(
         None => None,
     }
 }
+
+#[cfg(feature = "sqlite-async")]
+pub fn get_expires_at_for_sqlite(ttl: Option<chrono::Duration>) -> Option<chrono::NaiveDateTime> {
+    match ttl {
+        Some(ttl) => {
+            let expires_at = chrono::Utc::now().naive_utc() + ttl;
+
+            Some(expires_at)
+        }
+        None => None,
+    }
+}
+
+#[cfg(feature = "mongo-async")]
+pub fn get_expires_at_for_mongodb(ttl: Option<chrono::Duration>) -> Option<bson::DateTime> {
+    match ttl {
+        Some(ttl) => {
+            let expires_at = chrono::Utc::now() + ttl;
+
+            Some(bson::DateTime::from_chrono(expires_at))
+        }
+        None => None,
+    }
+}
@@ -1,20 +1,27 @@
-use crate::future::db_connectors::{conversations::*, memories::*, messages::*, state::*};
+use crate::future::db_connectors::{
+    conversations::*, memories::*, messages::*, schedules::*, state::*,
+};
+use crate::future::quotas::add_usage;
 use crate::future::utils::*;
 
-use crate::data::models::Direction;
+use crate::data::models::{Direction, Schedule};
 use crate::data::{AsyncConversationInfo, EngineError};
 use crate::interpreter_actions::models::{InterpreterReturn, SwitchBot};
 use csml_interpreter::data::context::ContextStepInfo;
 use csml_interpreter::{
     data::{
-        ast::ForgetMemory, csml_bot::CsmlBot, csml_flow::CsmlFlow, csml_logs::*, Client, Event,
-        Hold, Memory, Message, MultiBot, MSG,
+        ast::ForgetMemory,
+        csml_bot::CsmlBot,
+        csml_flow::CsmlFlow,
+        csml_logs::*,
+        csml_metrics::{self, Metric},
+        csml_otel, csml_usage, Client, Event, Hold, Memory, Message, MultiBot, MSG,
     },
-    interpret,
+    interpret_with_callback,
 };
 use serde_json::{map::Map, Value};
 use std::collections::HashMap;
-use std::{sync::mpsc, thread};
+use tokio::{sync::mpsc, task};
 
 /**
  * This is the CSML Engine action.
@@ -25,11 +32,25 @@ pub async fn interpret_step(
     data: &mut AsyncConversationInfo<'_>,
     event: Event,
     bot: &CsmlBot,
+) -> Result<(Map<String, Value>, Option<SwitchBot>), EngineError> {
+    let (flow, step) = (data.context.flow.clone(), data.context.step.get_step());
+
+    csml_otel::in_span(
+        "csml.interpret_step",
+        &[("csml.flow", &flow), ("csml.step", &step)],
+        run_step(data, event, bot),
+    )
+    .await
+}
+
+async fn run_step(
+    data: &mut AsyncConversationInfo<'_>,
+    event: Event,
+    bot: &CsmlBot,
 ) -> Result<(Map<String, Value>, Option<SwitchBot>), EngineError> {
     let mut current_flow: &CsmlFlow = get_flow_by_id(&data.context.flow, &bot.flows)?;
     let mut interaction_order = 0;
     let mut conversation_end = false;
-    let (sender, receiver) = mpsc::channel::<MSG>();
     let context = data.context.clone();
     let mut switch_bot = None;
 
@@ -54,14 +75,24 @@ pub async fn interpret_step(
         ),
         LogLvl::Debug,
     );
+
+    // the interpreter and its blocking HTTP() calls run on a blocking thread,
+    // each message is handled here as soon as it is emitted
+    let (sender, mut receiver) = mpsc::unbounded_channel();
     let new_bot = bot.clone();
-    thread::spawn(move || {
-        interpret(new_bot, context, event, Some(sender));
-    });
+    let interpretation = task::spawn_blocking(csml_otel::with_current_context(move || {
+        interpret_with_callback(new_bot, context, event, &mut |msg| {
+            // the receiver is dropped once the conversation ends or switches bot
+            let _ = sender.send(msg);
+        });
+
+        // read on the thread of the interpretation
+        csml_usage::get()
+    }));
 
     let mut memories = HashMap::new();
 
-    for received in receiver {
+    while let Some(received) = receiver.recv().await {
         match received {
             MSG::Remember(mem) => {
                 memories.insert(mem.key.clone(), mem);
@@ -102,6 +133,11 @@ pub async fn interpret_step(
                     LogLvl::Debug,
                 );
 
+                csml_metrics::record(Metric::Message {
+                    bot_id: &bot.id,
+                    flow: &data.context.flow,
+                });
+
                 send_msg_to_callback_url(data, vec![msg.clone()], interaction_order, false).await;
                 data.messages.push(msg);
             }
@@ -151,6 +187,11 @@ pub async fn interpret_step(
                     ),
                     LogLvl::Debug,
                 );
+                csml_metrics::record(Metric::Hold {
+                    bot_id: &bot.id,
+                    flow: &flow_name,
+                    step: &step_name,
+                });
 
                 set_state_items(
                     &data.client,
@@ -160,6 +201,14 @@ pub async fn interpret_step(
                     &mut data.db,
                 )
                 .await?;
+                // the interrupted hold is back at its step
+                if matches!(
+                    &data.context.interrupted,
+                    Some(interrupted) if interrupted.flow_name == flow_name && interrupted.step_name == step_name
+                ) {
+                    data.context.interrupted = None;
+                    delete_state_key(&data.client, "hold", "interrupted", &mut data.db).await?;
+                }
                 data.context.hold = Some(Hold {
                     index,
                     step_vars,
@@ -204,6 +253,23 @@ pub async fn interpret_step(
                 }
             }
 
+            MSG::Trace(event) => {
+                if let Some(trace) = &mut data.trace {
+                    trace.push(event);
+                }
+            }
+
+            MSG::Schedule(schedule) => {
+                let schedule = Schedule::new(
+                    &data.client,
+                    &schedule,
+                    data.callback_url.clone(),
+                    data.bot_reference.clone(),
+                )?;
+
+                create_schedule(&schedule, &mut data.db).await?;
+            }
+
             MSG::Error(err_msg) => {
                 conversation_end = true;
                 csml_logger(
@@ -224,6 +290,12 @@ pub async fn interpret_step(
         }
     }
 
+    drop(receiver);
+    let usage = interpretation
+        .await
+        .map_err(|err| EngineError::Interpreter(format!("interpretation failed: {}", err)))?;
+    add_usage(data, usage).await?;
+
     // save in db
     let msgs: Vec<serde_json::Value> = data
         .messages
@@ -237,15 +309,17 @@ pub async fn interpret_step(
 
     add_memories(data, &memories).await?;
 
-    Ok((
-        messages_formatter(
-            data,
-            data.messages.clone(),
-            interaction_order,
-            conversation_end,
-        ),
-        switch_bot,
-    ))
+    let mut response = messages_formatter(
+        data,
+        data.messages.clone(),
+        interaction_order,
+        conversation_end,
+    );
+    if let Some(trace) = &data.trace {
+        response.insert("trace".to_owned(), serde_json::json!(trace));
+    }
+
+    Ok((response, switch_bot))
 }
 
 async fn manage_switch_bot<'a>(
@@ -555,6 +629,9 @@ async fn goto_step<'a>(
         // send end of conversation
         send_msg_to_callback_url(data, vec![], *interaction_order, *conversation_end).await;
         close_conversation(data.conversation_id, &data.client, &mut data.db).await?;
+        if data.context.interrupted.take().is_some() {
+            delete_state_key(&data.client, "hold", "interrupted", &mut data.db).await?;
+        }
 
         // break interpret_step loop
         return Ok(*conversation_end);
@@ -4,13 +4,17 @@ pub mod utils;
 // mod encrypt;
 // mod error_messages;
 pub mod interpreter_actions;
+pub mod quotas;
 pub mod send;
 // mod models;
 
+pub use crate::encrypt::encode_bot_artifact;
 pub use csml_interpreter::{
     data::{
         ast::{Expr, Flow, InstructionScope},
+        bot_artifact::BotArtifact,
         csml_logs::*,
+        csml_metrics,
         error_info::ErrorInfo,
         position::Position,
         warnings::Warnings,
@@ -22,32 +26,65 @@ pub use csml_interpreter::{
 use crate::data::*;
 use crate::interpreter_actions::models::SwitchBot;
 use db_connectors::{
-    bot, clean_db, conversations, init_db, memories, messages, state,
+    bot, clean_db, conversations, init_db, memories, messages, outbox, schedules, state,
     state::{delete_state_key, set_state_items},
     user,
 };
 use init::*;
 use interpreter_actions::interpret_step;
+use quotas::{check_quotas, set_http_limit};
 use utils::*;
 
 use crate::data;
 use crate::data::filter::ClientMessageFilter;
-use crate::data::models::{BotOpt, Conversation, CsmlRequest, Direction, Message, Paginated};
+use crate::data::models::{
+    BotOpt, CallbackRedelivery, Conversation, CsmlRequest, Direction, Message, Paginated,
+    ScheduleRun, SCHEDULE_MAX_ATTEMPTS,
+};
+use crate::error_messages::ERROR_CALLBACK_ORDER;
 use crate::models::{BotVersion, BotVersionCreated};
 use chrono::prelude::*;
-use csml_interpreter::data::{csml_bot::CsmlBot, Hold, IndexInfo};
+use csml_interpreter::data::{
+    context::ContextStepInfo, csml_bot::CsmlBot, csml_otel, Hold, IndexInfo,
+};
 use futures::future::{BoxFuture, FutureExt};
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, sync::mpsc};
 use uuid::Uuid;
 
 pub async fn start_conversation_db(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    db: AsyncDatabase<'_>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    traced_conversation(request, bot_opt, db, None).await
+}
+
+async fn traced_conversation(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    db: AsyncDatabase<'_>,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    let (request_id, bot_id) = (request.request_id.clone(), request.client.bot_id.clone());
+
+    csml_otel::in_span(
+        "csml.start_conversation",
+        &[("csml.request_id", &request_id), ("csml.bot_id", &bot_id)],
+        run_conversation(request, bot_opt, db, stream),
+    )
+    .await
+}
+
+async fn run_conversation(
     request: CsmlRequest,
     mut bot_opt: BotOpt,
     mut db: AsyncDatabase<'_>,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
 ) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
     init_logger();
 
     let mut formatted_event = format_event(&request)?;
+    check_quotas(&request.client, &mut formatted_event, &mut db).await?;
 
     let mut bot = bot_opt.search_bot_async(&mut db).await?;
     init_bot(&mut bot)?;
@@ -60,8 +97,11 @@ pub async fn start_conversation_db(
         db,
     )
     .await?;
+    data.stream = stream;
+    data.bot_reference = bot_opt.to_reference();
 
     check_for_hold(&mut data, &bot, &mut formatted_event).await?;
+    load_interrupted_hold(&mut data, &bot).await?;
 
     /////////// block user event if delay variable si on and delay_time is bigger than current time
     if let Some(delay) = bot.no_interruption_delay {
@@ -107,6 +147,7 @@ pub async fn start_conversation_db(
         (true, _) => {}
     }
 
+    set_http_limit(&mut data, &mut formatted_event).await?;
     let result = interpret_step(&mut data, formatted_event.to_owned(), &bot).await;
 
     check_switch_bot(
@@ -140,6 +181,20 @@ pub async fn start_conversation(
     start_conversation_db(request, bot_opt, db).await
 }
 
+/**
+ * Same as `start_conversation`, but each message is also sent to `stream` as soon as the bot
+ * says it, formatted like the messages of the response, so that it can be displayed before
+ * the end of the request. The stream is closed once the request is over.
+ */
+pub async fn start_conversation_stream(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    stream: mpsc::Sender<serde_json::Value>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    let db = init_db().await?;
+    traced_conversation(request, bot_opt, db, Some(stream)).await
+}
+
 fn check_switch_bot<'a>(
     result: Result<
         (
@@ -300,12 +355,12 @@ pub async fn create_bot_version(mut csml_bot: CsmlBot) -> Result<BotVersionCreat
         return Err(EngineError::Interpreter(format!("{:?}", err)));
     }
 
-    match validate_bot(csml_bot.clone()) {
-        CsmlResult {
-            errors: Some(errors),
-            ..
-        } => Err(EngineError::Interpreter(format!("{:?}", errors))),
-        CsmlResult { .. } => {
+    match compile_bot(csml_bot.clone()) {
+        Err(errors) => Err(EngineError::Interpreter(format!("{:?}", errors))),
+        Ok(artifact) => {
+            // the compiled bot is stored with the version and used as is at runtime
+            csml_bot.bot_ast = Some(encode_bot_artifact(&artifact));
+
             let version_id = bot::create_bot_version(bot_id, csml_bot, &mut db).await?;
             let engine_version = env!("CARGO_PKG_VERSION").to_owned();
 
@@ -463,6 +518,26 @@ pub fn validate_bot(mut bot: CsmlBot) -> CsmlResult {
     csml_interpreter::validate_bot(&bot)
 }
 
+/**
+ * Validate the bot and compile it into a versioned artifact (see `BotArtifact`).
+ * The encoded artifact can be set as the `bot_ast` of the bot, so that its flows
+ * are not parsed again on each request.
+ */
+pub fn compile_bot(mut bot: CsmlBot) -> Result<BotArtifact, Vec<ErrorInfo>> {
+    // load native components into the bot
+    bot.native_components = match load_components() {
+        Ok(components) => Some(components),
+        Err(err) => return Err(vec![err]),
+    };
+
+    // search for modules to download
+    if let Err(err) = search_for_modules(&mut bot) {
+        return Err(vec![ErrorInfo::new(Position::default(), err)]);
+    }
+
+    BotArtifact::compile(&bot)
+}
+
 /**
  * fold CSML bot in one single flow.
  * Rename all existing steps, goto and functions in order to match their origin flow.
@@ -490,9 +565,51 @@ pub async fn user_close_all_conversations(client: Client) -> Result<(), EngineEr
     init_logger();
 
     state::delete_state_key(&client, "hold", "position", &mut db).await?;
+    state::delete_state_key(&client, "hold", "interrupted", &mut db).await?;
     conversations::close_all_conversations(&client, &mut db).await
 }
 
+/**
+ * Load the hold interrupted by a flow that resumes it (see utils::search_flow), so that
+ * going back to its step holds again. It is dropped if its step was updated since.
+ */
+async fn load_interrupted_hold(
+    data: &mut AsyncConversationInfo<'_>,
+    bot: &CsmlBot,
+) -> Result<(), EngineError> {
+    if bot.interruptions.is_none() {
+        return Ok(());
+    }
+
+    let hold = match state::get_state_key(&data.client, "hold", "interrupted", &mut data.db).await?
+    {
+        Some(hold) => hold,
+        None => return Ok(()),
+    };
+
+    let mut held_context = data.context.clone();
+    held_context.flow = hold["flow"].as_str().unwrap_or_default().to_owned();
+    held_context.step =
+        ContextStepInfo::UnknownFlow(hold["step"].as_str().unwrap_or_default().to_owned());
+
+    let hash = get_current_step_hash(&held_context, bot).ok();
+    match serde_json::from_value::<IndexInfo>(hold["index"].clone()) {
+        Ok(index) if hash.is_some() && hold["hash"].as_str() == hash.as_deref() => {
+            data.context.interrupted = Some(Hold {
+                index,
+                step_vars: hold["step_vars"].clone(),
+                step_name: held_context.step.get_step(),
+                flow_name: held_context.flow,
+                previous: serde_json::from_value(hold["previous"].clone()).unwrap_or(None),
+                secure: hold["secure"].as_bool().unwrap_or(false),
+            });
+        }
+        _ => state::delete_state_key(&data.client, "hold", "interrupted", &mut data.db).await?,
+    }
+
+    Ok(())
+}
+
 /**
  * Verify if the user is currently on hold in a given conversation.
  *
@@ -502,6 +619,10 @@ pub async fn user_close_all_conversations(client: Client) -> Result<(), EngineEr
  *
  * If the hold is valid, we also need to load the local step memory
  * (context.hold.step_vars) into the conversation context.
+ *
+ * A hold interrupted by a flow triggered in the user's input was already removed
+ * while searching for that flow (see utils::search_flow). When the bot has a list of
+ * interruptions, the other triggered flows do not remove it and the input answers the hold.
  */
 async fn check_for_hold(
     data: &mut AsyncConversationInfo<'_>,
@@ -546,6 +667,11 @@ async fn check_for_hold(
                 previous: serde_json::from_value(hold["previous"].clone()).unwrap_or(None),
                 secure: secure_hold,
             });
+            csml_metrics::record(csml_metrics::Metric::Resume {
+                bot_id: &bot.id,
+                flow: &data.context.flow,
+                step: &data.context.step.get_step(),
+            });
 
             delete_state_key(&data.client, "hold", "position", &mut data.db).await?;
         }
@@ -599,10 +725,13 @@ pub async fn get_status() -> Result<serde_json::Value, EngineError> {
         Err(_) => status.insert("server_auth_enabled".to_owned(), serde_json::json!(false)),
     };
 
-    match std::env::var("ENCRYPTION_SECRET") {
-        Ok(_) => status.insert("encryption_enabled".to_owned(), serde_json::json!(true)),
-        Err(_) => status.insert("encryption_enabled".to_owned(), serde_json::json!(false)),
-    };
+    status.insert(
+        "encryption_enabled".to_owned(),
+        serde_json::json!(crate::encrypt::is_encryption_enabled()),
+    );
+    if let Ok(Some(key_id)) = crate::encrypt::get_current_key_id() {
+        status.insert("encryption_key_id".to_owned(), serde_json::json!(key_id));
+    }
 
     match std::env::var("DEBUG") {
         Ok(_) => status.insert("debug_mode_enabled".to_owned(), serde_json::json!(true)),
@@ -633,3 +762,108 @@ pub async fn delete_expired_data() -> Result<(), EngineError> {
 
     clean_db::delete_expired_data(&mut db).await
 }
+
+/**
+ * Run the flow triggers planned with the `Schedule()` builtin whose date is passed,
+ * at most `limit` of them. Each schedule is claimed before it runs, by moving it to the
+ * time of its next try, so that it runs only once even if several engines run the due
+ * schedules at the same time. It is deleted once it succeeds, or after
+ * `SCHEDULE_MAX_ATTEMPTS` failed runs; otherwise it runs again at its next try.
+ * The bot of the request that planned the schedule is used, and the messages are sent
+ * to the callback_url of this request and returned with the run.
+ */
+pub async fn run_due_schedules(limit: u32) -> Result<Vec<ScheduleRun>, EngineError> {
+    let mut db = init_db().await?;
+    init_logger();
+
+    let mut runs = vec![];
+
+    for schedule in schedules::get_due_schedules(limit, &mut db).await? {
+        let retry_at = schedule.retry_at(Utc::now());
+        if !schedules::claim_schedule(&schedule, retry_at, &mut db).await? {
+            continue;
+        }
+
+        let (request, bot_opt) = schedule.to_request();
+        let (messages, error) = match start_conversation(request, bot_opt).await {
+            Ok(mut data) => match data.remove("messages") {
+                Some(serde_json::Value::Array(messages)) => (messages, None),
+                _ => (vec![], None),
+            },
+            Err(err) => (vec![], Some(format!("{:?}", err))),
+        };
+
+        let retry_at = match error {
+            Some(_) if schedule.attempts + 1 < SCHEDULE_MAX_ATTEMPTS => Some(retry_at),
+            _ => {
+                schedules::delete_schedule(schedule.id, &mut db).await?;
+                None
+            }
+        };
+
+        runs.push(ScheduleRun {
+            schedule,
+            error,
+            retry_at,
+            messages,
+        });
+    }
+
+    Ok(runs)
+}
+
+/**
+ * Send again the messages kept in the outbox because their callback_url could not be
+ * reached, oldest first and at most `limit` of them (only for the given bot if any).
+ * Each message is tried once with its original delivery id and signature: delivered
+ * messages leave the outbox, the others stay there for the next redelivery. Once a
+ * message of a user fails, their next messages are left in the outbox to keep their order.
+ * Messages refused by their endpoint (see `send::is_retryable`) are dropped from the outbox.
+ */
+pub async fn redeliver_callbacks(
+    bot_id: Option<&str>,
+    limit: u32,
+) -> Result<Vec<CallbackRedelivery>, EngineError> {
+    let mut db = init_db().await?;
+    init_logger();
+
+    let mut redeliveries = vec![];
+    let mut failed_clients: Vec<Client> = vec![];
+
+    for mut message in outbox::get_outbox_messages(bot_id, limit, &mut db).await? {
+        let result = if failed_clients.contains(&message.client) {
+            Err(crate::send::DeliveryError {
+                error: ERROR_CALLBACK_ORDER.to_owned(),
+                retryable: true,
+            })
+        } else {
+            send::deliver(&mut message, 0).await
+        };
+
+        match &result {
+            Ok(()) => {
+                outbox::delete_outbox_message(message.id, &mut db).await?;
+            }
+            Err(_) if failed_clients.contains(&message.client) => {}
+            Err(err) if !err.retryable => {
+                outbox::delete_outbox_message(message.id, &mut db).await?;
+            }
+            Err(_) => {
+                outbox::add_outbox_attempt(message.id, &mut db).await?;
+                failed_clients.push(message.client.to_owned());
+            }
+        }
+
+        redeliveries.push(CallbackRedelivery {
+            id: message.id,
+            client: message.client,
+            callback_url: message.callback_url,
+            attempts: message.attempts,
+            delivered: result.is_ok(),
+            dropped: matches!(&result, Err(err) if !err.retryable),
+            error: result.err().map(|err| err.error),
+        });
+    }
+
+    Ok(redeliveries)
+}
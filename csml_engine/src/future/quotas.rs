@@ -0,0 +1,128 @@
+/**
+ * Async version of the rate limits and quotas, see `crate::quotas`
+ */
+use crate::data::{AsyncConversationInfo, AsyncDatabase, EngineError};
+use crate::future::db_connectors::counters::{get_counter, increment_counter};
+use crate::quotas::{as_count, limit_reached, Counter, Quotas, DAY, MINUTE};
+
+use csml_interpreter::data::{csml_usage::Usage, Client, Event};
+use csml_interpreter::get_step_limit;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// the request is not counted for any client once one of them is over its limit
+async fn uncount_requests(
+    counted: &[Client],
+    counter: &Counter,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<(), EngineError> {
+    for client in counted {
+        increment_counter(client, &counter.key, -1, counter.ttl, db).await?;
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub async fn check_quotas(
+    client: &Client,
+    event: &mut Event,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<(), EngineError> {
+    let quotas = Quotas::from_env();
+    let (requests, steps) = (
+        Counter::window("requests", MINUTE),
+        Counter::window("steps", DAY),
+    );
+
+    let mut step_limit = None;
+    for (client, _, steps_limit) in quotas.clients(client) {
+        if let Some(limit) = steps_limit {
+            let count = as_count(get_counter(&client, &steps.key, db).await?);
+            if count >= limit {
+                return Err(limit_reached(&client, "steps per day"));
+            }
+
+            let left = (limit - count) as usize;
+            step_limit = Some(step_limit.map_or(left, |step_limit: usize| step_limit.min(left)));
+        }
+    }
+
+    let mut counted = vec![];
+    for (client, requests_limit, _) in quotas.clients(client) {
+        if let Some(limit) = requests_limit {
+            counted.push(client.to_owned());
+            let count = increment_counter(&client, &requests.key, 1, requests.ttl, db).await?;
+
+            // rejected requests are not counted
+            if as_count(count) > limit {
+                uncount_requests(&counted, &requests, db).await?;
+                return Err(limit_reached(&client, "requests per minute"));
+            }
+        }
+    }
+
+    if let Some(step_limit) = step_limit {
+        event.step_limit = Some(get_step_limit(event).min(step_limit));
+    }
+
+    Ok(())
+}
+
+pub async fn set_http_limit(
+    data: &mut AsyncConversationInfo<'_>,
+    event: &mut Event,
+) -> Result<(), EngineError> {
+    if let Some(limit) = Quotas::from_env().http_calls_per_conversation {
+        let calls = Counter::conversation("http_calls", data.conversation_id, data.ttl);
+        let count = as_count(get_counter(&data.client, &calls.key, &mut data.db).await?);
+
+        event.http_limit = Some(limit.saturating_sub(count) as usize);
+    }
+
+    Ok(())
+}
+
+/**
+ * Count the steps and HTTP() calls of an interpretation, see `csml_usage::get`
+ */
+pub async fn add_usage(
+    data: &mut AsyncConversationInfo<'_>,
+    usage: Usage,
+) -> Result<(), EngineError> {
+    let quotas = Quotas::from_env();
+    let steps = Counter::window("steps", DAY);
+
+    for (client, _, steps_limit) in quotas.clients(&data.client) {
+        if steps_limit.is_some() && usage.steps > 0 {
+            increment_counter(
+                &client,
+                &steps.key,
+                usage.steps as i64,
+                steps.ttl,
+                &mut data.db,
+            )
+            .await?;
+        }
+    }
+
+    if quotas.http_calls_per_conversation.is_some() && usage.http_calls > 0 {
+        let calls = Counter::conversation("http_calls", data.conversation_id, data.ttl);
+
+        increment_counter(
+            &data.client,
+            &calls.key,
+            usage.http_calls as i64,
+            calls.ttl,
+            &mut data.db,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
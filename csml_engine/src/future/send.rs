@@ -1,21 +1,82 @@
+use crate::data::models::OutboxMessage;
 use crate::data::AsyncConversationInfo;
+use crate::future::db_connectors::outbox::add_outbox_message;
+use crate::send::{
+    callback_retries, is_retryable, retry_delay, DeliveryError, DELIVERY_ID_HEADER,
+    SIGNATURE_HEADER,
+};
+use csml_interpreter::data::csml_metrics::{self, Metric};
+use csml_interpreter::data::csml_otel;
 
-async fn format_and_transfer(callback_url: &str, msg: serde_json::Value) {
-    let mut request = reqwest::Client::new().post(callback_url);
+async fn format_and_transfer(message: &OutboxMessage, body: &str) -> Result<(), DeliveryError> {
+    let mut span = csml_otel::span("csml.callback", &[("url.full", &message.callback_url)]);
+    let mut request = reqwest::Client::new().post(&message.callback_url);
 
     request = request
         .header("Accept", "application/json")
-        .header("Content-Type", "application/json");
+        .header("Content-Type", "application/json")
+        .header(DELIVERY_ID_HEADER, message.id.to_string());
+    if let Some(signature) = &message.signature {
+        request = request.header(SIGNATURE_HEADER, signature);
+    }
+    for (key, value) in span.propagation_headers() {
+        request = request.header(key, value);
+    }
+
+    match request.body(body.to_owned()).send().await {
+        Ok(response) if response.status().is_success() => {
+            span.set_http_status(response.status().as_u16());
+            Ok(())
+        }
+        Ok(response) => {
+            let status = response.status().as_u16();
+            span.set_http_status(status);
+            Err(DeliveryError {
+                error: format!("status code {}", status),
+                retryable: is_retryable(status),
+            })
+        }
+        Err(err) => {
+            span.set_error(&err.to_string());
+            Err(DeliveryError {
+                error: err.to_string(),
+                retryable: true,
+            })
+        }
+    }
+}
+
+/**
+ * Send the message to its callback_url, retrying up to `retries` times with an exponential backoff
+ */
+pub async fn deliver(message: &mut OutboxMessage, retries: u32) -> Result<(), DeliveryError> {
+    let body = message.payload.to_string();
 
-    let response = request.json(&msg).send().await;
+    let mut retry = 0;
+    loop {
+        message.attempts += 1;
+        match format_and_transfer(message, &body).await {
+            Ok(()) => return Ok(()),
+            Err(err) if !err.retryable || retry == retries => {
+                csml_metrics::record(Metric::CallbackFailure {
+                    bot_id: &message.client.bot_id,
+                });
+                return Err(err);
+            }
+            Err(_) => {}
+        }
 
-    if let Err(err) = response {
-        eprintln!("callback_url call failed: {:?}", err.to_string());
+        tokio::time::sleep(retry_delay(retry)).await;
+        retry += 1;
     }
 }
 
 /**
  * If a callback_url is defined, we must send each message to its endpoint as it comes.
+ * Messages that can not be delivered are kept in the outbox until they are redelivered,
+ * along with every following message of the client so that their order is preserved:
+ * while older messages of the client wait in the outbox, new ones are added after them.
+ * Messages refused by the endpoint are dropped, they would block the next ones.
  * Otherwise, just continue!
  */
 pub async fn send_to_callback_url(c_info: &mut AsyncConversationInfo<'_>, msg: serde_json::Value) {
@@ -24,5 +85,31 @@ pub async fn send_to_callback_url(c_info: &mut AsyncConversationInfo<'_>, msg: s
         None => return,
     };
 
-    format_and_transfer(callback_url, msg).await
+    let mut message = OutboxMessage::new(
+        &c_info.client,
+        callback_url,
+        msg,
+        c_info.callback_secret.as_deref(),
+    );
+
+    if !c_info.callback_failed {
+        match deliver(&mut message, callback_retries()).await {
+            Ok(()) => return,
+            Err(err) if !err.retryable => {
+                eprintln!("callback_url refused the message, dropped: {:?}", err.error);
+                return;
+            }
+            Err(err) => {
+                eprintln!("callback_url call failed: {:?}", err.error);
+                c_info.callback_failed = true;
+            }
+        }
+    }
+
+    if let Err(err) = add_outbox_message(&message, &mut c_info.db).await {
+        eprintln!(
+            "callback message could not be saved to the outbox: {:?}",
+            err
+        );
+    }
 }
@@ -1,24 +1,27 @@
 use crate::{
     data::{AsyncConversationInfo, AsyncDatabase, EngineError},
-    future::db_connectors::state::delete_state_key,
+    future::db_connectors::{
+        conversations::get_latest_open,
+        state::{delete_state_key, get_state_key, set_state_items},
+    },
     future::send::send_to_callback_url,
+    send::send_to_stream,
+    trigger::{choose_flow, find_triggered_flows},
     CsmlBot, CsmlFlow,
 };
 
-use base64::Engine;
 use chrono::{prelude::Utc, SecondsFormat};
 use csml_interpreter::{
     data::{
-        ast::{Flow, InsertStep, InstructionScope},
+        ast::{InsertStep, InstructionScope},
         context::ContextStepInfo,
         csml_logs::*,
-        Client, Context, Event, Interval, Memory, Message,
+        BotArtifact, Client, Context, Event, Interval, Memory, Message, PreviousInfo,
     },
     error_format::{ERROR_KEY_ALPHANUMERIC, ERROR_NUMBER_AS_KEY, ERROR_SIZE_IDENT},
     get_step,
     interpreter::json_to_literal,
 };
-use rand::seq::SliceRandom;
 use serde_json::{json, map::Map, Value};
 use std::collections::HashMap;
 use std::env;
@@ -150,12 +153,15 @@ pub fn format_event(request: &CsmlRequest) -> Result<Event, EngineError> {
         ttl_duration: json_event["ttl_duration"].as_i64(),
         low_data_mode: json_event["low_data_mode"].as_bool(),
         step_limit,
+        http_limit: None,
         secure: json_event["payload"]["secure"].as_bool().unwrap_or(false),
+        trace: request.debug,
+        seed: request.random_seed,
     })
 }
 
 /**
- * Send a message to the configured callback_url.
+ * Send a message to the configured callback_url, and to the stream of the request if any.
  * If not callback_url is configured, skip this action.
  */
 pub async fn send_msg_to_callback_url(
@@ -176,7 +182,9 @@ pub async fn send_msg_to_callback_url(
         LogLvl::Debug,
     );
 
-    send_to_callback_url(data, serde_json::json!(messages)).await
+    let messages = serde_json::json!(messages);
+    send_to_stream(data.stream.as_ref(), &messages);
+    send_to_callback_url(data, messages).await
 }
 
 /**
@@ -272,20 +280,102 @@ pub fn get_default_flow<'a>(bot: &'a CsmlBot) -> Result<&'a CsmlFlow, EngineErro
     }
 }
 
+/**
+ * Retrieve the flow of a new conversation when the user's input does not trigger any flow:
+ * the bot's fallback_flow if it is set, or its default flow.
+ */
+pub fn get_fallback_flow(bot: &CsmlBot) -> Result<&CsmlFlow, EngineError> {
+    match &bot.fallback_flow {
+        Some(fallback_flow) => get_flow_by_id(fallback_flow, &bot.flows),
+        None => get_default_flow(bot),
+    }
+}
+
+/**
+ * Clear the pending hold, if any, when a flow is triggered by the user's input.
+ * - when the bot has no `interruptions`, any triggered flow interrupts the hold
+ * - otherwise only the interruption flows can, and the input answers the hold for the others
+ * - interruptions that `resume` the hold keep the held step as the `previous` step
+ *   of the new conversation, so that `goto previous step` goes back to it, and keep the
+ *   hold as `interrupted` until then: the held step is not restarted but holds again
+ */
+async fn interrupt_hold(
+    flow: &CsmlFlow,
+    bot: &CsmlBot,
+    client: &Client,
+    context: &mut Context,
+    ttl: Option<chrono::Duration>,
+    db: &mut AsyncDatabase<'_>,
+) -> Result<(), EngineError> {
+    if bot.interruptions.is_none() {
+        return delete_state_key(client, "hold", "position", db).await;
+    }
+
+    let hold = match get_state_key(client, "hold", "position", db).await? {
+        Some(hold) => hold,
+        None => return Ok(()),
+    };
+
+    let interruption = match bot.get_interruption(flow) {
+        Some(interruption) => interruption,
+        None => {
+            return Err(EngineError::Interpreter(format!(
+                "Flow '{}' can not interrupt the current hold",
+                flow.name
+            )))
+        }
+    };
+
+    let conversation = match interruption.resume {
+        true => get_latest_open(client, db).await?,
+        false => None,
+    };
+    let held_flow = conversation.and_then(|conversation| {
+        get_flow_by_id(&conversation.flow_id, &bot.flows)
+            .ok()
+            .map(|flow| (flow, conversation.step_id))
+    });
+
+    match held_flow {
+        Some((held_flow, step_id)) => {
+            context.previous = Some(PreviousInfo::new(
+                held_flow.name.to_owned(),
+                ContextStepInfo::UnknownFlow(step_id.to_owned()),
+            ));
+
+            let mut interrupted = hold;
+            interrupted["flow"] = json!(held_flow.name);
+            interrupted["step"] = json!(step_id);
+            set_state_items(client, "hold", vec![("interrupted", &interrupted)], ttl, db).await?;
+        }
+        None => delete_state_key(client, "hold", "interrupted", db).await?,
+    }
+
+    delete_state_key(client, "hold", "position", db).await
+}
+
 /**
  * Find a flow in a bot based on the user's input.
  * - flow_trigger events must will match a flow's id or name and reset the hold position
- * - other events will try to match a flow trigger
+ * - other events will try to match a flow trigger, exactly or with the configured
+ *   trigger matcher (see the trigger module)
+ * - when several flows match, the one with the highest priority (or declared first) is used
+ * - a pending hold is only interrupted by the flows allowed to (see interrupt_hold)
  */
 pub async fn search_flow<'a>(
     event: &Event,
     bot: &'a CsmlBot,
     client: &Client,
+    context: &mut Context,
+    ttl: Option<chrono::Duration>,
     db: &mut AsyncDatabase<'_>,
 ) -> Result<(&'a CsmlFlow, String), EngineError> {
     match event {
         event if event.content_type == "flow_trigger" => {
             delete_state_key(client, "hold", "position", db).await?;
+            if bot.interruptions.is_some() {
+                delete_state_key(client, "hold", "interrupted", db).await?;
+            }
 
             let flow_trigger: FlowTrigger = serde_json::from_str(&event.content_value)?;
 
@@ -301,7 +391,7 @@ pub async fn search_flow<'a>(
             }
         }
         event if event.content_type == "regex" => {
-            let mut random_flows = vec![];
+            let mut matching_flows = vec![];
 
             for flow in bot.flows.iter() {
                 let contains_command = flow.commands.iter().any(|cmd| {
@@ -313,19 +403,13 @@ pub async fn search_flow<'a>(
                 });
 
                 if contains_command {
-                    random_flows.push(flow)
+                    matching_flows.push(flow)
                 }
             }
 
-            // Thread rng is not send so we have to drop it right away
-            let random_flow = {
-                let rng = &mut rand::thread_rng();
-                random_flows.choose(rng)
-            };
-
-            match random_flow {
+            match choose_flow(&matching_flows) {
                 Some(flow) => {
-                    delete_state_key(client, "hold", "position", db).await?;
+                    interrupt_hold(flow, bot, client, context, ttl, db).await?;
                     Ok((flow, "start".to_owned()))
                 }
                 None => Err(EngineError::Interpreter(format!(
@@ -335,28 +419,11 @@ pub async fn search_flow<'a>(
             }
         }
         event => {
-            let mut random_flows = vec![];
-
-            for flow in bot.flows.iter() {
-                let contains_command = flow
-                    .commands
-                    .iter()
-                    .any(|cmd| &cmd.as_str().to_lowercase() == &event.content_value.to_lowercase());
+            let matching_flows = find_triggered_flows(&event.content_value, bot)?;
 
-                if contains_command {
-                    random_flows.push(flow)
-                }
-            }
-
-            // Thread rng is not send so we have to drop it right away
-            let random_flow = {
-                let rng = &mut rand::thread_rng();
-                random_flows.choose(rng)
-            };
-
-            match random_flow {
+            match choose_flow(&matching_flows) {
                 Some(flow) => {
-                    delete_state_key(client, "hold", "position", db).await?;
+                    interrupt_hold(flow, bot, client, context, ttl, db).await?;
                     Ok((flow, "start".to_owned()))
                 }
                 None => Err(EngineError::Interpreter(format!(
@@ -369,105 +436,90 @@ pub async fn search_flow<'a>(
 }
 
 pub fn get_current_step_hash(context: &Context, bot: &CsmlBot) -> Result<String, EngineError> {
+    let artifact = match bot.bot_ast.as_deref().map(BotArtifact::decode) {
+        Some(Ok(artifact)) => artifact,
+        _ => return Err(EngineError::Manager("not valid ast".to_string())),
+    };
+    let csml_bot = &artifact.flows;
+
     let mut hash = Md5::new();
 
     let step = match &context.step {
         ContextStepInfo::Normal(step) => {
+            if let Some(checksum) = artifact.get_step_checksum(&context.flow, step) {
+                return Ok(checksum.to_owned());
+            }
+
             let flow = &get_flow_by_id(&context.flow, &bot.flows)?.content;
 
-            let ast = match &bot.bot_ast {
-                Some(ast) => {
-                    let base64decoded = base64::engine::general_purpose::STANDARD
-                        .decode(ast)
-                        .unwrap();
-                    let csml_bot: HashMap<String, Flow> =
-                        bincode::deserialize(&base64decoded[..]).unwrap();
-                    match csml_bot.get(&context.flow) {
-                        Some(flow) => flow.to_owned(),
-                        None => csml_bot
-                            .get(&get_default_flow(bot)?.name)
-                            .unwrap()
-                            .to_owned(),
-                    }
-                }
-                None => return Err(EngineError::Manager("not valid ast".to_string())),
+            let ast = match csml_bot.get(&context.flow) {
+                Some(flow) => flow,
+                None => csml_bot.get(&get_default_flow(bot)?.name).unwrap(),
             };
 
-            get_step(step, flow, &ast)
+            get_step(step, flow, ast)
         }
         ContextStepInfo::UnknownFlow(step) => {
             let flow = &get_flow_by_id(&context.flow, &bot.flows)?.content;
 
-            match &bot.bot_ast {
-                Some(ast) => {
-                    let base64decoded = base64::engine::general_purpose::STANDARD
-                        .decode(ast)
-                        .unwrap();
-                    let csml_bot: HashMap<String, Flow> =
-                        bincode::deserialize(&base64decoded[..]).unwrap();
-
-                    let default_flow = csml_bot.get(&get_default_flow(bot)?.name).unwrap();
-
-                    match csml_bot.get(&context.flow) {
-                        Some(target_flow) => {
-                            // check if there is a inserted step with the same name as the target step
-                            let insertion_expr = target_flow.flow_instructions.get_key_value(
-                                &InstructionScope::InsertStep(InsertStep {
-                                    name: step.clone(),
-                                    original_name: None,
-                                    from_flow: "".to_owned(),
-                                    interval: Interval::default(),
-                                }),
-                            );
-
-                            // if there is a inserted step get the flow of the target step and
-                            if let Some((InstructionScope::InsertStep(insert), _)) = insertion_expr
-                            {
-                                match csml_bot.get(&insert.from_flow) {
-                                    Some(inserted_step_flow) => {
-                                        let inserted_raw_flow =
-                                            &get_flow_by_id(&insert.from_flow, &bot.flows)?.content;
-
-                                        get_step(step, inserted_raw_flow, inserted_step_flow)
-                                    }
-                                    None => get_step(step, flow, default_flow),
-                                }
-                            } else {
-                                get_step(step, flow, target_flow)
+            let default_flow = csml_bot.get(&get_default_flow(bot)?.name).unwrap();
+
+            match csml_bot.get(&context.flow) {
+                Some(target_flow) => {
+                    // check if there is a inserted step with the same name as the target step
+                    let insertion_expr =
+                        target_flow
+                            .flow_instructions
+                            .get_key_value(&InstructionScope::InsertStep(InsertStep {
+                                name: step.clone(),
+                                original_name: None,
+                                from_flow: "".to_owned(),
+                                interval: Interval::default(),
+                            }));
+
+                    // if there is a inserted step get the flow of the target step and
+                    if let Some((InstructionScope::InsertStep(insert), _)) = insertion_expr {
+                        if let Some(checksum) = artifact.get_step_checksum(&insert.from_flow, step)
+                        {
+                            return Ok(checksum.to_owned());
+                        }
+
+                        match csml_bot.get(&insert.from_flow) {
+                            Some(inserted_step_flow) => {
+                                let inserted_raw_flow =
+                                    &get_flow_by_id(&insert.from_flow, &bot.flows)?.content;
+
+                                get_step(step, inserted_raw_flow, inserted_step_flow)
                             }
+                            None => get_step(step, flow, default_flow),
                         }
-                        None => get_step(step, flow, default_flow),
+                    } else {
+                        if let Some(checksum) = artifact.get_step_checksum(&context.flow, step) {
+                            return Ok(checksum.to_owned());
+                        }
+
+                        get_step(step, flow, target_flow)
                     }
                 }
-                None => return Err(EngineError::Manager("not valid ast".to_string())),
+                None => get_step(step, flow, default_flow),
             }
         }
         ContextStepInfo::InsertedStep {
             step,
             flow: inserted_flow,
         } => {
+            if let Some(checksum) = artifact.get_step_checksum(inserted_flow, step) {
+                return Ok(checksum.to_owned());
+            }
+
             let flow = &get_flow_by_id(inserted_flow, &bot.flows)?.content;
 
-            let ast = match &bot.bot_ast {
-                Some(ast) => {
-                    let base64decoded = base64::engine::general_purpose::STANDARD
-                        .decode(ast)
-                        .unwrap();
-                    let csml_bot: HashMap<String, Flow> =
-                        bincode::deserialize(&base64decoded[..]).unwrap();
-
-                    match csml_bot.get(inserted_flow) {
-                        Some(flow) => flow.to_owned(),
-                        None => csml_bot
-                            .get(&get_default_flow(bot)?.name)
-                            .unwrap()
-                            .to_owned(),
-                    }
-                }
-                None => return Err(EngineError::Manager("not valid ast".to_string())),
+            let ast = match csml_bot.get(inserted_flow) {
+                Some(flow) => flow,
+                None => csml_bot.get(&get_default_flow(bot)?.name).unwrap(),
             };
 
-            get_step(step, flow, &ast)
+            get_step(step, flow, ast)
         }
     };
 
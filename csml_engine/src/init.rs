@@ -1,26 +1,25 @@
-use crate::db_connectors::{conversations::*, memories::*, state};
+use crate::db_connectors::{conversations::*, memories::*, outbox::has_client_outbox, state};
 use crate::interpreter_actions::models::SwitchBot;
 use crate::{
+    cache,
     data::{ConversationInfo, Database, EngineError},
     utils::{
-        get_default_flow, get_flow_by_id, get_low_data_mode_value, get_ttl_duration_value,
+        get_fallback_flow, get_flow_by_id, get_low_data_mode_value, get_ttl_duration_value,
         search_flow, send_msg_to_callback_url,
     },
-    Context, CsmlBot, CsmlFlow, CsmlResult,
+    Context, CsmlBot, CsmlFlow,
 };
 
 use csml_interpreter::data::context::ContextStepInfo;
 use csml_interpreter::{
     data::{
-        ast::Flow,
         context::{get_hashmap_from_json, get_hashmap_from_mem},
-        ApiInfo, Client, Event, Message, PreviousBot,
+        ApiInfo, BotArtifact, Client, Event, Message, PreviousBot,
     },
-    load_components, search_for_modules, validate_bot,
+    load_components, search_for_modules,
 };
 
-use crate::data::models::{BotOpt, CsmlRequest};
-use base64::Engine;
+use crate::data::models::{BotOpt, BotReference, CsmlRequest};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -60,7 +59,7 @@ pub fn init_conversation_info<'a, 'b>(
     // Do we have a flow matching the request? If the user is requesting a flow in one way
     // or another, this takes precedence over any previously open conversation
     // and a new conversation is created with the new flow as a starting point.
-    let flow_found = search_flow(event, bot, &request.client, &mut db).ok();
+    let flow_found = search_flow(event, bot, &request.client, &mut context, ttl, &mut db).ok();
     let conversation_id =
         get_or_create_conversation(&mut context, bot, flow_found, &request.client, ttl, &mut db)?;
 
@@ -70,6 +69,12 @@ pub fn init_conversation_info<'a, 'b>(
         &context.flow,
     );
 
+    // messages of the client still in the outbox are sent before the new ones
+    let callback_failed = match request.callback_url {
+        Some(_) => has_client_outbox(&request.client, &mut db)?,
+        None => false,
+    };
+
     let mut data = ConversationInfo {
         conversation_id,
         context,
@@ -80,6 +85,11 @@ pub fn init_conversation_info<'a, 'b>(
         messages: vec![],
         ttl,
         low_data,
+        trace: request.debug.then(Vec::new),
+        callback_secret: bot.callback_secret.to_owned(),
+        callback_failed,
+        stream: None,
+        bot_reference: BotReference::default(),
         db,
     };
 
@@ -103,54 +113,82 @@ pub fn init_bot(bot: &mut CsmlBot) -> Result<(), EngineError> {
         Err(err) => return Err(EngineError::Interpreter(err.format_error())),
     };
 
+    set_cached_bot_ast(bot)
+}
+
+/**
+ * Bots can come with an artifact compiled by this interpreter version (stored with
+ * the bot version or sent by the client and signed, see `encode_bot_artifact`), in which
+ * case their flows are not parsed. Bots sent without their flows get them from the artifact.
+ */
+fn is_precompiled(bot: &mut CsmlBot) -> bool {
+    let ast = match bot.bot_ast.as_deref() {
+        Some(ast) => ast,
+        None => return false,
+    };
+
+    match BotArtifact::verify(ast, None) {
+        Ok(header) if header.bot_id == bot.id => {}
+        _ => return false,
+    }
+
+    if bot.flows.is_empty() {
+        match BotArtifact::decode(ast) {
+            Ok(artifact) => bot.flows = artifact.sources,
+            Err(_) => return false,
+        }
+    }
+
+    true
+}
+
+/**
+ * Initialize bot ast, from the AST cache if a bot with the same content was already parsed
+ */
+fn set_cached_bot_ast(bot: &mut CsmlBot) -> Result<(), EngineError> {
+    if is_precompiled(bot) {
+        return Ok(());
+    }
+
+    // modules are resolved before hashing the bot, so that a module whose version is a range
+    // is parsed again when a new version matches it
     if let Err(err) = search_for_modules(bot) {
         return Err(EngineError::Interpreter(format!("{:?}", err)));
     }
 
-    set_bot_ast(bot)
+    let hash = cache::bot_hash(bot);
+
+    match cache::get_bot_ast(&hash) {
+        Some(ast) => {
+            bot.bot_ast = Some(ast);
+            Ok(())
+        }
+        None => set_bot_ast(bot, hash),
+    }
 }
 
 /**
- * Initialize bot ast
+ * Initialize bot ast and store it in the AST cache
  */
-fn set_bot_ast(bot: &mut CsmlBot) -> Result<(), EngineError> {
-    match validate_bot(bot) {
-        CsmlResult {
-            flows: Some(flows),
-            extern_flows: Some(extern_flows),
-            errors: None,
-            ..
-        } => {
-            bot.bot_ast = Some(
-                base64::engine::general_purpose::STANDARD
-                    .encode(bincode::serialize(&(&flows, &extern_flows)).unwrap()),
-            );
-        }
-        CsmlResult {
-            flows: Some(flows),
-            extern_flows: None,
-            errors: None,
-            ..
-        } => {
-            let extern_flows: HashMap<String, Flow> = HashMap::new();
-
-            bot.bot_ast = Some(
-                base64::engine::general_purpose::STANDARD
-                    .encode(bincode::serialize(&(&flows, &extern_flows)).unwrap()),
-            );
-        }
-        CsmlResult {
-            errors: Some(errors),
-            ..
-        } => {
+fn set_bot_ast(bot: &mut CsmlBot, hash: String) -> Result<(), EngineError> {
+    let artifact = match BotArtifact::compile(bot) {
+        Ok(artifact) => artifact,
+        Err(errors) => {
             return Err(EngineError::Interpreter(format!(
                 "invalid bot {:?}",
                 errors
             )))
         }
-        _ => return Err(EngineError::Interpreter("empty bot".to_string())),
+    };
+
+    if artifact.flows.is_empty() {
+        return Err(EngineError::Interpreter("empty bot".to_string()));
     }
 
+    let ast = artifact.encode();
+    cache::set_bot_ast(hash, ast.to_owned());
+    bot.bot_ast = Some(ast);
+
     Ok(())
 }
 
@@ -178,6 +216,8 @@ pub fn init_context(
         step: ContextStepInfo::Normal("start".to_owned()),
         flow,
         previous_bot,
+        previous: None,
+        interrupted: None,
     }
 }
 
@@ -231,7 +271,8 @@ fn get_or_create_conversation<'a>(
 }
 
 /**
- * Create and save a new conversation in DB
+ * Create and save a new conversation in DB, starting at the flow found in the user's input
+ * or at the bot's fallback flow
  */
 fn create_new_conversation<'a>(
     context: &mut Context,
@@ -243,7 +284,7 @@ fn create_new_conversation<'a>(
 ) -> Result<Uuid, EngineError> {
     let (flow, step) = match flow_found {
         Some((flow, step)) => (flow, step),
-        None => (get_default_flow(bot)?, "start".to_owned()),
+        None => (get_fallback_flow(bot)?, "start".to_owned()),
     };
 
     let conversation_id = create_conversation(&flow.id, &step, client, ttl, db)?;
@@ -278,6 +319,7 @@ pub fn switch_bot(
             multibot: bot.multibot.take(),
         },
     };
+    data.bot_reference = bot_opt.to_reference();
 
     let mut new_bot = bot_opt.search_bot(&mut data.db)?;
     new_bot.custom_components = bot.custom_components.take();
@@ -285,7 +327,7 @@ pub fn switch_bot(
 
     *bot = new_bot;
 
-    set_bot_ast(bot)?;
+    set_cached_bot_ast(bot)?;
 
     data.context.step = ContextStepInfo::UnknownFlow(next_bot.step);
     data.context.flow = match next_bot.flow {
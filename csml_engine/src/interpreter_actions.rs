@@ -1,22 +1,26 @@
 pub mod models;
 
 use crate::data::*;
-use crate::db_connectors::{conversations::*, memories::*, messages::*, state::*};
+use crate::db_connectors::{conversations::*, memories::*, messages::*, schedules::*, state::*};
+use crate::quotas::add_usage;
 use crate::utils::*;
 
-use crate::data::models::Direction;
+use crate::data::models::{Direction, Schedule};
 use csml_interpreter::data::context::ContextStepInfo;
 use csml_interpreter::{
     data::{
-        ast::ForgetMemory, csml_bot::CsmlBot, csml_flow::CsmlFlow, csml_logs::*, Client, Event,
-        Hold, Memory, Message, MultiBot, MSG,
+        ast::ForgetMemory,
+        csml_bot::CsmlBot,
+        csml_flow::CsmlFlow,
+        csml_logs::*,
+        csml_metrics::{self, Metric},
+        csml_otel, csml_usage, Client, Event, Hold, Memory, Message, MultiBot, MSG,
     },
-    interpret,
+    interpret_with_callback,
 };
 pub use models::{InterpreterReturn, SwitchBot};
 use serde_json::{map::Map, Value};
 use std::collections::HashMap;
-use std::{sync::mpsc, thread};
 
 /**
  * This is the CSML Engine action.
@@ -27,11 +31,24 @@ pub fn interpret_step(
     data: &mut ConversationInfo,
     event: Event,
     bot: &CsmlBot,
+) -> Result<(Map<String, Value>, Option<SwitchBot>), EngineError> {
+    let (flow, step) = (data.context.flow.clone(), data.context.step.get_step());
+
+    csml_otel::with_span(
+        "csml.interpret_step",
+        &[("csml.flow", &flow), ("csml.step", &step)],
+        || run_step(data, event, bot),
+    )
+}
+
+fn run_step(
+    data: &mut ConversationInfo,
+    event: Event,
+    bot: &CsmlBot,
 ) -> Result<(Map<String, Value>, Option<SwitchBot>), EngineError> {
     let mut current_flow: &CsmlFlow = get_flow_by_id(&data.context.flow, &bot.flows)?;
     let mut interaction_order = 0;
     let mut conversation_end = false;
-    let (sender, receiver) = mpsc::channel::<MSG>();
     let context = data.context.clone();
     let mut switch_bot = None;
 
@@ -56,14 +73,11 @@ pub fn interpret_step(
         ),
         LogLvl::Debug,
     );
-    let new_bot = bot.clone();
-    thread::spawn(move || {
-        interpret(new_bot, context, event, Some(sender));
-    });
 
     let mut memories = HashMap::new();
 
-    for received in receiver {
+    // returns Ok(true) once the remaining messages of the step must be ignored
+    let mut handle_msg = |received: MSG| -> Result<bool, EngineError> {
         match received {
             MSG::Remember(mem) => {
                 memories.insert(mem.key.clone(), mem);
@@ -104,6 +118,11 @@ pub fn interpret_step(
                     LogLvl::Debug,
                 );
 
+                csml_metrics::record(Metric::Message {
+                    bot_id: &bot.id,
+                    flow: &data.context.flow,
+                });
+
                 send_msg_to_callback_url(data, vec![msg.clone()], interaction_order, false);
                 data.messages.push(msg);
             }
@@ -153,6 +172,11 @@ pub fn interpret_step(
                     ),
                     LogLvl::Debug,
                 );
+                csml_metrics::record(Metric::Hold {
+                    bot_id: &bot.id,
+                    flow: &flow_name,
+                    step: &step_name,
+                });
 
                 set_state_items(
                     &data.client,
@@ -161,6 +185,14 @@ pub fn interpret_step(
                     data.ttl,
                     &mut data.db,
                 )?;
+                // the interrupted hold is back at its step
+                if matches!(
+                    &data.context.interrupted,
+                    Some(interrupted) if interrupted.flow_name == flow_name && interrupted.step_name == step_name
+                ) {
+                    data.context.interrupted = None;
+                    delete_state_key(&data.client, "hold", "interrupted", &mut data.db)?;
+                }
                 data.context.hold = Some(Hold {
                     index,
                     step_vars,
@@ -185,7 +217,7 @@ pub fn interpret_step(
                     flow,
                     step,
                 ) {
-                    break;
+                    return Ok(true);
                 }
             }
 
@@ -198,10 +230,27 @@ pub fn interpret_step(
                     manage_switch_bot(data, &mut interaction_order, bot, flow, step, target_bot)
                 {
                     switch_bot = Some(s_bot);
-                    break;
+                    return Ok(true);
                 }
             }
 
+            MSG::Trace(event) => {
+                if let Some(trace) = &mut data.trace {
+                    trace.push(event);
+                }
+            }
+
+            MSG::Schedule(schedule) => {
+                let schedule = Schedule::new(
+                    &data.client,
+                    &schedule,
+                    data.callback_url.clone(),
+                    data.bot_reference.clone(),
+                )?;
+
+                create_schedule(&schedule, &mut data.db)?;
+            }
+
             MSG::Error(err_msg) => {
                 conversation_end = true;
                 csml_logger(
@@ -219,7 +268,19 @@ pub fn interpret_step(
                 close_conversation(data.conversation_id, &data.client, &mut data.db)?;
             }
         }
-    }
+
+        Ok(false)
+    };
+
+    // messages are handled on this thread, as soon as the interpreter emits them
+    let mut outcome = Ok(false);
+    interpret_with_callback(bot.clone(), context, event, &mut |received| {
+        if let Ok(false) = outcome {
+            outcome = handle_msg(received);
+        }
+    });
+    outcome?;
+    add_usage(data, csml_usage::get())?;
 
     // save in db
     let msgs: Vec<serde_json::Value> = data
@@ -234,15 +295,17 @@ pub fn interpret_step(
 
     add_memories(data, &memories)?;
 
-    Ok((
-        messages_formatter(
-            data,
-            data.messages.clone(),
-            interaction_order,
-            conversation_end,
-        ),
-        switch_bot,
-    ))
+    let mut response = messages_formatter(
+        data,
+        data.messages.clone(),
+        interaction_order,
+        conversation_end,
+    );
+    if let Some(trace) = &data.trace {
+        response.insert("trace".to_owned(), serde_json::json!(trace));
+    }
+
+    Ok((response, switch_bot))
 }
 
 fn manage_switch_bot(
@@ -549,6 +612,9 @@ fn goto_step<'a>(
         // send end of conversation
         send_msg_to_callback_url(data, vec![], *interaction_order, *conversation_end);
         close_conversation(data.conversation_id, &data.client, &mut data.db)?;
+        if data.context.interrupted.take().is_some() {
+            delete_state_key(&data.client, "hold", "interrupted", &mut data.db)?;
+        }
 
         // break interpret_step loop
         return Ok(*conversation_end);
@@ -0,0 +1,149 @@
+/**
+ * Encryption of the stored data with the current key, to stop using an old secret.
+ *
+ * Records are read in batches ordered by id, decrypted with the secret of the key id they
+ * were saved with (or ENCRYPTION_SECRET for data saved without key id) and saved again,
+ * which encrypts them with the current key. Once the rotation is done, the old secret can
+ * be removed from ENCRYPTION_SECRETS.
+ *
+ * As for a migration, the progress is reported after each batch with a
+ * `MigrationCheckpoint`, and a rotation that stopped can be started again from it.
+ *
+ * The bots can keep running: a memory or state key written between the read of its batch
+ * and its update is left as it is, already encrypted with the current key. The servers
+ * must use the new key before the rotation starts, and bot versions are not expected to
+ * change while it runs.
+ */
+use crate::data::models::{BotVersionRecord, MigrationCheckpoint, RecordKind, RotationReport};
+use crate::data::storage::StorageBackend;
+use crate::encrypt::{decrypt_data, encrypt_data, get_current_key_id, is_encryption_enabled};
+use crate::error_messages::ERROR_ENCRYPTION_DISABLED;
+use crate::record_batches::RecordBatches;
+use crate::{Database, EngineError};
+
+// kinds of records holding encrypted data, in the order they are rotated
+const ROTATED_KINDS: [RecordKind; 5] = [
+    RecordKind::BotVersions,
+    RecordKind::Messages,
+    RecordKind::Memories,
+    RecordKind::State,
+    RecordKind::Outbox,
+];
+
+// encrypted fields of a saved bot
+const BOT_ENCRYPTED_FIELDS: [&str; 2] = ["env", "callback_secret"];
+
+type UpdateRecords<T> = fn(&mut dyn StorageBackend, &[T]) -> Result<usize, EngineError>;
+
+fn rotate_records<T>(
+    storage: &mut dyn StorageBackend,
+    mut batches: RecordBatches<T>,
+    update: UpdateRecords<T>,
+    on_batch: &mut impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<usize, EngineError> {
+    let mut rotated = 0;
+
+    while let Some(batch) = batches.next_batch(storage)? {
+        rotated += update(storage, &batch)?;
+
+        on_batch(&batches.checkpoint())?;
+    }
+
+    Ok(rotated)
+}
+
+/**
+ * Bots are saved as JSON, with their env and callback secret encrypted inside
+ */
+fn reencrypt_bots(versions: &[BotVersionRecord]) -> Result<Vec<BotVersionRecord>, EngineError> {
+    versions
+        .iter()
+        .map(|version| {
+            let mut bot: serde_json::Value = serde_json::from_str(&version.bot)?;
+
+            for field in BOT_ENCRYPTED_FIELDS.iter() {
+                if let Some(serde_json::Value::String(encrypted)) = bot.get(field) {
+                    let value = decrypt_data(encrypted.to_owned())?;
+                    bot[field] = serde_json::Value::String(encrypt_data(&value)?);
+                }
+            }
+
+            Ok(BotVersionRecord {
+                bot: bot.to_string(),
+                ..version.to_owned()
+            })
+        })
+        .collect()
+}
+
+pub fn rotate_encryption_key(
+    db: &mut Database,
+    batch_size: u32,
+    checkpoint: Option<MigrationCheckpoint>,
+    mut on_batch: impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<RotationReport, EngineError> {
+    if !is_encryption_enabled() {
+        return Err(EngineError::Manager(ERROR_ENCRYPTION_DISABLED.to_owned()));
+    }
+
+    let storage = db.storage()?;
+
+    let start = checkpoint.unwrap_or(MigrationCheckpoint {
+        kind: ROTATED_KINDS[0],
+        after: None,
+    });
+
+    let mut report = RotationReport {
+        key_id: get_current_key_id()?,
+        ..Default::default()
+    };
+    for kind in ROTATED_KINDS
+        .iter()
+        .copied()
+        .filter(|kind| *kind >= start.kind)
+    {
+        let after = match kind == start.kind {
+            true => start.after,
+            false => None,
+        };
+
+        let rotated = match kind {
+            RecordKind::BotVersions => rotate_records(
+                storage,
+                RecordBatches::bot_versions(after, batch_size),
+                |db, versions| db.update_bot_version_records(&reencrypt_bots(versions)?),
+                &mut on_batch,
+            ),
+            RecordKind::Messages => rotate_records(
+                storage,
+                RecordBatches::messages(after, batch_size),
+                |db, messages| db.update_message_records(messages),
+                &mut on_batch,
+            ),
+            RecordKind::Memories => rotate_records(
+                storage,
+                RecordBatches::memories(after, batch_size),
+                |db, memories| db.update_memory_records(memories),
+                &mut on_batch,
+            ),
+            RecordKind::State => rotate_records(
+                storage,
+                RecordBatches::state(after, batch_size),
+                |db, state| db.update_state_records(state),
+                &mut on_batch,
+            ),
+            RecordKind::Outbox => rotate_records(
+                storage,
+                RecordBatches::outbox(after, batch_size),
+                |db, messages| db.update_outbox_records(messages),
+                &mut on_batch,
+            ),
+            // conversations and schedules are not encrypted
+            RecordKind::Conversations | RecordKind::Schedules => continue,
+        }?;
+
+        report.records.insert(kind, rotated);
+    }
+
+    Ok(report)
+}
@@ -1,5 +1,9 @@
 pub mod data;
 
+mod analytics;
+mod api_keys;
+mod archive;
+mod cache;
 mod db_connectors;
 mod encrypt;
 mod error_messages;
@@ -7,14 +11,21 @@ mod error_messages;
 pub mod future;
 mod init;
 mod interpreter_actions;
+mod key_rotation;
+mod migration;
 mod models;
+mod quotas;
+mod record_batches;
 mod send;
+mod trigger;
 mod utils;
 
 pub use csml_interpreter::{
     data::{
         ast::{Expr, Flow, InstructionScope},
+        bot_artifact::BotArtifact,
         csml_logs::*,
+        csml_metrics,
         error_info::ErrorInfo,
         position::Position,
         warnings::Warnings,
@@ -33,34 +44,73 @@ extern crate diesel_migrations;
 
 use data::*;
 use db_connectors::{
-    bot, clean_db, conversations, init_db, memories, messages, state,
+    bot, clean_db, conversations, init_db, init_db_from_url, memories, messages, outbox, schedules,
+    state,
     state::{delete_state_key, set_state_items},
     user,
 };
 use init::*;
 use interpreter_actions::interpret_step;
+use quotas::{check_quotas, set_http_limit};
 use utils::*;
 
 use crate::data::filter::ClientMessageFilter;
-use crate::data::models::{Conversation, Direction, Message, Paginated};
+use crate::data::models::{
+    ApiKey, ApiKeyRequest, Archive, ArchiveImport, CallbackRedelivery, Conversation, CreatedApiKey,
+    Direction, FlowAnalytics, HoldAnalytics, Message, MigrationCheckpoint, MigrationReport,
+    Paginated, RotationReport, ScheduleRun, StepTransition, TurnAnalytics, SCHEDULE_MAX_ATTEMPTS,
+};
+use crate::error_messages::ERROR_CALLBACK_ORDER;
+pub use cache::{ast_cache_metrics, clear_ast_cache, AstCacheMetrics};
 use chrono::prelude::*;
 use csml_interpreter::data::{
-    csml_bot::CsmlBot, csml_flow::CsmlFlow, Context, Hold, IndexInfo, Memory,
+    context::ContextStepInfo, csml_bot::CsmlBot, csml_flow::CsmlFlow, csml_otel, Context, Hold,
+    IndexInfo, Memory,
 };
 use data::models::{BotOpt, CsmlRequest};
+pub use encrypt::encode_bot_artifact;
 use interpreter_actions::models::SwitchBot;
-use models::{BotVersion, BotVersionCreated};
-use std::{collections::HashMap, env};
+pub use models::{BotVersion, BotVersionCreated};
+use std::{collections::HashMap, env, sync::mpsc};
+pub use trigger::{
+    set_trigger_matcher, ClassifierMatcher, ExactMatcher, FlowMatch, FuzzyMatcher, Intent,
+    IntentClassifier, TriggerMatcher,
+};
 use uuid::Uuid;
 
-pub fn start_conversation_db(
+pub fn start_conversation_db<'a>(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    db: impl Into<Database<'a>>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    traced_conversation(request, bot_opt, db.into(), None)
+}
+
+fn traced_conversation(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    db: Database,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    let (request_id, bot_id) = (request.request_id.clone(), request.client.bot_id.clone());
+
+    csml_otel::with_span(
+        "csml.start_conversation",
+        &[("csml.request_id", &request_id), ("csml.bot_id", &bot_id)],
+        || run_conversation(request, bot_opt, db, stream),
+    )
+}
+
+fn run_conversation(
     request: CsmlRequest,
     mut bot_opt: BotOpt,
     mut db: Database,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
 ) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
     init_logger();
 
     let mut formatted_event = format_event(&request)?;
+    check_quotas(&request.client, &mut formatted_event, &mut db)?;
 
     let mut bot = bot_opt.search_bot(&mut db)?;
     init_bot(&mut bot)?;
@@ -72,8 +122,11 @@ pub fn start_conversation_db(
         &bot,
         db,
     )?;
+    data.stream = stream;
+    data.bot_reference = bot_opt.to_reference();
 
     check_for_hold(&mut data, &bot, &mut formatted_event)?;
+    load_interrupted_hold(&mut data, &bot)?;
 
     /////////// block user event if delay variable si on and delay_time is bigger than current time
     if let Some(delay) = bot.no_interruption_delay {
@@ -116,6 +169,7 @@ pub fn start_conversation_db(
         (true, _) => {}
     }
 
+    set_http_limit(&mut data, &mut formatted_event)?;
     let result = interpret_step(&mut data, formatted_event.to_owned(), &bot);
 
     check_switch_bot(
@@ -148,6 +202,20 @@ pub fn start_conversation(
     start_conversation_db(request, bot_opt, db)
 }
 
+/**
+ * Same as `start_conversation`, but each message is also sent to `stream` as soon as the bot
+ * says it, formatted like the messages of the response, so that it can be displayed before
+ * the end of the request. The stream is closed once the request is over.
+ */
+pub fn start_conversation_stream(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    stream: mpsc::Sender<serde_json::Value>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    let db = init_db()?;
+    traced_conversation(request, bot_opt, db, Some(stream))
+}
+
 fn check_switch_bot(
     result: Result<
         (
@@ -301,12 +369,12 @@ pub fn create_bot_version(mut csml_bot: CsmlBot) -> Result<BotVersionCreated, En
         return Err(EngineError::Interpreter(format!("{:?}", err)));
     }
 
-    match validate_bot(csml_bot.clone()) {
-        CsmlResult {
-            errors: Some(errors),
-            ..
-        } => Err(EngineError::Interpreter(format!("{:?}", errors))),
-        CsmlResult { .. } => {
+    match compile_bot(csml_bot.clone()) {
+        Err(errors) => Err(EngineError::Interpreter(format!("{:?}", errors))),
+        Ok(artifact) => {
+            // the compiled bot is stored with the version and used as is at runtime
+            csml_bot.bot_ast = Some(encode_bot_artifact(&artifact));
+
             let version_id = bot::create_bot_version(bot_id, csml_bot, &mut db)?;
             let engine_version = env!("CARGO_PKG_VERSION").to_owned();
 
@@ -422,6 +490,211 @@ pub fn delete_client(client: &Client) -> Result<(), EngineError> {
     user::delete_client(client, &mut db)
 }
 
+/**
+ * Export the conversations, messages, memories and state of a client as a single document
+ */
+pub fn export_client(client: &Client) -> Result<Archive, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    archive::export_clients(std::slice::from_ref(client), &mut db)
+}
+
+/**
+ * Export the conversations, messages, memories and state of every client of a bot
+ * as a single document
+ */
+pub fn export_bot(bot_id: &str) -> Result<Archive, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    let clients = archive::get_bot_clients(bot_id, &mut db)?;
+    archive::export_clients(&clients, &mut db)
+}
+
+/**
+ * Import a document made by `export_client` or `export_bot`. The existing conversations,
+ * messages, memories and state of each client of the document are replaced by its own,
+ * and restored if the import fails.
+ */
+pub fn import_client(archive: &Archive) -> Result<ArchiveImport, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    let ttl = get_ttl_duration_value(None);
+
+    archive::import_archive(archive, ttl, &mut db)
+}
+
+/**
+ * Copy the bot versions, conversations, messages, memories, state, schedules and
+ * outbox messages of the `from` database into the `to` one, keeping their ids and dates.
+ * Both are given as urls: `sqlite://<path>`, `postgresql://...` or `memory://`.
+ *
+ * `on_batch` is called with the progress of the migration after each batch of
+ * `batch_size` records: starting again from the last checkpoint resumes the migration.
+ */
+pub fn migrate(
+    from: &str,
+    to: &str,
+    batch_size: u32,
+    checkpoint: Option<MigrationCheckpoint>,
+    on_batch: impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<MigrationReport, EngineError> {
+    let mut from = init_db_from_url(from)?;
+    let mut to = init_db_from_url(to)?;
+    init_logger();
+
+    migration::migrate(&mut from, &mut to, batch_size, checkpoint, on_batch)
+}
+
+/**
+ * Same as `migrate`, between any two databases, e.g. custom storage backends
+ */
+pub fn migrate_db<'a, 'b>(
+    from: impl Into<Database<'a>>,
+    to: impl Into<Database<'b>>,
+    batch_size: u32,
+    checkpoint: Option<MigrationCheckpoint>,
+    on_batch: impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<MigrationReport, EngineError> {
+    migration::migrate(
+        &mut from.into(),
+        &mut to.into(),
+        batch_size,
+        checkpoint,
+        on_batch,
+    )
+}
+
+/**
+ * Encrypt again the bot envs, messages, memories, state and outbox messages of the
+ * database with the current key (ENCRYPTION_KEY_ID), so that the other secrets of
+ * ENCRYPTION_SECRETS and ENCRYPTION_SECRET can be removed afterwards.
+ *
+ * As with `migrate`, `on_batch` is called after each batch with the progress of the
+ * rotation, which can be resumed from the last checkpoint. Memories and state written
+ * while it runs are not overwritten.
+ */
+pub fn rotate_encryption_key(
+    batch_size: u32,
+    checkpoint: Option<MigrationCheckpoint>,
+    on_batch: impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<RotationReport, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    key_rotation::rotate_encryption_key(&mut db, batch_size, checkpoint, on_batch)
+}
+
+/**
+ * Create an API key of the server with the given scopes, for every bot or only the
+ * listed ones. The key is only returned here: only its hash is saved.
+ */
+pub fn create_api_key(request: &ApiKeyRequest) -> Result<CreatedApiKey, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    api_keys::create_api_key(request, &mut db)
+}
+
+/**
+ * Find the saved API key matching a key sent to the server
+ */
+pub fn get_api_key(key: &str) -> Result<Option<ApiKey>, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    api_keys::get_api_key(key, &mut db)
+}
+
+pub fn get_api_keys() -> Result<Vec<ApiKey>, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    db_connectors::api_keys::get_api_keys(&mut db)
+}
+
+/**
+ * Delete an API key, return false if there is no key with this id
+ */
+pub fn revoke_api_key(id: &str) -> Result<bool, EngineError> {
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(_) => return Ok(false),
+    };
+
+    let mut db = init_db()?;
+    init_logger();
+
+    db_connectors::api_keys::delete_api_key(id, &mut db)
+}
+
+/**
+ * Conversations of a bot started and ended in each flow, over the conversations created
+ * from `from_date` to `to_date` (timestamps in seconds, now by default)
+ */
+pub fn get_flow_analytics(
+    bot_id: &str,
+    from_date: i64,
+    to_date: Option<i64>,
+) -> Result<Vec<FlowAnalytics>, EngineError> {
+    let filter = analytics::get_filter(bot_id, from_date, to_date)?;
+    let mut db = init_db()?;
+    init_logger();
+
+    db_connectors::analytics::get_flow_analytics(&filter, &mut db)
+}
+
+/**
+ * Holds of each step of a bot: answered by the users, or abandoned by the open conversations
+ * without activity at this step for ANALYTICS_ABANDON_DELAY seconds, see `get_flow_analytics`
+ * for the period
+ */
+pub fn get_hold_analytics(
+    bot_id: &str,
+    from_date: i64,
+    to_date: Option<i64>,
+) -> Result<Vec<HoldAnalytics>, EngineError> {
+    let filter = analytics::get_filter(bot_id, from_date, to_date)?;
+    let mut db = init_db()?;
+    init_logger();
+
+    db_connectors::analytics::get_hold_analytics(&filter, &mut db)
+}
+
+/**
+ * Inputs of the users of a bot and their average by conversation,
+ * see `get_flow_analytics` for the period
+ */
+pub fn get_turn_analytics(
+    bot_id: &str,
+    from_date: i64,
+    to_date: Option<i64>,
+) -> Result<TurnAnalytics, EngineError> {
+    let filter = analytics::get_filter(bot_id, from_date, to_date)?;
+    let mut db = init_db()?;
+    init_logger();
+
+    db_connectors::analytics::get_turn_analytics(&filter, &mut db)
+}
+
+/**
+ * Transitions between the steps of a bot, the most frequent first,
+ * see `get_flow_analytics` for the period
+ */
+pub fn get_step_transitions(
+    bot_id: &str,
+    from_date: i64,
+    to_date: Option<i64>,
+) -> Result<Vec<StepTransition>, EngineError> {
+    let filter = analytics::get_filter(bot_id, from_date, to_date)?;
+    let mut db = init_db()?;
+    init_logger();
+
+    db_connectors::analytics::get_step_transitions(&filter, &mut db)
+}
+
 /**
  * List all the steps in every flow of a given CSML bot
  */
@@ -461,6 +734,26 @@ pub fn validate_bot(mut bot: CsmlBot) -> CsmlResult {
     csml_interpreter::validate_bot(&bot)
 }
 
+/**
+ * Validate the bot and compile it into a versioned artifact (see `BotArtifact`).
+ * The encoded artifact can be set as the `bot_ast` of the bot, so that its flows
+ * are not parsed again on each request.
+ */
+pub fn compile_bot(mut bot: CsmlBot) -> Result<BotArtifact, Vec<ErrorInfo>> {
+    // load native components into the bot
+    bot.native_components = match load_components() {
+        Ok(components) => Some(components),
+        Err(err) => return Err(vec![err]),
+    };
+
+    // search for modules to download
+    if let Err(err) = search_for_modules(&mut bot) {
+        return Err(vec![ErrorInfo::new(Position::default(), err)]);
+    }
+
+    BotArtifact::compile(&bot)
+}
+
 /**
  * fold CSML bot in one single flow.
  * Rename all existing steps, goto and functions in order to match their origin flow.
@@ -488,9 +781,47 @@ pub fn user_close_all_conversations(client: Client) -> Result<(), EngineError> {
     init_logger();
 
     state::delete_state_key(&client, "hold", "position", &mut db)?;
+    state::delete_state_key(&client, "hold", "interrupted", &mut db)?;
     conversations::close_all_conversations(&client, &mut db)
 }
 
+/**
+ * Load the hold interrupted by a flow that resumes it (see utils::search_flow), so that
+ * going back to its step holds again. It is dropped if its step was updated since.
+ */
+fn load_interrupted_hold(data: &mut ConversationInfo, bot: &CsmlBot) -> Result<(), EngineError> {
+    if bot.interruptions.is_none() {
+        return Ok(());
+    }
+
+    let hold = match state::get_state_key(&data.client, "hold", "interrupted", &mut data.db)? {
+        Some(hold) => hold,
+        None => return Ok(()),
+    };
+
+    let mut held_context = data.context.clone();
+    held_context.flow = hold["flow"].as_str().unwrap_or_default().to_owned();
+    held_context.step =
+        ContextStepInfo::UnknownFlow(hold["step"].as_str().unwrap_or_default().to_owned());
+
+    let hash = get_current_step_hash(&held_context, bot).ok();
+    match serde_json::from_value::<IndexInfo>(hold["index"].clone()) {
+        Ok(index) if hash.is_some() && hold["hash"].as_str() == hash.as_deref() => {
+            data.context.interrupted = Some(Hold {
+                index,
+                step_vars: hold["step_vars"].clone(),
+                step_name: held_context.step.get_step(),
+                flow_name: held_context.flow,
+                previous: serde_json::from_value(hold["previous"].clone()).unwrap_or(None),
+                secure: hold["secure"].as_bool().unwrap_or(false),
+            });
+        }
+        _ => state::delete_state_key(&data.client, "hold", "interrupted", &mut data.db)?,
+    }
+
+    Ok(())
+}
+
 /**
  * Verify if the user is currently on hold in a given conversation.
  *
@@ -500,6 +831,10 @@ pub fn user_close_all_conversations(client: Client) -> Result<(), EngineError> {
  *
  * If the hold is valid, we also need to load the local step memory
  * (context.hold.step_vars) into the conversation context.
+ *
+ * A hold interrupted by a flow triggered in the user's input was already removed
+ * while searching for that flow (see utils::search_flow). When the bot has a list of
+ * interruptions, the other triggered flows do not remove it and the input answers the hold.
  */
 fn check_for_hold(
     data: &mut ConversationInfo,
@@ -544,6 +879,11 @@ fn check_for_hold(
                 previous: serde_json::from_value(hold["previous"].clone()).unwrap_or(None),
                 secure: secure_hold,
             });
+            csml_metrics::record(csml_metrics::Metric::Resume {
+                bot_id: &bot.id,
+                flow: &data.context.flow,
+                step: &data.context.step.get_step(),
+            });
 
             state::delete_state_key(&data.client, "hold", "position", &mut data.db)?;
         }
@@ -597,10 +937,13 @@ pub fn get_status() -> Result<serde_json::Value, EngineError> {
         Err(_) => status.insert("server_auth_enabled".to_owned(), serde_json::json!(false)),
     };
 
-    match std::env::var("ENCRYPTION_SECRET") {
-        Ok(_) => status.insert("encryption_enabled".to_owned(), serde_json::json!(true)),
-        Err(_) => status.insert("encryption_enabled".to_owned(), serde_json::json!(false)),
-    };
+    status.insert(
+        "encryption_enabled".to_owned(),
+        serde_json::json!(crate::encrypt::is_encryption_enabled()),
+    );
+    if let Ok(Some(key_id)) = crate::encrypt::get_current_key_id() {
+        status.insert("encryption_key_id".to_owned(), serde_json::json!(key_id));
+    }
 
     match std::env::var("DEBUG") {
         Ok(_) => status.insert("debug_mode_enabled".to_owned(), serde_json::json!(true)),
@@ -638,3 +981,108 @@ pub fn delete_expired_data() -> Result<(), EngineError> {
 
     clean_db::delete_expired_data(&mut db)
 }
+
+/**
+ * Run the flow triggers planned with the `Schedule()` builtin whose date is passed,
+ * at most `limit` of them. Each schedule is claimed before it runs, by moving it to the
+ * time of its next try, so that it runs only once even if several engines run the due
+ * schedules at the same time. It is deleted once it succeeds, or after
+ * `SCHEDULE_MAX_ATTEMPTS` failed runs; otherwise it runs again at its next try.
+ * The bot of the request that planned the schedule is used, and the messages are sent
+ * to the callback_url of this request and returned with the run.
+ */
+pub fn run_due_schedules(limit: u32) -> Result<Vec<ScheduleRun>, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    let mut runs = vec![];
+
+    for schedule in schedules::get_due_schedules(limit, &mut db)? {
+        let retry_at = schedule.retry_at(Utc::now());
+        if !schedules::claim_schedule(&schedule, retry_at, &mut db)? {
+            continue;
+        }
+
+        let (request, bot_opt) = schedule.to_request();
+        let (messages, error) = match start_conversation(request, bot_opt) {
+            Ok(mut data) => match data.remove("messages") {
+                Some(serde_json::Value::Array(messages)) => (messages, None),
+                _ => (vec![], None),
+            },
+            Err(err) => (vec![], Some(format!("{:?}", err))),
+        };
+
+        let retry_at = match error {
+            Some(_) if schedule.attempts + 1 < SCHEDULE_MAX_ATTEMPTS => Some(retry_at),
+            _ => {
+                schedules::delete_schedule(schedule.id, &mut db)?;
+                None
+            }
+        };
+
+        runs.push(ScheduleRun {
+            schedule,
+            error,
+            retry_at,
+            messages,
+        });
+    }
+
+    Ok(runs)
+}
+
+/**
+ * Send again the messages kept in the outbox because their callback_url could not be
+ * reached, oldest first and at most `limit` of them (only for the given bot if any).
+ * Each message is tried once with its original delivery id and signature: delivered
+ * messages leave the outbox, the others stay there for the next redelivery. Once a
+ * message of a user fails, their next messages are left in the outbox to keep their order.
+ * Messages refused by their endpoint (see `send::is_retryable`) are dropped from the outbox.
+ */
+pub fn redeliver_callbacks(
+    bot_id: Option<&str>,
+    limit: u32,
+) -> Result<Vec<CallbackRedelivery>, EngineError> {
+    let mut db = init_db()?;
+    init_logger();
+
+    let mut redeliveries = vec![];
+    let mut failed_clients: Vec<Client> = vec![];
+
+    for mut message in outbox::get_outbox_messages(bot_id, limit, &mut db)? {
+        let result = if failed_clients.contains(&message.client) {
+            Err(send::DeliveryError {
+                error: ERROR_CALLBACK_ORDER.to_owned(),
+                retryable: true,
+            })
+        } else {
+            send::deliver(&mut message, 0)
+        };
+
+        match &result {
+            Ok(()) => {
+                outbox::delete_outbox_message(message.id, &mut db)?;
+            }
+            Err(_) if failed_clients.contains(&message.client) => {}
+            Err(err) if !err.retryable => {
+                outbox::delete_outbox_message(message.id, &mut db)?;
+            }
+            Err(_) => {
+                outbox::add_outbox_attempt(message.id, &mut db)?;
+                failed_clients.push(message.client.to_owned());
+            }
+        }
+
+        redeliveries.push(CallbackRedelivery {
+            id: message.id,
+            client: message.client,
+            callback_url: message.callback_url,
+            attempts: message.attempts,
+            delivered: result.is_ok(),
+            dropped: matches!(&result, Err(err) if !err.retryable),
+            error: result.err().map(|err| err.error),
+        });
+    }
+
+    Ok(redeliveries)
+}
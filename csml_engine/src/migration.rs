@@ -0,0 +1,133 @@
+/**
+ * Copy of all the data of a database into another one, to change of storage backend.
+ *
+ * Records are read from the source in batches ordered by id and saved as they are in the
+ * target: ids, dates, expiration dates and status are kept. Message payloads, memories,
+ * state and outbox payloads are decrypted when read and encrypted again when saved, so
+ * data coming from a backend that does not encrypt it (the memory store) is encrypted
+ * in one that does (SQL databases).
+ *
+ * Records that already exist in the target are skipped. The progress is reported after
+ * each batch with a `MigrationCheckpoint`: a migration that stopped can be started again
+ * from its last checkpoint, or from the beginning without duplicating anything.
+ */
+use crate::data::models::{MigratedRecords, MigrationCheckpoint, MigrationReport, RecordKind};
+use crate::data::storage::StorageBackend;
+use crate::record_batches::RecordBatches;
+use crate::{Database, EngineError};
+
+// conversations are copied before the messages that belong to them
+const RECORD_KINDS: [RecordKind; 7] = [
+    RecordKind::BotVersions,
+    RecordKind::Conversations,
+    RecordKind::Messages,
+    RecordKind::Memories,
+    RecordKind::State,
+    RecordKind::Schedules,
+    RecordKind::Outbox,
+];
+
+type WriteRecords<T> = fn(&mut dyn StorageBackend, &[T]) -> Result<usize, EngineError>;
+
+fn copy_records<T>(
+    from: &mut dyn StorageBackend,
+    to: &mut dyn StorageBackend,
+    mut batches: RecordBatches<T>,
+    write: WriteRecords<T>,
+    on_batch: &mut impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<MigratedRecords, EngineError> {
+    let mut copied = MigratedRecords::default();
+
+    while let Some(batch) = batches.next_batch(from)? {
+        copied.read += batch.len();
+        copied.created += write(to, &batch)?;
+
+        on_batch(&batches.checkpoint())?;
+    }
+
+    Ok(copied)
+}
+
+pub fn migrate(
+    from: &mut Database,
+    to: &mut Database,
+    batch_size: u32,
+    checkpoint: Option<MigrationCheckpoint>,
+    mut on_batch: impl FnMut(&MigrationCheckpoint) -> Result<(), EngineError>,
+) -> Result<MigrationReport, EngineError> {
+    let from = from.storage()?;
+    let to = to.storage()?;
+
+    let start = checkpoint.unwrap_or(MigrationCheckpoint {
+        kind: RECORD_KINDS[0],
+        after: None,
+    });
+
+    let mut report = MigrationReport::default();
+    for kind in RECORD_KINDS
+        .iter()
+        .copied()
+        .filter(|kind| *kind >= start.kind)
+    {
+        let after = match kind == start.kind {
+            true => start.after,
+            false => None,
+        };
+
+        let copied = match kind {
+            RecordKind::BotVersions => copy_records(
+                from,
+                to,
+                RecordBatches::bot_versions(after, batch_size),
+                |db, versions| db.add_bot_version_records(versions),
+                &mut on_batch,
+            ),
+            RecordKind::Conversations => copy_records(
+                from,
+                to,
+                RecordBatches::conversations(after, batch_size),
+                |db, conversations| db.add_conversation_records(conversations),
+                &mut on_batch,
+            ),
+            RecordKind::Messages => copy_records(
+                from,
+                to,
+                RecordBatches::messages(after, batch_size),
+                |db, messages| db.add_message_records(messages),
+                &mut on_batch,
+            ),
+            RecordKind::Memories => copy_records(
+                from,
+                to,
+                RecordBatches::memories(after, batch_size),
+                |db, memories| db.add_memory_records(memories),
+                &mut on_batch,
+            ),
+            RecordKind::State => copy_records(
+                from,
+                to,
+                RecordBatches::state(after, batch_size),
+                |db, state| db.add_state_records(state),
+                &mut on_batch,
+            ),
+            RecordKind::Schedules => copy_records(
+                from,
+                to,
+                RecordBatches::schedules(after, batch_size),
+                |db, schedules| db.add_schedule_records(schedules),
+                &mut on_batch,
+            ),
+            RecordKind::Outbox => copy_records(
+                from,
+                to,
+                RecordBatches::outbox(after, batch_size),
+                |db, messages| db.add_outbox_records(messages),
+                &mut on_batch,
+            ),
+        }?;
+
+        report.records.insert(kind, copied);
+    }
+
+    Ok(report)
+}
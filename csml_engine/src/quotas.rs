@@ -0,0 +1,241 @@
+/**
+ * Rate limits and quotas of the conversations. Each one is disabled unless its environment
+ * variable is set:
+ * - CLIENT_REQUESTS_PER_MINUTE / BOT_REQUESTS_PER_MINUTE: requests of a client / of every client of a bot
+ * - CLIENT_STEPS_PER_DAY / BOT_STEPS_PER_DAY: steps run for a client / for every client of a bot
+ * - HTTP_CALLS_PER_CONVERSATION: HTTP() calls of the bot in a conversation
+ *
+ * Requests over a limit are rejected with `EngineError::RateLimit` before being interpreted, and
+ * the step limit of the others is lowered to the steps left for the day. Once the HTTP() calls of
+ * a conversation reach the quota, the next ones fail like any other HTTP error.
+ *
+ * The counters are kept apart from the state of the clients (see `CounterStorage`), for each
+ * client and for a client with the bot id and an empty channel and user id for the limits of
+ * the bot. They count in fixed windows (the current minute or UTC day, or the conversation),
+ * and expire with them. A request is counted and its count is read in one atomic increment,
+ * so concurrent requests can not go over the requests per minute. The steps of a request are
+ * only known once it has run: concurrent requests can still go over the steps of the day.
+ */
+use crate::data::{ConversationInfo, Database, EngineError};
+use crate::db_connectors::counters::{get_counter, increment_counter};
+
+use chrono::Utc;
+use csml_interpreter::data::{csml_usage::Usage, Client, Event};
+use csml_interpreter::get_step_limit;
+use std::env;
+use uuid::Uuid;
+
+pub(crate) const MINUTE: i64 = 60;
+pub(crate) const DAY: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Quotas {
+    pub client_requests_per_minute: Option<u64>,
+    pub bot_requests_per_minute: Option<u64>,
+    pub client_steps_per_day: Option<u64>,
+    pub bot_steps_per_day: Option<u64>,
+    pub http_calls_per_conversation: Option<u64>,
+}
+
+/**
+ * Counter of a quota in a window: a minute or day start timestamp, or a conversation id
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Counter {
+    pub key: String,
+    pub ttl: Option<chrono::Duration>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+// the request is not counted for any client once one of them is over its limit
+fn uncount_requests(
+    counted: &[Client],
+    counter: &Counter,
+    db: &mut Database,
+) -> Result<(), EngineError> {
+    for client in counted {
+        increment_counter(client, &counter.key, -1, counter.ttl, db)?;
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl Quotas {
+    pub fn from_env() -> Self {
+        let var = |name: &str| {
+            env::var(name)
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        Self {
+            client_requests_per_minute: var("CLIENT_REQUESTS_PER_MINUTE"),
+            bot_requests_per_minute: var("BOT_REQUESTS_PER_MINUTE"),
+            client_steps_per_day: var("CLIENT_STEPS_PER_DAY"),
+            bot_steps_per_day: var("BOT_STEPS_PER_DAY"),
+            http_calls_per_conversation: var("HTTP_CALLS_PER_CONVERSATION"),
+        }
+    }
+
+    /**
+     * The client itself, and the client keeping the counters of its bot, with their
+     * requests per minute and steps per day limits
+     */
+    pub(crate) fn clients(&self, client: &Client) -> Vec<(Client, Option<u64>, Option<u64>)> {
+        vec![
+            (
+                client.to_owned(),
+                self.client_requests_per_minute,
+                self.client_steps_per_day,
+            ),
+            (
+                Client::new(client.bot_id.to_owned(), String::new(), String::new()),
+                self.bot_requests_per_minute,
+                self.bot_steps_per_day,
+            ),
+        ]
+    }
+}
+
+impl Counter {
+    /**
+     * Counter of the current fixed window of `length` seconds, expiring at its end
+     */
+    pub fn window(name: &str, length: i64) -> Self {
+        let now = Utc::now().timestamp();
+        let elapsed = now.rem_euclid(length);
+
+        Self {
+            key: format!("{}:{}", name, now - elapsed),
+            ttl: Some(chrono::Duration::seconds(length - elapsed)),
+        }
+    }
+
+    /**
+     * Counter of a conversation, expiring with it
+     */
+    pub fn conversation(name: &str, conversation_id: Uuid, ttl: Option<chrono::Duration>) -> Self {
+        Self {
+            key: format!("{}:{}", name, conversation_id),
+            ttl,
+        }
+    }
+}
+
+// counts are never negative, the decrements of `uncount_requests` follow their increments
+pub(crate) fn as_count(value: i64) -> u64 {
+    value.max(0) as u64
+}
+
+pub(crate) fn limit_reached(client: &Client, limit: &str) -> EngineError {
+    let owner = match client.user_id.is_empty() {
+        true => "bot",
+        false => "client",
+    };
+
+    EngineError::RateLimit(format!("{} {} limit reached", owner, limit))
+}
+
+/**
+ * Reject the request of the client when it is over a limit, otherwise count it and lower
+ * the step limit of its event to the steps left for the day
+ */
+pub fn check_quotas(
+    client: &Client,
+    event: &mut Event,
+    db: &mut Database,
+) -> Result<(), EngineError> {
+    let quotas = Quotas::from_env();
+    let (requests, steps) = (
+        Counter::window("requests", MINUTE),
+        Counter::window("steps", DAY),
+    );
+
+    let mut step_limit = None;
+    for (client, _, steps_limit) in quotas.clients(client) {
+        if let Some(limit) = steps_limit {
+            let count = as_count(get_counter(&client, &steps.key, db)?);
+            if count >= limit {
+                return Err(limit_reached(&client, "steps per day"));
+            }
+
+            let left = (limit - count) as usize;
+            step_limit = Some(step_limit.map_or(left, |step_limit: usize| step_limit.min(left)));
+        }
+    }
+
+    let mut counted = vec![];
+    for (client, requests_limit, _) in quotas.clients(client) {
+        if let Some(limit) = requests_limit {
+            counted.push(client.to_owned());
+            let count = increment_counter(&client, &requests.key, 1, requests.ttl, db)?;
+
+            // rejected requests are not counted
+            if as_count(count) > limit {
+                uncount_requests(&counted, &requests, db)?;
+                return Err(limit_reached(&client, "requests per minute"));
+            }
+        }
+    }
+
+    if let Some(step_limit) = step_limit {
+        event.step_limit = Some(get_step_limit(event).min(step_limit));
+    }
+
+    Ok(())
+}
+
+/**
+ * Limit the HTTP() calls of the event to the ones left in the conversation
+ */
+pub fn set_http_limit(data: &mut ConversationInfo, event: &mut Event) -> Result<(), EngineError> {
+    if let Some(limit) = Quotas::from_env().http_calls_per_conversation {
+        let calls = Counter::conversation("http_calls", data.conversation_id, data.ttl);
+        let count = as_count(get_counter(&data.client, &calls.key, &mut data.db)?);
+
+        event.http_limit = Some(limit.saturating_sub(count) as usize);
+    }
+
+    Ok(())
+}
+
+/**
+ * Count the steps and HTTP() calls of an interpretation, see `csml_usage::get`
+ */
+pub fn add_usage(data: &mut ConversationInfo, usage: Usage) -> Result<(), EngineError> {
+    let quotas = Quotas::from_env();
+    let steps = Counter::window("steps", DAY);
+
+    for (client, _, steps_limit) in quotas.clients(&data.client) {
+        if steps_limit.is_some() && usage.steps > 0 {
+            increment_counter(
+                &client,
+                &steps.key,
+                usage.steps as i64,
+                steps.ttl,
+                &mut data.db,
+            )?;
+        }
+    }
+
+    if quotas.http_calls_per_conversation.is_some() && usage.http_calls > 0 {
+        let calls = Counter::conversation("http_calls", data.conversation_id, data.ttl);
+
+        increment_counter(
+            &data.client,
+            &calls.key,
+            usage.http_calls as i64,
+            calls.ttl,
+            &mut data.db,
+        )?;
+    }
+
+    Ok(())
+}
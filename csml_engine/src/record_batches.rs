@@ -0,0 +1,162 @@
+/**
+ * Reading of all the records of a kind in batches ordered by id, shared by `migrate` and
+ * `rotate_encryption_key`.
+ *
+ * The id of the last record of each batch is the `after` id of the next one, and the
+ * progress is reported with a `MigrationCheckpoint` that can be given back to start again
+ * from there.
+ */
+use crate::data::models::{
+    BotVersionRecord, Conversation, MemoryRecord, Message, MigrationCheckpoint, OutboxMessage,
+    RecordKind, Schedule, StateRecord,
+};
+use crate::data::storage::StorageBackend;
+use crate::EngineError;
+use uuid::Uuid;
+
+type ReadRecords<T> = fn(&mut dyn StorageBackend, Option<Uuid>, u32) -> Result<Vec<T>, EngineError>;
+
+pub struct RecordBatches<T> {
+    kind: RecordKind,
+    read: ReadRecords<T>,
+    id: fn(&T) -> Uuid,
+    after: Option<Uuid>,
+    batch_size: u32,
+    done: bool,
+}
+
+impl<T> RecordBatches<T> {
+    fn new(
+        kind: RecordKind,
+        read: ReadRecords<T>,
+        id: fn(&T) -> Uuid,
+        after: Option<Uuid>,
+        batch_size: u32,
+    ) -> Self {
+        Self {
+            kind,
+            read,
+            id,
+            after,
+            batch_size: batch_size.max(1),
+            done: false,
+        }
+    }
+
+    /**
+     * Next batch of records, `None` once they have all been read
+     */
+    pub fn next_batch(
+        &mut self,
+        db: &mut dyn StorageBackend,
+    ) -> Result<Option<Vec<T>>, EngineError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let batch = (self.read)(db, self.after, self.batch_size)?;
+        let last = match batch.last() {
+            Some(last) => (self.id)(last),
+            None => return Ok(None),
+        };
+
+        self.after = Some(last);
+        self.done = batch.len() < self.batch_size as usize;
+
+        Ok(Some(batch))
+    }
+
+    /**
+     * Progress after the last batch read
+     */
+    pub fn checkpoint(&self) -> MigrationCheckpoint {
+        MigrationCheckpoint {
+            kind: self.kind,
+            after: self.after,
+        }
+    }
+}
+
+impl RecordBatches<BotVersionRecord> {
+    pub fn bot_versions(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::BotVersions,
+            |db, after, limit| db.get_bot_version_records(after, limit),
+            |version| version.id,
+            after,
+            batch_size,
+        )
+    }
+}
+
+impl RecordBatches<Conversation> {
+    pub fn conversations(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::Conversations,
+            |db, after, limit| db.get_conversation_records(after, limit),
+            |conversation| conversation.id,
+            after,
+            batch_size,
+        )
+    }
+}
+
+impl RecordBatches<Message> {
+    pub fn messages(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::Messages,
+            |db, after, limit| db.get_message_records(after, limit),
+            |message| message.id,
+            after,
+            batch_size,
+        )
+    }
+}
+
+impl RecordBatches<MemoryRecord> {
+    pub fn memories(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::Memories,
+            |db, after, limit| db.get_memory_records(after, limit),
+            |memory| memory.id,
+            after,
+            batch_size,
+        )
+    }
+}
+
+impl RecordBatches<StateRecord> {
+    pub fn state(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::State,
+            |db, after, limit| db.get_state_records(after, limit),
+            |state| state.id,
+            after,
+            batch_size,
+        )
+    }
+}
+
+impl RecordBatches<Schedule> {
+    pub fn schedules(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::Schedules,
+            |db, after, limit| db.get_schedule_records(after, limit),
+            |schedule| schedule.id,
+            after,
+            batch_size,
+        )
+    }
+}
+
+impl RecordBatches<OutboxMessage> {
+    pub fn outbox(after: Option<Uuid>, batch_size: u32) -> Self {
+        Self::new(
+            RecordKind::Outbox,
+            |db, after, limit| db.get_outbox_records(after, limit),
+            |message| message.id,
+            after,
+            batch_size,
+        )
+    }
+}
@@ -1,21 +1,136 @@
+use crate::data::models::OutboxMessage;
 use crate::data::ConversationInfo;
+use crate::db_connectors::outbox::add_outbox_message;
+use csml_interpreter::data::csml_metrics::{self, Metric};
+use csml_interpreter::data::csml_otel;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::{env, sync::mpsc, thread, time::Duration};
 
-fn format_and_transfer(callback_url: &str, msg: serde_json::Value) {
-    let mut request = ureq::post(callback_url);
+pub const SIGNATURE_HEADER: &str = "X-Csml-Signature";
+pub const DELIVERY_ID_HEADER: &str = "X-Csml-Delivery-Id";
+
+const CALLBACK_RETRIES: &str = "CALLBACK_RETRIES";
+const CALLBACK_RETRY_DELAY: &str = "CALLBACK_RETRY_DELAY";
+
+/**
+ * Number of times a failed delivery is retried before the message is kept in the outbox
+ */
+pub fn callback_retries() -> u32 {
+    env::var(CALLBACK_RETRIES)
+        .ok()
+        .and_then(|retries| retries.parse().ok())
+        .unwrap_or(2)
+}
+
+/**
+ * Delay before the given retry (starting at 0), doubled after each attempt
+ */
+pub fn retry_delay(retry: u32) -> Duration {
+    let delay = env::var(CALLBACK_RETRY_DELAY)
+        .ok()
+        .and_then(|delay| delay.parse().ok())
+        .unwrap_or(100);
+
+    Duration::from_millis(delay) * 2u32.saturating_pow(retry)
+}
+
+/**
+ * HMAC-SHA256 of the request body with the bot's callback_secret, sent in the X-Csml-Signature header
+ */
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take a key of any size");
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/**
+ * Timeouts, rate limits and server errors are worth trying again, other statuses are not
+ */
+pub fn is_retryable(status: u16) -> bool {
+    status == 408 || status == 429 || status >= 500
+}
+
+/**
+ * Failed delivery of a message. It is not `retryable` when the endpoint refused it
+ * (4xx status other than a timeout or rate limit): it would be refused again.
+ */
+#[derive(Debug)]
+pub struct DeliveryError {
+    pub error: String,
+    pub retryable: bool,
+}
+
+fn format_and_transfer(message: &OutboxMessage, body: &str) -> Result<(), DeliveryError> {
+    let mut span = csml_otel::span("csml.callback", &[("url.full", &message.callback_url)]);
+    let mut request = ureq::post(&message.callback_url);
 
     request = request
         .set("Accept", "application/json")
-        .set("Content-Type", "application/json");
+        .set("Content-Type", "application/json")
+        .set(DELIVERY_ID_HEADER, &message.id.to_string());
+    if let Some(signature) = &message.signature {
+        request = request.set(SIGNATURE_HEADER, signature);
+    }
+    for (key, value) in span.propagation_headers() {
+        request = request.set(&key, &value);
+    }
+
+    match request.send_string(body) {
+        Ok(response) => {
+            span.set_http_status(response.status());
+            Ok(())
+        }
+        Err(ureq::Error::Status(status, _)) => {
+            span.set_http_status(status);
+            Err(DeliveryError {
+                error: format!("status code {}", status),
+                retryable: is_retryable(status),
+            })
+        }
+        Err(err) => {
+            span.set_error(&err.to_string());
+            Err(DeliveryError {
+                error: err.to_string(),
+                retryable: true,
+            })
+        }
+    }
+}
 
-    let response = request.send_json(msg);
+/**
+ * Send the message to its callback_url, retrying up to `retries` times with an exponential backoff
+ */
+pub fn deliver(message: &mut OutboxMessage, retries: u32) -> Result<(), DeliveryError> {
+    let body = message.payload.to_string();
 
-    if let Err(err) = response {
-        eprintln!("callback_url call failed: {:?}", err.to_string());
+    let mut retry = 0;
+    loop {
+        message.attempts += 1;
+        match format_and_transfer(message, &body) {
+            Ok(()) => return Ok(()),
+            Err(err) if !err.retryable || retry == retries => {
+                csml_metrics::record(Metric::CallbackFailure {
+                    bot_id: &message.client.bot_id,
+                });
+                return Err(err);
+            }
+            Err(_) => {}
+        }
+
+        thread::sleep(retry_delay(retry));
+        retry += 1;
     }
 }
 
 /**
  * If a callback_url is defined, we must send each message to its endpoint as it comes.
+ * Messages that can not be delivered are kept in the outbox until they are redelivered,
+ * along with every following message of the client so that their order is preserved:
+ * while older messages of the client wait in the outbox, new ones are added after them.
+ * Messages refused by the endpoint are dropped, they would block the next ones.
  * Otherwise, just continue!
  */
 pub fn send_to_callback_url(c_info: &mut ConversationInfo, msg: serde_json::Value) {
@@ -24,5 +139,175 @@ pub fn send_to_callback_url(c_info: &mut ConversationInfo, msg: serde_json::Valu
         None => return,
     };
 
-    format_and_transfer(callback_url, msg)
+    let mut message = OutboxMessage::new(
+        &c_info.client,
+        callback_url,
+        msg,
+        c_info.callback_secret.as_deref(),
+    );
+
+    if !c_info.callback_failed {
+        match deliver(&mut message, callback_retries()) {
+            Ok(()) => return,
+            Err(err) if !err.retryable => {
+                eprintln!("callback_url refused the message, dropped: {:?}", err.error);
+                return;
+            }
+            Err(err) => {
+                eprintln!("callback_url call failed: {:?}", err.error);
+                c_info.callback_failed = true;
+            }
+        }
+    }
+
+    if let Err(err) = add_outbox_message(&message, &mut c_info.db) {
+        eprintln!(
+            "callback message could not be saved to the outbox: {:?}",
+            err
+        );
+    }
+}
+
+/**
+ * If the request is streamed, each message is also sent to the stream as it comes.
+ */
+pub fn send_to_stream(stream: Option<&mpsc::Sender<serde_json::Value>>, msg: &serde_json::Value) {
+    let stream = match stream {
+        Some(stream) => stream,
+        None => return,
+    };
+
+    for message in msg["messages"].as_array().into_iter().flatten() {
+        // the receiver is dropped if the client closed the stream, the request still completes
+        let _ = stream.send(message.to_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csml_interpreter::data::Client;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    // headers and body of a received request
+    type Request = (Vec<String>, String);
+
+    // answer each request with the next status, and return every request received
+    fn callback_stub(statuses: Vec<u16>) -> (String, thread::JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/callback", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = vec![];
+
+            for status in statuses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut headers = vec![];
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end().to_owned();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    headers.push(line);
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                requests.push((headers, String::from_utf8(body).unwrap()));
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} STUB\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+            }
+
+            requests
+        });
+
+        (url, handle)
+    }
+
+    fn header<'a>(headers: &'a [String], name: &str) -> Option<&'a str> {
+        headers.iter().find_map(|header| {
+            let (key, value) = header.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    fn get_message(callback_url: &str) -> OutboxMessage {
+        let client = Client::new(
+            "bot_id".to_owned(),
+            "channel_id".to_owned(),
+            "user_id".to_owned(),
+        );
+
+        OutboxMessage::new(
+            &client,
+            callback_url,
+            serde_json::json!({"messages": [{"payload": "hello"}]}),
+            Some("secret"),
+        )
+    }
+
+    #[test]
+    fn ok_sign_payload() {
+        assert_eq!(
+            sign_payload("key", "The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn ok_deliver_after_retries() {
+        let (url, stub) = callback_stub(vec![503, 429, 200]);
+        let mut message = get_message(&url);
+
+        assert!(deliver(&mut message, 2).is_ok());
+        assert_eq!(message.attempts, 3);
+
+        let requests = stub.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        for (headers, body) in requests {
+            assert_eq!(body, message.payload.to_string());
+            assert_eq!(
+                header(&headers, SIGNATURE_HEADER),
+                Some(sign_payload("secret", &body).as_str())
+            );
+            assert_eq!(
+                header(&headers, DELIVERY_ID_HEADER),
+                Some(message.id.to_string().as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn ko_deliver_not_retryable() {
+        let (url, stub) = callback_stub(vec![400]);
+        let mut message = get_message(&url);
+
+        assert!(!deliver(&mut message, 2).unwrap_err().retryable);
+        assert_eq!(message.attempts, 1);
+        assert_eq!(stub.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn ko_deliver_retries_exhausted() {
+        let (url, stub) = callback_stub(vec![500, 500]);
+        let mut message = get_message(&url);
+
+        assert!(deliver(&mut message, 1).unwrap_err().retryable);
+        assert_eq!(message.attempts, 2);
+        assert_eq!(stub.join().unwrap().len(), 2);
+    }
 }
@@ -0,0 +1,521 @@
+/**
+ * Matching of user inputs to the commands of the bot's flows.
+ *
+ * A flow is always triggered when one of its commands is exactly the user input
+ * (ignoring case). When no command matches exactly, the input is given to the
+ * configured `TriggerMatcher`, which returns a confidence score for the flows it
+ * considers a match:
+ * - `ExactMatcher` (default) never matches anything else,
+ * - `FuzzyMatcher` tolerates typos, accents and punctuation, each extra word of the
+ *   input lowering its score,
+ * - `ClassifierMatcher` asks an external NLU service (any `IntentClassifier`)
+ *   for the intent of the input, and maps intents to flow names or commands.
+ *
+ * When several flows match, `choose_flow` starts the one with the highest priority.
+ *
+ * A bot chooses its matcher with its `trigger_matcher` (`exact` or `fuzzy`, with an
+ * optional threshold). The bots that do not set it use the engine's default matcher,
+ * configured with `FLOW_TRIGGER_MATCHER` (`exact` or `fuzzy`) and `FLOW_TRIGGER_THRESHOLD`
+ * (0.75 by default), or set with `set_trigger_matcher`.
+ */
+use crate::{data::EngineError, CsmlBot, CsmlFlow};
+use csml_interpreter::data::TriggerMatcherConfig;
+
+use std::env;
+use std::sync::{Arc, OnceLock, RwLock};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+pub const DEFAULT_TRIGGER_THRESHOLD: f64 = 0.75;
+
+// words shorter than this must be spelled exactly
+const MIN_FUZZY_LENGTH: usize = 4;
+
+static TRIGGER_MATCHER: OnceLock<RwLock<Arc<dyn TriggerMatcher>>> = OnceLock::new();
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone)]
+pub struct FlowMatch<'a> {
+    pub flow: &'a CsmlFlow,
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Intent {
+    pub name: String,
+    pub confidence: f64,
+}
+
+pub trait TriggerMatcher: Send + Sync {
+    /**
+     * Flows triggered by the user input, with their confidence (between 0 and 1).
+     * Only the matches above the matcher's threshold are returned.
+     */
+    fn match_flows<'a>(
+        &self,
+        input: &str,
+        flows: &'a [CsmlFlow],
+    ) -> Result<Vec<FlowMatch<'a>>, EngineError>;
+}
+
+/**
+ * External NLU service detecting the intent of a user input
+ */
+pub trait IntentClassifier: Send + Sync {
+    fn classify(&self, input: &str) -> Result<Vec<Intent>, EngineError>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExactMatcher;
+
+#[derive(Debug, Clone)]
+pub struct FuzzyMatcher {
+    pub threshold: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassifierMatcher<C: IntentClassifier> {
+    pub classifier: C,
+    pub threshold: f64,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_threshold() -> f64 {
+    match env::var("FLOW_TRIGGER_THRESHOLD") {
+        Ok(threshold) => threshold
+            .parse::<f64>()
+            .unwrap_or(DEFAULT_TRIGGER_THRESHOLD),
+        Err(_) => DEFAULT_TRIGGER_THRESHOLD,
+    }
+}
+
+fn default_matcher() -> Arc<dyn TriggerMatcher> {
+    match env::var("FLOW_TRIGGER_MATCHER").as_deref() {
+        Ok("fuzzy") => Arc::new(FuzzyMatcher::new(get_threshold())),
+        _ => Arc::new(ExactMatcher),
+    }
+}
+
+fn matcher() -> &'static RwLock<Arc<dyn TriggerMatcher>> {
+    TRIGGER_MATCHER.get_or_init(|| RwLock::new(default_matcher()))
+}
+
+/**
+ * Matcher of the bot's `trigger_matcher`, or the engine's default one
+ */
+fn bot_matcher(bot: &CsmlBot) -> Arc<dyn TriggerMatcher> {
+    match &bot.trigger_matcher {
+        Some(TriggerMatcherConfig::Exact) => Arc::new(ExactMatcher),
+        Some(TriggerMatcherConfig::Fuzzy { threshold }) => {
+            Arc::new(FuzzyMatcher::new(threshold.unwrap_or_else(get_threshold)))
+        }
+        None => match matcher().read() {
+            Ok(current) => current.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        },
+    }
+}
+
+fn word_similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+
+    if a.chars().count().min(b.chars().count()) < MIN_FUZZY_LENGTH {
+        return 0.0;
+    }
+
+    strsim::normalized_damerau_levenshtein(a, b)
+}
+
+/**
+ * Jaccard similarity of the words of the input and of the command, each word of the
+ * command counting for its best similarity with a word of the input: the words of
+ * the input that are not in the command lower the score as much as the missing ones.
+ */
+fn token_overlap(input: &[&str], command: &[&str]) -> f64 {
+    let matched: f64 = command
+        .iter()
+        .map(|command_word| {
+            input
+                .iter()
+                .map(|input_word| word_similarity(input_word, command_word))
+                .fold(0.0, f64::max)
+        })
+        .sum::<f64>()
+        .min(input.len() as f64);
+
+    matched / ((input.len() + command.len()) as f64 - matched)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Lowercase, remove accents and punctuation, and collapse whitespaces
+ */
+pub fn normalize(text: &str) -> String {
+    let folded: String = text
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    folded.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/**
+ * Confidence that a user input means the given command, between 0 and 1
+ */
+pub fn command_similarity(input: &str, command: &str) -> f64 {
+    let input = normalize(input);
+    let command = normalize(command);
+
+    if input.is_empty() || command.is_empty() {
+        return 0.0;
+    }
+
+    let input_words: Vec<&str> = input.split(' ').collect();
+    let command_words: Vec<&str> = command.split(' ').collect();
+
+    word_similarity(&input, &command).max(token_overlap(&input_words, &command_words))
+}
+
+pub fn is_exact_command(input: &str, flow: &CsmlFlow) -> bool {
+    flow.commands
+        .iter()
+        .any(|cmd| cmd.to_lowercase() == input.to_lowercase())
+}
+
+/**
+ * Flow to start among the ones matching a user input: the one with the highest
+ * priority, or the first one declared in the bot if several share it
+ */
+pub fn choose_flow<'a>(flows: &[&'a CsmlFlow]) -> Option<&'a CsmlFlow> {
+    flows.iter().copied().reduce(|chosen, flow| {
+        if flow.priority > chosen.priority {
+            flow
+        } else {
+            chosen
+        }
+    })
+}
+
+/**
+ * Replace the engine's default trigger matcher, used by the bots without `trigger_matcher`
+ */
+pub fn set_trigger_matcher<M: TriggerMatcher + 'static>(new_matcher: M) {
+    match matcher().write() {
+        Ok(mut current) => *current = Arc::new(new_matcher),
+        Err(poisoned) => *poisoned.into_inner() = Arc::new(new_matcher),
+    }
+}
+
+/**
+ * Flows of the bot that the user input triggers with the best confidence: exact
+ * command matches if any, otherwise the best matches of the bot's matcher.
+ */
+pub fn find_triggered_flows<'a>(
+    input: &str,
+    bot: &'a CsmlBot,
+) -> Result<Vec<&'a CsmlFlow>, EngineError> {
+    find_flows_with(bot_matcher(bot).as_ref(), input, &bot.flows)
+}
+
+pub fn find_flows_with<'a>(
+    matcher: &dyn TriggerMatcher,
+    input: &str,
+    flows: &'a [CsmlFlow],
+) -> Result<Vec<&'a CsmlFlow>, EngineError> {
+    let exact: Vec<&CsmlFlow> = flows
+        .iter()
+        .filter(|flow| is_exact_command(input, flow))
+        .collect();
+    if !exact.is_empty() {
+        return Ok(exact);
+    }
+
+    let matches = matcher.match_flows(input, flows)?;
+    let best = matches
+        .iter()
+        .map(|flow_match| flow_match.confidence)
+        .fold(0.0, f64::max);
+
+    Ok(matches
+        .into_iter()
+        .filter(|flow_match| flow_match.confidence >= best)
+        .map(|flow_match| flow_match.flow)
+        .collect())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl TriggerMatcher for ExactMatcher {
+    fn match_flows<'a>(
+        &self,
+        input: &str,
+        flows: &'a [CsmlFlow],
+    ) -> Result<Vec<FlowMatch<'a>>, EngineError> {
+        Ok(flows
+            .iter()
+            .filter(|flow| is_exact_command(input, flow))
+            .map(|flow| FlowMatch {
+                flow,
+                confidence: 1.0,
+            })
+            .collect())
+    }
+}
+
+impl FuzzyMatcher {
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold }
+    }
+}
+
+impl Default for FuzzyMatcher {
+    fn default() -> Self {
+        Self::new(DEFAULT_TRIGGER_THRESHOLD)
+    }
+}
+
+impl TriggerMatcher for FuzzyMatcher {
+    fn match_flows<'a>(
+        &self,
+        input: &str,
+        flows: &'a [CsmlFlow],
+    ) -> Result<Vec<FlowMatch<'a>>, EngineError> {
+        Ok(flows
+            .iter()
+            .filter_map(|flow| {
+                let confidence = flow
+                    .commands
+                    .iter()
+                    .map(|cmd| command_similarity(input, cmd))
+                    .fold(0.0, f64::max);
+
+                (confidence >= self.threshold).then_some(FlowMatch { flow, confidence })
+            })
+            .collect())
+    }
+}
+
+impl<C: IntentClassifier> ClassifierMatcher<C> {
+    pub fn new(classifier: C, threshold: f64) -> Self {
+        Self {
+            classifier,
+            threshold,
+        }
+    }
+}
+
+impl<C: IntentClassifier> TriggerMatcher for ClassifierMatcher<C> {
+    /**
+     * An intent triggers the flows named after it or having it as a command
+     */
+    fn match_flows<'a>(
+        &self,
+        input: &str,
+        flows: &'a [CsmlFlow],
+    ) -> Result<Vec<FlowMatch<'a>>, EngineError> {
+        let intents = self.classifier.classify(input)?;
+
+        Ok(flows
+            .iter()
+            .filter_map(|flow| {
+                let confidence = intents
+                    .iter()
+                    .filter(|intent| {
+                        flow.name.to_lowercase() == intent.name.to_lowercase()
+                            || is_exact_command(&intent.name, flow)
+                    })
+                    .map(|intent| intent.confidence)
+                    .fold(0.0, f64::max);
+
+                (confidence >= self.threshold).then_some(FlowMatch { flow, confidence })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flows() -> Vec<CsmlFlow> {
+        vec![
+            CsmlFlow::new("1", "help", "", vec!["help".to_owned()]),
+            CsmlFlow::new("2", "agent", "", vec!["talk to an agent".to_owned()]),
+            CsmlFlow::new("3", "cafe", "", vec!["Café".to_owned(), "hi".to_owned()]),
+        ]
+    }
+
+    fn names(flows: Vec<&CsmlFlow>) -> Vec<&str> {
+        flows.iter().map(|flow| flow.name.as_str()).collect()
+    }
+
+    struct StaticClassifier(Vec<Intent>);
+
+    impl IntentClassifier for StaticClassifier {
+        fn classify(&self, _input: &str) -> Result<Vec<Intent>, EngineError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn normalize_folds_accents_and_punctuation() {
+        assert_eq!(normalize("  Ça VA, Café?! "), "ca va cafe");
+    }
+
+    #[test]
+    fn exact_matcher_keeps_exact_commands_only() {
+        let flows = flows();
+
+        assert_eq!(
+            names(find_flows_with(&ExactMatcher, "HELP", &flows).unwrap()),
+            ["help"]
+        );
+        assert!(find_flows_with(&ExactMatcher, "helo", &flows)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn fuzzy_matcher() {
+        let flows = flows();
+        let matcher = FuzzyMatcher::default();
+
+        for (input, flow) in [
+            ("helo", "help"),
+            ("Help!", "help"),
+            ("cafe", "cafe"),
+            ("talk to an agnet", "agent"),
+        ] {
+            assert_eq!(
+                names(find_flows_with(&matcher, input, &flows).unwrap()),
+                [flow],
+                "{}",
+                input
+            );
+        }
+
+        // short words, unrelated inputs and commands lost in longer inputs are not matched
+        for input in [
+            "ho",
+            "this is high",
+            "goodbye",
+            "I need help please",
+            "I want to talk to an agnet",
+        ] {
+            assert!(
+                find_flows_with(&matcher, input, &flows).unwrap().is_empty(),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn choose_flow_by_priority_then_order() {
+        let mut flows = vec![
+            CsmlFlow::new("1", "first", "", vec!["go".to_owned()]),
+            CsmlFlow::new("2", "second", "", vec!["go".to_owned()]),
+            CsmlFlow::new("3", "third", "", vec!["go".to_owned()]),
+        ];
+
+        let matching = find_flows_with(&ExactMatcher, "go", &flows).unwrap();
+        assert_eq!(choose_flow(&matching).unwrap().name, "first");
+
+        flows[2].priority = 1;
+        let matching = find_flows_with(&ExactMatcher, "go", &flows).unwrap();
+        assert_eq!(choose_flow(&matching).unwrap().name, "third");
+
+        assert!(choose_flow(&[]).is_none());
+    }
+
+    #[test]
+    fn fuzzy_matcher_threshold() {
+        let flows = flows();
+
+        assert!(find_flows_with(&FuzzyMatcher::new(0.9), "helo", &flows)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            names(find_flows_with(&FuzzyMatcher::new(0.25), "I need help please", &flows).unwrap()),
+            ["help"]
+        );
+    }
+
+    #[test]
+    fn extra_words_lower_the_similarity() {
+        assert_eq!(command_similarity("help please", "help"), 0.5);
+        assert!(
+            command_similarity("I need help please", "help")
+                < command_similarity("help please", "help")
+        );
+        assert!(command_similarity("help help", "help") <= 1.0);
+    }
+
+    #[test]
+    fn matcher_of_the_bot() {
+        let mut bot = CsmlBot::new(
+            "bot",
+            "bot",
+            None,
+            flows(),
+            None,
+            None,
+            "help",
+            None,
+            None,
+            None,
+            None,
+            None,
+        );
+
+        bot.trigger_matcher = Some(TriggerMatcherConfig::Exact);
+        assert!(find_triggered_flows("helo", &bot).unwrap().is_empty());
+
+        bot.trigger_matcher = Some(TriggerMatcherConfig::Fuzzy { threshold: None });
+        assert_eq!(names(find_triggered_flows("helo", &bot).unwrap()), ["help"]);
+
+        bot.trigger_matcher = Some(TriggerMatcherConfig::Fuzzy {
+            threshold: Some(0.9),
+        });
+        assert!(find_triggered_flows("helo", &bot).unwrap().is_empty());
+    }
+
+    #[test]
+    fn classifier_matcher() {
+        let flows = flows();
+        let matcher = ClassifierMatcher::new(
+            StaticClassifier(vec![
+                Intent {
+                    name: "Agent".to_owned(),
+                    confidence: 0.6,
+                },
+                Intent {
+                    name: "help".to_owned(),
+                    confidence: 0.9,
+                },
+            ]),
+            0.5,
+        );
+
+        let matches = matcher.match_flows("I'm lost", &flows).unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(
+            names(find_flows_with(&matcher, "I'm lost", &flows).unwrap()),
+            ["help"]
+        );
+    }
+}
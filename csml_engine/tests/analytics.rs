@@ -0,0 +1,144 @@
+#![cfg(any(
+    feature = "memory",
+    feature = "sqlite",
+    feature = "postgresql",
+    feature = "mongo"
+))]
+
+use chrono::Utc;
+use csml_engine::data::models::{
+    BotOpt, CsmlRequest, FlowAnalytics, HoldAnalytics, StepTransition, TurnAnalytics,
+};
+use csml_engine::{
+    get_flow_analytics, get_hold_analytics, get_step_transitions, get_turn_analytics,
+    make_migrations, start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+use uuid::Uuid;
+
+fn init_bot(bot_id: &str) -> CsmlBot {
+    CsmlBot {
+        id: bot_id.to_owned(),
+        name: bot_id.to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new(
+            "Default",
+            "Default",
+            "start:\n  say \"What is your name?\"\n  hold\n  goto bye\n\nbye:\n  say \"Bye\"\n  goto end",
+            vec![],
+        )],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    }
+}
+
+fn run(bot_id: &str, user_id: &str) {
+    let request = CsmlRequest {
+        request_id: "analytics".to_owned(),
+        client: Client::new(bot_id.to_owned(), "channel".to_owned(), user_id.to_owned()),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": "hello" },
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    };
+
+    start_conversation(request, BotOpt::CsmlBot(init_bot(bot_id))).unwrap();
+}
+
+/**
+ * Analytics of the database of ENGINE_DB_TYPE (memory by default), as in db_test, e.g.
+ * `ENGINE_DB_TYPE=sqlite SQLITE_URL=/tmp/csml.db cargo test --features sqlite --test analytics`
+ */
+#[test]
+fn ok_analytics() {
+    #[cfg(feature = "memory")]
+    if std::env::var("ENGINE_DB_TYPE").is_err() {
+        std::env::set_var("ENGINE_DB_TYPE", "memory");
+    }
+    make_migrations().unwrap_or(());
+    std::env::remove_var("ANALYTICS_ABANDON_DELAY");
+
+    // the database may keep the conversations of the previous runs
+    let bot_id = format!("analytics_bot_{}", Uuid::new_v4().simple());
+    let bot_id = bot_id.as_str();
+    let from_date = Utc::now().timestamp() - 60;
+
+    // alice answers the hold and ends the conversation, bob does not answer
+    run(bot_id, "alice");
+    run(bot_id, "alice");
+    run(bot_id, "bob");
+
+    assert_eq!(
+        get_flow_analytics(bot_id, from_date, None).unwrap(),
+        vec![FlowAnalytics {
+            flow_id: "Default".to_owned(),
+            started: 2,
+            ended: 1,
+        }]
+    );
+    // bob may still answer
+    assert_eq!(
+        get_hold_analytics(bot_id, from_date, None).unwrap(),
+        vec![HoldAnalytics {
+            flow_id: "Default".to_owned(),
+            step_id: "start".to_owned(),
+            answered: 1,
+            abandoned: 0,
+        }]
+    );
+    assert_eq!(
+        get_turn_analytics(bot_id, from_date, None).unwrap(),
+        TurnAnalytics {
+            conversations: 2,
+            turns: 3,
+            average_turns: 1.5,
+        }
+    );
+    assert_eq!(
+        get_step_transitions(bot_id, from_date, None).unwrap(),
+        vec![StepTransition {
+            from_flow: "Default".to_owned(),
+            from_step: "start".to_owned(),
+            to_flow: "Default".to_owned(),
+            to_step: "bye".to_owned(),
+            count: 1,
+        }]
+    );
+
+    // until bob has no activity for the abandon delay
+    std::env::set_var("ANALYTICS_ABANDON_DELAY", "0");
+    assert_eq!(
+        get_hold_analytics(bot_id, from_date, None).unwrap(),
+        vec![HoldAnalytics {
+            flow_id: "Default".to_owned(),
+            step_id: "start".to_owned(),
+            answered: 1,
+            abandoned: 1,
+        }]
+    );
+
+    // conversations created after the period are not counted
+    let to_date = Some(from_date + 1);
+    assert!(get_flow_analytics(bot_id, from_date, to_date)
+        .unwrap()
+        .is_empty());
+    assert!(get_flow_analytics(bot_id, from_date, Some(from_date)).is_err());
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "memory")]
+
+use csml_engine::data::models::{ApiKeyRequest, ApiKeyScope};
+use csml_engine::data::EngineError;
+use csml_engine::{create_api_key, get_api_key, get_api_keys, revoke_api_key};
+
+fn check_api_keys() {
+    let no_scope = ApiKeyRequest {
+        name: "none".to_owned(),
+        scopes: vec![],
+        bot_ids: None,
+    };
+    assert!(matches!(
+        create_api_key(&no_scope),
+        Err(EngineError::Format(_))
+    ));
+
+    let no_bot = ApiKeyRequest {
+        name: "none".to_owned(),
+        scopes: vec![ApiKeyScope::Run],
+        bot_ids: Some(vec![]),
+    };
+    assert!(matches!(
+        create_api_key(&no_bot),
+        Err(EngineError::Format(_))
+    ));
+
+    let created = create_api_key(&ApiKeyRequest {
+        name: "runner".to_owned(),
+        scopes: vec![ApiKeyScope::Run, ApiKeyScope::Read],
+        bot_ids: Some(vec!["bot_a".to_owned()]),
+    })
+    .unwrap();
+    assert!(created.key.starts_with("csml_"));
+
+    let api_key = get_api_key(&created.key).unwrap().unwrap();
+    assert_eq!(api_key, created.api_key);
+    assert!(get_api_key("csml_unknown").unwrap().is_none());
+    assert!(get_api_keys().unwrap().contains(&api_key));
+
+    assert!(api_key.allows(ApiKeyScope::Run, &["bot_a"]));
+    assert!(api_key.allows(ApiKeyScope::Read, &["bot_a"]));
+    assert!(!api_key.allows(ApiKeyScope::Bots, &["bot_a"]));
+    assert!(!api_key.allows(ApiKeyScope::Run, &["bot_b"]));
+    assert!(!api_key.allows(ApiKeyScope::Run, &["bot_a", "bot_b"]));
+    assert!(!api_key.allows(ApiKeyScope::Run, &[]));
+
+    let admin = create_api_key(&ApiKeyRequest {
+        name: "admin".to_owned(),
+        scopes: vec![ApiKeyScope::Admin],
+        bot_ids: None,
+    })
+    .unwrap();
+    assert!(admin.api_key.allows(ApiKeyScope::Bots, &["bot_b"]));
+    assert!(admin.api_key.allows(ApiKeyScope::Admin, &[]));
+
+    assert!(revoke_api_key(&api_key.id.to_string()).unwrap());
+    assert!(!revoke_api_key(&api_key.id.to_string()).unwrap());
+    assert!(!revoke_api_key("not a uuid").unwrap());
+    assert!(get_api_key(&created.key).unwrap().is_none());
+    assert!(get_api_key(&admin.key).unwrap().is_some());
+}
+
+#[test]
+fn ok_api_keys() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    check_api_keys();
+
+    #[cfg(feature = "sqlite")]
+    {
+        let path = std::env::temp_dir().join(format!("csml_api_keys_{}.db", std::process::id()));
+        std::env::set_var("ENGINE_DB_TYPE", "sqlite");
+        std::env::set_var("SQLITE_URL", path.to_str().unwrap());
+        csml_engine::make_migrations().unwrap();
+
+        check_api_keys();
+
+        std::fs::remove_file(path).ok();
+    }
+}
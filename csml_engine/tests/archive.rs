@@ -0,0 +1,173 @@
+#![cfg(feature = "memory")]
+
+use csml_engine::data::models::{BotOpt, CsmlRequest};
+use csml_engine::{
+    delete_client, export_bot, export_client, get_client_memories, import_client,
+    start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n  say \"Hi\"\n  remember name = \"Alice\"\n  hold\n  say \"Bye {{name}}\"\n  goto end";
+
+    CsmlBot {
+        id: "archive_bot".to_owned(),
+        name: "archive_bot".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new("Default", "Default", content, vec![])],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    }
+}
+
+fn init_client() -> Client {
+    Client::new(
+        "archive_bot".to_owned(),
+        "channel".to_owned(),
+        "user".to_owned(),
+    )
+}
+
+fn init_request(text: &str) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "archive".to_owned(),
+        client: init_client(),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": text },
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    }
+}
+
+fn texts(archive: &csml_engine::data::models::ClientArchive) -> Vec<serde_json::Value> {
+    archive
+        .messages
+        .iter()
+        .map(|message| message.payload["content"]["text"].clone())
+        .collect()
+}
+
+#[test]
+fn ok_export_import_client() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    let client = init_client();
+
+    start_conversation(init_request("hello"), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    let archive = export_client(&client).unwrap();
+    assert_eq!(archive.clients.len(), 1);
+    let exported = &archive.clients[0];
+    assert_eq!(exported.conversations.len(), 1);
+    assert_eq!(exported.conversations[0].status, "OPEN");
+    assert_eq!(texts(exported), vec![json!("hello"), json!("Hi")]);
+    assert_eq!(exported.memories["name"], json!("Alice"));
+    assert!(exported.state.iter().any(|item| item._type == "hold"));
+
+    let bot_archive = export_bot("archive_bot").unwrap();
+    assert_eq!(bot_archive.clients.len(), 1);
+    assert_eq!(bot_archive.clients[0].client, client);
+
+    // the document goes through JSON, as it would between two engines
+    let document = serde_json::to_string(&archive).unwrap();
+
+    delete_client(&client).unwrap();
+    assert_eq!(get_client_memories(&client).unwrap(), json!([]));
+
+    let imported = import_client(&serde_json::from_str(&document).unwrap()).unwrap();
+    assert_eq!(imported.clients, 1);
+    assert_eq!(imported.conversations, 1);
+    assert_eq!(imported.messages, 2);
+    assert_eq!(imported.memories, 1);
+
+    // importing again replaces the data instead of duplicating it
+    import_client(&serde_json::from_str(&document).unwrap()).unwrap();
+    let reimported = export_client(&client).unwrap();
+    assert_eq!(reimported.clients[0].conversations.len(), 1);
+    assert_eq!(
+        reimported.clients[0].conversations[0].id,
+        exported.conversations[0].id
+    );
+    assert_eq!(
+        reimported.clients[0].conversations[0].created_at,
+        exported.conversations[0].created_at
+    );
+    assert_eq!(
+        reimported.clients[0].messages[0].id,
+        exported.messages[0].id
+    );
+    assert_eq!(texts(&reimported.clients[0]), texts(exported));
+    assert_eq!(reimported.clients[0].memories, exported.memories);
+    assert_eq!(reimported.clients[0].state, exported.state);
+
+    // the conversation goes on from the imported hold and memories
+    let response = start_conversation(init_request("again"), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(
+        response["messages"][0]["payload"]["content"]["text"],
+        json!("Bye Alice")
+    );
+}
+
+#[test]
+fn ko_import_restores_client() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    // a bot of its own, as `ok_export_import_client` exports all the clients of its bot
+    let mut bot = init_bot();
+    bot.id = "archive_restore_bot".to_owned();
+    let client = Client::new(bot.id.to_owned(), "channel".to_owned(), "user".to_owned());
+    let other = Client::new(bot.id.to_owned(), "channel".to_owned(), "other".to_owned());
+
+    for client in [&client, &other] {
+        let mut request = init_request("hello");
+        request.client = client.to_owned();
+        start_conversation(request, BotOpt::CsmlBot(bot.to_owned())).unwrap();
+    }
+    let before = export_client(&client).unwrap();
+
+    // the conversation of the other client can not be saved a second time: the import
+    // fails once the data of the client has been deleted
+    let mut archive = export_client(&other).unwrap();
+    archive.clients[0].client = client.to_owned();
+    assert!(import_client(&archive).is_err());
+
+    let after = export_client(&client).unwrap();
+    assert_eq!(
+        after.clients[0].conversations[0].id,
+        before.clients[0].conversations[0].id
+    );
+    assert_eq!(texts(&after.clients[0]), texts(&before.clients[0]));
+    assert_eq!(after.clients[0].memories, before.clients[0].memories);
+    assert_eq!(after.clients[0].state, before.clients[0].state);
+}
+
+#[test]
+fn ko_import_unknown_version() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+
+    let mut archive = export_client(&Client::new(
+        "archive_version_bot".to_owned(),
+        "channel".to_owned(),
+        "user".to_owned(),
+    ))
+    .unwrap();
+    archive.version = 0;
+
+    assert!(import_client(&archive).is_err());
+}
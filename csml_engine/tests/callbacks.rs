@@ -0,0 +1,207 @@
+#![cfg(feature = "memory")]
+
+use csml_engine::data::models::{BotOpt, CsmlRequest};
+use csml_engine::{redeliver_callbacks, start_conversation};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+// headers and body of a received request
+type Request = (HashMap<String, String>, String);
+
+// answer the first requests with the given statuses and the next ones with 200,
+// and send back their headers and body
+fn serve(mut statuses: Vec<u16>) -> (String, mpsc::Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/callback", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    statuses.reverse();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut headers = HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+                if let Some((key, value)) = line.trim_end().split_once(':') {
+                    headers.insert(key.to_lowercase(), value.trim().to_owned());
+                }
+            }
+
+            let length = headers
+                .get("content-length")
+                .map_or(0, |length| length.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let status = statuses.pop().unwrap_or(200);
+            sender
+                .send((headers, String::from_utf8(body).unwrap()))
+                .unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 {} STUB\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+        }
+    });
+
+    (url, receiver)
+}
+
+fn sign(body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+    mac.update(body.as_bytes());
+
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n  say \"one\"\n  goto next\n\nnext:\n  say \"two\"\n  goto end";
+
+    CsmlBot {
+        id: "callbacks_bot".to_owned(),
+        name: "callbacks_bot".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new("Default", "Default", content, vec![])],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: Some("secret".to_owned()),
+        trigger_matcher: None,
+    }
+}
+
+fn init_request(url: &str) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "callbacks".to_owned(),
+        client: Client {
+            user_id: "user".to_owned(),
+            bot_id: "callbacks_bot".to_owned(),
+            channel_id: "channel".to_owned(),
+        },
+        callback_url: Some(url.to_owned()),
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": "hello"},
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    }
+}
+
+#[test]
+fn ok_redeliver_callbacks() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    std::env::set_var("CALLBACK_RETRIES", "1");
+    std::env::set_var("CALLBACK_RETRY_DELAY", "1");
+
+    // the callback_url is down for the whole request and the first redelivery
+    let (url, requests) = serve(vec![503, 503, 502]);
+
+    start_conversation(init_request(&url), BotOpt::CsmlBot(init_bot())).unwrap();
+
+    // the first message is retried once, the next ones are not sent to keep their order
+    let received: Vec<Request> = requests.try_iter().collect();
+    assert_eq!(received.len(), 2);
+    let (headers, body) = &received[0];
+    assert_eq!(headers["x-csml-signature"], sign(body));
+    assert_eq!(received[1], received[0]);
+
+    // while messages of the client wait in the outbox, the new ones are added after them
+    start_conversation(init_request(&url), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(requests.try_iter().count(), 0);
+
+    let redeliveries = redeliver_callbacks(Some("callbacks_bot"), 100).unwrap();
+    assert!(redeliveries.len() > 1);
+    assert!(redeliveries.iter().all(|redelivery| !redelivery.delivered));
+    assert_eq!(redeliveries[0].attempts, 3);
+    assert_eq!(redeliveries[1].attempts, 0);
+    assert_eq!(requests.try_iter().count(), 1);
+
+    // the callback_url is back up
+    let redeliveries = redeliver_callbacks(Some("callbacks_bot"), 100).unwrap();
+    assert!(redeliveries.iter().all(|redelivery| redelivery.delivered));
+
+    let received: Vec<Request> = requests.try_iter().collect();
+    assert_eq!(received.len(), redeliveries.len());
+    for ((headers, body), redelivery) in received.iter().zip(&redeliveries) {
+        assert_eq!(headers["x-csml-signature"], sign(body));
+        assert_eq!(headers["x-csml-delivery-id"], redelivery.id.to_string());
+    }
+    assert_eq!(
+        received[0].0["x-csml-delivery-id"],
+        headers["x-csml-delivery-id"]
+    );
+
+    let texts: Vec<serde_json::Value> = received
+        .iter()
+        .flat_map(|(_, body)| {
+            let body: serde_json::Value = serde_json::from_str(body).unwrap();
+            body["messages"].as_array().cloned().unwrap_or_default()
+        })
+        .map(|message| message["payload"]["content"]["text"].clone())
+        .collect();
+    assert_eq!(
+        texts,
+        vec![json!("one"), json!("two"), json!("one"), json!("two")]
+    );
+
+    assert!(redeliver_callbacks(Some("callbacks_bot"), 100)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn ok_drop_refused_callbacks() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    std::env::set_var("CALLBACK_RETRIES", "1");
+    std::env::set_var("CALLBACK_RETRY_DELAY", "1");
+
+    // the first message is refused, the second one can not be delivered then is refused
+    let (url, requests) = serve(vec![400, 503, 503, 400]);
+
+    let mut bot = init_bot();
+    bot.id = "refused_callbacks_bot".to_owned();
+    let mut request = init_request(&url);
+    request.client.bot_id = bot.id.to_owned();
+
+    // a refused message is not retried nor kept in the outbox
+    start_conversation(request, BotOpt::CsmlBot(bot)).unwrap();
+    assert_eq!(requests.try_iter().count(), 3);
+
+    // the refused message leaves the outbox without blocking the end of the conversation
+    let redeliveries = redeliver_callbacks(Some("refused_callbacks_bot"), 100).unwrap();
+    assert_eq!(redeliveries.len(), 2);
+    assert!(!redeliveries[0].delivered);
+    assert!(redeliveries[0].dropped);
+    assert!(redeliveries[1].delivered);
+
+    assert!(redeliver_callbacks(Some("refused_callbacks_bot"), 100)
+        .unwrap()
+        .is_empty());
+}
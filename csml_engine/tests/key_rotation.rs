@@ -0,0 +1,144 @@
+#![cfg(feature = "sqlite")]
+
+use csml_engine::data::models::{BotOpt, CsmlRequest, RecordKind};
+use csml_engine::data::storage::MemoryStorage;
+use csml_engine::data::{EngineError, SqliteClient};
+use csml_engine::{
+    create_bot_version, create_client_memory, export_client, get_last_bot_version, make_migrations,
+    rotate_encryption_key, start_conversation,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use diesel::{Connection, SqliteConnection};
+use serde_json::json;
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n  say \"Hi\"\n  remember name = \"Alice\"\n  hold\n  say \"Bye {{name}}\"\n  goto end";
+
+    CsmlBot {
+        id: "rotation_bot".to_owned(),
+        name: "rotation_bot".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new("Default", "Default", content, vec![])],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: Some(json!({ "token": "s3cr3t" })),
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: Some("callback".to_owned()),
+        trigger_matcher: None,
+    }
+}
+
+fn init_client() -> Client {
+    Client::new(
+        "rotation_bot".to_owned(),
+        "channel".to_owned(),
+        "user".to_owned(),
+    )
+}
+
+fn init_request(text: &str) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "rotation".to_owned(),
+        client: init_client(),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": text },
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    }
+}
+
+#[test]
+fn ok_rotate_encryption_key() {
+    let path = std::env::temp_dir().join(format!("csml_rotation_{}.db", std::process::id()));
+    std::env::set_var("ENGINE_DB_TYPE", "sqlite");
+    std::env::set_var("SQLITE_URL", path.to_str().unwrap());
+    make_migrations().unwrap();
+
+    // data saved before key ids, with a single secret
+    std::env::set_var("ENCRYPTION_SECRET", "old secret");
+    std::env::remove_var("ENCRYPTION_SECRETS");
+    create_bot_version(init_bot()).unwrap();
+    start_conversation(
+        init_request("hello"),
+        BotOpt::BotId {
+            bot_id: "rotation_bot".to_owned(),
+            apps_endpoint: None,
+            multibot: None,
+        },
+    )
+    .unwrap();
+    let exported = export_client(&init_client()).unwrap();
+
+    // a new key is used for new data, the old secret still decrypts the saved one
+    std::env::set_var("ENCRYPTION_SECRETS", "2024:new secret,2023:older secret");
+    assert_eq!(
+        json!(export_client(&init_client()).unwrap().clients),
+        json!(exported.clients)
+    );
+
+    // stop after the first batch, then resume
+    let mut last = None;
+    let result = rotate_encryption_key(1, None, |checkpoint| {
+        if last.is_some() {
+            return Err(EngineError::Manager("interrupted".to_owned()));
+        }
+        last = Some(checkpoint.clone());
+        Ok(())
+    });
+    assert!(result.is_err());
+
+    let report = rotate_encryption_key(1, last, |_| Ok(())).unwrap();
+    assert_eq!(report.key_id, Some("2024".to_owned()));
+    assert_eq!(report.records[&RecordKind::Messages], 2);
+    assert_eq!(report.records[&RecordKind::Memories], 1);
+
+    // everything is readable without the old secret
+    std::env::remove_var("ENCRYPTION_SECRET");
+    assert_eq!(
+        json!(export_client(&init_client()).unwrap().clients),
+        json!(exported.clients)
+    );
+
+    let bot = get_last_bot_version("rotation_bot").unwrap().unwrap();
+    assert_eq!(bot.bot.env, Some(json!({ "token": "s3cr3t" })));
+    assert_eq!(bot.bot.callback_secret, Some("callback".to_owned()));
+
+    let response = start_conversation(
+        init_request("again"),
+        BotOpt::BotId {
+            bot_id: "rotation_bot".to_owned(),
+            apps_endpoint: None,
+            multibot: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        response["messages"][0]["payload"]["content"]["text"],
+        json!("Bye Alice")
+    );
+
+    // a memory written after its batch was read is not overwritten by the rotation
+    let mut db = SqliteClient::new(SqliteConnection::establish(path.to_str().unwrap()).unwrap());
+    let memories = db.get_memory_records(None, 10).unwrap();
+    create_client_memory(&init_client(), "name".to_owned(), json!("Bob")).unwrap();
+    assert_eq!(db.update_memory_records(&memories).unwrap(), 0);
+    assert_eq!(
+        db.get_memory_records(None, 10).unwrap()[0].value,
+        json!("Bob")
+    );
+
+    std::fs::remove_file(path).ok();
+}
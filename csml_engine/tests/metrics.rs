@@ -0,0 +1,115 @@
+#![cfg(feature = "memory")]
+
+use csml_engine::csml_metrics::{self, Metric, Recorder};
+use csml_engine::data::models::{BotOpt, CsmlRequest};
+use csml_engine::start_conversation;
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Metrics(Mutex<Vec<String>>);
+
+impl Recorder for Metrics {
+    fn record(&self, metric: &Metric) {
+        let metric = match metric {
+            Metric::Step { flow, step, .. } => format!("step {}.{}", flow, step),
+            Metric::Message { flow, .. } => format!("message {}", flow),
+            Metric::Hold { flow, step, .. } => format!("hold {}.{}", flow, step),
+            Metric::Resume { flow, step, .. } => format!("resume {}.{}", flow, step),
+            Metric::Http { .. } => "http".to_owned(),
+            Metric::CallbackFailure { .. } => "callback_failure".to_owned(),
+            Metric::Db {
+                backend, operation, ..
+            } => format!("db {} {}", backend, operation),
+        };
+
+        self.0.lock().unwrap().push(metric);
+    }
+}
+
+fn init_bot() -> CsmlBot {
+    CsmlBot {
+        id: "metrics_bot".to_owned(),
+        name: "metrics_bot".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new(
+            "Default",
+            "Default",
+            "start:\n  say \"Hi\"\n  hold\n  goto next\n\nnext:\n  say \"Bye\"\n  goto end",
+            vec![],
+        )],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    }
+}
+
+fn run() {
+    let request = CsmlRequest {
+        request_id: "metrics".to_owned(),
+        client: Client::new(
+            "metrics_bot".to_owned(),
+            "channel".to_owned(),
+            "user".to_owned(),
+        ),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": "hello" },
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    };
+
+    start_conversation(request, BotOpt::CsmlBot(init_bot())).unwrap();
+}
+
+#[test]
+fn ok_metrics() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+
+    let metrics = Arc::new(Metrics::default());
+    csml_metrics::set_recorder(metrics.clone());
+
+    run();
+    let recorded = metrics.0.lock().unwrap().drain(..).collect::<Vec<_>>();
+    for metric in [
+        "message Default",
+        "hold Default.start",
+        "step Default.start",
+    ] {
+        assert!(recorded.contains(&metric.to_owned()), "{:?}", recorded);
+    }
+    assert!(recorded.contains(&"db memory get_state_key".to_owned()));
+
+    run();
+    csml_metrics::remove_recorder();
+    let recorded = metrics.0.lock().unwrap().drain(..).collect::<Vec<_>>();
+    for metric in [
+        "resume Default.start",
+        "step Default.start",
+        "step Default.next",
+        "message Default",
+    ] {
+        assert!(recorded.contains(&metric.to_owned()), "{:?}", recorded);
+    }
+    assert!(!recorded.iter().any(|metric| metric.starts_with("hold")));
+
+    // without a recorder, nothing is recorded
+    run();
+    assert!(metrics.0.lock().unwrap().is_empty());
+}
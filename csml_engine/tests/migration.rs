@@ -0,0 +1,226 @@
+#![cfg(feature = "memory")]
+
+use csml_engine::data::models::{BotOpt, CsmlRequest, MigrationCheckpoint, RecordKind};
+use csml_engine::data::storage::*;
+use csml_engine::data::{EngineError, MemoryClient};
+use csml_engine::migrate_db;
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+
+fn init_bot() -> CsmlBot {
+    let content = "start:\n  say \"Hi\"\n  remember name = \"Alice\"\n  hold\n  say \"Bye {{name}}\"\n  goto end";
+
+    CsmlBot {
+        id: "migration_bot".to_owned(),
+        name: "migration_bot".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new("Default", "Default", content, vec![])],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    }
+}
+
+fn init_client() -> Client {
+    Client::new(
+        "migration_bot".to_owned(),
+        "channel".to_owned(),
+        "user".to_owned(),
+    )
+}
+
+fn init_request(text: &str) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "migration".to_owned(),
+        client: init_client(),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": text },
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    }
+}
+
+fn records(db: &mut MemoryClient) -> serde_json::Value {
+    json!({
+        "bot_versions": db.get_bot_version_records(None, 100).unwrap(),
+        "conversations": db.get_conversation_records(None, 100).unwrap(),
+        "messages": db.get_message_records(None, 100).unwrap(),
+        "memories": db.get_memory_records(None, 100).unwrap(),
+        "state": db.get_state_records(None, 100).unwrap(),
+        "schedules": db.get_schedule_records(None, 100).unwrap(),
+        "outbox": db.get_outbox_records(None, 100).unwrap(),
+    })
+}
+
+#[test]
+fn ok_migrate_memory_stores() {
+    let mut source = MemoryClient::isolated();
+    source
+        .create_bot_version("migration_bot".to_owned(), init_bot())
+        .unwrap();
+    csml_engine::start_conversation_db(
+        init_request("hello"),
+        BotOpt::CsmlBot(init_bot()),
+        source.clone(),
+    )
+    .unwrap();
+
+    let mut target = MemoryClient::isolated();
+    let mut checkpoints = vec![];
+    let report = migrate_db(source.clone(), target.clone(), 1, None, |checkpoint| {
+        checkpoints.push(checkpoint.clone());
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(report.records[&RecordKind::BotVersions].created, 1);
+    assert_eq!(report.records[&RecordKind::Conversations].created, 1);
+    assert_eq!(report.records[&RecordKind::Messages].created, 2);
+    assert_eq!(report.records[&RecordKind::Memories].created, 1);
+    assert_eq!(report.records[&RecordKind::Outbox].read, 0);
+    for copied in report.records.values() {
+        assert_eq!(copied.read, copied.created);
+    }
+    assert_eq!(
+        checkpoints.len(),
+        report
+            .records
+            .values()
+            .map(|copied| copied.read)
+            .sum::<usize>()
+    );
+
+    // same records, with the same ids and dates
+    assert_eq!(records(&mut target), records(&mut source));
+
+    // records already copied are skipped when running the migration again
+    let report = migrate_db(
+        source.clone(),
+        target.clone(),
+        1,
+        Some(checkpoints[2].clone()),
+        |_| Ok(()),
+    )
+    .unwrap();
+    assert!(!report.records.contains_key(&RecordKind::BotVersions));
+    assert!(report.records.values().all(|copied| copied.created == 0));
+
+    // a migration that stopped goes on from its last checkpoint
+    let mut resumed = MemoryClient::isolated();
+    let mut last: Option<MigrationCheckpoint> = None;
+    let result = migrate_db(source.clone(), resumed.clone(), 1, None, |checkpoint| {
+        if checkpoint.kind == RecordKind::Messages {
+            return Err(EngineError::Manager("interrupted".to_owned()));
+        }
+        last = Some(checkpoint.clone());
+        Ok(())
+    });
+    assert!(result.is_err());
+    assert_eq!(last.as_ref().unwrap().kind, RecordKind::Conversations);
+
+    migrate_db(source.clone(), resumed.clone(), 1, last, |_| Ok(())).unwrap();
+    assert_eq!(records(&mut resumed), records(&mut source));
+}
+
+#[test]
+fn ko_migrate_message_without_conversation() {
+    let source = MemoryClient::isolated();
+    csml_engine::start_conversation_db(
+        init_request("hello"),
+        BotOpt::CsmlBot(init_bot()),
+        source.clone(),
+    )
+    .unwrap();
+
+    // starting at the messages leaves their conversation out of the target
+    let mut target = MemoryClient::isolated();
+    let start = MigrationCheckpoint {
+        kind: RecordKind::Messages,
+        after: None,
+    };
+    let result = migrate_db(source.clone(), target.clone(), 100, Some(start), |_| Ok(()));
+
+    assert!(result.is_err());
+    assert!(target.get_message_records(None, 100).unwrap().is_empty());
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn ok_migrate_sqlite_to_sqlite() {
+    use csml_engine::{export_client, get_last_bot_version, migrate, start_conversation};
+
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    std::env::set_var("ENCRYPTION_SECRET", "migration");
+    let client = init_client();
+
+    let version = csml_engine::create_bot_version(init_bot()).unwrap();
+    start_conversation(init_request("hello"), BotOpt::CsmlBot(init_bot())).unwrap();
+    let exported = export_client(&client).unwrap();
+
+    let path = |name: &str| {
+        std::env::temp_dir().join(format!("csml_migration_{}_{}.db", std::process::id(), name))
+    };
+    let (first, second) = (path("first"), path("second"));
+    let first_url = format!("sqlite://{}", first.display());
+    let second_url = format!("sqlite://{}", second.display());
+
+    let report = migrate("memory://", &first_url, 25, None, |_| Ok(())).unwrap();
+    assert_eq!(report.records[&RecordKind::Messages].created, 2);
+
+    // stop after the first batch, then resume
+    let mut last = None;
+    let result = migrate(&first_url, &second_url, 1, None, |checkpoint| {
+        if last.is_some() {
+            return Err(EngineError::Manager("interrupted".to_owned()));
+        }
+        last = Some(checkpoint.clone());
+        Ok(())
+    });
+    assert!(result.is_err());
+
+    let report = migrate(&first_url, &second_url, 1, last, |_| Ok(())).unwrap();
+    // the conversation was saved before its checkpoint failed
+    assert_eq!(report.records[&RecordKind::Conversations].read, 1);
+    assert_eq!(report.records[&RecordKind::Conversations].created, 0);
+    assert_eq!(report.records[&RecordKind::Messages].created, 2);
+
+    std::env::set_var("ENGINE_DB_TYPE", "sqlite");
+    std::env::set_var("SQLITE_URL", second.to_str().unwrap());
+
+    let migrated = export_client(&client).unwrap();
+    assert_eq!(
+        migrated.clients[0].conversations,
+        exported.clients[0].conversations
+    );
+    assert_eq!(migrated.clients[0].messages, exported.clients[0].messages);
+    assert_eq!(migrated.clients[0].memories, exported.clients[0].memories);
+
+    let bot = get_last_bot_version("migration_bot").unwrap().unwrap();
+    assert_eq!(bot.version_id, version.version_id);
+
+    // the conversation goes on in the new database
+    let response = start_conversation(init_request("again"), BotOpt::CsmlBot(init_bot())).unwrap();
+    assert_eq!(
+        response["messages"][0]["payload"]["content"]["text"],
+        json!("Bye Alice")
+    );
+
+    std::fs::remove_file(first).ok();
+    std::fs::remove_file(second).ok();
+}
@@ -0,0 +1,188 @@
+#![cfg(all(feature = "otel", feature = "memory"))]
+
+use csml_engine::data::models::{BotOpt, CsmlRequest};
+use csml_engine::start_conversation;
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::{SpanId, TraceContextExt, TraceId};
+use opentelemetry::{global, Context};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const PARENT_ID: &str = "00f067aa0ba902b7";
+
+// path and headers of a received request
+type Request = (String, HashMap<String, String>);
+
+// answer every request with an empty json object, and send back its path and headers
+fn serve() -> (String, mpsc::Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let path = line
+                .split_whitespace()
+                .nth(1)
+                .unwrap_or_default()
+                .to_owned();
+
+            let mut headers = HashMap::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end().split_once(':') {
+                    Some((key, value)) => {
+                        headers.insert(key.to_lowercase(), value.trim().to_owned());
+                    }
+                    None => break,
+                }
+            }
+
+            let length = headers
+                .get("content-length")
+                .map_or(0, |length| length.parse().unwrap());
+            reader.read_exact(&mut vec![0; length]).unwrap();
+
+            sender.send((path, headers)).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}")
+                .unwrap();
+        }
+    });
+
+    (url, receiver)
+}
+
+fn init_bot(url: &str) -> CsmlBot {
+    let content = format!(
+        "start:\n  do res = HTTP(\"{}/api\").get().send()\n  say \"done\"\n  goto end",
+        url
+    );
+
+    CsmlBot {
+        id: "otel_bot".to_owned(),
+        name: "otel_bot".to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new("Default", "Default", &content, vec![])],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    }
+}
+
+fn init_request(url: &str) -> CsmlRequest {
+    CsmlRequest {
+        request_id: "otel".to_owned(),
+        client: Client {
+            user_id: "user".to_owned(),
+            bot_id: "otel_bot".to_owned(),
+            channel_id: "channel".to_owned(),
+        },
+        callback_url: Some(format!("{}/callback", url)),
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": "hello"},
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    }
+}
+
+fn find<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+    spans
+        .iter()
+        .find(|span| span.name == name)
+        .unwrap_or_else(|| panic!("no span {}", name))
+}
+
+#[test]
+fn spans_and_propagation() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    global::set_tracer_provider(provider);
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let (url, requests) = serve();
+
+    // trace context of an incoming request
+    let incoming = HashMap::from([(
+        "traceparent".to_owned(),
+        format!("00-{}-{}-01", TRACE_ID, PARENT_ID),
+    )]);
+    let context = TraceContextPropagator::new().extract(&incoming);
+    {
+        let _guard = context.attach();
+        start_conversation(init_request(&url), BotOpt::CsmlBot(init_bot(&url))).unwrap();
+    }
+
+    let spans = exporter.get_finished_spans().unwrap();
+    let trace_id = TraceId::from_hex(TRACE_ID).unwrap();
+    assert!(spans
+        .iter()
+        .all(|span| span.span_context.trace_id() == trace_id));
+
+    let conversation = find(&spans, "csml.start_conversation");
+    let step = find(&spans, "csml.interpret_step");
+    let http = find(&spans, "csml.http");
+    let callback = find(&spans, "csml.callback");
+
+    assert_eq!(
+        conversation.parent_span_id,
+        SpanId::from_hex(PARENT_ID).unwrap()
+    );
+    assert_eq!(step.parent_span_id, conversation.span_context.span_id());
+    assert_eq!(http.parent_span_id, step.span_context.span_id());
+    assert_eq!(callback.parent_span_id, step.span_context.span_id());
+    assert!(spans.iter().any(|span| span.name.starts_with("csml.db.")
+        && span.parent_span_id == conversation.span_context.span_id()));
+
+    // outbound calls are children of their span
+    let received: Vec<Request> = requests.try_iter().collect();
+    let (_, api_headers) = received.iter().find(|(path, _)| path == "/api").unwrap();
+    assert_eq!(
+        api_headers["traceparent"],
+        format!("00-{}-{}-01", TRACE_ID, http.span_context.span_id())
+    );
+    let callbacks: Vec<&HashMap<String, String>> = received
+        .iter()
+        .filter(|(path, _)| path == "/callback")
+        .map(|(_, headers)| headers)
+        .collect();
+    assert!(!callbacks.is_empty());
+    assert!(callbacks
+        .iter()
+        .all(|headers| headers["traceparent"].starts_with(&format!("00-{}-", TRACE_ID))));
+
+    assert!(!Context::current().has_active_span());
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "memory")]
+
+use chrono::Utc;
+use csml_engine::data::models::{BotOpt, CsmlRequest};
+use csml_engine::data::EngineError;
+use csml_engine::{export_client, start_conversation};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use serde_json::json;
+
+fn init_bot(bot_id: &str) -> CsmlBot {
+    CsmlBot {
+        id: bot_id.to_owned(),
+        name: bot_id.to_owned(),
+        apps_endpoint: None,
+        flows: vec![CsmlFlow::new(
+            "Default",
+            "Default",
+            "start:\n  say \"Hi\"\n  goto end",
+            vec![],
+        )],
+        native_components: None,
+        custom_components: None,
+        default_flow: "Default".to_owned(),
+        bot_ast: None,
+        no_interruption_delay: None,
+        env: None,
+        modules: None,
+        multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
+    }
+}
+
+fn run_bot(
+    bot_id: &str,
+    user_id: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    let request = CsmlRequest {
+        request_id: "quotas".to_owned(),
+        client: Client::new(bot_id.to_owned(), "channel".to_owned(), user_id.to_owned()),
+        callback_url: None,
+        payload: json!({
+            "content_type": "text",
+            "content": { "text": "hello" },
+        }),
+        metadata: json!({}),
+        ttl_duration: None,
+        step_limit: None,
+        low_data_mode: None,
+        debug: false,
+        random_seed: None,
+    };
+
+    start_conversation(request, BotOpt::CsmlBot(init_bot(bot_id)))
+}
+
+fn run(user_id: &str) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    run_bot("quotas_bot", user_id)
+}
+
+fn set_quotas() {
+    std::env::set_var("ENGINE_DB_TYPE", "memory");
+    std::env::set_var("CLIENT_REQUESTS_PER_MINUTE", "2");
+    std::env::set_var("BOT_STEPS_PER_DAY", "3");
+
+    // the requests of the client must be in the same minute
+    if Utc::now().timestamp() % 60 > 55 {
+        std::thread::sleep(std::time::Duration::from_secs(5));
+    }
+}
+
+#[test]
+fn ok_quotas() {
+    set_quotas();
+
+    // each request runs one step
+    assert!(run("alice").is_ok());
+    assert!(run("alice").is_ok());
+    assert!(matches!(run("alice"), Err(EngineError::RateLimit(_))));
+
+    // the counters are not in the state of the client
+    let client = Client::new(
+        "quotas_bot".to_owned(),
+        "channel".to_owned(),
+        "alice".to_owned(),
+    );
+    let archive = export_client(&client).unwrap();
+    assert!(archive.clients[0]
+        .state
+        .iter()
+        .all(|item| item._type != "quota"));
+
+    assert!(run("bob").is_ok());
+    match run("bob") {
+        Err(EngineError::RateLimit(limit)) => assert!(limit.starts_with("bot steps per day")),
+        other => panic!("expected a rate limit, got {:?}", other),
+    }
+}
+
+#[test]
+fn ok_concurrent_requests() {
+    set_quotas();
+
+    let requests: Vec<_> = (0..8)
+        .map(|_| std::thread::spawn(|| run_bot("quotas_concurrent_bot", "alice")))
+        .collect();
+    let accepted = requests
+        .into_iter()
+        .map(|request| request.join().unwrap())
+        .filter(Result::is_ok)
+        .count();
+
+    assert_eq!(accepted, 2);
+}
@@ -1,11 +1,17 @@
-use csml_engine::data::models::{BotOpt, CsmlRequest};
-use csml_engine::{delete_client, start_conversation};
-use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client};
+use csml_engine::data::filter::ClientMessageFilter;
+use csml_engine::data::models::{BotOpt, CsmlRequest, ScheduleRun};
+use csml_engine::{
+    compile_bot, create_bot_version, delete_all_bot_data, delete_bot_version_id, delete_client,
+    encode_bot_artifact, get_client_messages, run_due_schedules, start_conversation,
+    start_conversation_stream,
+};
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow, Client, Interruption};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::fs;
 use std::io::prelude::*;
 use std::path::Path;
+use std::sync::mpsc;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +74,7 @@ fn init_bot(bot_name: &str) -> Result<CsmlBot, std::io::Error> {
             name: name.to_owned(),
             commands,
             content: flow_content,
+            priority: 0,
         });
     }
 
@@ -87,6 +94,10 @@ fn init_bot(bot_name: &str) -> Result<CsmlBot, std::io::Error> {
         })),
         modules: None,
         multibot: None,
+        fallback_flow: None,
+        interruptions: None,
+        callback_secret: None,
+        trigger_matcher: None,
     };
 
     Ok(bot)
@@ -109,6 +120,8 @@ fn init_request(string: &str, bot_id: String, channel_id: String) -> CsmlRequest
         ttl_duration: None,
         step_limit: None,
         low_data_mode: None,
+        debug: false,
+        random_seed: None,
     }
 }
 
@@ -298,6 +311,46 @@ fn ok_test_commands() {
     .unwrap();
 }
 
+#[test]
+fn ok_test_precompiled_bot() {
+    std::env::set_var("BOT_ARTIFACT_SECRET", "test_bot_secret");
+    let bot = init_bot("goto_flow").unwrap();
+    let artifact = compile_bot(bot.clone()).unwrap();
+
+    // the flows and their commands are read from the signed artifact
+    let mut precompiled = bot.clone();
+    precompiled.flows = vec![];
+    precompiled.bot_ast = Some(encode_bot_artifact(&artifact));
+
+    let channel_id = Uuid::new_v4().to_string();
+    let bot_id = Uuid::new_v4().to_string();
+
+    let obj = start_conversation(
+        init_request("/flow4", bot_id.clone(), channel_id.clone()),
+        BotOpt::CsmlBot(precompiled),
+    )
+    .unwrap();
+    assert_eq!(obj["messages"][0]["payload"]["content"]["text"], "flow4");
+
+    // an artifact that is not signed by the engine is not used
+    let mut unsigned = bot;
+    unsigned.flows = vec![];
+    unsigned.bot_ast = Some(artifact.encode());
+
+    assert!(start_conversation(
+        init_request("/flow4", bot_id.clone(), channel_id.clone()),
+        BotOpt::CsmlBot(unsigned),
+    )
+    .is_err());
+
+    delete_client(&Client {
+        user_id: "test".to_owned(),
+        bot_id,
+        channel_id,
+    })
+    .unwrap();
+}
+
 #[test]
 fn ok_test_goto_var() {
     let bot = init_bot("goto_flow").unwrap();
@@ -426,3 +479,214 @@ fn ok_test_memory() {
     })
     .unwrap();
 }
+
+fn run_events(bot: &CsmlBot, events: &[&str]) -> Vec<String> {
+    let channel_id = Uuid::new_v4().to_string();
+    let bot_id = Uuid::new_v4().to_string();
+
+    let mut output_message = vec![];
+
+    for event in events.iter() {
+        let obj = start_conversation(
+            init_request(event, bot_id.clone(), channel_id.clone()),
+            BotOpt::CsmlBot(bot.to_owned()),
+        )
+        .unwrap();
+
+        for message in obj["messages"].as_array().unwrap().iter() {
+            output_message.push(
+                message["payload"]["content"]["text"]
+                    .as_str()
+                    .unwrap()
+                    .to_owned(),
+            );
+        }
+    }
+
+    delete_client(&Client {
+        user_id: "test".to_owned(),
+        bot_id,
+        channel_id,
+    })
+    .unwrap();
+
+    output_message
+}
+
+fn init_interruptions_bot() -> CsmlBot {
+    let mut bot = init_bot("interruptions").unwrap();
+    bot.interruptions = Some(vec![
+        Interruption {
+            flow: "help".to_owned(),
+            resume: true,
+        },
+        Interruption {
+            flow: "cancel".to_owned(),
+            resume: false,
+        },
+    ]);
+
+    bot
+}
+
+#[test]
+fn ok_test_fallback_flow() {
+    let mut bot = init_bot("interruptions").unwrap();
+
+    assert_eq!(run_events(&bot, &["unknown"]), vec!["question"]);
+
+    bot.fallback_flow = Some("fallback".to_owned());
+
+    assert_eq!(run_events(&bot, &["unknown"]), vec!["fallback"]);
+    assert_eq!(run_events(&bot, &["other"]), vec!["other"]);
+}
+
+#[test]
+fn ok_test_hold_interrupted_by_any_command() {
+    let bot = init_bot("interruptions").unwrap();
+
+    let output = run_events(&bot, &["hi", "other", "answer"]);
+
+    assert_eq!(output, vec!["question", "other", "question"]);
+}
+
+#[test]
+fn ok_test_hold_interruptions() {
+    let bot = init_interruptions_bot();
+
+    let output = run_events(&bot, &["hi", "other"]);
+    assert_eq!(output, vec!["question", "answer:other"]);
+
+    let output = run_events(&bot, &["hi", "cancel", "answer"]);
+    assert_eq!(output, vec!["question", "cancelled", "question"]);
+}
+
+#[test]
+fn ok_test_hold_interruption_resume() {
+    let bot = init_interruptions_bot();
+
+    // going back to the held step waits for the answer without restarting the step
+    let output = run_events(&bot, &["hi", "help", "answer"]);
+    assert_eq!(output, vec!["question", "help", "answer:answer"]);
+
+    let output = run_events(&bot, &["hi", "help", "help", "cancel", "hi"]);
+    assert_eq!(
+        output,
+        vec!["question", "help", "help", "cancelled", "question"]
+    );
+}
+
+/**
+ * Plan a schedule with a version of a new bot, return its client and the version id
+ */
+fn plan_schedule() -> (Client, String) {
+    let bot_id = Uuid::new_v4().to_string();
+    let channel_id = Uuid::new_v4().to_string();
+    let client = Client {
+        user_id: "test".to_owned(),
+        bot_id: bot_id.clone(),
+        channel_id: channel_id.clone(),
+    };
+
+    let mut bot = init_bot("schedules").unwrap();
+    bot.id = bot_id.clone();
+    let version_id = create_bot_version(bot).unwrap().version_id;
+
+    let mut request = init_request("hi", bot_id.clone(), channel_id);
+    request.callback_url = None;
+
+    let bot_opt = BotOpt::Id {
+        version_id: version_id.clone(),
+        bot_id,
+        apps_endpoint: None,
+        multibot: None,
+    };
+    let obj = start_conversation(request, bot_opt).unwrap();
+    assert_eq!(obj["messages"][0]["payload"]["content"]["text"], "planned");
+
+    (client, version_id)
+}
+
+fn run_client_schedules(client: &Client) -> Vec<ScheduleRun> {
+    run_due_schedules(100)
+        .unwrap()
+        .into_iter()
+        .filter(|run| run.schedule.client == *client)
+        .collect()
+}
+
+#[test]
+fn ok_test_schedule() {
+    let (client, version_id) = plan_schedule();
+
+    let runs = run_client_schedules(&client);
+
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].schedule.flow_id, "reminder");
+    assert_eq!(runs[0].schedule.step_id, "remind");
+    assert_eq!(runs[0].schedule.bot.version_id, Some(version_id));
+    assert!(runs[0].error.is_none());
+    assert!(runs[0].retry_at.is_none());
+    assert_eq!(
+        runs[0].messages[0]["payload"]["content"]["text"],
+        "reminder"
+    );
+
+    let messages = get_client_messages(ClientMessageFilter::builder().client(&client).build())
+        .unwrap()
+        .data;
+    assert!(messages
+        .iter()
+        .any(|message| message.payload["content"]["text"] == "reminder"));
+
+    assert!(run_client_schedules(&client).is_empty());
+
+    delete_client(&client).unwrap();
+    delete_all_bot_data(&client.bot_id).unwrap();
+}
+
+#[test]
+fn ok_test_schedule_retry() {
+    let (client, version_id) = plan_schedule();
+
+    // the version that planned the schedule is gone: the run fails and is tried again later
+    delete_bot_version_id(&version_id, &client.bot_id).unwrap();
+
+    let runs = run_client_schedules(&client);
+
+    assert_eq!(runs.len(), 1);
+    assert!(runs[0].error.is_some());
+    assert!(runs[0].retry_at.is_some());
+    assert!(run_client_schedules(&client).is_empty());
+
+    delete_client(&client).unwrap();
+    delete_all_bot_data(&client.bot_id).unwrap();
+}
+
+#[test]
+fn ok_test_stream() {
+    let bot = init_bot("goto_flow").unwrap();
+    let mut request = init_request(
+        "start",
+        Uuid::new_v4().to_string(),
+        Uuid::new_v4().to_string(),
+    );
+    request.callback_url = None;
+
+    let (sender, receiver) = mpsc::channel();
+    let obj = start_conversation_stream(request, BotOpt::CsmlBot(bot), sender).unwrap();
+
+    // the stream is closed at the end of the request, after the same messages as the response
+    let streamed: Vec<serde_json::Value> = receiver
+        .iter()
+        .map(|message| message["payload"].clone())
+        .collect();
+    let messages: Vec<serde_json::Value> = obj["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|message| message["payload"].clone())
+        .collect();
+    assert!(!streamed.is_empty());
+    assert_eq!(streamed, messages);
+}
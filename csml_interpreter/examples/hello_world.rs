@@ -46,7 +46,10 @@ fn main() {
         ttl_duration: None,
         low_data_mode: None,
         step_limit: None,
+        http_limit: None,
         secure: false,
+        trace: false,
+        seed: None,
     };
 
     // Create context
@@ -38,6 +38,7 @@ fn main() {
             name: "module".to_string(),
             url: Some("https://raw.githubusercontent.com/CSML-by-Clevy/csml-engine/dev/csml_engine/CSML/flow2.csml".to_string()),
             version: "latest".to_string(),
+            integrity: None,
             flow: None,
             auth: None,
         }]),
@@ -52,7 +53,10 @@ fn main() {
         ttl_duration: None,
         low_data_mode: None,
         step_limit: None,
+        http_limit: None,
         secure: false,
+        trace: false,
+        seed: None,
     };
 
     // Create context
@@ -1,10 +1,15 @@
 pub mod ast;
+pub mod bot_artifact;
 pub mod client;
 pub mod context;
 pub mod csml_bot;
 pub mod csml_flow;
 pub mod csml_logs;
+pub mod csml_metrics;
+pub mod csml_otel;
 pub mod csml_result;
+pub mod csml_rng;
+pub mod csml_usage;
 pub mod data;
 pub mod error_info;
 pub mod event;
@@ -17,16 +22,19 @@ pub mod message_data;
 pub mod msg;
 pub mod position;
 pub mod primitive;
+pub mod schedule;
 pub mod tokens;
+pub mod trace;
 pub mod warnings;
 
 pub use ast::Interval;
+pub use bot_artifact::{BotArtifact, BotArtifactHeader};
 pub use client::Client;
 pub use context::{ApiInfo, Context, PreviousBot};
-pub use csml_bot::{CsmlBot, Module, MultiBot};
+pub use csml_bot::{CsmlBot, Interruption, Module, MultiBot, TriggerMatcherConfig};
 pub use csml_flow::CsmlFlow;
 pub use csml_result::CsmlResult;
-pub use data::Data;
+pub use data::{Data, PreviousInfo};
 pub use event::Event;
 pub use fn_args_type::ArgsType;
 pub use hold::{Hold, IndexInfo};
@@ -35,8 +43,10 @@ pub use memories::{Memory, MemoryType};
 pub use message::Message;
 pub use message_data::MessageData;
 pub use position::Position;
+pub use schedule::Schedule;
+pub use trace::{TraceEvent, TraceKind};
 
-pub use msg::MSG;
+pub use msg::{MsgSender, MSG};
 
 // limit of steps in a single execution
 pub static STEP_LIMIT: usize = 100;
@@ -0,0 +1,332 @@
+use crate::data::ast::{Flow, ImportScope, InstructionScope};
+use crate::data::error_info::ErrorInfo;
+use crate::data::{CsmlBot, CsmlFlow, CsmlResult, Literal};
+use crate::get_step;
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+// encoded artifacts are this prefix followed by the base64 bincode of the artifact, and
+// by `.` and the hex HMAC-SHA256 of everything before it when the artifact is signed
+pub const BOT_ARTIFACT_PREFIX: &str = "csmlc:";
+// bump when the layout of BotArtifact changes
+pub const BOT_ARTIFACT_FORMAT_VERSION: u32 = 2;
+
+const SIGNATURE_SEPARATOR: char = '.';
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BotArtifactHeader {
+    pub format_version: u32,
+    pub interpreter_version: String,
+    pub bot_id: String,
+    pub default_flow: String,
+    // "sha256:<hex>" of the serialized body of the artifact
+    pub checksum: String,
+}
+
+/**
+ * Compiled form of a bot: the ASTs of its flows and modules, with the checksums
+ * of every step (used to detect changes on hold), the constants and the imports
+ * of each flow, and the flows it was compiled from (with their commands).
+ * The header is serialized first, followed by the body it holds the checksum of, so it
+ * can be read on its own to check that the artifact was built by the current interpreter.
+ */
+#[derive(Debug, Clone)]
+pub struct BotArtifact {
+    pub header: BotArtifactHeader,
+    pub flows: HashMap<String, Flow>,
+    pub extern_flows: HashMap<String, Flow>,
+    pub step_checksums: HashMap<String, HashMap<String, String>>,
+    pub constants: HashMap<String, HashMap<String, Literal>>,
+    pub imports: HashMap<String, Vec<ImportScope>>,
+    pub sources: Vec<CsmlFlow>,
+}
+
+type BotArtifactBody = (
+    HashMap<String, Flow>,
+    HashMap<String, Flow>,
+    HashMap<String, HashMap<String, String>>,
+    HashMap<String, HashMap<String, Literal>>,
+    HashMap<String, Vec<ImportScope>>,
+    Vec<CsmlFlow>,
+);
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn invalid(err: impl std::fmt::Display) -> String {
+    format!("invalid bot artifact: {}", err)
+}
+
+fn checksum(body: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(body)))
+}
+
+fn hmac(key: &[u8]) -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take a key of any size")
+}
+
+/**
+ * Split an encoded artifact into the signed part and its signature, if any
+ */
+fn split_signature(ast: &str) -> (&str, Option<&str>) {
+    match ast.rsplit_once(SIGNATURE_SEPARATOR) {
+        Some((encoded, signature)) => (encoded, Some(signature)),
+        None => (ast, None),
+    }
+}
+
+fn decode_bytes(ast: &str) -> Result<Vec<u8>, String> {
+    let encoded = match split_signature(ast).0.strip_prefix(BOT_ARTIFACT_PREFIX) {
+        Some(encoded) => encoded,
+        None => return Err("not a compiled bot artifact".to_owned()),
+    };
+
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(invalid)
+}
+
+/**
+ * Read the header of the artifact and return it with the body, once checked against
+ * the checksum of the header
+ */
+fn decode_checked(ast: &str) -> Result<(BotArtifactHeader, Vec<u8>), String> {
+    let bytes = decode_bytes(ast)?;
+
+    let mut body = &bytes[..];
+    let header: BotArtifactHeader = bincode::deserialize_from(&mut body).map_err(invalid)?;
+    if !header.is_compatible() {
+        return Err(format!(
+            "bot artifact was compiled by interpreter {} (format {})",
+            header.interpreter_version, header.format_version
+        ));
+    }
+    if header.checksum != checksum(body) {
+        return Err(invalid("checksum mismatch"));
+    }
+
+    let body = body.to_vec();
+    Ok((header, body))
+}
+
+fn step_checksums(flow: &Flow, content: &str) -> HashMap<String, String> {
+    let mut checksums = HashMap::new();
+
+    for instruction in flow.flow_instructions.keys() {
+        if let InstructionScope::StepScope(name) = instruction {
+            let mut hash = Md5::new();
+            hash.update(get_step(name, content, flow).as_bytes());
+
+            checksums.insert(name.to_owned(), format!("{:x}", hash.finalize()));
+        }
+    }
+
+    checksums
+}
+
+fn flow_imports(flow: &Flow) -> Vec<ImportScope> {
+    flow.flow_instructions
+        .keys()
+        .filter_map(|instruction| match instruction {
+            InstructionScope::ImportScope(import) => Some(import.to_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// STATIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl BotArtifactHeader {
+    pub fn new(bot: &CsmlBot) -> Self {
+        Self {
+            format_version: BOT_ARTIFACT_FORMAT_VERSION,
+            interpreter_version: env!("CARGO_PKG_VERSION").to_owned(),
+            bot_id: bot.id.to_owned(),
+            default_flow: bot.default_flow.to_owned(),
+            checksum: String::new(),
+        }
+    }
+}
+
+impl BotArtifact {
+    /**
+     * Validate the bot and compile it. Modules must already be downloaded.
+     */
+    pub fn compile(bot: &CsmlBot) -> Result<Self, Vec<ErrorInfo>> {
+        let (flows, extern_flows) = match crate::validate_bot(bot) {
+            CsmlResult {
+                errors: Some(errors),
+                ..
+            } => return Err(errors),
+            CsmlResult {
+                flows,
+                extern_flows,
+                ..
+            } => (flows.unwrap_or_default(), extern_flows.unwrap_or_default()),
+        };
+
+        let mut step_checksums = HashMap::new();
+        for flow in bot.flows.iter() {
+            if let Some(ast) = flows.get(&flow.name) {
+                step_checksums.insert(
+                    flow.name.to_owned(),
+                    self::step_checksums(ast, &flow.content),
+                );
+            }
+        }
+
+        let constants = flows
+            .iter()
+            .map(|(name, flow)| (name.to_owned(), flow.constants.to_owned()))
+            .collect();
+
+        let imports = flows
+            .iter()
+            .map(|(name, flow)| (name.to_owned(), flow_imports(flow)))
+            .collect();
+
+        let mut artifact = Self {
+            header: BotArtifactHeader::new(bot),
+            flows,
+            extern_flows,
+            step_checksums,
+            constants,
+            imports,
+            sources: bot.flows.to_owned(),
+        };
+        artifact.header.checksum = checksum(&artifact.body_bytes());
+
+        Ok(artifact)
+    }
+
+    pub fn is_artifact(ast: &str) -> bool {
+        ast.starts_with(BOT_ARTIFACT_PREFIX)
+    }
+
+    /**
+     * Read the header of an encoded artifact without decoding the ASTs
+     */
+    pub fn decode_header(ast: &str) -> Result<BotArtifactHeader, String> {
+        let bytes = decode_bytes(ast)?;
+
+        bincode::deserialize(&bytes).map_err(invalid)
+    }
+
+    /**
+     * Check that the artifact was compiled by this interpreter version and was not altered,
+     * without decoding the ASTs. With a `key`, the artifact must also have been signed
+     * with it (see `encode_signed`).
+     */
+    pub fn verify(ast: &str, key: Option<&[u8]>) -> Result<BotArtifactHeader, String> {
+        if let Some(key) = key {
+            let (signed, signature) = split_signature(ast);
+            let signature = signature
+                .and_then(|signature| hex::decode(signature).ok())
+                .ok_or_else(|| invalid("missing signature"))?;
+
+            let mut mac = hmac(key);
+            mac.update(signed.as_bytes());
+            mac.verify_slice(&signature)
+                .map_err(|_| invalid("signature mismatch"))?;
+        }
+
+        decode_checked(ast).map(|(header, _)| header)
+    }
+
+    /**
+     * Decode an artifact, which must have been compiled by this interpreter version.
+     * The signature of the artifact is not checked, see `verify`.
+     */
+    pub fn decode(ast: &str) -> Result<Self, String> {
+        let (header, body) = decode_checked(ast)?;
+
+        let (flows, extern_flows, step_checksums, constants, imports, sources): BotArtifactBody =
+            bincode::deserialize(&body).map_err(invalid)?;
+
+        Ok(Self {
+            header,
+            flows,
+            extern_flows,
+            step_checksums,
+            constants,
+            imports,
+            sources,
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl BotArtifactHeader {
+    pub fn is_compatible(&self) -> bool {
+        self.format_version == BOT_ARTIFACT_FORMAT_VERSION
+            && self.interpreter_version == env!("CARGO_PKG_VERSION")
+    }
+}
+
+impl BotArtifact {
+    fn body_bytes(&self) -> Vec<u8> {
+        bincode::serialize(&(
+            &self.flows,
+            &self.extern_flows,
+            &self.step_checksums,
+            &self.constants,
+            &self.imports,
+            &self.sources,
+        ))
+        .unwrap()
+    }
+
+    pub fn encode(&self) -> String {
+        let body = self.body_bytes();
+        let header = BotArtifactHeader {
+            checksum: checksum(&body),
+            ..self.header.to_owned()
+        };
+
+        let mut bytes = bincode::serialize(&header).unwrap();
+        bytes.extend(body);
+
+        format!(
+            "{}{}",
+            BOT_ARTIFACT_PREFIX,
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        )
+    }
+
+    /**
+     * Encode the artifact with a signature, so that it can be trusted when it is sent
+     * back by a client that does not know the key
+     */
+    pub fn encode_signed(&self, key: &[u8]) -> String {
+        let encoded = self.encode();
+
+        let mut mac = hmac(key);
+        mac.update(encoded.as_bytes());
+
+        format!(
+            "{}{}{}",
+            encoded,
+            SIGNATURE_SEPARATOR,
+            hex::encode(mac.finalize().into_bytes())
+        )
+    }
+
+    pub fn get_step_checksum(&self, flow: &str, step: &str) -> Option<&String> {
+        self.step_checksums.get(flow)?.get(step)
+    }
+}
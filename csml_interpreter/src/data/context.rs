@@ -1,4 +1,5 @@
 use crate::data::{
+    data::PreviousInfo,
     primitive::{PrimitiveObject, PrimitiveType},
     Client, Hold, Interval, Literal,
 };
@@ -67,6 +68,12 @@ pub struct Context {
     pub step: ContextStepInfo,
     pub flow: String,
     pub previous_bot: Option<PreviousBot>,
+    // target of `previous` when the interpretation does not resume a hold
+    // (set when an interruption flow was triggered during a hold)
+    pub previous: Option<PreviousInfo>,
+    // hold interrupted by a flow that resumes it: going back to its step holds again
+    // at the same position, with the same step variables
+    pub interrupted: Option<Hold>,
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -135,6 +142,8 @@ impl Context {
             step: ContextStepInfo::Normal(step.to_owned()),
             flow: flow.to_owned(),
             previous_bot,
+            previous: None,
+            interrupted: None,
         }
     }
 }
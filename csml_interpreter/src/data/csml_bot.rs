@@ -22,6 +22,19 @@ pub struct CsmlBot {
     pub bot_ast: Option<String>,
     pub no_interruption_delay: Option<i32>,
     pub env: Option<serde_json::Value>,
+    // flow started when the user input does not trigger any flow and there is
+    // no open conversation to continue (defaults to the default_flow)
+    #[serde(default)]
+    pub fallback_flow: Option<String>,
+    // if set, only the commands of these flows can interrupt a pending hold
+    #[serde(default)]
+    pub interruptions: Option<Vec<Interruption>>,
+    // secret used to sign the messages sent to the callback url
+    #[serde(default)]
+    pub callback_secret: Option<String>,
+    // how user inputs trigger the commands of the flows, the engine's matcher if not set
+    #[serde(default)]
+    pub trigger_matcher: Option<TriggerMatcherConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,10 +44,39 @@ pub struct Module {
     pub auth: Option<String>,
     #[serde(default = "default_version")]
     pub version: String,
+    // "sha256:<hex>" of the content, checked when the module is downloaded
+    #[serde(default)]
+    pub integrity: Option<String>,
     pub flow: Option<CsmlFlow>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/**
+ * Flow whose commands interrupt a pending hold. With `resume`, the held step is
+ * the target of `previous step` (and its flow of `previous flow`) in the interruption flow,
+ * and going back to it waits again for the answer of the hold instead of restarting the step.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interruption {
+    pub flow: String,
+    #[serde(default)]
+    pub resume: bool,
+}
+
+/**
+ * Matcher of the user inputs that are not exactly one of the commands of the flows:
+ * `{"type": "exact"}` or `{"type": "fuzzy", "threshold": 0.8}`
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum TriggerMatcherConfig {
+    Exact,
+    Fuzzy {
+        #[serde(default)]
+        threshold: Option<f64>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct MultiBot {
     pub id: String,
     pub name: Option<String>,
@@ -77,9 +119,23 @@ impl CsmlBot {
             bot_ast,
             no_interruption_delay,
             env,
+            fallback_flow: None,
+            interruptions: None,
+            callback_secret: None,
+            trigger_matcher: None,
         }
     }
 
+    /**
+     * Interruption of the flow, matching its id or name (case insensitive)
+     */
+    pub fn get_interruption(&self, flow: &CsmlFlow) -> Option<&Interruption> {
+        self.interruptions.as_ref()?.iter().find(|interruption| {
+            interruption.flow.eq_ignore_ascii_case(&flow.id)
+                || interruption.flow.eq_ignore_ascii_case(&flow.name)
+        })
+    }
+
     pub fn get_default_flow_name(&self) -> String {
         match self.flows.iter().find(|&val| {
             val.id.to_ascii_lowercase() == self.default_flow.to_ascii_lowercase()
@@ -128,6 +184,14 @@ impl CsmlBot {
             serde_json::json!(self.no_interruption_delay),
         );
         map.insert("env".to_owned(), serde_json::json!(self.env));
+        map.insert(
+            "fallback_flow".to_owned(),
+            serde_json::json!(self.fallback_flow),
+        );
+        map.insert(
+            "interruptions".to_owned(),
+            serde_json::json!(self.interruptions),
+        );
 
         serde_json::json!(map)
     }
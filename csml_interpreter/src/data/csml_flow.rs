@@ -10,6 +10,9 @@ pub struct CsmlFlow {
     pub name: String,
     pub content: String,
     pub commands: Vec<String>,
+    // when several flows are triggered by the same command, the highest priority wins
+    #[serde(default)]
+    pub priority: i64,
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -23,6 +26,7 @@ impl CsmlFlow {
             name: name.to_owned(),
             content: content.to_owned(),
             commands,
+            priority: 0,
         }
     }
 }
@@ -0,0 +1,93 @@
+/**
+ * Metrics of the interpreter and the engine, collected by the application.
+ *
+ * The interpreter and the engine report each event of interest (a step interpreted, a message
+ * sent, an HTTP() call...) to the global `Recorder`, which is installed by the application with
+ * `set_recorder` (see the /metrics route of csml_server). Without a recorder, nothing is recorded.
+ */
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric<'a> {
+    // a step was interpreted
+    Step {
+        bot_id: &'a str,
+        flow: &'a str,
+        step: &'a str,
+        duration: Duration,
+    },
+    // a message was sent by the bot
+    Message {
+        bot_id: &'a str,
+        flow: &'a str,
+    },
+    // the conversation is waiting for the user's input
+    Hold {
+        bot_id: &'a str,
+        flow: &'a str,
+        step: &'a str,
+    },
+    // the user's input was given to a step on hold
+    Resume {
+        bot_id: &'a str,
+        flow: &'a str,
+        step: &'a str,
+    },
+    // an HTTP() or App() call, without status when no response was received
+    Http {
+        method: &'a str,
+        status: Option<u16>,
+        duration: Duration,
+    },
+    // a message could not be delivered to the callback_url
+    CallbackFailure {
+        bot_id: &'a str,
+    },
+    // a call to the engine database
+    Db {
+        backend: &'a str,
+        operation: &'a str,
+        duration: Duration,
+    },
+}
+
+pub trait Recorder: Send + Sync {
+    fn record(&self, metric: &Metric);
+}
+
+static RECORDER: RwLock<Option<Arc<dyn Recorder>>> = RwLock::new(None);
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Install the recorder receiving every metric, replacing the previous one
+ */
+pub fn set_recorder(recorder: Arc<dyn Recorder>) {
+    if let Ok(mut current) = RECORDER.write() {
+        *current = Some(recorder);
+    }
+}
+
+pub fn remove_recorder() {
+    if let Ok(mut current) = RECORDER.write() {
+        *current = None;
+    }
+}
+
+pub fn record(metric: Metric) {
+    let recorder = match RECORDER.read() {
+        Ok(recorder) => recorder.clone(),
+        Err(_) => return,
+    };
+
+    if let Some(recorder) = recorder {
+        recorder.record(&metric);
+    }
+}
@@ -0,0 +1,205 @@
+/**
+ * OpenTelemetry instrumentation, shared by the interpreter and the engine.
+ *
+ * Spans are created with the global tracer provider and trace context is propagated
+ * with the global text map propagator: both are installed by the application
+ * (see the `otel` feature of csml_server). Without the `otel` feature, every function
+ * of this module is a no-op.
+ */
+#[cfg(feature = "otel")]
+use opentelemetry::{
+    context::FutureExt,
+    global::{self, BoxedSpan},
+    trace::{Span as _, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+#[cfg(feature = "otel")]
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::future::Future;
+
+#[cfg(feature = "otel")]
+const TRACER_NAME: &str = "csml";
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Span ending when dropped. It is never made current, so it can be held across
+ * awaits: spans started while it is alive are not its children.
+ */
+pub struct Span {
+    #[cfg(feature = "otel")]
+    span: BoxedSpan,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(feature = "otel")]
+fn start(name: &'static str, attributes: &[(&'static str, &str)]) -> BoxedSpan {
+    let tracer = global::tracer(TRACER_NAME);
+
+    tracer
+        .span_builder(name)
+        .with_attributes(
+            attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(*key, value.to_string())),
+        )
+        .start(&tracer)
+}
+
+#[cfg(feature = "otel")]
+fn set_error<E: Debug>(context: &Context, result: &Result<impl Sized, E>) {
+    if let Err(err) = result {
+        context
+            .span()
+            .set_status(Status::error(format!("{:?}", err)));
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Start a span, child of the current one
+ */
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub fn span(name: &'static str, attributes: &[(&'static str, &str)]) -> Span {
+    Span {
+        #[cfg(feature = "otel")]
+        span: start(name, attributes),
+    }
+}
+
+/**
+ * Run `f` in a new span, which is the current one until `f` returns.
+ * The span is marked as failed if `f` returns an error.
+ */
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub fn with_span<T, E, F>(
+    name: &'static str,
+    attributes: &[(&'static str, &str)],
+    f: F,
+) -> Result<T, E>
+where
+    E: Debug,
+    F: FnOnce() -> Result<T, E>,
+{
+    #[cfg(feature = "otel")]
+    {
+        let context = Context::current_with_span(start(name, attributes));
+        let result = {
+            let _guard = context.clone().attach();
+            f()
+        };
+        set_error(&context, &result);
+
+        result
+    }
+
+    #[cfg(not(feature = "otel"))]
+    f()
+}
+
+/**
+ * Async version of `with_span`: the span is current each time the future is polled.
+ */
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub async fn in_span<T, E, F>(
+    name: &'static str,
+    attributes: &[(&'static str, &str)],
+    future: F,
+) -> Result<T, E>
+where
+    E: Debug,
+    F: Future<Output = Result<T, E>>,
+{
+    #[cfg(feature = "otel")]
+    {
+        let context = Context::current_with_span(start(name, attributes));
+        let result = future.with_context(context.clone()).await;
+        set_error(&context, &result);
+
+        result
+    }
+
+    #[cfg(not(feature = "otel"))]
+    future.await
+}
+
+/**
+ * Wrap `f` so that, when it runs on another thread, the spans it starts are children
+ * of the current span.
+ */
+pub fn with_current_context<T, F>(f: F) -> impl FnOnce() -> T
+where
+    F: FnOnce() -> T,
+{
+    #[cfg(feature = "otel")]
+    {
+        let context = Context::current();
+
+        move || {
+            let _guard = context.attach();
+            f()
+        }
+    }
+
+    #[cfg(not(feature = "otel"))]
+    f
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// METHOD FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl Span {
+    /**
+     * Record the status code of an outbound http call. 4xx and 5xx are errors.
+     */
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    pub fn set_http_status(&mut self, status: u16) {
+        #[cfg(feature = "otel")]
+        {
+            self.span
+                .set_attribute(KeyValue::new("http.response.status_code", status as i64));
+
+            if status >= 400 {
+                self.span
+                    .set_status(Status::error(format!("status {}", status)));
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    pub fn set_error(&mut self, message: &str) {
+        #[cfg(feature = "otel")]
+        self.span.set_status(Status::error(message.to_owned()));
+    }
+
+    /**
+     * Headers propagating this span to an outbound call (`traceparent`...)
+     */
+    pub fn propagation_headers(&self) -> Vec<(String, String)> {
+        #[cfg(feature = "otel")]
+        {
+            let context =
+                Context::current().with_remote_span_context(self.span.span_context().clone());
+            let mut headers = HashMap::new();
+
+            global::get_text_map_propagator(|propagator| {
+                propagator.inject_context(&context, &mut headers)
+            });
+
+            headers.into_iter().collect()
+        }
+
+        #[cfg(not(feature = "otel"))]
+        Vec::new()
+    }
+}
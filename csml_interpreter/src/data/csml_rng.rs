@@ -0,0 +1,35 @@
+/**
+ * Random number generator of the `random()`, `shuffle()` and `one_of()` builtins.
+ *
+ * The generator is reset at the start of each interpretation: it is seeded with
+ * the event's `seed` if any, so that the same event always produces the same
+ * random values, or from the OS entropy otherwise.
+ * Interpretation runs on the calling thread, so each thread has its own generator.
+ */
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::cell::RefCell;
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn seed(seed: Option<u64>) {
+    let rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    RNG.with(|current| *current.borrow_mut() = rng);
+}
+
+pub fn with_rng<T, F>(f: F) -> T
+where
+    F: FnOnce(&mut StdRng) -> T,
+{
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
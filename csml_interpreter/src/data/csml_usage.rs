@@ -0,0 +1,63 @@
+/**
+ * Number of steps and HTTP() calls of the current interpretation, so that the engine
+ * can enforce its quotas.
+ *
+ * The counters are reset at the start of each interpretation, with the event's `http_limit`:
+ * once it is reached, the next HTTP() calls fail instead of being sent.
+ * Interpretation runs on the calling thread, so each thread has its own counters.
+ */
+use std::cell::Cell;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    pub steps: usize,
+    pub http_calls: usize,
+}
+
+thread_local! {
+    static USAGE: Cell<Usage> = const { Cell::new(Usage { steps: 0, http_calls: 0 }) };
+    static HTTP_LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn start(http_limit: Option<usize>) {
+    USAGE.with(|usage| usage.set(Usage::default()));
+    HTTP_LIMIT.with(|limit| limit.set(http_limit));
+}
+
+pub fn add_step() {
+    USAGE.with(|usage| {
+        let mut current = usage.get();
+        current.steps += 1;
+        usage.set(current);
+    });
+}
+
+/**
+ * Count an HTTP() call, returns false without counting it when the limit is reached
+ */
+pub fn add_http_call() -> bool {
+    let limit = HTTP_LIMIT.with(|limit| limit.get());
+
+    USAGE.with(|usage| {
+        let mut current = usage.get();
+        match limit {
+            Some(limit) if current.http_calls >= limit => false,
+            _ => {
+                current.http_calls += 1;
+                usage.set(current);
+                true
+            }
+        }
+    })
+}
+
+/**
+ * Usage of the last interpretation of this thread
+ */
+pub fn get() -> Usage {
+    USAGE.with(|usage| usage.get())
+}
@@ -144,6 +144,8 @@ pub fn init_child_context(data: &Data) -> Context {
         step: data.context.step.clone(),
         flow: data.context.flow.clone(),
         previous_bot: data.context.previous_bot.clone(),
+        previous: None,
+        interrupted: None,
     }
 }
 
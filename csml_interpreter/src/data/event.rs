@@ -10,7 +10,13 @@ pub struct Event {
     pub ttl_duration: Option<i64>,
     pub low_data_mode: Option<bool>,
     pub step_limit: Option<usize>,
+    // maximum number of HTTP() calls, the next ones fail
+    pub http_limit: Option<usize>,
     pub secure: bool,
+    // emit a MSG::Trace for each executed instruction
+    pub trace: bool,
+    // seed of the random builtins, to reproduce the same interpretation
+    pub seed: Option<u64>,
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -26,7 +32,10 @@ impl Default for Event {
             ttl_duration: None,
             low_data_mode: None,
             step_limit: None,
+            http_limit: None,
             secure: false,
+            trace: false,
+            seed: None,
         }
     }
 }
@@ -44,7 +53,10 @@ impl Event {
             ttl_duration: None,
             low_data_mode: None,
             step_limit: None,
+            http_limit: None,
             secure: false,
+            trace: false,
+            seed: None,
         }
     }
 }
@@ -2,8 +2,8 @@ use crate::data::error_info::ErrorInfo;
 use crate::data::{Hold, Literal, Memory, Message, MSG};
 use crate::parser::ExitCondition;
 
+use crate::data::msg::MsgSender;
 use core::ops::Add;
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURE
@@ -49,10 +49,7 @@ impl Add<MessageData> for MessageData {
 ////////////////////////////////////////////////////////////////////////////////
 
 impl MessageData {
-    pub fn error_to_message(
-        result: Result<Self, ErrorInfo>,
-        sender: &Option<mpsc::Sender<MSG>>,
-    ) -> Self {
+    pub fn error_to_message(result: Result<Self, ErrorInfo>, sender: &MsgSender) -> Self {
         match result {
             Ok(message_data) => message_data,
             Err(err) => {
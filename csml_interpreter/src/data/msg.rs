@@ -1,9 +1,10 @@
 use crate::data::{
     ast::ForgetMemory, context::ContextStepInfo, csml_logs::LogLvl, error_info::ErrorInfo,
-    hold::Hold, message::Message, primitive::PrimitiveNull, Literal, Memory, MessageData,
+    hold::Hold, message::Message, primitive::PrimitiveNull, schedule::Schedule, trace::TraceEvent,
+    Literal, Memory, MessageData,
 };
 
-use std::sync::mpsc;
+use std::{cell::RefCell, sync::mpsc};
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURE
@@ -27,21 +28,55 @@ pub enum MSG {
         bot: Option<String>,
     },
     Error(Message),
+    Trace(TraceEvent),
+    Schedule(Schedule),
+}
+
+/**
+ * Destination of the MSG emitted during the interpretation of a bot:
+ * either a channel consumed by another thread, or a callback called
+ * directly on the interpreter thread.
+ */
+pub enum MsgSender<'a> {
+    None,
+    Channel(mpsc::Sender<MSG>),
+    Callback(RefCell<&'a mut dyn FnMut(MSG)>),
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 // STATIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-impl MSG {
-    pub fn send(sender: &Option<mpsc::Sender<MSG>>, msg: MSG) {
-        if let Some(sender) = sender {
-            sender.send(msg).unwrap();
+impl<'a> MsgSender<'a> {
+    pub fn callback(callback: &'a mut dyn FnMut(MSG)) -> Self {
+        MsgSender::Callback(RefCell::new(callback))
+    }
+
+    pub fn send(&self, msg: MSG) {
+        match self {
+            MsgSender::None => {}
+            MsgSender::Channel(sender) => sender.send(msg).unwrap(),
+            MsgSender::Callback(callback) => (callback.borrow_mut())(msg),
         }
     }
+}
+
+impl From<Option<mpsc::Sender<MSG>>> for MsgSender<'static> {
+    fn from(sender: Option<mpsc::Sender<MSG>>) -> Self {
+        match sender {
+            Some(sender) => MsgSender::Channel(sender),
+            None => MsgSender::None,
+        }
+    }
+}
+
+impl MSG {
+    pub fn send(sender: &MsgSender, msg: MSG) {
+        sender.send(msg);
+    }
 
     pub fn send_error_msg(
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
         msg_data: &mut MessageData,
         value: Result<Literal, ErrorInfo>,
     ) -> Literal {
@@ -53,10 +88,7 @@ impl MSG {
                     content: serde_json::json!({"error": err.format_error()}),
                 };
                 msg_data.messages.push(message.clone());
-                if let Some(sender) = sender {
-                    let msg = MSG::Message(message);
-                    sender.send(msg).unwrap();
-                }
+                sender.send(MSG::Message(message));
 
                 let mut error_lit = PrimitiveNull::get_literal(err.position.interval);
                 error_lit.additional_info = err.additional_info;
@@ -27,9 +27,10 @@ use crate::data::primitive::tools::*;
 use crate::data::{Data, Interval, Literal, MemoryType, Message, MessageData, MSG};
 use crate::error_format::*;
 
+use crate::data::msg::MsgSender;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::ops::{Add, Div, Mul, Rem, Sub};
-use std::{collections::HashMap, sync::mpsc};
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
@@ -84,7 +85,7 @@ pub trait Primitive: Send {
         content_type: &ContentType,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo>;
 }
 
@@ -119,7 +120,7 @@ impl dyn Primitive {
         mem_update: &mut bool,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         *mem_update = false;
 
@@ -1,5 +1,6 @@
 use crate::data::position::Position;
 use crate::data::{
+    csml_rng,
     data::{init_child_context, init_child_scope, Data},
     literal,
     literal::ContentType,
@@ -8,7 +9,7 @@ use crate::data::{
         PrimitiveString, PrimitiveType, Right,
     },
     tokens::TYPES,
-    ArgsType, Interval, Literal, MemoryType, Message, MessageData, MSG,
+    ArgsType, Interval, Literal, MemoryType, Message, MessageData, MsgSender,
 };
 use crate::error_format::*;
 use crate::interpreter::variable_handler::resolve_csml_object::{
@@ -20,8 +21,8 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::usize;
-use std::{collections::HashMap, sync::mpsc};
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
@@ -34,7 +35,7 @@ type PrimitiveMethod = fn(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo>;
 
 const FUNCTIONS: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map! {
@@ -107,7 +108,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_number() => boolean";
 
@@ -128,7 +129,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_int() => boolean";
 
@@ -149,7 +150,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_float() => boolean";
 
@@ -170,7 +171,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "type_of() => string";
 
@@ -191,7 +192,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         literal::get_info(args, additional_info, interval, data)
     }
@@ -203,7 +204,7 @@ impl PrimitiveArray {
         interval: Interval,
         _data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         match additional_info {
             Some(map) if map.contains_key("error") => {
@@ -220,7 +221,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "to_string() => string";
 
@@ -243,7 +244,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "init(capacity: Int) => [Literal]";
 
@@ -286,7 +287,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "find(value: primitive) => array";
 
@@ -329,7 +330,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_empty() => boolean";
 
@@ -352,7 +353,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "insert_at(index: int, value: primitive) => null";
 
@@ -409,7 +410,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "index_of(value: primitive) => int";
 
@@ -446,7 +447,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "join(separator: string) => string";
 
@@ -495,7 +496,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "length() => int";
 
@@ -518,7 +519,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "one_of() => primitive";
 
@@ -529,10 +530,9 @@ impl PrimitiveArray {
             ));
         }
 
-        if let Some(res) = array
-            .value
-            .get(rand::thread_rng().gen_range(0..array.value.len()))
-        {
+        let index = csml_rng::with_rng(|rng| rng.gen_range(0..array.value.len()));
+
+        if let Some(res) = array.value.get(index) {
             return Ok(res.to_owned());
         }
 
@@ -546,7 +546,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "push(value: primitive) => null";
 
@@ -586,7 +586,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "pop() => primitive";
 
@@ -613,7 +613,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "remove_at(index: int) => primitive";
 
@@ -658,7 +658,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "shuffle() => array";
 
@@ -671,7 +671,7 @@ impl PrimitiveArray {
 
         let mut vector = array.value.to_owned();
 
-        vector.shuffle(&mut rand::thread_rng());
+        csml_rng::with_rng(|rng| vector.shuffle(rng));
 
         Ok(PrimitiveArray::get_literal(&vector, interval))
     }
@@ -683,7 +683,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "slice(start: Integer, end: Optional<Integer>) => [Literal]";
         let len = array.value.len();
@@ -792,7 +792,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "reverse() => [Literal]";
 
@@ -816,7 +816,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "append(other_array: [Literal]) => [Literal]";
 
@@ -860,7 +860,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "flatten() => [Literal]";
 
@@ -901,7 +901,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "map(fn) expect one argument of type [Closure]";
 
@@ -975,7 +975,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "filter(fn) expect one argument of type [Closure]";
 
@@ -1054,7 +1054,7 @@ impl PrimitiveArray {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "reduce(acc, fn) expect tow arguments an initial value and 'Closure' with two arguments: an 'accumulator', and an element";
 
@@ -1295,7 +1295,7 @@ impl Primitive for PrimitiveArray {
         _content_type: &ContentType,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         if let Some((f, right)) = FUNCTIONS.get(name) {
             if *mem_type == MemoryType::Constant && *right == Right::Write {
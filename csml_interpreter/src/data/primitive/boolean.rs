@@ -4,13 +4,15 @@ use crate::data::primitive::object::PrimitiveObject;
 use crate::data::primitive::string::PrimitiveString;
 use crate::data::primitive::Right;
 use crate::data::primitive::{Primitive, PrimitiveType};
-use crate::data::{ast::Interval, message::Message, Data, Literal, MemoryType, MessageData, MSG};
+use crate::data::{
+    ast::Interval, message::Message, Data, Literal, MemoryType, MessageData, MsgSender,
+};
 use crate::data::{literal, literal::ContentType};
 use crate::error_format::*;
 use phf::phf_map;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
@@ -203,7 +205,7 @@ impl Primitive for PrimitiveBoolean {
         _content_type: &ContentType,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         if let Some((f, right)) = FUNCTIONS.get(name) {
             if *mem_type == MemoryType::Constant && *right == Right::Write {
@@ -9,13 +9,13 @@ use crate::data::primitive::{Primitive, PrimitiveType};
 use crate::data::{
     ast::{Expr, Interval},
     message::Message,
-    Data, Literal, MemoryType, MessageData, MSG,
+    Data, Literal, MemoryType, MessageData, MsgSender,
 };
 use crate::error_format::*;
 use phf::phf_map;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 
 pub fn capture_variables(
     literal: &mut Literal,
@@ -346,7 +346,7 @@ impl Primitive for PrimitiveClosure {
         _content_type: &ContentType,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         if let Some((f, right)) = FUNCTIONS.get(name) {
             if *mem_type == MemoryType::Constant && *right == Right::Write {
@@ -10,13 +10,13 @@ use crate::data::{
         Primitive, PrimitiveBoolean, PrimitiveInt, PrimitiveObject, PrimitiveString, PrimitiveType,
         Right,
     },
-    Data, Literal, MemoryType, MessageData, MSG,
+    Data, Literal, MemoryType, MessageData, MsgSender,
 };
 use crate::error_format::*;
 use phf::phf_map;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
@@ -732,7 +732,7 @@ impl Primitive for PrimitiveFloat {
         _content_type: &ContentType,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         if let Some((f, right)) = FUNCTIONS.get(name) {
             if *mem_type == MemoryType::Constant && *right == Right::Write {
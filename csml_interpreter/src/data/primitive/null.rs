@@ -7,13 +7,13 @@ use crate::data::primitive::{
 };
 use crate::data::{
     ast::Interval, literal::ContentType, message::Message, tokens::NULL, Data, Literal, MemoryType,
-    MessageData, MSG,
+    MessageData, MsgSender,
 };
 use crate::error_format::*;
 use phf::phf_map;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
@@ -332,7 +332,7 @@ impl Primitive for PrimitiveNull {
         _content_type: &ContentType,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         if let Some((f, right)) = FUNCTIONS.get(name) {
             if *mem_type == MemoryType::Constant && *right == Right::Write {
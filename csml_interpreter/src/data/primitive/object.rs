@@ -1,17 +1,19 @@
 use crate::data::error_info::ErrorInfo;
+use crate::data::msg::MsgSender;
 use crate::data::position::Position;
 use crate::data::{
     ast::Interval,
     csml_logs::*,
-    literal,
+    csml_usage, literal,
     literal::ContentType,
     message::Message,
     primitive::{
         tools_crypto, tools_jwt, tools_smtp, tools_time, Data, MessageData, Primitive,
         PrimitiveArray, PrimitiveBoolean, PrimitiveInt, PrimitiveNull, PrimitiveString,
-        PrimitiveType, Right, MSG,
+        PrimitiveType, Right,
     },
     tokens::TYPES,
+    trace::{http_status, trace, TraceKind},
     Literal, MemoryType,
 };
 use crate::error_format::*;
@@ -21,7 +23,8 @@ use crate::interpreter::{
 };
 use base64::Engine;
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
+use std::time::Instant;
 
 use chrono::{DateTime, FixedOffset, LocalResult, TimeZone, Timelike, Utc};
 use chrono_tz::{Tz, UTC};
@@ -502,6 +505,13 @@ impl PrimitiveObject {
                 }
             };
 
+            if !csml_usage::add_http_call() {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    ERROR_HTTP_LIMIT.to_owned(),
+                ));
+            }
+
             let (value, response_info) =
                 http_request(&object.value, method, &data.context.flow, interval, false)?;
             let mut literal = json_to_literal(&value, interval, &data.context.flow)?;
@@ -2735,7 +2745,7 @@ impl Primitive for PrimitiveObject {
         content_type: &ContentType,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         let event = vec![FUNCTIONS_EVENT];
         let http = vec![FUNCTIONS_HTTP, FUNCTIONS_READ, FUNCTIONS_WRITE];
@@ -2748,6 +2758,7 @@ impl Primitive for PrimitiveObject {
         let generics = vec![FUNCTIONS_READ, FUNCTIONS_WRITE];
 
         let mut is_event = false;
+        let is_http_send = matches!(content_type, ContentType::Http) && name == "send";
 
         let (content_type, vector) = match content_type {
             ContentType::Event(event_type) => {
@@ -2773,9 +2784,30 @@ impl Primitive for PrimitiveObject {
                         ERROR_CONSTANT_MUTABLE_FUNCTION.to_string(),
                     ));
                 } else {
-                    let result = f(self, args, additional_info, data, interval, content_type)?;
+                    let start = Instant::now();
+                    let result = f(self, args, additional_info, data, interval, content_type);
+
+                    if is_http_send {
+                        trace(data, sender, interval, || {
+                            let response_info = match &result {
+                                Ok(literal) => literal.additional_info.as_ref(),
+                                Err(err) => err.additional_info.as_ref(),
+                            };
+                            let get = |key: &str| match self.value.get(key) {
+                                Some(literal) => literal.primitive.to_string(),
+                                None => String::new(),
+                            };
+
+                            TraceKind::Http {
+                                method: get("method"),
+                                url: get("url"),
+                                status: http_status(response_info),
+                                duration_ms: start.elapsed().as_millis() as u64,
+                            }
+                        });
+                    }
 
-                    return Ok((result, *right));
+                    return Ok((result?, *right));
                 }
             }
         }
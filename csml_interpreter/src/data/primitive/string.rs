@@ -9,7 +9,9 @@ use crate::data::primitive::object::PrimitiveObject;
 use crate::data::primitive::tools::*;
 use crate::data::primitive::Right;
 use crate::data::primitive::{Primitive, PrimitiveType};
-use crate::data::{ast::Interval, message::Message, Data, Literal, MemoryType, MessageData, MSG};
+use crate::data::{
+    ast::Interval, message::Message, Data, Literal, MemoryType, MessageData, MsgSender,
+};
 use crate::data::{literal, literal::ContentType};
 use crate::error_format::*;
 use crate::interpreter::json_to_literal;
@@ -18,7 +20,7 @@ use phf::phf_map;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 use url::form_urlencoded;
 use url::form_urlencoded::Parse;
 use url::Url;
@@ -34,7 +36,7 @@ type PrimitiveMethod = fn(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo>;
 
 const FUNCTIONS: phf::Map<&'static str, (PrimitiveMethod, Right)> = phf_map! {
@@ -186,7 +188,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_number() => boolean";
 
@@ -209,7 +211,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_int() => boolean";
 
@@ -232,7 +234,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_float() => boolean";
 
@@ -260,7 +262,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_email() => boolean";
 
@@ -285,7 +287,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "type_of() => string";
 
@@ -306,7 +308,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         literal::get_info(args, additional_info, interval, data)
     }
@@ -318,7 +320,7 @@ impl PrimitiveString {
         interval: Interval,
         _data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         match additional_info {
             Some(map) if map.contains_key("error") => {
@@ -335,7 +337,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "to_string() => string";
 
@@ -356,7 +358,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "to_json() => obj";
 
@@ -399,7 +401,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "encode_uri() => String";
 
@@ -452,7 +454,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "encode_uri() => String";
 
@@ -505,7 +507,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "encode_uri_component() => String";
 
@@ -528,7 +530,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "decode_uri_component() => String";
 
@@ -555,7 +557,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "decode_html_entities() => String";
 
@@ -578,7 +580,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "encode_html_entities() => String";
 
@@ -603,7 +605,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "append(value: string) => string";
 
@@ -645,7 +647,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "contains(value: string) => boolean";
 
@@ -685,7 +687,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "contains_regex(value: string) => boolean";
 
@@ -735,7 +737,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "replace(value_to_replace: string, replace_by: string) => string";
 
@@ -786,7 +788,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "replace_all(value_to_replace: string, replace_by: string) => string";
 
@@ -837,7 +839,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "replace_regex(regex: string, replace_by: string) => string";
 
@@ -898,7 +900,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "ends_with(value: string) => boolean";
 
@@ -938,7 +940,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "ends_with_regex(value: string) => boolean";
 
@@ -992,7 +994,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "from_json() => object";
 
@@ -1023,7 +1025,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "is_empty() => boolean";
 
@@ -1046,7 +1048,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "length() => int";
 
@@ -1069,7 +1071,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "match(value: string>) => array";
 
@@ -1117,7 +1119,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "match_regex(value: string>) => array";
 
@@ -1180,7 +1182,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "starts_with(value: string) => boolean";
 
@@ -1220,7 +1222,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "starts_with_regex(value: string) => boolean";
 
@@ -1274,7 +1276,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "to_lowercase() => string";
 
@@ -1296,7 +1298,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "to_uppercase() => string";
 
@@ -1318,7 +1320,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "capitalize() => string";
 
@@ -1347,7 +1349,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "slice(start: Integer, end: Optional<Integer>) => string";
         let text_vec = string.value.chars().collect::<Vec<_>>();
@@ -1457,7 +1459,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "string(separator: string) => array";
 
@@ -1501,7 +1503,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "trim() => string";
 
@@ -1523,7 +1525,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "trim_left() => string";
 
@@ -1545,7 +1547,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         _msg_data: &mut MessageData,
-        _sender: &Option<mpsc::Sender<MSG>>,
+        _sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         let usage = "trim_right() => string";
 
@@ -1570,7 +1572,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1620,7 +1622,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1671,7 +1673,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1721,7 +1723,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1771,7 +1773,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1821,7 +1823,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1871,7 +1873,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1921,7 +1923,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -1971,7 +1973,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -2021,7 +2023,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -2071,7 +2073,7 @@ impl PrimitiveString {
         interval: Interval,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<Literal, ErrorInfo> {
         if let Ok(int) = string.value.parse::<i64>() {
             let mut primitive = PrimitiveInt::new(int);
@@ -2431,7 +2433,7 @@ impl Primitive for PrimitiveString {
         _content_type: &ContentType,
         data: &mut Data,
         msg_data: &mut MessageData,
-        sender: &Option<mpsc::Sender<MSG>>,
+        sender: &MsgSender,
     ) -> Result<(Literal, Right), ErrorInfo> {
         if let Some((f, right)) = FUNCTIONS.get(name) {
             if *mem_type == MemoryType::Constant && *right == Right::Write {
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Future flow trigger of the current client, created by the `Schedule()` builtin.
+ * The engine saves it, and runs it as a `flow_trigger` event once its date is passed.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub flow: String,
+    pub step: String,
+    // unix timestamp in milliseconds
+    pub at: i64,
+}
@@ -167,12 +167,13 @@ pub const DEBUG: &str = "Debug";
 pub const UUID: &str = "UUID";
 pub const TIME: &str = "Time";
 pub const EXISTS: &str = "Exists";
+pub const SCHEDULE: &str = "Schedule";
 
 pub const OBJECT: &str = "Object";
 
 pub const BUILT_IN: &[&str] = &[
     ONE_OF, SHUFFLE, LENGTH, FIND, RANDOM, FLOOR, FN, APP, HTTP, OBJECT, DEBUG, UUID, BASE64, HEX,
-    JWT, CRYPTO, TIME, SMTP, EXISTS,
+    JWT, CRYPTO, TIME, SMTP, EXISTS, SCHEDULE,
 ];
 
 pub const OR_BUILT_IN: &str = "Or";
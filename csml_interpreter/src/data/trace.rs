@@ -0,0 +1,153 @@
+use crate::data::ast::{Expr, Interval, ObjectType};
+use crate::data::msg::{MsgSender, MSG};
+use crate::data::{Data, Literal, MemoryType};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Structured event emitted for each executed instruction when the event
+ * is interpreted in trace mode. `flow` and `step` are the ones being executed
+ * when the event is emitted (the origin of a goto).
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub flow: String,
+    pub step: String,
+    pub interval: Interval,
+    #[serde(flatten)]
+    pub kind: TraceKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceKind {
+    Instruction {
+        instruction: String,
+    },
+    Mutation {
+        variable: String,
+        memory: String,
+        value: serde_json::Value,
+    },
+    Goto {
+        to_flow: String,
+        to_step: String,
+    },
+    Hold {
+        secure: bool,
+    },
+    Http {
+        method: String,
+        url: String,
+        status: Option<i64>,
+        duration_ms: u64,
+    },
+    App {
+        name: String,
+        status: Option<i64>,
+        duration_ms: u64,
+    },
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Send a trace event if trace mode is on. The kind is only built when needed.
+ */
+pub fn trace<F>(data: &Data, sender: &MsgSender, interval: Interval, kind: F)
+where
+    F: FnOnce() -> TraceKind,
+{
+    if !data.event.trace {
+        return;
+    }
+
+    MSG::send(
+        sender,
+        MSG::Trace(TraceEvent {
+            flow: data.context.flow.to_owned(),
+            step: data.context.step.get_step(),
+            interval,
+            kind: kind(),
+        }),
+    );
+}
+
+/**
+ * Send the goto event of a flow/step change, once the context has been updated.
+ * `flow` and `step` are the origin of the goto.
+ */
+pub fn trace_goto(data: &Data, sender: &MsgSender, interval: Interval, flow: String, step: String) {
+    if !data.event.trace {
+        return;
+    }
+
+    MSG::send(
+        sender,
+        MSG::Trace(TraceEvent {
+            flow,
+            step,
+            interval,
+            kind: TraceKind::Goto {
+                to_flow: data.context.flow.to_owned(),
+                to_step: data.context.step.get_step(),
+            },
+        }),
+    );
+}
+
+pub fn memory_type_name(memory_type: &MemoryType) -> String {
+    match memory_type {
+        MemoryType::Event(_) => "event",
+        MemoryType::Metadata => "metadata",
+        MemoryType::Use => "use",
+        MemoryType::Remember => "remember",
+        MemoryType::Constant => "constant",
+    }
+    .to_owned()
+}
+
+pub fn instruction_name(action: &Expr) -> &'static str {
+    match action {
+        Expr::ObjectExpr(object) => match object {
+            ObjectType::Goto(..) => "goto",
+            ObjectType::Previous(..) => "previous",
+            ObjectType::Hold(..) => "hold",
+            ObjectType::HoldSecure(..) => "hold_secure",
+            ObjectType::Say(..) => "say",
+            ObjectType::Debug(..) => "debug",
+            ObjectType::Log { .. } => "log",
+            ObjectType::Return(..) => "return",
+            ObjectType::Do(..) => "do",
+            ObjectType::Use(..) => "use",
+            ObjectType::Remember(..) => "remember",
+            ObjectType::Assign(..) => "assign",
+            ObjectType::Forget(..) => "forget",
+            ObjectType::As(..) => "as",
+            ObjectType::BuiltIn(..) => "builtin",
+            ObjectType::Break(..) => "break",
+            ObjectType::Continue(..) => "continue",
+        },
+        Expr::IfExpr(..) => "if",
+        Expr::ForEachExpr(..) => "foreach",
+        Expr::WhileExpr(..) => "while",
+        _ => "expression",
+    }
+}
+
+/**
+ * Status code of an http call, read from the response info added to its result
+ * (or to its error)
+ */
+pub fn http_status(response_info: Option<&HashMap<String, Literal>>) -> Option<i64> {
+    response_info?
+        .get("status")
+        .and_then(|status| status.primitive.to_json().as_i64())
+}
@@ -157,6 +157,7 @@ pub const ERROR_SMTP: &str =
     "SMTP builtin expects SMTP Server Address. Example: SMTP(\"smtp.gmail.com\")";
 pub const ERROR_CRYPTO: &str =
     "CRYPTO builtin expects one argument of type string. Example: CRYPTO(\"text\")";
+pub const ERROR_SCHEDULE: &str = "Schedule builtin expects the name of a flow of the bot, an optional step and a date of type Time or Int (unix timestamp in milliseconds). Example: Schedule(\"reminder\", step = \"start\", at = Time().add(3600))";
 pub const ERROR_BUILTIN_UNKNOWN: &str = "Unknown builtin";
 
 // ### native Components
@@ -300,6 +301,7 @@ pub const ERROR_HTTP_QUERY: &str =
 
 pub const ERROR_HTTP_SEND: &str = "[send] HTTP Object is bad formatted read doc for correct usage";
 pub const ERROR_HTTP_UNKNOWN_METHOD: &str = "is not a method of HTTP";
+pub const ERROR_HTTP_LIMIT: &str = "[send] HTTP calls quota of the conversation reached";
 
 // #### OBJECT
 pub const ERROR_OBJECT_TYPE: &str = "value must be of type Object";
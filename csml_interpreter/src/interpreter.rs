@@ -10,7 +10,10 @@ pub use json_to_rust::{json_to_literal, memory_to_literal};
 use crate::data::error_info::ErrorInfo;
 use crate::data::position::Position;
 use crate::data::{
-    ast::*, warnings::DisplayWarnings, Data, Hold, IndexInfo, Literal, MessageData, MSG,
+    ast::*,
+    trace::{instruction_name, trace, trace_goto, TraceKind},
+    warnings::DisplayWarnings,
+    Data, Hold, IndexInfo, Literal, MessageData, MSG,
 };
 use crate::error_format::*;
 use crate::interpreter::{
@@ -19,8 +22,8 @@ use crate::interpreter::{
 };
 use crate::parser::ExitCondition;
 
+use crate::data::msg::MsgSender;
 use nom::lib::std::collections::HashMap;
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTION
@@ -44,7 +47,7 @@ fn step_vars_to_json(map: HashMap<String, Literal>) -> serde_json::Value {
 pub fn interpret_scope(
     actions: &Block,
     data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<MessageData, ErrorInfo> {
     let mut message_data = MessageData::default();
 
@@ -64,6 +67,12 @@ pub fn interpret_scope(
             return Ok(message_data);
         }
 
+        trace(data, sender, interval_from_expr(action), || {
+            TraceKind::Instruction {
+                instruction: instruction_name(action).to_owned(),
+            }
+        });
+
         match action {
             Expr::ObjectExpr(ObjectType::Return(var)) => {
                 let lit = expr_to_literal(
@@ -72,7 +81,7 @@ pub fn interpret_scope(
                     None,
                     data,
                     &mut message_data,
-                    &None,
+                    &MsgSender::None,
                 )?;
                 message_data.exit_condition = Some(ExitCondition::Return(lit));
 
@@ -106,6 +115,9 @@ pub fn interpret_scope(
 
                 message_data.hold = Some(hold.to_owned());
 
+                trace(data, sender, interval_from_expr(action), || {
+                    TraceKind::Hold { secure: false }
+                });
                 MSG::send(sender, MSG::Hold(hold));
                 message_data.exit_condition = Some(ExitCondition::Hold);
                 return Ok(message_data);
@@ -128,10 +140,24 @@ pub fn interpret_scope(
 
                 message_data.hold = Some(hold.to_owned());
 
+                trace(data, sender, interval_from_expr(action), || {
+                    TraceKind::Hold { secure: true }
+                });
                 MSG::send(sender, MSG::Hold(hold));
                 message_data.exit_condition = Some(ExitCondition::Hold);
                 return Ok(message_data);
             }
+            Expr::ObjectExpr(fun @ (ObjectType::Goto(..) | ObjectType::Previous(..)))
+                if !matches!(
+                    fun,
+                    ObjectType::Goto(GotoType::StepFlow { bot: Some(_), .. }, ..)
+                ) =>
+            {
+                let (flow, step) = (data.context.flow.to_owned(), data.context.step.get_step());
+
+                message_data = match_actions(fun, message_data, data, sender)?;
+                trace_goto(data, sender, interval_from_expr(action), flow, step);
+            }
             Expr::ObjectExpr(fun) => message_data = match_actions(fun, message_data, data, sender)?,
             Expr::IfExpr(ref if_statement) => {
                 message_data =
@@ -1,4 +1,5 @@
 use crate::data::data::PreviousInfo;
+use crate::data::msg::MsgSender;
 use crate::data::position::Position;
 use crate::data::warnings::DisplayWarnings;
 use crate::data::{
@@ -8,6 +9,7 @@ use crate::data::{
     literal::ContentType,
     message::*,
     primitive::{closure::capture_variables, PrimitiveNull, PrimitiveString},
+    trace::{memory_type_name, trace, TraceKind},
     Literal, Memory, MemoryType, MessageData, MSG,
 };
 use crate::error_format::*;
@@ -21,14 +23,13 @@ use crate::interpreter::variable_handler::{
 };
 use crate::parser::ExitCondition;
 use std::collections::HashMap;
-use std::sync::mpsc;
 
 fn get_var_info<'a>(
     expr: &'a Expr,
     path: Option<&[(Interval, PathState)]>,
     data: &'a mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<
     (
         &'a mut Literal,
@@ -95,7 +96,7 @@ pub fn match_actions(
     function: &ObjectType,
     mut msg_data: MessageData,
     data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<MessageData, ErrorInfo> {
     match function {
         ObjectType::Say(arg) => {
@@ -213,6 +214,7 @@ pub fn match_actions(
             let memory: HashMap<String, Literal> = data.get_all_memories();
             capture_variables(&mut new_value, memory, &data.context.flow);
 
+            let tracing = data.event.trace;
             let (lit, name, mem_type, path) = get_var_info(old, None, data, &mut msg_data, sender)?;
 
             let primitive = match assign_type {
@@ -287,6 +289,14 @@ pub fn match_actions(
                 sender,
             )?;
 
+            let mutation = tracing.then(|| {
+                (
+                    name.to_owned(),
+                    memory_type_name(&mem_type),
+                    lit.primitive.to_json(),
+                )
+            });
+
             save_literal_in_mem(
                 lit.to_owned(),
                 name,
@@ -297,6 +307,16 @@ pub fn match_actions(
                 sender,
             );
 
+            if let Some((variable, memory, value)) = mutation {
+                trace(data, sender, interval_from_expr(old), || {
+                    TraceKind::Mutation {
+                        variable,
+                        memory,
+                        value,
+                    }
+                });
+            }
+
             Ok(msg_data)
         }
         ObjectType::Do(DoType::Exec(expr)) => {
@@ -567,6 +587,12 @@ pub fn match_actions(
 
             msg_data.add_to_memory(&name.ident, new_value.clone());
 
+            trace(data, sender, name.interval, || TraceKind::Mutation {
+                variable: name.ident.to_owned(),
+                memory: memory_type_name(&MemoryType::Remember),
+                value: new_value.primitive.to_json(),
+            });
+
             MSG::send(
                 sender,
                 MSG::Remember(Memory::new(name.ident.to_owned(), new_value.clone())),
@@ -6,13 +6,12 @@ use crate::data::{
     },
     primitive::tools::get_array,
     warnings::DisplayWarnings,
-    Data, MessageData, MSG,
+    Data, MessageData, MsgSender,
 };
 use crate::error_format::*;
 use crate::interpreter::interpret_scope;
 use crate::interpreter::variable_handler::expr_to_literal::expr_to_literal;
 use crate::parser::ExitCondition;
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
@@ -26,7 +25,7 @@ pub fn for_loop(
     _range_interval: &Interval,
     mut msg_data: MessageData,
     data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<MessageData, ErrorInfo> {
     let literal = expr_to_literal(
         expr,
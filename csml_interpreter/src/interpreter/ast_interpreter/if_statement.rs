@@ -2,7 +2,7 @@ use crate::data::{
     ast::{Block, Expr, IfStatement, Infix, InstructionInfo},
     context::ContextStepInfo,
     warnings::DisplayWarnings,
-    Data, Literal, MessageData, MSG,
+    Data, Literal, MessageData, MsgSender,
 };
 use crate::error_format::*;
 use crate::interpreter::{
@@ -12,7 +12,6 @@ use crate::interpreter::{
         operations::{evaluate_infix, evaluate_postfix, valid_literal},
     },
 };
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
@@ -23,7 +22,7 @@ pub fn valid_condition(
     expr: &Expr,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> bool {
     match expr {
         Expr::LitExpr { literal, .. } => valid_literal(Ok(literal.to_owned())),
@@ -58,7 +57,7 @@ fn evaluate_if_condition(
     data: &mut Data,
     consequence: &Block,
     instruction_info: &InstructionInfo,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
     then_branch: &Option<Box<IfStatement>>,
 ) -> Result<MessageData, ErrorInfo> {
     if valid_condition(cond, data, &mut msg_data, sender) {
@@ -82,7 +81,7 @@ pub fn evaluate_condition(
     expr2: &Expr,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let flow_name = if let ContextStepInfo::InsertedStep { step: _, ref flow } = data.context.step {
         flow.clone()
@@ -123,7 +122,7 @@ pub fn solve_if_statement(
     mut msg_data: MessageData,
     data: &mut Data,
     instruction_info: &InstructionInfo,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<MessageData, ErrorInfo> {
     match statement {
         IfStatement::IfStmt {
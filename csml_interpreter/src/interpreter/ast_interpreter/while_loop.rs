@@ -6,12 +6,11 @@ use crate::data::{
     // primitive::tools::get_array,
     Data,
     MessageData,
-    MSG,
+    MsgSender,
 };
 use crate::error_format::*;
 use crate::interpreter::{ast_interpreter::if_statement::valid_condition, interpret_scope};
 use crate::parser::ExitCondition;
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
@@ -23,7 +22,7 @@ pub fn while_loop(
     _range_interval: &Interval,
     mut msg_data: MessageData,
     data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<MessageData, ErrorInfo> {
     while valid_condition(cond, data, &mut msg_data, sender) {
         msg_data = msg_data + interpret_scope(block, data, sender)?;
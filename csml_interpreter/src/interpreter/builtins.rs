@@ -5,17 +5,17 @@ pub mod format;
 pub mod functions;
 pub mod http_builtin;
 pub mod jwt;
+pub mod schedule;
 pub mod smtp;
 pub mod time;
 
 pub mod tools;
 
 use crate::data::{
-    ast::*, position::Position, tokens::*, ArgsType, Data, Literal, MessageData, MSG,
+    ast::*, position::Position, tokens::*, ArgsType, Data, Literal, MessageData, MsgSender,
 };
 use crate::error_format::{gen_error_info, ErrorInfo, ERROR_NATIVE_COMPONENT};
 use crate::interpreter::variable_handler::gen_generic_component::gen_generic_component;
-use std::sync::mpsc;
 
 use api::api;
 use crypto::crypto;
@@ -24,6 +24,7 @@ use format::*;
 use functions::*;
 use http_builtin::http;
 use jwt::jwt;
+use schedule::schedule;
 use smtp::smtp;
 use time::time;
 // use uri::*;
@@ -50,7 +51,7 @@ pub fn match_builtin(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match name {
         HTTP => http(args, &data.context.flow, interval),
@@ -71,6 +72,7 @@ pub fn match_builtin(
         CRYPTO => crypto(args, &data.context.flow, interval),
         TIME => time(args, &data.context.flow, interval),
         EXISTS => exists(args, data, interval),
+        SCHEDULE => schedule(args, interval, data, sender).map_err(|err| *err),
 
         //old builtin
         _object => object(args, &data.context.flow, interval),
@@ -8,7 +8,10 @@ use crate::interpreter::{
     json_to_rust::interpolate,
 };
 
-use std::{collections::HashMap, sync::mpsc};
+use crate::data::msg::MsgSender;
+use crate::data::trace::{http_status, trace, TraceKind};
+use std::collections::HashMap;
+use std::time::Instant;
 
 fn format_body(
     args: &ArgsType,
@@ -75,7 +78,7 @@ pub fn api(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let (client, url) = match &data.context.api_info {
         Some(ApiInfo {
@@ -103,7 +106,28 @@ pub fn api(
     http.insert("header".to_owned(), lit_header);
     http.insert("body".to_owned(), body);
 
-    match http_request(&http, "post", &data.context.flow, interval, true) {
+    let name = match args.get("fn_id", 0) {
+        Some(literal) => literal.primitive.to_string(),
+        None => String::new(),
+    };
+
+    let start = Instant::now();
+    let response = http_request(&http, "post", &data.context.flow, interval, true);
+
+    trace(data, sender, interval, || {
+        let response_info = match &response {
+            Ok((_, response_info)) => Some(response_info),
+            Err(err) => err.additional_info.as_ref(),
+        };
+
+        TraceKind::App {
+            name,
+            status: http_status(response_info),
+            duration_ms: start.elapsed().as_millis() as u64,
+        }
+    });
+
+    match response {
         Ok((value, response_info)) => match value.get("data") {
             Some(value) => {
                 let mut literal = interpolate(value, interval, data, msg_data, sender)?;
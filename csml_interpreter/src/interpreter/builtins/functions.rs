@@ -4,7 +4,7 @@ use crate::data::primitive::{
 };
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::data::{ast::Interval, ArgsType, Literal};
+use crate::data::{ast::Interval, csml_rng, ArgsType, Literal};
 use crate::error_format::*;
 use uuid::v1::{Context, Timestamp};
 use uuid::Uuid;
@@ -25,7 +25,7 @@ pub fn one_of(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Lit
                 literal.interval,
                 ERROR_ONE_OF.to_owned(),
             )?;
-            match res.get(rand::thread_rng().gen_range(0..res.len())) {
+            match res.get(csml_rng::with_rng(|rng| rng.gen_range(0..res.len()))) {
                 Some(lit) => Ok(lit.to_owned()),
                 None => Err(gen_error_info(
                     Position::new(literal.interval, flow_name),
@@ -63,7 +63,7 @@ pub fn shuffle(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Li
                 ERROR_SHUFFLE.to_owned(),
             )?;
             let mut vec = res.to_owned();
-            vec.shuffle(&mut rand::thread_rng());
+            csml_rng::with_rng(|rng| vec.shuffle(rng));
             Ok(PrimitiveArray::get_literal(&vec, literal.interval))
         }
         None => Err(gen_error_info(
@@ -170,9 +170,7 @@ pub fn find(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Liter
 }
 
 pub fn random(interval: Interval) -> Result<Literal, ErrorInfo> {
-    let mut rng = rand::thread_rng();
-
-    let random: f64 = rng.gen();
+    let random: f64 = csml_rng::with_rng(|rng| rng.gen());
 
     Ok(PrimitiveFloat::get_literal(random, interval))
 }
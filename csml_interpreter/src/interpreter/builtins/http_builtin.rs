@@ -1,12 +1,14 @@
+use crate::data::csml_metrics::{self, Metric};
 use crate::data::error_info::ErrorInfo;
 use crate::data::position::Position;
 use crate::data::primitive::{PrimitiveInt, PrimitiveObject, PrimitiveString, PrimitiveType};
-use crate::data::{ast::Interval, csml_logs::*, ArgsType, Literal};
+use crate::data::{ast::Interval, csml_logs::*, csml_otel, ArgsType, Literal};
 use crate::error_format::*;
 use std::collections::HashMap;
 use std::env;
 
 use std::sync::Arc;
+use std::time::Instant;
 use ureq::{Request, Response};
 
 use rustls::{
@@ -241,6 +243,10 @@ pub fn http_request(
     )?;
 
     let mut request = get_http_request(method, &url, flow_name, interval, is_ssl_disable)?;
+    let mut span = csml_otel::span(
+        "csml.http",
+        &[("http.request.method", method), ("url.full", &url)],
+    );
 
     for key in header.keys() {
         let value = match header.get(key) {
@@ -275,11 +281,33 @@ pub fn http_request(
         LogLvl::Debug,
     );
 
+    for (key, value) in span.propagation_headers() {
+        request = request.set(&key, &value);
+    }
+
+    let start = Instant::now();
     let response = match object.get("body") {
         Some(body) => request.send_json(body.primitive.to_json()),
         None => request.call(),
     };
 
+    let status = match &response {
+        Ok(response) => Some(response.status()),
+        Err(ureq::Error::Status(status, _)) => Some(*status),
+        Err(err) => {
+            span.set_error(&err.to_string());
+            None
+        }
+    };
+    if let Some(status) = status {
+        span.set_http_status(status);
+    }
+    csml_metrics::record(Metric::Http {
+        method,
+        status,
+        duration: start.elapsed(),
+    });
+
     match response {
         Ok(response) => {
             let response_info = get_request_info(&response, interval);
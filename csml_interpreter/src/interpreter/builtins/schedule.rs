@@ -0,0 +1,93 @@
+use crate::data::error_info::ErrorInfo;
+use crate::data::position::Position;
+use crate::data::primitive::{PrimitiveInt, PrimitiveObject, PrimitiveString, PrimitiveType};
+use crate::data::{ast::Interval, ArgsType, Data, Literal, MsgSender, Schedule, MSG};
+use crate::error_format::*;
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_string(lit: &Literal) -> bool {
+    lit.primitive.get_type() == PrimitiveType::PrimitiveString
+}
+
+fn get_timestamp(lit: &Literal, flow_name: &str, interval: Interval) -> Option<i64> {
+    let lit = match lit.primitive.get_type() {
+        PrimitiveType::PrimitiveObject if lit.content_type == "time" => {
+            let object = Literal::get_value::<HashMap<String, Literal>>(
+                &lit.primitive,
+                flow_name,
+                interval,
+                "".to_owned(),
+            )
+            .ok()?;
+
+            object.get("milliseconds")?
+        }
+        _ => lit,
+    };
+
+    match lit.primitive.get_type() {
+        PrimitiveType::PrimitiveInt => {
+            Literal::get_value::<i64>(&lit.primitive, flow_name, interval, "".to_owned())
+                .ok()
+                .copied()
+        }
+        _ => None,
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn schedule(
+    args: ArgsType,
+    interval: Interval,
+    data: &mut Data,
+    sender: &MsgSender,
+) -> Result<Literal, Box<ErrorInfo>> {
+    let flow_name = &data.context.flow;
+    let error = || {
+        Box::new(gen_error_info(
+            Position::new(interval, flow_name),
+            ERROR_SCHEDULE.to_owned(),
+        ))
+    };
+
+    let flow = match args.get("flow", 0) {
+        Some(lit) if is_string(lit) && data.flows.contains_key(&lit.primitive.to_string()) => {
+            lit.primitive.to_string()
+        }
+        _ => return Err(error()),
+    };
+    let step = match args.get("step", 1) {
+        Some(lit) if is_string(lit) => lit.primitive.to_string(),
+        Some(_) => return Err(error()),
+        None => "start".to_owned(),
+    };
+    let at = match args.get("at", 2) {
+        Some(lit) => get_timestamp(lit, flow_name, interval).ok_or_else(error)?,
+        None => return Err(error()),
+    };
+
+    let mut schedule = HashMap::new();
+    schedule.insert(
+        "flow".to_owned(),
+        PrimitiveString::get_literal(&flow, interval),
+    );
+    schedule.insert(
+        "step".to_owned(),
+        PrimitiveString::get_literal(&step, interval),
+    );
+    schedule.insert("at".to_owned(), PrimitiveInt::get_literal(at, interval));
+
+    MSG::send(sender, MSG::Schedule(Schedule { flow, step, at }));
+
+    let mut result = PrimitiveObject::get_literal(&schedule, interval);
+    result.set_content_type("schedule");
+
+    Ok(result)
+}
@@ -1,7 +1,8 @@
 use crate::data::error_info::ErrorInfo;
 use crate::data::position::Position;
 use crate::data::{
-    ast::*, primitive::PrimitiveNull, warnings::DisplayWarnings, Data, Literal, MessageData, MSG,
+    ast::*, primitive::PrimitiveNull, warnings::DisplayWarnings, Data, Literal, MessageData,
+    MsgSender,
 };
 use crate::error_format::*;
 use crate::interpreter::{
@@ -9,7 +10,6 @@ use crate::interpreter::{
     variable_handler::{expr_to_literal, interval::interval_from_expr},
 };
 use crate::parser::ExitCondition;
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
@@ -18,7 +18,7 @@ use std::sync::mpsc;
 fn interpret_function_scope(
     actions: &Block,
     data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<MessageData, ErrorInfo> {
     let mut message_data = MessageData::default();
 
@@ -72,7 +72,7 @@ pub fn exec_fn_in_new_scope(
     expr: &Expr,
     new_scope_data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match expr {
         Expr::Scope {
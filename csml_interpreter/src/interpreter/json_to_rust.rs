@@ -3,10 +3,10 @@ use crate::data::primitive::{
     PrimitiveArray, PrimitiveBoolean, PrimitiveClosure, PrimitiveFloat, PrimitiveInt,
     PrimitiveNull, PrimitiveObject, PrimitiveString,
 };
-use crate::data::{ast::Interval, Data, Literal, MessageData, MSG};
+use crate::data::{ast::Interval, Data, Literal, MessageData, MsgSender};
 use crate::error_format::*;
 use crate::parser::parse_string::interpolate_string;
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
@@ -17,7 +17,7 @@ pub fn interpolate(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match literal {
         serde_json::Value::String(val) => interpolate_string(val, data, msg_data, sender),
@@ -12,6 +12,7 @@ use crate::data::literal::ContentType;
 pub use expr_to_literal::{expr_to_literal, resolve_fn_args};
 
 use crate::data::error_info::ErrorInfo;
+use crate::data::msg::MsgSender;
 use crate::data::position::Position;
 use crate::data::primitive::{
     tools::get_array, PrimitiveNull, PrimitiveObject, PrimitiveString, PrimitiveType,
@@ -19,7 +20,7 @@ use crate::data::primitive::{
 use crate::data::{
     ast::{Expr, Function, GotoValueType, Identifier, Interval, PathLiteral, PathState},
     data::Data,
-    tokens::{COMPONENT, EVENT, _ENV, _MEMORY, _METADATA},
+    tokens::{_ENV, _MEMORY, _METADATA, COMPONENT, EVENT},
     warnings::DisplayWarnings,
     ArgsType, Literal, MemoryType, MessageData, MSG,
 };
@@ -29,8 +30,8 @@ use crate::interpreter::variable_handler::{
     gen_literal::gen_literal_from_event,
     memory::{save_literal_in_mem, search_in_memory_type, search_var_memory},
 };
+use std::collections::HashMap;
 use std::slice::Iter;
-use std::{collections::HashMap, sync::mpsc};
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
@@ -71,7 +72,7 @@ fn loop_path(
     content_type: &ContentType,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<(Literal, bool), ErrorInfo> {
     let mut tmp_update_var = false;
     // this is temporary until we find a better way, it helps restore the string in the
@@ -366,7 +367,7 @@ pub fn resolve_path(
     dis_warnings: &DisplayWarnings,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Vec<(Interval, PathLiteral)>, ErrorInfo> {
     let mut new_path = vec![];
 
@@ -424,7 +425,7 @@ pub fn exec_path_actions(
     content_type: &ContentType,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<(Literal, bool), ErrorInfo> {
     if let Some(vec) = path {
         let mut path = vec.iter();
@@ -509,7 +510,7 @@ pub fn get_metadata_context_literal(
     dis_warnings: &DisplayWarnings,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let flow_context = get_flow_context(data, interval);
     let mut path_skip = 1;
@@ -549,7 +550,7 @@ pub fn get_literal_from_metadata(
     dis_warnings: &DisplayWarnings,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let mut lit = match path.get(0) {
         Some((interval, PathLiteral::MapIndex(name))) if name == "_context" => {
@@ -596,7 +597,7 @@ pub fn get_var(
     path: Option<&[(Interval, PathState)]>,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let interval = &var.interval;
 
@@ -776,7 +777,7 @@ pub fn get_var_from_mem<'a>(
     path: Option<&[(Interval, PathState)]>,
     data: &'a mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<
     (
         &'a mut Literal,
@@ -812,7 +813,7 @@ pub fn search_goto_var_memory(
     var: &GotoValueType,
     msg_data: &mut MessageData,
     data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<String, ErrorInfo> {
     let flow_name = data.context.flow.clone();
 
@@ -838,7 +839,7 @@ pub fn get_string_from_complex_string(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let mut new_string = String::new();
     let mut is_secure = false;
@@ -2,8 +2,8 @@ use crate::data::error_info::ErrorInfo;
 use crate::data::literal::ContentType;
 use crate::data::primitive::{closure::capture_variables, PrimitiveArray, PrimitiveObject};
 use crate::data::{
-    ast::*, warnings::DisplayWarnings, ArgsType, Data, Literal, MemoryType, MessageData, Position,
-    MSG,
+    ast::*, warnings::DisplayWarnings, ArgsType, Data, Literal, MemoryType, MessageData, MsgSender,
+    Position,
 };
 use crate::error_format::*;
 use crate::interpreter::{
@@ -13,7 +13,7 @@ use crate::interpreter::{
         operations::evaluate_postfix, resolve_csml_object::resolve_object, resolve_path,
     },
 };
-use std::{collections::HashMap, sync::mpsc};
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
@@ -25,7 +25,7 @@ fn exec_path_literal(
     path: Option<&[(Interval, PathState)]>,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     if let Some(path) = path {
         let path = resolve_path(path, dis_warnings, data, msg_data, sender)?;
@@ -57,7 +57,7 @@ pub fn expr_to_literal(
     path: Option<&[(Interval, PathState)]>,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match expr {
         Expr::ObjectExpr(ObjectType::As(name, var)) => {
@@ -168,7 +168,7 @@ pub fn resolve_fn_args(
     data: &mut Data,
     msg_data: &mut MessageData,
     dis_warnings: &DisplayWarnings,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<ArgsType, ErrorInfo> {
     match expr {
         Expr::VecExpr(vec, ..) => {
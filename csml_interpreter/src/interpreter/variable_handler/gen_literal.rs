@@ -7,7 +7,7 @@ use crate::data::{
 };
 use crate::data::{
     ast::{Interval, PathState},
-    Data, Literal, MemoryType, MessageData, MSG,
+    Data, Literal, MemoryType, MessageData, MsgSender,
 };
 use crate::error_format::*;
 use crate::interpreter::variable_handler::gen_generic_component::gen_generic_component;
@@ -15,7 +15,6 @@ use crate::interpreter::{
     json_to_rust::json_to_literal,
     variable_handler::{exec_path_actions, resolve_path},
 };
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTIONS
@@ -27,7 +26,7 @@ pub fn gen_literal_from_event(
     path: Option<&[(Interval, PathState)]>,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match path {
         Some(path) => {
@@ -77,7 +76,7 @@ pub fn gen_literal_from_component(
     path: Option<&[(Interval, PathState)]>,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match path {
         Some(path) => {
@@ -137,7 +136,7 @@ pub fn get_literal_from_metadata(
     data: &mut Data,
     msg_data: &mut MessageData,
     interval: Interval,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let mut lit = match path.get(0) {
         Some((interval, PathLiteral::MapIndex(name))) => match data.context.metadata.get(name) {
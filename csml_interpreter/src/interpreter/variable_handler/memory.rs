@@ -1,8 +1,8 @@
 use crate::data::error_info::ErrorInfo;
+use crate::data::msg::MsgSender;
 use crate::data::position::Position;
 use crate::data::{ast::Identifier, Data, Literal, Memory, MemoryType, MessageData, MSG};
 use crate::error_format::*;
-use std::sync::mpsc;
 
 pub fn search_in_memory_type(name: &Identifier, data: &Data) -> Result<String, ErrorInfo> {
     match (
@@ -43,7 +43,7 @@ pub fn save_literal_in_mem(
     update: bool,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) {
     match mem_type {
         MemoryType::Remember if update => {
@@ -3,13 +3,12 @@ use crate::data::{
     position::Position,
     primitive::boolean::PrimitiveBoolean,
     warnings::DisplayWarnings,
-    Data, Literal, MessageData, MSG,
+    Data, Literal, MessageData, MsgSender,
 };
 use crate::error_format::{gen_error_info, ErrorInfo};
 use crate::interpreter::variable_handler::{
     expr_to_literal, interval::interval_from_expr, match_literals::match_obj,
 };
-use std::sync::mpsc;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTION
@@ -143,7 +142,7 @@ pub fn evaluate_postfix(
     expr: &Box<Expr>,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let value = valid_literal(expr_to_literal(
         expr,
@@ -16,7 +16,8 @@ use crate::interpreter::{
     variable_handler::save_literal_in_mem,
 };
 
-use std::{collections::HashMap, sync::mpsc};
+use crate::data::msg::MsgSender;
+use std::collections::HashMap;
 
 ////////////////////////////////////////////////////////////////////////////////
 // Local Struct
@@ -233,7 +234,7 @@ pub fn insert_args_in_scope_memory(
     fn_args: &[String],
     args: &ArgsType,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) {
     for (index, name) in fn_args.iter().enumerate() {
         let value = args.get(name, index).unwrap();
@@ -254,7 +255,7 @@ pub fn insert_memories_in_scope_memory(
     new_scope_data: &mut Data,
     memories: HashMap<String, Literal>,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) {
     for (name, value) in memories.iter() {
         save_literal_in_mem(
@@ -275,7 +276,7 @@ pub fn resolve_object(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     match get_type(name, interval, data) {
         ObjType::NativeComponent => {
@@ -404,7 +405,7 @@ pub fn exec_fn(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     if fn_args.len() > args.len() {
         return Err(gen_error_info(
@@ -431,7 +432,7 @@ pub fn exec_closure(
     interval: Interval,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     if fn_args.len() > args.len() {
         return Err(gen_error_info(
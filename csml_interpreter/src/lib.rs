@@ -3,9 +3,11 @@ pub mod error_format;
 pub mod fold_bot;
 pub mod interpreter;
 pub mod linter;
+pub mod module_registry;
 pub mod parser;
 
 pub use data::csml_logs;
+pub use data::csml_otel;
 pub use interpreter::components::load_components;
 pub use parser::step_checksum::get_step;
 
@@ -14,34 +16,51 @@ use parser::parse_flow;
 
 use data::ast::{Expr, Flow, InsertStep, InstructionScope, Interval};
 use data::context::{get_hashmap_from_mem, ContextStepInfo};
+use data::csml_metrics::{self, Metric};
 use data::error_info::ErrorInfo;
 use data::event::Event;
 use data::literal::create_error_info;
 use data::message_data::MessageData;
-use data::msg::MSG;
-use data::CsmlResult;
-use data::{csml_bot::CsmlBot, CsmlFlow};
-use data::{Context, Data, Position, STEP_LIMIT};
+use data::msg::{MsgSender, MSG};
+use data::{csml_bot::CsmlBot, csml_rng, csml_usage, CsmlFlow};
+use data::{BotArtifact, CsmlResult};
+use data::{Context, Data, Hold, Position, STEP_LIMIT};
 use error_format::*;
 use fold_bot::fold_bot as fold;
-use linter::{linter::lint_bot, FlowToValidate};
+use linter::{
+    linter::{lint_bot, validate_bot_flows, validate_commands},
+    FlowToValidate,
+};
+use module_registry::ModuleRegistry;
 use parser::ExitCondition;
 
 use base64::Engine;
 use std::collections::HashMap;
 use std::env;
 use std::sync::mpsc;
+use std::time::Instant;
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-fn execute_step(
-    step: &str,
-    flow: &Flow,
-    data: &mut Data,
-    sender: &Option<mpsc::Sender<MSG>>,
-) -> MessageData {
+/**
+ * Hold interrupted by a flow that resumes it, when the interpretation goes back to its step
+ */
+fn take_interrupted_hold(
+    context: &mut Context,
+    flow: &str,
+    step: &ContextStepInfo,
+) -> Option<Hold> {
+    match &context.interrupted {
+        Some(hold) if hold.flow_name == flow && step.is_step(&hold.step_name) => {
+            context.interrupted.take()
+        }
+        _ => None,
+    }
+}
+
+fn execute_step(step: &str, flow: &Flow, data: &mut Data, sender: &MsgSender) -> MessageData {
     // stop execution if step_count >= STEP_LIMIT in order to avoid infinite loops
     if *data.step_count >= data.step_limit {
         let msg_data = Err(gen_error_info(
@@ -61,6 +80,7 @@ fn execute_step(
     {
         Some(Expr::Scope { scope, .. }) => {
             *data.step_count += 1;
+            csml_usage::add_step();
             interpret_scope(scope, data, sender)
         }
         _ => Err(gen_error_info(
@@ -97,7 +117,10 @@ fn execute_step(
     MessageData::error_to_message(msg_data, sender)
 }
 
-fn get_step_limit(event: &Event) -> usize {
+/**
+ * Maximum number of steps of an interpretation: the event's `step_limit`, or STEP_LIMIT
+ */
+pub fn get_step_limit(event: &Event) -> usize {
     match (event.step_limit, env::var("STEP_LIMIT").ok()) {
         (Some(step_limit), _) => step_limit,
         (None, Some(step_limit)) => step_limit.parse::<usize>().unwrap_or(STEP_LIMIT),
@@ -109,7 +132,7 @@ fn get_flow_ast<'a, 'b>(
     flows: &'a HashMap<String, Flow>,
     flow: &'b str,
     bot_id: &'b str,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<&'a Flow, MessageData> {
     match flows.get(flow) {
         Some(result) => Ok(result),
@@ -137,7 +160,7 @@ fn get_inserted_ast<'a>(
     ast: &'a Flow,
     step: &ContextStepInfo,
     bot_id: &str,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> (bool, Option<&'a Flow>) {
     match &step {
         ContextStepInfo::Normal(step) => {
@@ -279,6 +302,9 @@ pub fn validate_bot(bot: &CsmlBot) -> CsmlResult {
         );
     }
 
+    validate_commands(&bot.flows, &mut warnings);
+    validate_bot_flows(bot, &mut errors);
+
     CsmlResult::new(
         FlowToValidate::get_flows(flows),
         FlowToValidate::get_flows(modules),
@@ -351,34 +377,42 @@ pub fn fold_bot(bot: &CsmlBot) -> String {
 
 fn get_flows(bot: &CsmlBot) -> (HashMap<String, Flow>, HashMap<String, Flow>) {
     match &bot.bot_ast {
+        // artifacts compiled by another interpreter version are parsed again from source
+        Some(ast) if BotArtifact::is_artifact(ast) => match BotArtifact::decode(ast) {
+            Ok(artifact) => (artifact.flows, artifact.extern_flows),
+            Err(_) => validate_flows(bot),
+        },
         Some(bot) => {
             let base64decoded = base64::engine::general_purpose::STANDARD
                 .decode(bot)
                 .unwrap();
             bincode::deserialize(&base64decoded[..]).unwrap()
         }
-        None => {
-            let bot = validate_bot(bot);
-
-            let flows = match bot.flows {
-                Some(flows) => flows,
-                None => HashMap::new(),
-            };
+        None => validate_flows(bot),
+    }
+}
 
-            let extern_flows = match bot.extern_flows {
-                Some(extern_flows) => extern_flows,
-                None => HashMap::new(),
-            };
+fn validate_flows(bot: &CsmlBot) -> (HashMap<String, Flow>, HashMap<String, Flow>) {
+    let bot = validate_bot(bot);
 
-            (flows, extern_flows)
-        }
-    }
+    (
+        bot.flows.unwrap_or_default(),
+        bot.extern_flows.unwrap_or_default(),
+    )
 }
 
+/**
+ * Download the modules of the bot that are not already loaded, see `module_registry`
+ * for the MODULES_* environment variables
+ */
 pub fn search_for_modules(bot: &mut CsmlBot) -> Result<(), String> {
-    let default_auth = env::var("MODULES_AUTH").ok();
-    let default_url = env::var("MODULES_URL").ok();
+    search_for_modules_in(bot, &ModuleRegistry::from_env())
+}
 
+/**
+ * Same as `search_for_modules`, with the given registry
+ */
+pub fn search_for_modules_in(bot: &mut CsmlBot, registry: &ModuleRegistry) -> Result<(), String> {
     if let Some(ref mut modules) = bot.modules {
         for module in modules.iter_mut() {
             if module.flow.is_some() {
@@ -386,60 +420,15 @@ pub fn search_for_modules(bot: &mut CsmlBot) -> Result<(), String> {
                 continue;
             }
 
-            let request = match (&module.url, &default_url) {
-                (Some(url), _) => {
-                    let request = ureq::get(url);
-                    match &module.auth {
-                        Some(auth) => {
-                            let authorization = format!(
-                                "Basic {}",
-                                base64::engine::general_purpose::STANDARD.encode(auth.as_bytes())
-                            );
-
-                            request.set("Authorization", &authorization)
-                        }
-                        _ => request,
-                    }
-                }
-                (None, Some(url)) => {
-                    let request = ureq::get(url);
-
-                    match &default_auth {
-                        Some(auth) => {
-                            let authorization = format!(
-                                "Basic {}",
-                                base64::engine::general_purpose::STANDARD.encode(auth.as_bytes())
-                            );
-
-                            request.set("Authorization", &authorization)
-                        }
-                        _ => request,
-                    }
-                }
-                _ => {
-                    return Err(format!(
-                        "missing url in order to get module [{}]",
-                        module.name
-                    ));
-                }
-            };
-
-            match request.call() {
-                Ok(response) => {
-                    let flow_content = match response.into_string() {
-                        Ok(flow) => flow,
-                        Err(_) => return Err(format!("invalid module {}", module.name)),
-                    };
-
-                    module.flow = Some(CsmlFlow {
-                        id: module.name.clone(),
-                        name: module.name.clone(),
-                        content: flow_content,
-                        commands: vec![],
-                    });
-                }
-                Err(error) => return Err(error.to_string()),
-            }
+            let resolved = registry.resolve(module)?;
+
+            module.flow = Some(CsmlFlow {
+                id: module.name.clone(),
+                name: module.name.clone(),
+                content: resolved.content,
+                commands: vec![],
+                priority: 0,
+            });
         }
     }
 
@@ -448,11 +437,35 @@ pub fn search_for_modules(bot: &mut CsmlBot) -> Result<(), String> {
 
 pub fn interpret(
     bot: CsmlBot,
-    mut context: Context,
+    context: Context,
     event: Event,
     sender: Option<mpsc::Sender<MSG>>,
+) -> MessageData {
+    interpret_with_sender(bot, context, event, &sender.into())
+}
+
+/**
+ * Interpret the bot on the calling thread: every MSG is handed to `callback`
+ * as soon as it is emitted, without going through a channel.
+ */
+pub fn interpret_with_callback(
+    bot: CsmlBot,
+    context: Context,
+    event: Event,
+    callback: &mut dyn FnMut(MSG),
+) -> MessageData {
+    interpret_with_sender(bot, context, event, &MsgSender::callback(callback))
+}
+
+fn interpret_with_sender(
+    bot: CsmlBot,
+    mut context: Context,
+    event: Event,
+    sender: &MsgSender,
 ) -> MessageData {
     csml_logs::init_logger();
+    csml_rng::seed(event.seed);
+    csml_usage::start(event.http_limit);
 
     let mut msg_data = MessageData::default();
 
@@ -486,16 +499,16 @@ pub fn interpret(
 
     let mut previous_info = match &context.hold {
         Some(hold) => hold.previous.as_ref().cloned(),
-        None => None,
+        None => context.previous.clone(),
     };
 
     while msg_data.exit_condition.is_none() {
-        let ast = match get_flow_ast(&flows, &flow, &bot.id, &sender) {
+        let ast = match get_flow_ast(&flows, &flow, &bot.id, sender) {
             Ok(ast) => ast,
             Err(message_data) => return message_data,
         };
 
-        let (missing_step, inserted_ast) = get_inserted_ast(&flows, ast, &step, &bot.id, &sender);
+        let (missing_step, inserted_ast) = get_inserted_ast(&flows, ast, &step, &bot.id, sender);
 
         // if the target flow dose not contains a 'start' flow change the target to the default_flow
         if step.is_step("start") && missing_step {
@@ -521,12 +534,19 @@ pub fn interpret(
             &native,
         );
 
+        let start = Instant::now();
         msg_data = match inserted_ast {
             Some(inserted_ast) => {
-                msg_data + execute_step(&step.get_step(), inserted_ast, &mut data, &sender)
+                msg_data + execute_step(&step.get_step(), inserted_ast, &mut data, sender)
             }
-            None => msg_data + execute_step(&step.get_step(), ast, &mut data, &sender),
+            None => msg_data + execute_step(&step.get_step(), ast, &mut data, sender),
         };
+        csml_metrics::record(Metric::Step {
+            bot_id: &bot.id,
+            flow: &flow,
+            step: &step.get_step(),
+            duration: start.elapsed(),
+        });
 
         previous_info = data.previous_info.clone();
         flow = data.context.flow.to_string();
@@ -534,6 +554,15 @@ pub fn interpret(
 
         // add reset loops index
         step_vars = HashMap::new();
+
+        // the step of an interrupted hold is not restarted: it holds again where it was
+        if msg_data.exit_condition.is_none() {
+            if let Some(hold) = take_interrupted_hold(&mut context, &flow, &step) {
+                MSG::send(sender, MSG::Hold(hold.clone()));
+                msg_data.hold = Some(hold);
+                msg_data.exit_condition = Some(ExitCondition::Hold);
+            }
+        }
     }
 
     msg_data
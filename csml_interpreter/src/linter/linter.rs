@@ -4,7 +4,7 @@ use crate::data::{
     primitive::{PrimitiveClosure, PrimitiveType},
     tokens::{Span, BUILT_IN, BUILT_IN_WITHOUT_WARNINGS, COMPONENT},
     warnings::*,
-    Literal,
+    CsmlBot, CsmlFlow, Literal,
 };
 use crate::error_format::{
     convert_error_from_interval, gen_error_info, gen_infinite_loop_error_msg, gen_warning_info,
@@ -376,6 +376,77 @@ pub fn validate_constants(linter_info: &mut LinterInfo) {
     }
 }
 
+/**
+ * Warn about flows that can never be triggered by one of their commands, because a
+ * flow declared before them has the same command and the same priority.
+ */
+pub fn validate_commands(flows: &[CsmlFlow], warnings: &mut Vec<Warnings>) {
+    let mut commands: Vec<(String, Vec<&CsmlFlow>)> = vec![];
+
+    for flow in flows.iter() {
+        for command in flow.commands.iter() {
+            let command = command.to_lowercase();
+
+            match commands.iter_mut().find(|(name, _)| *name == command) {
+                Some((_, command_flows)) => {
+                    if !command_flows.iter().any(|other| other.name == flow.name) {
+                        command_flows.push(flow);
+                    }
+                }
+                None => commands.push((command, vec![flow])),
+            }
+        }
+    }
+
+    for (command, command_flows) in commands.iter() {
+        let chosen = command_flows.iter().fold(command_flows[0], |chosen, flow| {
+            if flow.priority > chosen.priority {
+                flow
+            } else {
+                chosen
+            }
+        });
+
+        for flow in command_flows.iter() {
+            if flow.name != chosen.name && flow.priority == chosen.priority {
+                warnings.push(gen_warning_info(
+                    Position::new(Interval::default(), &flow.name),
+                    format!(
+                        "command '{}' is also declared by flow '{}' with the same priority: flow '{}' will never be triggered by it",
+                        command, chosen.name, flow.name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/**
+ * The fallback and interruption flows of the bot must exist
+ */
+pub fn validate_bot_flows(bot: &CsmlBot, errors: &mut Vec<ErrorInfo>) {
+    let interruptions = bot.interruptions.iter().flatten();
+
+    let bot_flows = bot
+        .fallback_flow
+        .iter()
+        .map(|flow| ("fallback_flow", flow))
+        .chain(interruptions.map(|interruption| ("interruption", &interruption.flow)));
+
+    for (kind, flow_name) in bot_flows {
+        let exists = bot.flows.iter().any(|flow| {
+            flow.id.eq_ignore_ascii_case(flow_name) || flow.name.eq_ignore_ascii_case(flow_name)
+        });
+
+        if !exists {
+            errors.push(gen_error_info(
+                Position::new(Interval::default(), flow_name),
+                format!("{} flow '{}' does not exist", kind, flow_name),
+            ));
+        }
+    }
+}
+
 pub fn validate_flow_ast(flow: &FlowToValidate, linter_info: &mut LinterInfo, extern_module: bool) {
     let mut is_step_start_present = false;
     let mut steps_nbr = 0;
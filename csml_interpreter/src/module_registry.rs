@@ -0,0 +1,376 @@
+/**
+ * Resolution and download of the modules of a bot.
+ *
+ * A module with a `url` is downloaded from it. Other modules are resolved in the registry
+ * of MODULES_REGISTRY, an http(s) url or a local directory laid out as:
+ *
+ *   <registry>/<name>/index.json       [{"version": "1.2.0", "integrity": "sha256:<hex>"}, ...]
+ *   <registry>/<name>/<version>.csml
+ *
+ * The `version` of the module is a semver range with the same syntax as Cargo ("1.2" or "^1.2",
+ * "~1.2.0", "=1.2.3", ">=1.0, <2.0"...), and the highest version of the index matching it is
+ * used. "latest" (the default) and "*" match any version. Without a registry, the modules
+ * without url are downloaded from MODULES_URL.
+ *
+ * The content of a module is checked against its `integrity` and the one of the registry index.
+ * With MODULES_CACHE_DIR, each downloaded version is kept in <dir>/<source>/<name>/<version>.csml,
+ * where <source> is the sha256 of the url or registry it comes from, and is not downloaded again
+ * while it matches the expected integrity. Modules are resolved with the cached versions when the
+ * registry can not be reached.
+ *
+ * Module names can not contain path separators or "..", and local files are only read inside
+ * the directory of a local registry: the `url` of a module comes from the bot, and must not
+ * give access to the other files of the server.
+ */
+use crate::data::csml_bot::Module;
+
+use base64::Engine;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Entry of the index of a module in a registry
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleVersion {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ModuleRegistry {
+    // http(s) url or path of a local directory
+    pub registry: Option<String>,
+    // basic auth of the registry and of MODULES_URL
+    pub auth: Option<String>,
+    // url of the modules without url when there is no registry
+    pub default_url: Option<String>,
+    pub cache_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedModule {
+    pub name: String,
+    // not set for a module downloaded from a url without an exact version
+    pub version: Option<String>,
+    pub integrity: String,
+    pub content: String,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn version_req(name: &str, version: &str) -> Result<VersionReq, String> {
+    match version.trim() {
+        "" | "latest" | "*" => Ok(VersionReq::STAR),
+        version => VersionReq::parse(version).map_err(|err| {
+            format!(
+                "invalid version [{}] of module [{}]: {}",
+                version, name, err
+            )
+        }),
+    }
+}
+
+fn check_integrity(name: &str, content: &str, expected: Option<&str>) -> Result<(), String> {
+    match expected {
+        Some(expected) if expected != integrity(content) => Err(format!(
+            "integrity check failed for module [{}]: expected [{}], got [{}]",
+            name,
+            expected,
+            integrity(content)
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn local_path(location: &str) -> &Path {
+    Path::new(location.strip_prefix("file://").unwrap_or(location))
+}
+
+// the name of a module is a directory of the registry and of the cache
+fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(format!("invalid module name [{}]", name));
+    }
+
+    Ok(())
+}
+
+fn download(location: &str, auth: Option<&str>) -> Result<String, String> {
+    let request = ureq::get(location);
+    let request = match auth {
+        Some(auth) => {
+            let authorization = format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(auth.as_bytes())
+            );
+
+            request.set("Authorization", &authorization)
+        }
+        None => request,
+    };
+
+    match request.call() {
+        Ok(response) => response.into_string().map_err(|err| err.to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+fn registry_location(registry: &str, name: &str, file: &str) -> String {
+    if is_url(registry) {
+        format!("{}/{}/{}", registry.trim_end_matches('/'), name, file)
+    } else {
+        local_path(registry)
+            .join(name)
+            .join(file)
+            .display()
+            .to_string()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Integrity of the content of a module, as written in `Module.integrity` and registry indexes
+ */
+pub fn integrity(content: &str) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(content.as_bytes())))
+}
+
+impl ModuleRegistry {
+    /**
+     * Registry configured with MODULES_REGISTRY, MODULES_AUTH, MODULES_URL and MODULES_CACHE_DIR
+     */
+    pub fn from_env() -> Self {
+        let var = |name: &str| env::var(name).ok().filter(|value| !value.is_empty());
+
+        Self {
+            registry: var("MODULES_REGISTRY"),
+            auth: var("MODULES_AUTH"),
+            default_url: var("MODULES_URL"),
+            cache_dir: var("MODULES_CACHE_DIR").map(PathBuf::from),
+        }
+    }
+
+    /**
+     * Registry in a local directory, e.g. the modules vendored in a project
+     */
+    pub fn local(path: &Path) -> Self {
+        Self {
+            registry: Some(path.display().to_string()),
+            ..Default::default()
+        }
+    }
+
+    // a local file is only read if it is in the directory of the registry
+    fn read_local(&self, location: &str) -> Result<String, String> {
+        let root = match &self.registry {
+            Some(registry) if !is_url(registry) => local_path(registry).canonicalize().ok(),
+            _ => None,
+        };
+        let path = local_path(location);
+
+        match (root, path.canonicalize()) {
+            (Some(root), Ok(file)) if file.starts_with(&root) => {
+                fs::read_to_string(file).map_err(|err| format!("{}: {}", path.display(), err))
+            }
+            (_, Err(err)) => Err(format!("{}: {}", path.display(), err)),
+            _ => Err(format!(
+                "module location [{}] is not in the registry",
+                location
+            )),
+        }
+    }
+
+    fn get(&self, location: &str, auth: Option<&str>) -> Result<String, String> {
+        match is_url(location) {
+            true => download(location, auth),
+            false => self.read_local(location),
+        }
+    }
+
+    // modules with the same name and version from different urls or registries are not shared
+    fn cache_path(&self, source: &str, name: &str) -> Option<PathBuf> {
+        let source = hex::encode(Sha256::digest(source.as_bytes()));
+
+        Some(self.cache_dir.as_ref()?.join(source).join(name))
+    }
+
+    // a cached module that does not match the expected integrity is downloaded again
+    fn read_cache(
+        &self,
+        source: &str,
+        name: &str,
+        version: &str,
+        expected: &[Option<&str>],
+    ) -> Option<String> {
+        let path = self
+            .cache_path(source, name)?
+            .join(format!("{}.csml", version));
+        let content = fs::read_to_string(path).ok()?;
+
+        expected
+            .iter()
+            .all(|expected| check_integrity(name, &content, *expected).is_ok())
+            .then_some(content)
+    }
+
+    // the cache is only an optimization, a module that can not be saved in it is still used
+    fn write_cache(&self, source: &str, name: &str, version: &str, content: &str) {
+        if let Some(dir) = self.cache_path(source, name) {
+            if fs::create_dir_all(&dir).is_ok() {
+                fs::write(dir.join(format!("{}.csml", version)), content).ok();
+            }
+        }
+    }
+
+    fn cached_versions(&self, source: &str, name: &str) -> Vec<ModuleVersion> {
+        let entries = match self.cache_path(source, name) {
+            Some(dir) => match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(_) => return vec![],
+            },
+            None => return vec![],
+        };
+
+        entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                match path.extension() {
+                    Some(extension) if extension == "csml" => Some(ModuleVersion {
+                        version: path.file_stem()?.to_str()?.to_owned(),
+                        integrity: None,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /**
+     * Versions of a module published in the registry
+     */
+    pub fn get_versions(&self, registry: &str, name: &str) -> Result<Vec<ModuleVersion>, String> {
+        check_name(name)?;
+
+        let index = self.get(
+            &registry_location(registry, name, "index.json"),
+            self.auth.as_deref(),
+        )?;
+
+        serde_json::from_str(&index)
+            .map_err(|err| format!("invalid index of module [{}]: {}", name, err))
+    }
+
+    fn resolve_url(
+        &self,
+        module: &Module,
+        url: &str,
+        auth: Option<&str>,
+    ) -> Result<ResolvedModule, String> {
+        // only an exact version can be cached, the content of the url may change otherwise
+        let version = Version::parse(&module.version)
+            .ok()
+            .map(|version| version.to_string());
+
+        let cached = version.as_ref().and_then(|version| {
+            self.read_cache(url, &module.name, version, &[module.integrity.as_deref()])
+        });
+        let content = match cached {
+            Some(content) => content,
+            None => self.get(url, auth)?,
+        };
+        check_integrity(&module.name, &content, module.integrity.as_deref())?;
+
+        if let Some(version) = &version {
+            self.write_cache(url, &module.name, version, &content);
+        }
+
+        Ok(ResolvedModule {
+            name: module.name.to_owned(),
+            version,
+            integrity: integrity(&content),
+            content,
+        })
+    }
+
+    fn resolve_registry(&self, module: &Module, registry: &str) -> Result<ResolvedModule, String> {
+        let req = version_req(&module.name, &module.version)?;
+
+        let versions = match self.get_versions(registry, &module.name) {
+            Ok(versions) => versions,
+            Err(err) => match self.cached_versions(registry, &module.name) {
+                cached if cached.is_empty() => return Err(err),
+                cached => cached,
+            },
+        };
+
+        let (version, entry) = versions
+            .iter()
+            .filter_map(|entry| Some((Version::parse(&entry.version).ok()?, entry)))
+            .filter(|(version, _)| req.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .ok_or_else(|| {
+                format!(
+                    "no version of module [{}] matches [{}]",
+                    module.name, module.version
+                )
+            })?;
+        let version = version.to_string();
+
+        let expected = [entry.integrity.as_deref(), module.integrity.as_deref()];
+        let content = match self.read_cache(registry, &module.name, &version, &expected) {
+            Some(content) => content,
+            None => self.get(
+                &registry_location(registry, &module.name, &format!("{}.csml", entry.version)),
+                self.auth.as_deref(),
+            )?,
+        };
+        for expected in expected {
+            check_integrity(&module.name, &content, expected)?;
+        }
+
+        self.write_cache(registry, &module.name, &version, &content);
+
+        Ok(ResolvedModule {
+            name: module.name.to_owned(),
+            version: Some(version),
+            integrity: integrity(&content),
+            content,
+        })
+    }
+
+    /**
+     * Find the version of the module to use and get its content
+     */
+    pub fn resolve(&self, module: &Module) -> Result<ResolvedModule, String> {
+        check_name(&module.name)?;
+
+        match (&module.url, &self.registry, &self.default_url) {
+            (Some(url), _, _) => self.resolve_url(module, url, module.auth.as_deref()),
+            (None, Some(registry), _) => self.resolve_registry(module, registry),
+            (None, None, Some(url)) => self.resolve_url(module, url, self.auth.as_deref()),
+            (None, None, None) => Err(format!(
+                "missing url in order to get module [{}]",
+                module.name
+            )),
+        }
+    }
+}
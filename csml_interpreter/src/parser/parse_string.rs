@@ -1,7 +1,7 @@
 use crate::data::primitive::string::PrimitiveString;
 use crate::data::{
     ast::*, position::Position, tokens::*, warnings::DisplayWarnings, Data, Literal, MessageData,
-    MSG,
+    MsgSender,
 };
 use crate::error_format::{gen_nom_failure, CustomError, *};
 use crate::interpreter::variable_handler::expr_to_literal;
@@ -15,7 +15,6 @@ use nom::{
     sequence::{delimited, preceded},
     *,
 };
-use std::sync::mpsc;
 
 use nom::branch::alt;
 use nom::bytes::complete::escaped_transform;
@@ -336,7 +335,7 @@ pub fn interpolate_string(
     string: &str,
     data: &mut Data,
     msg_data: &mut MessageData,
-    sender: &Option<mpsc::Sender<MSG>>,
+    sender: &MsgSender,
 ) -> Result<Literal, ErrorInfo> {
     let string_formatted = format!("{:?}", string);
     let span = Span::new(&string_formatted);
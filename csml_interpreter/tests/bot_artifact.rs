@@ -0,0 +1,141 @@
+mod support;
+
+use csml_interpreter::data::bot_artifact::BOT_ARTIFACT_PREFIX;
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::csml_bot::CsmlBot;
+use csml_interpreter::data::csml_flow::CsmlFlow;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::BotArtifact;
+use csml_interpreter::interpret;
+use std::collections::HashMap;
+
+use crate::support::tools::read_file;
+
+use base64::Engine;
+use serde_json::Value;
+
+const DEFAULT_ID_NAME: &str = "id";
+const DEFAULT_FLOW_NAME: &str = "default";
+const DEFAULT_STEP_NAME: &str = "start";
+const DEFAULT_BOT_NAME: &str = "my_bot";
+
+fn get_bot() -> CsmlBot {
+    let default_content = read_file("CSML/basic_test/bot/default.csml".to_owned()).unwrap();
+    let default_flow = CsmlFlow::new(DEFAULT_ID_NAME, "default", &default_content, Vec::default());
+
+    let other_content = read_file("CSML/basic_test/bot/other.csml".to_owned()).unwrap();
+    let other_flow = CsmlFlow::new(DEFAULT_ID_NAME, "other", &other_content, Vec::default());
+
+    CsmlBot::new(
+        DEFAULT_ID_NAME,
+        DEFAULT_BOT_NAME,
+        None,
+        vec![default_flow, other_flow],
+        None,
+        None,
+        DEFAULT_FLOW_NAME,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn run(bot: CsmlBot) -> Vec<Value> {
+    let context = Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        None,
+        DEFAULT_STEP_NAME,
+        DEFAULT_FLOW_NAME,
+        None,
+    );
+    let event = Event::new("payload", "", serde_json::json!({}));
+
+    interpret(bot, context, event, None)
+        .messages
+        .into_iter()
+        .map(|mut message| message.message_to_json())
+        .collect()
+}
+
+#[test]
+fn artifact_roundtrip() {
+    let bot = get_bot();
+    let artifact = BotArtifact::compile(&bot).unwrap();
+    let encoded = artifact.encode();
+
+    assert!(BotArtifact::is_artifact(&encoded));
+    assert!(BotArtifact::decode_header(&encoded)
+        .unwrap()
+        .is_compatible());
+
+    let decoded = BotArtifact::decode(&encoded).unwrap();
+    assert_eq!(decoded.header, artifact.header);
+    assert_eq!(decoded.step_checksums, artifact.step_checksums);
+    assert!(decoded.get_step_checksum("default", "start").is_some());
+
+    // the flows of the bot can be resolved from the artifact
+    let sources: Vec<&str> = decoded.sources.iter().map(|flow| &*flow.name).collect();
+    assert_eq!(sources, vec!["default", "other"]);
+    assert_eq!(decoded.sources[0].content, bot.flows[0].content);
+}
+
+#[test]
+fn verify_signed_artifact() {
+    let artifact = BotArtifact::compile(&get_bot()).unwrap();
+    let signed = artifact.encode_signed(b"secret");
+
+    assert_eq!(
+        BotArtifact::verify(&signed, Some(b"secret")).unwrap(),
+        artifact.header
+    );
+    assert!(BotArtifact::verify(&signed, Some(b"other secret")).is_err());
+    assert!(BotArtifact::verify(&artifact.encode(), Some(b"secret")).is_err());
+
+    // the signature is not needed to decode the artifact
+    assert!(BotArtifact::verify(&signed, None).is_ok());
+    assert!(BotArtifact::decode(&signed).is_ok());
+}
+
+#[test]
+fn reject_altered_artifact() {
+    let encoded = BotArtifact::compile(&get_bot()).unwrap().encode();
+
+    // a body that does not match the checksum of the header
+    let mut altered = encoded.clone();
+    let mut bytes = base64::engine::general_purpose::STANDARD
+        .decode(&altered[BOT_ARTIFACT_PREFIX.len()..])
+        .unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    altered.truncate(BOT_ARTIFACT_PREFIX.len());
+    altered.push_str(&base64::engine::general_purpose::STANDARD.encode(bytes));
+
+    assert!(BotArtifact::verify(&encoded, None).is_ok());
+    assert!(BotArtifact::verify(&altered, None).is_err());
+    assert!(BotArtifact::decode(&altered).is_err());
+}
+
+#[test]
+fn interpret_precompiled_bot() {
+    let bot = get_bot();
+    let expected = run(bot.clone());
+
+    // the flows sources are not needed anymore once the bot is compiled
+    let mut precompiled = bot.clone();
+    precompiled.bot_ast = Some(BotArtifact::compile(&bot).unwrap().encode());
+    for flow in precompiled.flows.iter_mut() {
+        flow.content = String::new();
+    }
+
+    assert_eq!(run(precompiled), expected);
+}
+
+#[test]
+fn reject_invalid_artifact() {
+    assert!(BotArtifact::decode("csmlc:not base64").is_err());
+    assert!(BotArtifact::decode("bm90IGFuIGFydGlmYWN0").is_err());
+}
@@ -0,0 +1,104 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::csml_bot::CsmlBot;
+use csml_interpreter::data::csml_flow::CsmlFlow;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MSG;
+use csml_interpreter::{interpret, interpret_with_callback};
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+use crate::support::tools::read_file;
+
+use serde_json::Value;
+
+const DEFAULT_ID_NAME: &str = "id";
+const DEFAULT_FLOW_NAME: &str = "default";
+const DEFAULT_STEP_NAME: &str = "start";
+const DEFAULT_BOT_NAME: &str = "my_bot";
+
+fn get_bot() -> CsmlBot {
+    let default_content = read_file("CSML/basic_test/bot/default.csml".to_owned()).unwrap();
+    let default_flow = CsmlFlow::new(DEFAULT_ID_NAME, "default", &default_content, Vec::default());
+
+    let other_content = read_file("CSML/basic_test/bot/other.csml".to_owned()).unwrap();
+    let other_flow = CsmlFlow::new(DEFAULT_ID_NAME, "other", &other_content, Vec::default());
+
+    CsmlBot::new(
+        DEFAULT_ID_NAME,
+        DEFAULT_BOT_NAME,
+        None,
+        vec![default_flow, other_flow],
+        None,
+        None,
+        DEFAULT_FLOW_NAME,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn get_context() -> Context {
+    Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        None,
+        DEFAULT_STEP_NAME,
+        DEFAULT_FLOW_NAME,
+        None,
+    )
+}
+
+fn get_event() -> Event {
+    Event::new("payload", "", serde_json::json!({}))
+}
+
+// keep the messages and memories of the MSG stream, in order
+fn format_msgs(msgs: Vec<MSG>) -> Vec<Value> {
+    msgs.into_iter()
+        .filter_map(|msg| match msg {
+            MSG::Message(mut message) => Some(message.message_to_json()),
+            MSG::Remember(memory) => Some(serde_json::json!({"remember": memory.key})),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn callback_receives_every_msg() {
+    let mut msgs = vec![];
+    let msg_data = interpret_with_callback(get_bot(), get_context(), get_event(), &mut |msg| {
+        msgs.push(msg)
+    });
+
+    let messages: Vec<Value> = msg_data
+        .messages
+        .into_iter()
+        .map(|mut message| message.message_to_json())
+        .collect();
+    let sent: Vec<Value> = format_msgs(msgs)
+        .into_iter()
+        .filter(|msg| msg.get("remember").is_none())
+        .collect();
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(sent, messages);
+}
+
+#[test]
+fn callback_matches_channel() {
+    let (sender, receiver) = mpsc::channel::<MSG>();
+    interpret(get_bot(), get_context(), get_event(), Some(sender));
+    let from_channel: Vec<MSG> = receiver.into_iter().collect();
+
+    let mut from_callback = vec![];
+    interpret_with_callback(get_bot(), get_context(), get_event(), &mut |msg| {
+        from_callback.push(msg)
+    });
+
+    assert_eq!(format_msgs(from_callback), format_msgs(from_channel));
+}
@@ -0,0 +1,91 @@
+use csml_interpreter::data::csml_bot::{CsmlBot, Interruption};
+use csml_interpreter::data::csml_flow::CsmlFlow;
+use csml_interpreter::validate_bot;
+
+const FLOW_CONTENT: &str = "start:\n  say \"hello\"\n  goto end";
+
+fn get_bot(flows: Vec<CsmlFlow>) -> CsmlBot {
+    CsmlBot::new(
+        "id", "my_bot", None, flows, None, None, "default", None, None, None, None, None,
+    )
+}
+
+fn get_flow(name: &str, commands: &[&str], priority: i64) -> CsmlFlow {
+    let mut flow = CsmlFlow::new(
+        name,
+        name,
+        FLOW_CONTENT,
+        commands.iter().map(|cmd| cmd.to_string()).collect(),
+    );
+    flow.priority = priority;
+
+    flow
+}
+
+fn get_warnings(bot: &CsmlBot) -> Vec<(String, String)> {
+    let result = validate_bot(bot);
+    assert!(result.errors.is_none());
+
+    result
+        .warnings
+        .unwrap_or_default()
+        .into_iter()
+        .map(|warning| (warning.position.flow, warning.message))
+        .collect()
+}
+
+#[test]
+fn ambiguous_command() {
+    let bot = get_bot(vec![
+        get_flow("default", &["/start"], 0),
+        get_flow("help", &["help", "/start"], 0),
+        get_flow("faq", &["HELP"], 0),
+    ]);
+
+    let warnings = get_warnings(&bot);
+
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].0, "help");
+    assert!(warnings[0].1.contains("'/start'"));
+    assert_eq!(warnings[1].0, "faq");
+    assert!(warnings[1].1.contains("flow 'help'"));
+}
+
+#[test]
+fn command_with_priority() {
+    let bot = get_bot(vec![
+        get_flow("default", &["help"], 0),
+        get_flow("help", &["help"], 1),
+        get_flow("faq", &["help"], -1),
+    ]);
+
+    assert!(get_warnings(&bot).is_empty());
+}
+
+#[test]
+fn unknown_bot_flows() {
+    let mut bot = get_bot(vec![
+        get_flow("default", &[], 0),
+        get_flow("help", &["help"], 0),
+    ]);
+    bot.fallback_flow = Some("fallback".to_owned());
+    bot.interruptions = Some(vec![
+        Interruption {
+            flow: "HELP".to_owned(),
+            resume: true,
+        },
+        Interruption {
+            flow: "cancel".to_owned(),
+            resume: false,
+        },
+    ]);
+
+    let errors: Vec<String> = validate_bot(&bot)
+        .errors
+        .unwrap_or_default()
+        .into_iter()
+        .map(|error| error.position.flow)
+        .collect();
+
+    assert_eq!(errors, vec!["fallback", "cancel"]);
+}
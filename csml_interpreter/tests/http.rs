@@ -1,7 +1,9 @@
 mod support;
 
 use csml_interpreter::data::context::Context;
+use csml_interpreter::data::csml_usage;
 use csml_interpreter::data::event::Event;
+use csml_interpreter::error_format::ERROR_HTTP_LIMIT;
 use std::collections::HashMap;
 
 use crate::support::tools::format_message;
@@ -563,3 +565,35 @@ fn http_auth_0() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn http_limit() {
+    let mut event = Event::new("payload", "", serde_json::json!({}));
+    event.http_limit = Some(0);
+
+    let msg = format_message(
+        event,
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "http_limit",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/stdlib/http.csml",
+    );
+
+    let usage = csml_usage::get();
+    assert_eq!(usage.steps, 1);
+    assert_eq!(usage.http_calls, 0);
+
+    let v1: Value = message_to_json_value(msg);
+    assert_eq!(v1["messages"][0]["content"]["text"], "before");
+    assert_eq!(v1["messages"][1]["content_type"], "error");
+    assert!(v1["messages"][1]["content"]["error"]
+        .as_str()
+        .unwrap()
+        .contains(ERROR_HTTP_LIMIT));
+}
@@ -0,0 +1,219 @@
+use csml_interpreter::data::csml_bot::Module;
+use csml_interpreter::module_registry::{integrity, ModuleRegistry};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+// directory removed at the end of the test
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("csml_modules_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+fn module(name: &str, version: &str) -> Module {
+    Module {
+        name: name.to_owned(),
+        url: None,
+        auth: None,
+        version: version.to_owned(),
+        integrity: None,
+        flow: None,
+    }
+}
+
+fn cached(cache: &Path, source: &str, name: &str, version: &str) -> PathBuf {
+    cache
+        .join(hex::encode(Sha256::digest(source.as_bytes())))
+        .join(name)
+        .join(format!("{}.csml", version))
+}
+
+fn publish(registry: &Path, name: &str, versions: &[(&str, &str)]) {
+    let dir = registry.join(name);
+    fs::create_dir_all(&dir).unwrap();
+
+    let index: Vec<serde_json::Value> = versions
+        .iter()
+        .map(|(version, content)| {
+            fs::write(dir.join(format!("{}.csml", version)), content).unwrap();
+            serde_json::json!({"version": version, "integrity": integrity(content)})
+        })
+        .collect();
+    fs::write(
+        dir.join("index.json"),
+        serde_json::to_string(&index).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn resolve_semver_range() {
+    let registry = TempDir::new();
+    publish(
+        &registry,
+        "utils",
+        &[
+            ("1.0.0", "start: say \"1.0.0\""),
+            ("1.2.3", "start: say \"1.2.3\""),
+            ("2.0.0", "start: say \"2.0.0\""),
+        ],
+    );
+    let registry = ModuleRegistry::local(&registry);
+
+    let resolved = registry.resolve(&module("utils", "^1.0")).unwrap();
+    assert_eq!(resolved.version.as_deref(), Some("1.2.3"));
+    assert_eq!(resolved.content, "start: say \"1.2.3\"");
+    assert_eq!(resolved.integrity, integrity("start: say \"1.2.3\""));
+
+    let resolved = registry.resolve(&module("utils", "latest")).unwrap();
+    assert_eq!(resolved.version.as_deref(), Some("2.0.0"));
+
+    let resolved = registry.resolve(&module("utils", "=1.0.0")).unwrap();
+    assert_eq!(resolved.version.as_deref(), Some("1.0.0"));
+
+    assert!(registry.resolve(&module("utils", "^3")).is_err());
+    assert!(registry.resolve(&module("utils", "not a version")).is_err());
+}
+
+#[test]
+fn reject_invalid_integrity() {
+    let dir = TempDir::new();
+    publish(&dir, "utils", &[("1.0.0", "start: say \"1.0.0\"")]);
+    let registry = ModuleRegistry::local(&dir);
+
+    let mut pinned = module("utils", "1.0.0");
+    pinned.integrity = Some(integrity("start: say \"other\""));
+    assert!(registry.resolve(&pinned).is_err());
+
+    // content modified after being published in the index
+    fs::write(
+        dir.join("utils").join("1.0.0.csml"),
+        "start: say \"tampered\"",
+    )
+    .unwrap();
+    assert!(registry.resolve(&module("utils", "1.0.0")).is_err());
+}
+
+#[test]
+fn resolve_from_cache() {
+    let dir = TempDir::new();
+    publish(
+        &dir.join("registry"),
+        "utils",
+        &[("1.0.0", "start: say \"1.0.0\"")],
+    );
+
+    let registry = ModuleRegistry {
+        cache_dir: Some(dir.join("cache")),
+        ..ModuleRegistry::local(&dir.join("registry"))
+    };
+
+    registry.resolve(&module("utils", "1")).unwrap();
+    let source = registry.registry.as_deref().unwrap();
+    let cached = cached(&dir.join("cache"), source, "utils", "1.0.0");
+    assert!(cached.exists());
+
+    // a cached version that does not match the index is downloaded again
+    fs::write(&cached, "start: say \"tampered\"").unwrap();
+    let resolved = registry.resolve(&module("utils", "1")).unwrap();
+    assert_eq!(resolved.content, "start: say \"1.0.0\"");
+    assert_eq!(fs::read_to_string(&cached).unwrap(), resolved.content);
+
+    // the registry is not reachable anymore
+    fs::remove_dir_all(dir.join("registry")).unwrap();
+    let resolved = registry.resolve(&module("utils", "1")).unwrap();
+    assert_eq!(resolved.version.as_deref(), Some("1.0.0"));
+    assert_eq!(resolved.content, "start: say \"1.0.0\"");
+}
+
+#[test]
+fn reject_path_outside_registry() {
+    let dir = TempDir::new();
+    publish(
+        &dir.join("registry"),
+        "utils",
+        &[("1.0.0", "start: say \"1.0.0\"")],
+    );
+    fs::write(dir.join("secret.csml"), "start: say \"secret\"").unwrap();
+
+    let registry = ModuleRegistry {
+        cache_dir: Some(dir.join("cache")),
+        ..ModuleRegistry::local(&dir.join("registry"))
+    };
+
+    let mut local = module("utils", "1.0.0");
+    local.url = Some(format!(
+        "file://{}",
+        dir.join("registry/utils/1.0.0.csml").display()
+    ));
+    assert!(registry.resolve(&local).is_ok());
+
+    let mut local = module("secret", "1.0.0");
+    local.url = Some(dir.join("secret.csml").display().to_string());
+    assert!(registry.resolve(&local).is_err());
+    local.url = Some(dir.join("registry/../secret.csml").display().to_string());
+    assert!(registry.resolve(&local).is_err());
+
+    // without a local registry, no local file can be read
+    local.url = Some(dir.join("secret.csml").display().to_string());
+    assert!(ModuleRegistry::default().resolve(&local).is_err());
+
+    assert!(registry.resolve(&module("../utils", "1.0.0")).is_err());
+    assert!(registry.resolve(&module("utils/..", "1.0.0")).is_err());
+    assert!(registry.resolve(&module("..", "1.0.0")).is_err());
+    assert!(!dir.join("utils").exists());
+}
+
+#[test]
+fn cache_by_source() {
+    let dir = TempDir::new();
+    publish(
+        &dir.join("registry"),
+        "utils",
+        &[("1.0.0", "start: say \"registry\"")],
+    );
+    publish(
+        &dir.join("registry"),
+        "other",
+        &[("1.0.0", "start: say \"url\"")],
+    );
+
+    let registry = ModuleRegistry {
+        cache_dir: Some(dir.join("cache")),
+        ..ModuleRegistry::local(&dir.join("registry"))
+    };
+
+    // a module from a url with the same name and version as a module of the registry
+    let url = dir.join("registry/other/1.0.0.csml").display().to_string();
+    let mut from_url = module("utils", "1.0.0");
+    from_url.url = Some(url.clone());
+    let resolved = registry.resolve(&from_url).unwrap();
+    assert_eq!(resolved.content, "start: say \"url\"");
+    assert!(cached(&dir.join("cache"), &url, "utils", "1.0.0").exists());
+
+    let resolved = registry.resolve(&module("utils", "1.0.0")).unwrap();
+    assert_eq!(resolved.content, "start: say \"registry\"");
+
+    let resolved = registry.resolve(&from_url).unwrap();
+    assert_eq!(resolved.content, "start: say \"url\"");
+}
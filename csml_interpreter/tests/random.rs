@@ -37,3 +37,34 @@ fn ok_random() {
         panic!("Random fail {}", float);
     }
 }
+
+fn run_with_seed(seed: Option<u64>) -> Value {
+    let mut event = Event::new("payload", "", serde_json::json!({}));
+    event.seed = seed;
+
+    let msg = format_message(
+        event,
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "start",
+            "flow",
+            None,
+        ),
+        "CSML/basic_test/built-in/random_seed.csml",
+    );
+
+    message_to_json_value(msg)["messages"].clone()
+}
+
+#[test]
+fn ok_random_seed() {
+    let first = run_with_seed(Some(42));
+
+    assert_eq!(first, run_with_seed(Some(42)));
+    assert_ne!(first, run_with_seed(Some(43)));
+    // an unseeded run is not affected by a previous seeded one
+    assert_ne!(first, run_with_seed(None));
+}
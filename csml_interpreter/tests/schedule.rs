@@ -0,0 +1,102 @@
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::csml_bot::CsmlBot;
+use csml_interpreter::data::csml_flow::CsmlFlow;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{Schedule, MSG};
+use csml_interpreter::interpret_with_callback;
+use std::collections::HashMap;
+
+const REMINDER_FLOW: &str = "start:\n  say \"reminder\"\n  goto end";
+
+fn get_bot(content: &str) -> CsmlBot {
+    CsmlBot::new(
+        "id",
+        "my_bot",
+        None,
+        vec![
+            CsmlFlow::new("default", "default", content, Vec::default()),
+            CsmlFlow::new("reminder", "reminder", REMINDER_FLOW, Vec::default()),
+        ],
+        None,
+        None,
+        "default",
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn get_context() -> Context {
+    Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        None,
+        "start",
+        "default",
+        None,
+    )
+}
+
+fn interpret(content: &str) -> (Vec<Schedule>, Vec<String>) {
+    let event = Event::new("payload", "", serde_json::json!({}));
+
+    let (mut schedules, mut errors) = (vec![], vec![]);
+    interpret_with_callback(
+        get_bot(content),
+        get_context(),
+        event,
+        &mut |msg| match msg {
+            MSG::Schedule(schedule) => schedules.push(schedule),
+            MSG::Message(message) if message.content_type == "error" => {
+                errors.push(message.content["error"].to_string())
+            }
+            _ => {}
+        },
+    );
+
+    (schedules, errors)
+}
+
+#[test]
+fn schedule_flow() {
+    let (schedules, errors) = interpret(
+        "start:\n  do Schedule(\"reminder\", step = \"later\", at = 1700000000000)\n  goto end",
+    );
+
+    assert!(errors.is_empty());
+    assert_eq!(schedules.len(), 1);
+    assert_eq!(schedules[0].flow, "reminder");
+    assert_eq!(schedules[0].step, "later");
+    assert_eq!(schedules[0].at, 1700000000000);
+}
+
+#[test]
+fn schedule_with_time() {
+    let (schedules, errors) = interpret(
+        "start:\n  do date = Time().add(3600)\n  do Schedule(\"reminder\", at = date)\n  goto end",
+    );
+
+    assert!(errors.is_empty());
+    assert_eq!(schedules[0].step, "start");
+    assert!(schedules[0].at > chrono::Utc::now().timestamp_millis());
+}
+
+#[test]
+fn schedule_unknown_flow() {
+    let (schedules, errors) =
+        interpret("start:\n  do Schedule(\"unknown\", at = 1700000000000)\n  goto end");
+
+    assert!(schedules.is_empty());
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn schedule_without_date() {
+    let (schedules, errors) = interpret("start:\n  do Schedule(\"reminder\")\n  goto end");
+
+    assert!(schedules.is_empty());
+    assert_eq!(errors.len(), 1);
+}
@@ -0,0 +1,121 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::csml_bot::CsmlBot;
+use csml_interpreter::data::csml_flow::CsmlFlow;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{TraceEvent, TraceKind, MSG};
+use csml_interpreter::interpret_with_callback;
+use std::collections::HashMap;
+
+use crate::support::tools::read_file;
+
+const DEFAULT_ID_NAME: &str = "id";
+const DEFAULT_FLOW_NAME: &str = "default";
+const DEFAULT_STEP_NAME: &str = "start";
+const DEFAULT_BOT_NAME: &str = "my_bot";
+
+fn get_bot() -> CsmlBot {
+    let default_content = read_file("CSML/basic_test/bot/default.csml".to_owned()).unwrap();
+    let default_flow = CsmlFlow::new(DEFAULT_ID_NAME, "default", &default_content, Vec::default());
+
+    let other_content = read_file("CSML/basic_test/bot/other.csml".to_owned()).unwrap();
+    let other_flow = CsmlFlow::new(DEFAULT_ID_NAME, "other", &other_content, Vec::default());
+
+    CsmlBot::new(
+        DEFAULT_ID_NAME,
+        DEFAULT_BOT_NAME,
+        None,
+        vec![default_flow, other_flow],
+        None,
+        None,
+        DEFAULT_FLOW_NAME,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+fn get_context() -> Context {
+    Context::new(
+        HashMap::new(),
+        HashMap::new(),
+        None,
+        None,
+        DEFAULT_STEP_NAME,
+        DEFAULT_FLOW_NAME,
+        None,
+    )
+}
+
+fn get_trace(trace: bool) -> Vec<TraceEvent> {
+    let mut event = Event::new("payload", "", serde_json::json!({}));
+    event.trace = trace;
+
+    let mut events = vec![];
+    interpret_with_callback(get_bot(), get_context(), event, &mut |msg| {
+        if let MSG::Trace(trace_event) = msg {
+            events.push(trace_event)
+        }
+    });
+
+    events
+}
+
+#[test]
+fn trace_disabled_by_default() {
+    assert!(get_trace(false).is_empty());
+}
+
+#[test]
+fn trace_instructions() {
+    let events = get_trace(true);
+
+    let first = &events[0];
+    assert_eq!(
+        (first.flow.as_str(), first.step.as_str()),
+        ("default", "start")
+    );
+    assert!(
+        matches!(&first.kind, TraceKind::Instruction { instruction } if instruction == "remember")
+    );
+
+    assert!(events.iter().any(|event| matches!(
+        &event.kind,
+        TraceKind::Mutation { variable, memory, value }
+            if variable == "var" && memory == "remember" && *value == serde_json::json!(42)
+    )));
+
+    let gotos: Vec<(&str, &str, &str, &str)> = events
+        .iter()
+        .filter_map(|event| match &event.kind {
+            TraceKind::Goto { to_flow, to_step } => Some((
+                event.flow.as_str(),
+                event.step.as_str(),
+                to_flow.as_str(),
+                to_step.as_str(),
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        gotos[..2],
+        [
+            ("default", "start", "default", "step_0"),
+            ("default", "step_0", "other", "start"),
+        ]
+    );
+}
+
+#[test]
+fn trace_event_format() {
+    let events = get_trace(true);
+    let json = serde_json::to_value(&events[0]).unwrap();
+
+    assert_eq!(json["type"], "instruction");
+    assert_eq!(json["instruction"], "remember");
+    assert_eq!(json["flow"], "default");
+    assert_eq!(json["step"], "start");
+}
@@ -4,6 +4,9 @@ use actix_web::{http::header, middleware, web, App, HttpServer};
 use csml_engine::make_migrations;
 use csml_interpreter::csml_logs::init_logger;
 
+mod metrics;
+#[cfg(feature = "otel")]
+mod otel;
 mod routes;
 
 const MAX_BODY_SIZE: usize = 8_388_608; // 8MB
@@ -11,6 +14,13 @@ const MAX_BODY_SIZE: usize = 8_388_608; // 8MB
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
     init_logger();
+    metrics::install();
+
+    #[cfg(feature = "otel")]
+    let tracer_provider = match otel::init_tracer() {
+        Ok(tracer_provider) => tracer_provider,
+        Err(err) => panic!("OpenTelemetry setup ERROR: {:?}", err),
+    };
 
     let server_port: String = match std::env::var("ENGINE_SERVER_PORT") {
         Ok(val) => val,
@@ -24,7 +34,9 @@ async fn main() -> std::io::Result<()> {
         Err(err) => panic!("PgSQL Migration ERROR: {:?}", err),
     };
 
-    HttpServer::new(|| {
+    let gateway = web::Data::new(routes::websocket::Gateway::default());
+
+    HttpServer::new(move || {
         App::new()
             .wrap(
                 Cors::default()
@@ -38,12 +50,19 @@ async fn main() -> std::io::Result<()> {
                     .max_age(86_400), //24h
             )
             .wrap(middleware::Logger::default())
+            .wrap_fn(metrics::track_request)
             .app_data(web::JsonConfig::default().limit(MAX_BODY_SIZE))
+            .app_data(gateway.clone())
             .service(fs::Files::new("/static", "./static").use_last_modified(true))
             .service(routes::index::home)
             .service(routes::validate::handler)
+            .service(routes::compile::handler)
             .service(routes::status::get_status)
+            .service(routes::metrics::get_metrics)
             .service(routes::run::handler)
+            .service(routes::run::stream_handler)
+            .service(routes::websocket::handler)
+            .service(routes::websocket::push)
             .service(routes::sns::handler)
             .service(routes::bot_versions::add_bot_version)
             .service(routes::bot_versions::get_bot_version)
@@ -64,8 +83,27 @@ async fn main() -> std::io::Result<()> {
             .service(routes::data::delete_expired_data)
             .service(routes::data::delete_bot)
             .service(routes::data::delete_client)
+            .service(routes::data::export_client)
+            .service(routes::data::export_bot)
+            .service(routes::data::import)
+            .service(routes::schedules::run_due_schedules)
+            .service(routes::callbacks::redeliver_callbacks)
+            .service(routes::api_keys::create_api_key)
+            .service(routes::api_keys::get_api_keys)
+            .service(routes::api_keys::revoke_api_key)
+            .service(routes::analytics::get_flow_analytics)
+            .service(routes::analytics::get_hold_analytics)
+            .service(routes::analytics::get_turn_analytics)
+            .service(routes::analytics::get_step_transitions)
     })
     .bind(format!("0.0.0.0:{}", server_port))?
     .run()
-    .await
+    .await?;
+
+    #[cfg(feature = "otel")]
+    if let Err(err) = tracer_provider.shutdown() {
+        eprintln!("OpenTelemetry shutdown error: {:?}", err);
+    }
+
+    Ok(())
 }
@@ -0,0 +1,362 @@
+/**
+ * Prometheus metrics of the server, exposed in the text format by the /metrics route.
+ *
+ * The requests are counted by the `track_request` middleware, and the engine metrics
+ * (steps, messages, holds, HTTP() calls, callbacks and database calls) are received
+ * from the engine instrumentation hook once `install` is called (see `csml_metrics`).
+ */
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse};
+use actix_web::Error;
+use csml_engine::csml_metrics::{self, Metric, Recorder};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// default buckets of the prometheus clients, in seconds
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// name, type and help of each metric, in the order of the exposition
+const METRICS: [(&str, &str, &str); 10] = [
+    (
+        "csml_http_requests_total",
+        "counter",
+        "Requests handled by the server",
+    ),
+    (
+        "csml_http_request_duration_seconds",
+        "histogram",
+        "Time to handle a request",
+    ),
+    (
+        "csml_step_duration_seconds",
+        "histogram",
+        "Time to interpret a step",
+    ),
+    (
+        "csml_messages_sent_total",
+        "counter",
+        "Messages sent by the bots",
+    ),
+    (
+        "csml_holds_total",
+        "counter",
+        "Conversations put on hold, waiting for the user's input",
+    ),
+    (
+        "csml_resumes_total",
+        "counter",
+        "Conversations resumed from a hold",
+    ),
+    (
+        "csml_http_calls_total",
+        "counter",
+        "HTTP() and App() calls of the bots, the status is empty when no response was received",
+    ),
+    (
+        "csml_http_call_duration_seconds",
+        "histogram",
+        "Time of the HTTP() and App() calls of the bots",
+    ),
+    (
+        "csml_callback_failures_total",
+        "counter",
+        "Messages that could not be delivered to the callback_url",
+    ),
+    (
+        "csml_db_operation_duration_seconds",
+        "histogram",
+        "Time of the calls to the engine database",
+    ),
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    counters: Mutex<BTreeMap<(&'static str, Labels), u64>>,
+    histograms: Mutex<BTreeMap<(&'static str, Labels), Histogram>>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_labels(labels: &[(&str, String)]) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+        .collect();
+
+    match labels.is_empty() {
+        true => String::new(),
+        false => format!("{{{}}}", labels.join(",")),
+    }
+}
+
+fn labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect()
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+
+        for (bucket, le) in self.buckets.iter_mut().zip(BUCKETS.iter()) {
+            if seconds <= *le {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &Labels) {
+        for (count, le) in self.buckets.iter().zip(BUCKETS.iter()) {
+            let mut labels = labels.clone();
+            labels.push(("le", le.to_string()));
+            let _ = writeln!(out, "{}_bucket{} {}", name, format_labels(&labels), count);
+        }
+
+        let mut labels_inf = labels.clone();
+        labels_inf.push(("le", "+Inf".to_owned()));
+        let _ = writeln!(
+            out,
+            "{}_bucket{} {}",
+            name,
+            format_labels(&labels_inf),
+            self.count
+        );
+        let _ = writeln!(out, "{}_sum{} {}", name, format_labels(labels), self.sum);
+        let _ = writeln!(
+            out,
+            "{}_count{} {}",
+            name,
+            format_labels(labels),
+            self.count
+        );
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl Registry {
+    pub fn increment(&self, name: &'static str, labels: Labels, value: u64) {
+        if let Ok(mut counters) = self.counters.lock() {
+            *counters.entry((name, labels)).or_insert(0) += value;
+        }
+    }
+
+    pub fn observe(&self, name: &'static str, labels: Labels, duration: Duration) {
+        if let Ok(mut histograms) = self.histograms.lock() {
+            histograms
+                .entry((name, labels))
+                .or_default()
+                .observe(duration);
+        }
+    }
+
+    /**
+     * Every metric in the prometheus text format
+     */
+    pub fn render(&self) -> String {
+        let counters = match self.counters.lock() {
+            Ok(counters) => counters.clone(),
+            Err(_) => BTreeMap::new(),
+        };
+        let histograms = match self.histograms.lock() {
+            Ok(histograms) => histograms.clone(),
+            Err(_) => BTreeMap::new(),
+        };
+
+        let mut out = String::new();
+        for (name, kind, help) in METRICS.iter() {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+
+            for ((_, labels), value) in counters.iter().filter(|((key, _), _)| key == name) {
+                let _ = writeln!(out, "{}{} {}", name, format_labels(labels), value);
+            }
+            for ((_, labels), histogram) in histograms.iter().filter(|((key, _), _)| key == name) {
+                histogram.render(&mut out, name, labels);
+            }
+        }
+
+        out
+    }
+}
+
+impl Recorder for Registry {
+    fn record(&self, metric: &Metric) {
+        match *metric {
+            Metric::Step {
+                bot_id,
+                flow,
+                step,
+                duration,
+            } => self.observe(
+                "csml_step_duration_seconds",
+                labels(&[("bot_id", bot_id), ("flow", flow), ("step", step)]),
+                duration,
+            ),
+            Metric::Message { bot_id, flow } => self.increment(
+                "csml_messages_sent_total",
+                labels(&[("bot_id", bot_id), ("flow", flow)]),
+                1,
+            ),
+            Metric::Hold { bot_id, flow, step } => self.increment(
+                "csml_holds_total",
+                labels(&[("bot_id", bot_id), ("flow", flow), ("step", step)]),
+                1,
+            ),
+            Metric::Resume { bot_id, flow, step } => self.increment(
+                "csml_resumes_total",
+                labels(&[("bot_id", bot_id), ("flow", flow), ("step", step)]),
+                1,
+            ),
+            Metric::Http {
+                method,
+                status,
+                duration,
+            } => {
+                let status = status.map(|status| status.to_string()).unwrap_or_default();
+                self.increment(
+                    "csml_http_calls_total",
+                    labels(&[("method", method), ("status", &status)]),
+                    1,
+                );
+                self.observe(
+                    "csml_http_call_duration_seconds",
+                    labels(&[("method", method)]),
+                    duration,
+                );
+            }
+            Metric::CallbackFailure { bot_id } => self.increment(
+                "csml_callback_failures_total",
+                labels(&[("bot_id", bot_id)]),
+                1,
+            ),
+            Metric::Db {
+                backend,
+                operation,
+                duration,
+            } => self.observe(
+                "csml_db_operation_duration_seconds",
+                labels(&[("backend", backend), ("operation", operation)]),
+                duration,
+            ),
+        }
+    }
+}
+
+/**
+ * Registry of the server
+ */
+pub fn registry() -> Arc<Registry> {
+    static REGISTRY: OnceLock<Arc<Registry>> = OnceLock::new();
+
+    REGISTRY.get_or_init(Arc::default).clone()
+}
+
+/**
+ * Receive the engine metrics in the registry of the server
+ */
+pub fn install() {
+    csml_metrics::set_recorder(registry());
+}
+
+/**
+ * Metrics of the server, with the AST cache lookups of the engine
+ */
+pub fn render() -> String {
+    let cache = csml_engine::ast_cache_metrics();
+    let mut out = registry().render();
+
+    let _ = writeln!(
+        out,
+        "# HELP csml_ast_cache_events_total Lookups in the cache of the parsed flows"
+    );
+    let _ = writeln!(out, "# TYPE csml_ast_cache_events_total counter");
+    let _ = writeln!(
+        out,
+        "csml_ast_cache_events_total{{event=\"hit\"}} {}",
+        cache.hits
+    );
+    let _ = writeln!(
+        out,
+        "csml_ast_cache_events_total{{event=\"miss\"}} {}",
+        cache.misses
+    );
+    let _ = writeln!(
+        out,
+        "# HELP csml_ast_cache_entries Parsed flows in the cache"
+    );
+    let _ = writeln!(out, "# TYPE csml_ast_cache_entries gauge");
+    let _ = writeln!(out, "csml_ast_cache_entries {}", cache.entries);
+
+    out
+}
+
+/**
+ * Middleware counting the requests by method, route and status (use with `App::wrap_fn`).
+ * The route is the pattern of the matched resource, so that the ids in the path do not make
+ * a new series for each request.
+ */
+pub fn track_request<S, B>(
+    req: ServiceRequest,
+    service: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let route = req
+        .match_pattern()
+        .unwrap_or_else(|| "unmatched".to_owned());
+    let response = service.call(req);
+
+    async move {
+        let response = response.await;
+        let status = match &response {
+            Ok(response) => response.status(),
+            Err(err) => err.as_response_error().status_code(),
+        };
+
+        let labels = labels(&[("method", &method), ("route", &route)]);
+        let mut with_status = labels.clone();
+        with_status.push(("status", status.as_u16().to_string()));
+
+        let registry = registry();
+        registry.increment("csml_http_requests_total", with_status, 1);
+        registry.observe(
+            "csml_http_request_duration_seconds",
+            labels,
+            start.elapsed(),
+        );
+
+        response
+    }
+}
@@ -0,0 +1,85 @@
+/**
+ * OpenTelemetry setup of the server, enabled with the `otel` feature.
+ *
+ * Spans are exported with OTLP over http, configured with the standard
+ * `OTEL_EXPORTER_OTLP_*` environment variables. The W3C trace context of incoming
+ * requests (`traceparent` header) is the parent of the engine spans, and is
+ * propagated to outbound calls (HTTP() builtin, callback_url).
+ */
+use actix_web::{http::header::HeaderMap, HttpRequest};
+use opentelemetry::{global, propagation::Extractor, Context};
+use opentelemetry_otlp::{ExporterBuildError, SpanExporter};
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::SdkTracerProvider, Resource};
+
+const DEFAULT_SERVICE_NAME: &str = "csml_server";
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+/**
+ * Install the global tracer provider and propagator.
+ * The provider must be shut down before exiting to flush the remaining spans.
+ */
+pub fn init_tracer() -> Result<SdkTracerProvider, ExporterBuildError> {
+    let exporter = SpanExporter::builder().with_http().build()?;
+
+    let service_name =
+        std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| DEFAULT_SERVICE_NAME.to_owned());
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(service_name).build())
+        .build();
+
+    global::set_tracer_provider(provider.clone());
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    Ok(provider)
+}
+
+/**
+ * Trace context of an incoming request
+ */
+pub fn extract_context(req: &HttpRequest) -> Context {
+    global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(req.headers()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test;
+    use opentelemetry::trace::TraceContextExt;
+
+    #[actix_rt::test]
+    async fn test_extract_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+
+        let req = test::TestRequest::default()
+            .insert_header((
+                "traceparent",
+                "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            ))
+            .to_http_request();
+
+        let context = extract_context(&req);
+        let span_context = context.span().span_context().clone();
+
+        assert!(span_context.is_remote());
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+        assert_eq!(span_context.span_id().to_string(), "00f067aa0ba902b7");
+    }
+}
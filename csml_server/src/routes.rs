@@ -1,13 +1,20 @@
+pub mod analytics;
+pub mod api_keys;
+pub mod callbacks;
+pub mod compile;
 pub mod conversations;
 pub mod data;
 pub mod index;
 pub mod memories;
 pub mod messages;
+pub mod metrics;
 pub mod run;
+pub mod schedules;
 pub mod sns;
 pub mod state;
 pub mod status;
 pub mod validate;
+pub mod websocket;
 
 pub mod bot_versions;
 
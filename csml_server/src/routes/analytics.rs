@@ -0,0 +1,165 @@
+use crate::routes::tools::validate_api_key;
+use actix_web::{get, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
+use csml_engine::data::EngineError;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+    bot_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyticsQuery {
+    from_date: i64,
+    to_date: Option<i64>,
+}
+
+fn analytics_response<T: Serialize>(res: Result<T, EngineError>) -> HttpResponse {
+    match res {
+        Ok(data) => HttpResponse::Ok().json(data),
+        Err(EngineError::DateTimeError(err)) => {
+            HttpResponse::BadRequest().json(serde_json::json!({ "error": err }))
+        }
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/**
+ * Conversations started and ended in each flow, over the conversations of the bot created
+ * from from_date to to_date (timestamps in seconds, now by default)
+ *
+ * [{"flow_id": String, "started": u64, "ended": u64}]
+ */
+#[get("/bots/{bot_id}/analytics/flows")]
+pub async fn get_flow_analytics(
+    path: web::Path<BotIdPath>,
+    query: web::Query<AnalyticsQuery>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let bot_id = path.bot_id.to_owned();
+
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[bot_id.as_str()]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let (from_date, to_date) = (query.from_date, query.to_date);
+    let res = thread::spawn(move || csml_engine::get_flow_analytics(&bot_id, from_date, to_date))
+        .join()
+        .unwrap();
+
+    analytics_response(res)
+}
+
+/**
+ * Holds of each step, answered by the users or abandoned by the conversations still waiting
+ *
+ * [{"flow_id": String, "step_id": String, "answered": u64, "abandoned": u64}]
+ */
+#[get("/bots/{bot_id}/analytics/holds")]
+pub async fn get_hold_analytics(
+    path: web::Path<BotIdPath>,
+    query: web::Query<AnalyticsQuery>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let bot_id = path.bot_id.to_owned();
+
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[bot_id.as_str()]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let (from_date, to_date) = (query.from_date, query.to_date);
+    let res = thread::spawn(move || csml_engine::get_hold_analytics(&bot_id, from_date, to_date))
+        .join()
+        .unwrap();
+
+    analytics_response(res)
+}
+
+/**
+ * Inputs of the users and their average by conversation
+ *
+ * {"conversations": u64, "turns": u64, "average_turns": f64}
+ */
+#[get("/bots/{bot_id}/analytics/turns")]
+pub async fn get_turn_analytics(
+    path: web::Path<BotIdPath>,
+    query: web::Query<AnalyticsQuery>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let bot_id = path.bot_id.to_owned();
+
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[bot_id.as_str()]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let (from_date, to_date) = (query.from_date, query.to_date);
+    let res = thread::spawn(move || csml_engine::get_turn_analytics(&bot_id, from_date, to_date))
+        .join()
+        .unwrap();
+
+    analytics_response(res)
+}
+
+/**
+ * Transitions between steps, the most frequent first
+ *
+ * [{"from_flow": String, "from_step": String, "to_flow": String, "to_step": String, "count": u64}]
+ */
+#[get("/bots/{bot_id}/analytics/transitions")]
+pub async fn get_step_transitions(
+    path: web::Path<BotIdPath>,
+    query: web::Query<AnalyticsQuery>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let bot_id = path.bot_id.to_owned();
+
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[bot_id.as_str()]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let (from_date, to_date) = (query.from_date, query.to_date);
+    let res = thread::spawn(move || csml_engine::get_step_transitions(&bot_id, from_date, to_date))
+        .join()
+        .unwrap();
+
+    analytics_response(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    #[actix_rt::test]
+    async fn test_get_turn_analytics() {
+        let app = test::init_service(App::new().service(get_turn_analytics)).await;
+
+        let resp = test::TestRequest::get()
+            .uri("/bots/test_get_turn_analytics/analytics/turns?from_date=0")
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(
+            body,
+            serde_json::json!({ "conversations": 0, "turns": 0, "average_turns": 0.0 })
+        );
+
+        let resp = test::TestRequest::get()
+            .uri("/bots/test_get_turn_analytics/analytics/turns?from_date=10&to_date=5")
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::routes::tools::validate_api_key;
+use actix_web::{delete, get, post, web, HttpResponse};
+use csml_engine::data::models::{ApiKeyRequest, ApiKeyScope};
+use csml_engine::data::EngineError;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyIdPath {
+    id: String,
+}
+
+/**
+ * Create an API key with some scopes (run, read, bots or admin), for every bot
+ * or only the listed ones. The key is only returned in this response.
+ *
+ * {"name": "...", "scopes": ["run"], "bot_ids": ["..."]}
+ *
+ * {"key": "csml_...", "id": "...", "name": "...", "scopes": ["run"], "bot_ids": ["..."], "created_at": "..."}
+ */
+#[post("/api_keys")]
+pub async fn create_api_key(
+    body: web::Json<ApiKeyRequest>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Admin, &[]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let res = thread::spawn(move || csml_engine::create_api_key(&body))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(created) => HttpResponse::Created().json(created),
+        Err(EngineError::Format(err)) => {
+            HttpResponse::BadRequest().json(serde_json::json!({ "error": err }))
+        }
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/**
+ * List the API keys, without the keys themselves
+ *
+ * [{"id": "...", "name": "...", "scopes": ["run"], "bot_ids": ["..."], "created_at": "..."}]
+ */
+#[get("/api_keys")]
+pub async fn get_api_keys(req: actix_web::HttpRequest) -> HttpResponse {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Admin, &[]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let res = thread::spawn(csml_engine::get_api_keys).join().unwrap();
+
+    match res {
+        Ok(api_keys) => HttpResponse::Ok().json(api_keys),
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/**
+ * Revoke an API key
+ *
+ * {"statusCode": 204}
+ */
+#[delete("/api_keys/{id}")]
+pub async fn revoke_api_key(
+    path: web::Path<ApiKeyIdPath>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Admin, &[]) {
+        eprintln!("AuthError: {:?}", value);
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let res = thread::spawn(move || csml_engine::revoke_api_key(&path.id))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(true) => HttpResponse::NoContent().finish(),
+        Ok(false) => HttpResponse::NotFound().finish(),
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    #[actix_rt::test]
+    async fn test_api_keys() {
+        let app = test::init_service(
+            App::new()
+                .service(create_api_key)
+                .service(get_api_keys)
+                .service(revoke_api_key),
+        )
+        .await;
+
+        let resp = test::TestRequest::post()
+            .uri("/api_keys")
+            .set_json(serde_json::json!({
+                "name": "test_api_keys",
+                "scopes": ["run", "read"],
+                "bot_ids": ["botid"]
+            }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let created: serde_json::Value = test::read_body_json(resp).await;
+        assert!(created["key"].as_str().unwrap().starts_with("csml_"));
+        let id = created["id"].as_str().unwrap();
+
+        let resp = test::TestRequest::get()
+            .uri("/api_keys")
+            .send_request(&app)
+            .await;
+        let api_keys: serde_json::Value = test::read_body_json(resp).await;
+        let api_key = api_keys
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|api_key| api_key["id"] == id)
+            .unwrap();
+        assert_eq!(api_key["bot_ids"], serde_json::json!(["botid"]));
+        assert!(api_key.get("key").is_none());
+
+        let resp = test::TestRequest::delete()
+            .uri(&format!("/api_keys/{}", id))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let resp = test::TestRequest::delete()
+            .uri(&format!("/api_keys/{}", id))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = test::TestRequest::post()
+            .uri("/api_keys")
+            .set_json(serde_json::json!({ "name": "no scope", "scopes": [] }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
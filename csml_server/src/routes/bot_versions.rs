@@ -1,5 +1,6 @@
 use crate::routes::tools::validate_api_key;
 use actix_web::{delete, get, post, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
 use csml_engine::{
     create_bot_version, delete_all_bot_versions, delete_bot_version_id, fold_bot,
     get_bot_by_version_id, get_bot_versions, get_last_bot_version,
@@ -18,7 +19,7 @@ use std::thread;
 pub async fn make_bot_fold(body: web::Json<CsmlBot>, req: actix_web::HttpRequest) -> HttpResponse {
     let bot = body.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot.id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -47,7 +48,7 @@ pub async fn add_bot_version(
 ) -> HttpResponse {
     let bot = body.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot.id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -98,7 +99,7 @@ pub async fn get_bot_latest_version(
 ) -> HttpResponse {
     let bot_id = path.bot_id.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -129,7 +130,7 @@ pub async fn delete_bot_versions(
 ) -> HttpResponse {
     let bot_id = path.bot_id.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -177,7 +178,7 @@ pub async fn get_bot_latest_versions(
         None => None,
     };
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -224,7 +225,7 @@ pub async fn get_bot_version(
     let bot_id = path.bot_id.to_owned();
     let version_id = path.version_id.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -256,7 +257,7 @@ pub async fn delete_bot_version(
     let bot_id = path.bot_id.to_owned();
     let version_id = path.version_id.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Bots, &[bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
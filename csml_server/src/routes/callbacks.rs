@@ -0,0 +1,46 @@
+use crate::routes::tools::validate_api_key;
+use actix_web::{post, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+const DEFAULT_REDELIVERY_LIMIT: u32 = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RedeliverCallbacksQuery {
+    bot_id: Option<String>,
+    limit: Option<u32>,
+}
+
+/**
+ * Send again the messages that could not be delivered to their callback_url,
+ * optionally only for one bot.
+ *
+ * [{"id": "...", "client": {...}, "callback_url": "...", "attempts": 3, "delivered": false, "error": "..."}]
+ *
+ */
+#[post("/callbacks/redeliver")]
+pub async fn redeliver_callbacks(
+    query: web::Query<RedeliverCallbacksQuery>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let bot_ids: Vec<&str> = query.bot_id.iter().map(String::as_str).collect();
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &bot_ids) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let RedeliverCallbacksQuery { bot_id, limit } = query.into_inner();
+    let limit = limit.unwrap_or(DEFAULT_REDELIVERY_LIMIT);
+
+    let res = thread::spawn(move || csml_engine::redeliver_callbacks(bot_id.as_deref(), limit))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(redeliveries) => HttpResponse::Ok().json(redeliveries),
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
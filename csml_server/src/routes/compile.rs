@@ -0,0 +1,75 @@
+use crate::routes::validate::ValidationError;
+use actix_web::{post, web, HttpResponse};
+use csml_engine::{compile_bot, encode_bot_artifact};
+use csml_interpreter::data::csml_bot::CsmlBot;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CompileBotResponse {
+    valid: bool,
+    errors: Vec<ValidationError>,
+    interpreter_version: Option<String>,
+    bot_ast: Option<String>,
+}
+
+/**
+ * Compile a bot into an artifact, to be sent as the `bot_ast` of the bot in
+ * `/run` requests instead of having its flows parsed on each request.
+ * The artifact is only used by `/run` if it is signed with BOT_ARTIFACT_SECRET, in which
+ * case the flows can be left out of the bot.
+ */
+#[post("/compile")]
+pub async fn handler(body: web::Json<CsmlBot>) -> HttpResponse {
+    let response = match compile_bot(body.into_inner()) {
+        Ok(artifact) => CompileBotResponse {
+            valid: true,
+            errors: Vec::new(),
+            interpreter_version: Some(artifact.header.interpreter_version.to_owned()),
+            bot_ast: Some(encode_bot_artifact(&artifact)),
+        },
+        Err(errors) => CompileBotResponse {
+            valid: false,
+            errors: errors.iter().map(ValidationError::new).collect(),
+            interpreter_version: None,
+            bot_ast: None,
+        },
+    };
+
+    HttpResponse::Ok().json(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    #[actix_rt::test]
+    async fn test_compile() {
+        let app = test::init_service(App::new().service(handler)).await;
+
+        let resp = test::TestRequest::post()
+            .uri("/compile")
+            .set_json(serde_json::json!({
+                  "id": "test_compile",
+                  "name": "test_compile",
+                  "flows": [
+                    {
+                      "id": "Default",
+                      "name": "Default",
+                      "content": "start: say \"Hello\" goto end",
+                      "commands": [],
+                    }
+                  ],
+                  "default_flow": "Default",
+            }))
+            .send_request(&app)
+            .await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body: CompileBotResponse = test::read_body_json(resp).await;
+        assert!(body.valid);
+        assert!(body.bot_ast.is_some());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::routes::tools::validate_api_key;
 use actix_web::{get, post, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
 use csml_engine::{get_open_conversation, user_close_all_conversations, Client};
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -10,7 +11,7 @@ use std::thread;
  */
 #[post("/conversations/open")]
 pub async fn get_open(body: web::Json<Client>, req: actix_web::HttpRequest) -> HttpResponse {
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[body.bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -37,7 +38,7 @@ pub async fn close_user_conversations(
     body: web::Json<Client>,
     req: actix_web::HttpRequest,
 ) -> HttpResponse {
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Run, &[body.bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -72,7 +73,7 @@ pub async fn get_client_conversations(
     query: web::Query<GetClientInfoQuery>,
     req: actix_web::HttpRequest,
 ) -> HttpResponse {
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[query.bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
@@ -1,5 +1,7 @@
 use crate::routes::tools::validate_api_key;
-use actix_web::{delete, post, web, HttpResponse};
+use actix_web::{delete, get, post, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
+use csml_engine::data::models::Archive;
 use csml_interpreter::data::Client;
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -33,7 +35,7 @@ pub async fn delete_client(
         bot_id: query.bot_id.clone(),
     };
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
@@ -58,7 +60,7 @@ pub async fn delete_client(
  */
 #[delete("/data/bots/{bot_id}")]
 pub async fn delete_bot(path: web::Path<BotIdPath>, req: actix_web::HttpRequest) -> HttpResponse {
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[path.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
@@ -75,6 +77,94 @@ pub async fn delete_bot(path: web::Path<BotIdPath>, req: actix_web::HttpRequest)
     }
 }
 
+/**
+ * Export the conversations, messages, memories and state of a Client
+ *
+ * {"version": 1, "exported_at": "...", "clients": [{"client": {...}, "conversations": [...], "messages": [...], "memories": {...}, "state": [...]}]}
+ *
+ */
+#[get("/data/clients/export")]
+pub async fn export_client(
+    query: web::Query<ClientQuery>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let client = Client {
+        user_id: query.user_id.clone(),
+        channel_id: query.channel_id.clone(),
+        bot_id: query.bot_id.clone(),
+    };
+
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Read, &[query.bot_id.as_str()]) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let res = thread::spawn(move || csml_engine::export_client(&client))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(archive) => HttpResponse::Ok().json(archive),
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/**
+ * Export the conversations, messages, memories and state of every Client of a bot,
+ * in the same format as /data/clients/export
+ */
+#[get("/data/bots/{bot_id}/export")]
+pub async fn export_bot(path: web::Path<BotIdPath>, req: actix_web::HttpRequest) -> HttpResponse {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Read, &[path.bot_id.as_str()]) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let res = thread::spawn(move || csml_engine::export_bot(&path.bot_id))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(archive) => HttpResponse::Ok().json(archive),
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/**
+ * Import a document made by an export route, replacing the data of its Clients
+ *
+ * {"clients": 1, "conversations": 2, "messages": 12, "memories": 3}
+ *
+ */
+#[post("/data/import")]
+pub async fn import(body: web::Json<Archive>, req: actix_web::HttpRequest) -> HttpResponse {
+    let bot_ids: Vec<&str> = body
+        .clients
+        .iter()
+        .map(|client| client.client.bot_id.as_str())
+        .collect();
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &bot_ids) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let archive = body.into_inner();
+    let res = thread::spawn(move || csml_engine::import_client(&archive))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(imported) => HttpResponse::Ok().json(imported),
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
 /**
  * Delete all expired data in db (Conversation, Messages, Memory and State)
  *
@@ -95,3 +185,34 @@ pub async fn delete_expired_data() -> HttpResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+
+    #[actix_rt::test]
+    async fn test_export_import_client() {
+        let app = test::init_service(App::new().service(export_client).service(import)).await;
+
+        let resp = test::TestRequest::get()
+            .uri("/data/clients/export?bot_id=test_export&channel_id=channel_id&user_id=user_id")
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let archive: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(archive["clients"][0]["client"]["bot_id"], "test_export");
+
+        let resp = test::TestRequest::post()
+            .uri("/data/import")
+            .set_json(&archive)
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let imported: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(imported["clients"], 1);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::routes::tools::validate_api_key;
 use actix_web::{delete, get, post, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
 use csml_interpreter::data::Client;
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -40,7 +41,7 @@ pub async fn create_client_memory(
         bot_id: query.bot_id.clone(),
     };
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
@@ -79,7 +80,7 @@ pub async fn delete_memory(
         bot_id: query.bot_id.clone(),
     };
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
@@ -113,7 +114,7 @@ pub async fn delete_memories(
         bot_id: query.bot_id.clone(),
     };
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
@@ -148,7 +149,7 @@ pub async fn get_memory(
         bot_id: query.bot_id.clone(),
     };
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Read, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
@@ -180,7 +181,7 @@ pub async fn get_memories(
         bot_id: query.bot_id.clone(),
     };
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Read, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
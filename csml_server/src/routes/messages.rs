@@ -1,5 +1,6 @@
 use crate::routes::tools::validate_api_key;
 use actix_web::{get, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
 use csml_interpreter::data::Client;
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -44,7 +45,7 @@ pub async fn get_client_messages(
     let from_date = query.limit.to_owned();
     let to_date = query.limit.to_owned();
 
-    if let Some(_value) = validate_api_key(&req) {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Read, &[query.bot_id.as_str()]) {
         return HttpResponse::Forbidden().finish();
     }
 
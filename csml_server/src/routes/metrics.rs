@@ -0,0 +1,77 @@
+use crate::metrics;
+use crate::routes::tools::validate_api_key;
+use actix_web::{get, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
+
+/*
+* Get the server metrics, in the prometheus text format.
+* They have the ids, flows and steps of every bot, so they need an admin API key.
+*
+*/
+#[get("/metrics")]
+pub async fn get_metrics(req: actix_web::HttpRequest) -> HttpResponse {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[]) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics::render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use csml_engine::csml_metrics::{self, Metric};
+    use std::time::Duration;
+
+    #[actix_rt::test]
+    async fn test_get_metrics() {
+        metrics::install();
+        let app = test::init_service(
+            App::new()
+                .wrap_fn(metrics::track_request)
+                .service(get_metrics),
+        )
+        .await;
+
+        csml_metrics::record(Metric::Step {
+            bot_id: "test_get_metrics",
+            flow: "Default",
+            step: "start",
+            duration: Duration::from_millis(20),
+        });
+        csml_metrics::record(Metric::Message {
+            bot_id: "test_get_metrics",
+            flow: "Default",
+        });
+
+        let resp = test::TestRequest::get()
+            .uri("/metrics")
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = test::TestRequest::get()
+            .uri("/metrics")
+            .send_request(&app)
+            .await;
+        let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+
+        assert!(body.contains(
+            "csml_http_requests_total{method=\"GET\",route=\"/metrics\",status=\"200\"} 1\n"
+        ));
+        assert!(body.contains(
+            "csml_step_duration_seconds_bucket{bot_id=\"test_get_metrics\",flow=\"Default\",step=\"start\",le=\"0.025\"} 1\n"
+        ));
+        assert!(body.contains(
+            "csml_step_duration_seconds_bucket{bot_id=\"test_get_metrics\",flow=\"Default\",step=\"start\",le=\"0.01\"} 0\n"
+        ));
+        assert!(body.contains(
+            "csml_messages_sent_total{bot_id=\"test_get_metrics\",flow=\"Default\"} 1\n"
+        ));
+        assert!(body.contains("# TYPE csml_db_operation_duration_seconds histogram\n"));
+    }
+}
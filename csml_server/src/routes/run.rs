@@ -1,39 +1,72 @@
 use crate::routes::tools::validate_api_key;
 use actix_web::{post, web, HttpResponse};
-use csml_engine::data::models::RunRequest;
-use csml_engine::start_conversation;
+use csml_engine::data::models::{ApiKeyScope, BotOpt, CsmlRequest, RunRequest};
+use csml_engine::data::EngineError;
+use csml_engine::{start_conversation, start_conversation_stream};
+use futures::StreamExt;
 use serde_json::{json, Value};
-use std::thread;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
-#[post("/run")]
-pub async fn handler(body: web::Json<RunRequest>, req: actix_web::HttpRequest) -> HttpResponse {
+fn get_request(
+    body: &RunRequest,
+    req: &actix_web::HttpRequest,
+) -> Result<(CsmlRequest, BotOpt), HttpResponse> {
     let mut request = body.event.to_owned();
 
-    if let Some(value) = validate_api_key(&req) {
-        eprintln!("AuthError: {:?}", value);
-        return HttpResponse::Forbidden().finish();
-    }
-
     let bot_opt = match body.get_bot_opt() {
         Ok(bot_opt) => bot_opt,
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            return HttpResponse::BadRequest().finish();
+            return Err(HttpResponse::BadRequest().finish());
         }
     };
 
+    if let Some(value) = validate_api_key(req, ApiKeyScope::Run, &[bot_opt.bot_id()]) {
+        eprintln!("AuthError: {:?}", value);
+        return Err(HttpResponse::Forbidden().finish());
+    }
+
+    // the memories and conversations of the event client are read and written: they
+    // must belong to the bot the API key was checked for
+    if request.client.bot_id != bot_opt.bot_id() {
+        eprintln!(
+            "AuthError: the event client does not belong to bot [{}]",
+            bot_opt.bot_id()
+        );
+        return Err(HttpResponse::Forbidden().finish());
+    }
+
     // request metadata should be an empty object by default
     request.metadata = match request.metadata {
         Value::Null => json!({}),
         val => val,
     };
 
-    let res = thread::spawn(move || start_conversation(request, bot_opt))
-        .join()
-        .unwrap();
+    Ok((request, bot_opt))
+}
+
+#[post("/run")]
+pub async fn handler(body: web::Json<RunRequest>, req: actix_web::HttpRequest) -> HttpResponse {
+    let (request, bot_opt) = match get_request(&body, &req) {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    #[cfg(feature = "otel")]
+    let context = crate::otel::extract_context(&req);
+
+    let res = join_conversation(thread::spawn(move || {
+        #[cfg(feature = "otel")]
+        let _guard = context.attach();
+
+        start_conversation(request, bot_opt)
+    }));
 
     match res {
         Ok(data) => HttpResponse::Ok().json(data),
+        Err(EngineError::RateLimit(err)) => too_many_requests(&err),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
             HttpResponse::InternalServerError().finish()
@@ -41,6 +74,112 @@ pub async fn handler(body: web::Json<RunRequest>, req: actix_web::HttpRequest) -
     }
 }
 
+// a panic of the engine is answered as an error of the request
+pub(crate) fn join_conversation<T>(
+    conversation: JoinHandle<Result<T, EngineError>>,
+) -> Result<T, EngineError> {
+    conversation.join().unwrap_or_else(|_| {
+        Err(EngineError::Manager(
+            "the conversation stopped unexpectedly".to_owned(),
+        ))
+    })
+}
+
+// the request is over a rate limit or quota of the engine
+pub(crate) fn too_many_requests(err: &str) -> HttpResponse {
+    HttpResponse::TooManyRequests().json(json!({ "error": err }))
+}
+
+fn sse_event(event: &str, data: &Value) -> web::Bytes {
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event, data))
+}
+
+// Typing and Wait ask the client to pause before displaying the next messages
+pub(crate) fn get_delay(message: &Value) -> Duration {
+    match message["payload"]["content_type"].as_str() {
+        Some("typing") | Some("wait") => {
+            let duration = message["payload"]["content"]["duration"].as_f64();
+            Duration::from_millis(duration.map_or(0, |duration| duration.max(0.0) as u64))
+        }
+        _ => Duration::ZERO,
+    }
+}
+
+/**
+ * Same as /run, but the messages are sent as Server-Sent Events as the bot says them.
+ *
+ * event: message
+ * data: {"payload": {...}, "interaction_order": 0, "conversation_id": "...", "direction": "SEND"}
+ *
+ * The message following a Typing or a Wait is sent after its duration.
+ * The last event is `end` with the same data as the /run response, or `error`.
+ * Requests over a rate limit or quota get a 429 response instead.
+ */
+#[post("/run/stream")]
+pub async fn stream_handler(
+    body: web::Json<RunRequest>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    let (request, bot_opt) = match get_request(&body, &req) {
+        Ok(request) => request,
+        Err(response) => return response,
+    };
+
+    #[cfg(feature = "otel")]
+    let context = crate::otel::extract_context(&req);
+
+    let (sender, receiver) = mpsc::channel();
+    let conversation = thread::spawn(move || {
+        #[cfg(feature = "otel")]
+        let _guard = context.attach();
+
+        start_conversation_stream(request, bot_opt, sender)
+    });
+
+    // a request rejected by the rate limits ends before its first message
+    let first = receiver.recv().ok();
+    let (conversation, result) = match first {
+        Some(_) => (Some(conversation), None),
+        None => match join_conversation(conversation) {
+            Err(EngineError::RateLimit(err)) => return too_many_requests(&err),
+            result => (None, Some(result)),
+        },
+    };
+
+    let (events, stream) = futures::channel::mpsc::unbounded();
+    thread::spawn(move || {
+        for message in first.into_iter().chain(receiver) {
+            // stop sending once the client is gone, the request still completes
+            if events
+                .unbounded_send(sse_event("message", &message))
+                .is_err()
+            {
+                return;
+            }
+
+            thread::sleep(get_delay(&message));
+        }
+
+        let result = match (conversation, result) {
+            (Some(conversation), _) => join_conversation(conversation),
+            (None, result) => result.unwrap(),
+        };
+        let event = match result {
+            Ok(data) => sse_event("end", &json!(data)),
+            Err(err) => {
+                eprintln!("EngineError: {:?}", err);
+                sse_event("error", &json!({ "error": format!("{:?}", err) }))
+            }
+        };
+        events.unbounded_send(event).ok();
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream.map(Ok::<_, actix_web::Error>))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +227,108 @@ mod tests {
 
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    #[actix_rt::test]
+    async fn test_run_other_bot_client() {
+        let app = test::init_service(App::new().service(handler)).await;
+
+        let api_key = csml_engine::create_api_key(&csml_engine::data::models::ApiKeyRequest {
+            name: "test_run_other_bot_client".to_owned(),
+            scopes: vec![ApiKeyScope::Run],
+            bot_ids: Some(vec!["bot_a".to_owned()]),
+        })
+        .unwrap();
+
+        let resp = test::TestRequest::post()
+            .uri("/run")
+            .insert_header(("X-Api-Key", api_key.key))
+            .set_json(serde_json::json!({
+                "bot": {
+                    "id": "bot_a",
+                    "name": "bot_a",
+                    "flows": [
+                      {
+                        "id": "Default",
+                        "name": "Default",
+                        "content": "start: say \"Hello\" goto end",
+                        "commands": [],
+                      }
+                    ],
+                    "default_flow": "Default",
+                },
+                "event": {
+                    "request_id": "request_id",
+                    "client": {
+                        "user_id": "user_id",
+                        "channel_id": "channel_id",
+                        "bot_id": "bot_b"
+                    },
+                    "payload": {
+                      "content_type": "text" ,
+                      "content": {
+                        "text": "toto"
+                      }
+                    },
+                    "metadata": Value::Null,
+                },
+            }))
+            .send_request(&app)
+            .await;
+
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn test_run_stream() {
+        let mut app = test::init_service(App::new().service(stream_handler)).await;
+
+        let resp = test::TestRequest::post()
+            .uri("/run/stream")
+            .set_json(&serde_json::json!({
+                "bot": {
+                    "id": "test_run_stream",
+                    "name": "test_run_stream",
+                    "flows": [
+                      {
+                        "id": "Default",
+                        "name": "Default",
+                        "content": "start: say Typing(10) say \"Hello\" goto end",
+                        "commands": [],
+                      }
+                    ],
+                    "default_flow": "Default",
+                },
+                "event": {
+                    "request_id": "request_id",
+                    "client": {
+                        "user_id": "user_id",
+                        "channel_id": "channel_id",
+                        "bot_id": "test_run_stream"
+                    },
+                    "payload": {
+                      "content_type": "text" ,
+                      "content": {
+                        "text": "toto"
+                      }
+                    },
+                    "metadata": Value::Null,
+                },
+            }))
+            .send_request(&mut app)
+            .await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            "text/event-stream"
+        );
+
+        let body = test::read_body(resp).await;
+        let events: Vec<&str> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.strip_prefix("event: "))
+            .collect();
+        assert_eq!(events, vec!["message", "message", "end"]);
+    }
 }
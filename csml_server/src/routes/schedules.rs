@@ -0,0 +1,52 @@
+use crate::routes::tools::validate_api_key;
+use crate::routes::websocket::Gateway;
+use actix_web::{post, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+const DEFAULT_SCHEDULES_LIMIT: u32 = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunSchedulesQuery {
+    limit: Option<u32>,
+}
+
+/**
+ * Run the flow triggers planned with Schedule() whose date is passed.
+ * The messages are sent to the callback_url of each schedule, and to the open WebSocket
+ * connections of its client.
+ *
+ * [{"id": "...", "client": {...}, "flow_id": "...", "step_id": "...", "run_at": "...", "error": "..."}]
+ *
+ */
+#[post("/schedules/run")]
+pub async fn run_due_schedules(
+    query: web::Query<RunSchedulesQuery>,
+    gateway: web::Data<Gateway>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[]) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_SCHEDULES_LIMIT);
+
+    let res = thread::spawn(move || csml_engine::run_due_schedules(limit))
+        .join()
+        .unwrap();
+
+    match res {
+        Ok(runs) => {
+            for run in runs.iter() {
+                gateway.push(&run.schedule.client, &run.messages);
+            }
+
+            HttpResponse::Ok().json(runs)
+        }
+        Err(err) => {
+            eprintln!("EngineError: {:?}", err);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
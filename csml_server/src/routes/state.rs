@@ -1,5 +1,6 @@
 use crate::routes::tools::validate_api_key;
 use actix_web::{get, web, HttpResponse};
+use csml_engine::data::models::ApiKeyScope;
 use csml_engine::Client;
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -22,7 +23,7 @@ pub async fn get_client_current_state(
         user_id: query.user_id.to_owned(),
     };
 
-    if let Some(value) = validate_api_key(&req) {
+    if let Some(value) = validate_api_key(&req, ApiKeyScope::Read, &[query.bot_id.as_str()]) {
         eprintln!("AuthError: {:?}", value);
         return HttpResponse::Forbidden().finish();
     }
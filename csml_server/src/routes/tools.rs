@@ -1,19 +1,58 @@
-pub fn validate_api_key(req: &actix_web::HttpRequest) -> Option<String> {
+use csml_engine::data::models::{ApiKey, ApiKeyScope};
+use std::thread;
+
+/**
+ * Find the API key of the request. Keys of ENGINE_SERVER_API_KEYS can be used for every
+ * route and every bot, other keys are the ones created with POST /api_keys.
+ *
+ * Return None when the request can do anything: API keys are only checked
+ * when ENGINE_SERVER_API_KEYS is set.
+ */
+pub fn get_api_key(req: &actix_web::HttpRequest) -> Result<Option<ApiKey>, String> {
     let api_keys = match std::env::var("ENGINE_SERVER_API_KEYS") {
         Ok(val) if !val.is_empty() => val,
-        _ => return None,
+        _ => return Ok(None),
     };
 
     let vec = api_keys.split(',').collect::<Vec<&str>>();
 
-    match req.headers().get("X-Api-Key") {
-        Some(val) => {
-            let val = val.to_str().unwrap_or("");
-            if val.is_empty() || !vec.contains(&val) {
-                return Some(format!("Invalid X-Api-Key value [{}]", val));
-            }
-            None
-        }
-        None => Some("Missing X-Api-Key in header".to_owned()),
+    let val = match req.headers().get("X-Api-Key") {
+        Some(val) => val.to_str().unwrap_or("").to_owned(),
+        None => return Err("Missing X-Api-Key in header".to_owned()),
+    };
+    if val.is_empty() {
+        return Err("Invalid X-Api-Key value []".to_owned());
+    }
+    if vec.contains(&val.as_str()) {
+        return Ok(None);
+    }
+
+    let key = val.clone();
+    match thread::spawn(move || csml_engine::get_api_key(&key))
+        .join()
+        .unwrap()
+    {
+        Ok(Some(api_key)) => Ok(Some(api_key)),
+        Ok(None) => Err(format!("Invalid X-Api-Key value [{}]", val)),
+        Err(err) => Err(format!("EngineError: {:?}", err)),
+    }
+}
+
+/**
+ * Return an error if the API key of the request does not have the scope,
+ * or is not allowed for all of the bots
+ */
+pub fn validate_api_key(
+    req: &actix_web::HttpRequest,
+    scope: ApiKeyScope,
+    bot_ids: &[&str],
+) -> Option<String> {
+    match get_api_key(req) {
+        Ok(Some(api_key)) if !api_key.allows(scope, bot_ids) => Some(format!(
+            "X-Api-Key [{}] does not allow {:?} for bots {:?}",
+            api_key.name, scope, bot_ids
+        )),
+        Ok(_) => None,
+        Err(err) => Some(err),
     }
 }
@@ -1,5 +1,5 @@
 use actix_web::{post, web, HttpResponse};
-use csml_engine::{validate_bot, CsmlResult};
+use csml_engine::{validate_bot, CsmlResult, ErrorInfo};
 use csml_interpreter::data::csml_bot::CsmlBot;
 use serde::{Deserialize, Serialize};
 
@@ -19,7 +19,7 @@ impl ValidateBotResponse {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-struct ValidationError {
+pub(crate) struct ValidationError {
     flow: String,
     start_line: u32,
     start_column: u32,
@@ -28,6 +28,19 @@ struct ValidationError {
     message: String,
 }
 
+impl ValidationError {
+    pub(crate) fn new(error_info: &ErrorInfo) -> Self {
+        Self {
+            flow: error_info.position.flow.clone(),
+            start_line: error_info.position.interval.start_line,
+            start_column: error_info.position.interval.start_column,
+            end_line: error_info.position.interval.end_line,
+            end_column: error_info.position.interval.end_column,
+            message: error_info.message.clone(),
+        }
+    }
+}
+
 #[post("/validate")]
 pub async fn handler(body: web::Json<CsmlBot>) -> HttpResponse {
     let response = match validate_bot(body.clone()) {
@@ -44,17 +57,7 @@ pub async fn handler(body: web::Json<CsmlBot>) -> HttpResponse {
             warnings: _,
             errors: Some(errors),
         } => {
-            let mut errors_array = Vec::new();
-            for (_, error_info) in errors.iter().enumerate() {
-                errors_array.push(ValidationError {
-                    flow: error_info.position.flow.clone(),
-                    start_line: error_info.position.interval.start_line,
-                    start_column: error_info.position.interval.start_column,
-                    end_line: error_info.position.interval.end_line,
-                    end_column: error_info.position.interval.end_column,
-                    message: error_info.message.clone(),
-                })
-            }
+            let errors_array = errors.iter().map(ValidationError::new).collect();
             ValidateBotResponse {
                 valid: false,
                 errors: errors_array,
@@ -0,0 +1,462 @@
+use crate::routes::run::{get_delay, join_conversation};
+use crate::routes::tools::{get_api_key, validate_api_key};
+use actix_web::{get, post, web, HttpResponse};
+use actix_ws::AggregatedMessage;
+use csml_engine::data::models::{ApiKey, ApiKeyScope, BotOpt, CsmlRequest, RunRequest};
+use csml_engine::start_conversation_stream;
+use csml_interpreter::data::Client;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+struct Connection {
+    id: usize,
+    client: Client,
+    sender: UnboundedSender<String>,
+}
+
+/**
+ * Open WebSocket connections, each one bound to a client
+ */
+#[derive(Default)]
+pub struct Gateway {
+    next_id: AtomicUsize,
+    connections: Mutex<Vec<Connection>>,
+}
+
+impl Gateway {
+    fn connect(&self, client: &Client, sender: UnboundedSender<String>) -> usize {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        self.connections.lock().unwrap().push(Connection {
+            id,
+            client: client.to_owned(),
+            sender,
+        });
+
+        id
+    }
+
+    fn disconnect(&self, id: usize) {
+        self.connections
+            .lock()
+            .unwrap()
+            .retain(|connection| connection.id != id);
+    }
+
+    /**
+     * Send the messages to every connection of the client,
+     * and return the number of connections they were sent to
+     */
+    pub fn push(&self, client: &Client, messages: &[Value]) -> usize {
+        let mut connections = self.connections.lock().unwrap();
+        connections.retain(|connection| !connection.sender.is_closed());
+
+        connections
+            .iter()
+            .filter(|connection| connection.client == *client)
+            .filter(|connection| {
+                messages.iter().all(|message| {
+                    connection
+                        .sender
+                        .unbounded_send(ws_event("message", message))
+                        .is_ok()
+                })
+            })
+            .count()
+    }
+}
+
+fn ws_event(event: &str, data: &Value) -> String {
+    json!({ "event": event, "data": data }).to_string()
+}
+
+/**
+ * A frame is either the same body as /run, or a bare event that runs the latest version of the client's bot.
+ * The client of the event can be left out, but must match the one of the connection,
+ * and the API key of the connection must allow to run the bot.
+ */
+fn get_request(
+    text: &str,
+    client: &Client,
+    api_key: Option<&ApiKey>,
+) -> Result<(CsmlRequest, BotOpt), String> {
+    let mut body: Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+
+    if body.get("event").is_none() {
+        body = json!({ "bot_id": client.bot_id, "event": body });
+    }
+
+    let event = &mut body["event"];
+    if event.get("client").is_none() {
+        event["client"] = json!(client);
+    }
+    // request metadata should be an empty object by default
+    if event["metadata"].is_null() {
+        event["metadata"] = json!({});
+    }
+
+    let body: RunRequest = serde_json::from_value(body).map_err(|err| err.to_string())?;
+    if body.event.client != *client {
+        return Err("the event client does not match the connection client".to_owned());
+    }
+
+    let bot_opt = body.get_bot_opt().map_err(|err| format!("{:?}", err))?;
+    if let Some(api_key) = api_key {
+        if !api_key.allows(ApiKeyScope::Run, &[bot_opt.bot_id()]) {
+            return Err(format!(
+                "the API key can not run the bot [{}]",
+                bot_opt.bot_id()
+            ));
+        }
+    }
+
+    Ok((body.event, bot_opt))
+}
+
+/**
+ * Run the request, the messages are sent to every connection of the client as the bot says them
+ * and the last event is only sent to the connection of the request.
+ */
+fn run_request(
+    gateway: web::Data<Gateway>,
+    client: Client,
+    api_key: Option<&ApiKey>,
+    sender: UnboundedSender<String>,
+    text: &str,
+) {
+    let (request, bot_opt) = match get_request(text, &client, api_key) {
+        Ok(request) => request,
+        Err(err) => {
+            sender
+                .unbounded_send(ws_event("error", &json!({ "error": err })))
+                .ok();
+            return;
+        }
+    };
+
+    let (messages, receiver) = mpsc::channel();
+    let conversation = thread::spawn(move || start_conversation_stream(request, bot_opt, messages));
+
+    thread::spawn(move || {
+        for message in receiver {
+            gateway.push(&client, std::slice::from_ref(&message));
+
+            thread::sleep(get_delay(&message));
+        }
+
+        let event = match join_conversation(conversation) {
+            Ok(data) => ws_event("end", &json!(data)),
+            Err(err) => {
+                eprintln!("EngineError: {:?}", err);
+                ws_event("error", &json!({ "error": format!("{:?}", err) }))
+            }
+        };
+        sender.unbounded_send(event).ok();
+    });
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConnectQuery {
+    bot_id: String,
+    channel_id: String,
+    user_id: String,
+}
+
+/**
+ * Open a WebSocket connection for a client. Each text frame is a request, with the same body as /run
+ * or only its event, and the server answers with text frames:
+ *
+ * {"event": "message", "data": {"payload": {...}, "interaction_order": 0, "conversation_id": "...", "direction": "SEND"}}
+ * {"event": "end", "data": {...same as the /run response...}}
+ * {"event": "error", "data": {"error": "..."}}
+ *
+ * The messages of a request are sent to all the connections of the client.
+ */
+#[get("/ws")]
+pub async fn handler(
+    query: web::Query<ConnectQuery>,
+    gateway: web::Data<Gateway>,
+    req: actix_web::HttpRequest,
+    body: web::Payload,
+) -> HttpResponse {
+    let ConnectQuery {
+        bot_id,
+        channel_id,
+        user_id,
+    } = query.into_inner();
+    let client = Client::new(bot_id, channel_id, user_id);
+
+    // the key is kept to check the bot of each request
+    let api_key = match get_api_key(&req) {
+        Ok(Some(api_key)) if !api_key.allows(ApiKeyScope::Run, &[&client.bot_id]) => {
+            eprintln!("AuthError: {:?} can not run the bot", api_key.name);
+            return HttpResponse::Forbidden().finish();
+        }
+        Ok(api_key) => api_key,
+        Err(value) => {
+            eprintln!("AuthError: {:?}", value);
+            return HttpResponse::Forbidden().finish();
+        }
+    };
+
+    let (response, mut session, stream) = match actix_ws::handle(&req, body) {
+        Ok(handle) => handle,
+        Err(err) => return err.error_response(),
+    };
+
+    let (sender, mut frames) = unbounded();
+    let id = gateway.connect(&client, sender.clone());
+
+    let mut writer = session.clone();
+    actix_web::rt::spawn(async move {
+        while let Some(frame) = frames.next().await {
+            if writer.text(frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    actix_web::rt::spawn(async move {
+        let mut stream = stream.aggregate_continuations();
+
+        while let Some(Ok(message)) = stream.next().await {
+            match message {
+                AggregatedMessage::Text(text) => run_request(
+                    gateway.clone(),
+                    client.clone(),
+                    api_key.as_ref(),
+                    sender.clone(),
+                    &text,
+                ),
+                AggregatedMessage::Ping(bytes) => {
+                    if session.pong(&bytes).await.is_err() {
+                        break;
+                    }
+                }
+                AggregatedMessage::Close(_) => break,
+                AggregatedMessage::Binary(_) | AggregatedMessage::Pong(_) => {}
+            }
+        }
+
+        gateway.disconnect(id);
+        session.close(None).await.ok();
+    });
+
+    response
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PushRequest {
+    client: Client,
+    messages: Vec<Value>,
+}
+
+/**
+ * Send messages to the open WebSocket connections of a client, without a request from them.
+ *
+ * {"client": {...}, "messages": [{"payload": {...}}]}
+ *
+ * Returns the number of connections the messages were sent to: {"connections": 1}
+ */
+#[post("/ws/push")]
+pub async fn push(
+    body: web::Json<PushRequest>,
+    gateway: web::Data<Gateway>,
+    req: actix_web::HttpRequest,
+) -> HttpResponse {
+    if let Some(_value) = validate_api_key(&req, ApiKeyScope::Admin, &[&body.client.bot_id]) {
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let connections = gateway.push(&body.client, &body.messages);
+
+    HttpResponse::Ok().json(json!({ "connections": connections }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{App, HttpServer};
+    use awc::ws::{Frame, Message};
+    use futures::SinkExt;
+
+    async fn next_event<S>(connection: &mut S) -> Value
+    where
+        S: futures::Stream<Item = Result<Frame, awc::error::WsProtocolError>> + Unpin,
+    {
+        loop {
+            if let Frame::Text(text) = connection.next().await.unwrap().unwrap() {
+                return serde_json::from_slice(&text).unwrap();
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_websocket() {
+        let gateway = web::Data::new(Gateway::default());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(gateway.clone())
+                .service(handler)
+                .service(push)
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let addr = server.addrs()[0];
+        actix_rt::spawn(server.run());
+
+        let (_, mut connection) = awc::Client::new()
+            .ws(format!(
+                "ws://{}/ws?bot_id=test_websocket&channel_id=channel_id&user_id=user_id",
+                addr
+            ))
+            .connect()
+            .await
+            .unwrap();
+
+        let request = json!({
+            "bot": {
+                "id": "test_websocket",
+                "name": "test_websocket",
+                "flows": [
+                  {
+                    "id": "Default",
+                    "name": "Default",
+                    "content": "start: say \"Hello\" goto end",
+                    "commands": [],
+                  }
+                ],
+                "default_flow": "Default",
+            },
+            "event": {
+                "request_id": "request_id",
+                "payload": {
+                  "content_type": "text" ,
+                  "content": {
+                    "text": "toto"
+                  }
+                },
+            },
+        });
+        connection
+            .send(Message::Text(request.to_string().into()))
+            .await
+            .unwrap();
+
+        let event = next_event(&mut connection).await;
+        assert_eq!(event["event"], "message");
+        assert_eq!(event["data"]["payload"]["content"]["text"], "Hello");
+        assert_eq!(next_event(&mut connection).await["event"], "end");
+
+        // an event for another client is refused
+        let mut request = request;
+        request["event"]["client"] = json!({
+            "user_id": "other_user_id",
+            "channel_id": "channel_id",
+            "bot_id": "test_websocket"
+        });
+        connection
+            .send(Message::Text(request.to_string().into()))
+            .await
+            .unwrap();
+        assert_eq!(next_event(&mut connection).await["event"], "error");
+
+        let mut resp = awc::Client::new()
+            .post(format!("http://{}/ws/push", addr))
+            .send_json(&json!({
+                "client": {
+                    "user_id": "user_id",
+                    "channel_id": "channel_id",
+                    "bot_id": "test_websocket"
+                },
+                "messages": [{"payload": {"content_type": "text", "content": {"text": "Hi"}}}],
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.json::<Value>().await.unwrap(),
+            json!({ "connections": 1 })
+        );
+
+        let event = next_event(&mut connection).await;
+        assert_eq!(event["event"], "message");
+        assert_eq!(event["data"]["payload"]["content"]["text"], "Hi");
+    }
+
+    #[actix_rt::test]
+    async fn test_websocket_schedule() {
+        let bot: csml_interpreter::data::csml_bot::CsmlBot = serde_json::from_value(json!({
+            "id": "test_websocket_schedule",
+            "name": "test_websocket_schedule",
+            "flows": [
+              {
+                "id": "Default",
+                "name": "Default",
+                "content": "start: do Schedule(\"reminder\", at = Time()) say \"planned\" goto end",
+                "commands": [],
+              },
+              {
+                "id": "reminder",
+                "name": "reminder",
+                "content": "start: say \"reminder\" goto end",
+                "commands": [],
+              }
+            ],
+            "default_flow": "Default",
+        }))
+        .unwrap();
+        csml_engine::create_bot_version(bot).unwrap();
+
+        let gateway = web::Data::new(Gateway::default());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(gateway.clone())
+                .service(handler)
+                .service(crate::routes::schedules::run_due_schedules)
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let addr = server.addrs()[0];
+        actix_rt::spawn(server.run());
+
+        let (_, mut connection) = awc::Client::new()
+            .ws(format!(
+                "ws://{}/ws?bot_id=test_websocket_schedule&channel_id=channel_id&user_id=user_id",
+                addr
+            ))
+            .connect()
+            .await
+            .unwrap();
+
+        let event = json!({
+            "request_id": "request_id",
+            "payload": {"content_type": "text", "content": {"text": "hi"}},
+        });
+        connection
+            .send(Message::Text(event.to_string().into()))
+            .await
+            .unwrap();
+        let event = next_event(&mut connection).await;
+        assert_eq!(event["data"]["payload"]["content"]["text"], "planned");
+        assert_eq!(next_event(&mut connection).await["event"], "end");
+
+        // the messages of the scheduled flow are sent to the open connection of the client
+        let resp = awc::Client::new()
+            .post(format!("http://{}/schedules/run", addr))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+
+        let event = next_event(&mut connection).await;
+        assert_eq!(event["event"], "message");
+        assert_eq!(event["data"]["payload"]["content"]["text"], "reminder");
+    }
+}